@@ -1,5 +1,23 @@
+use std::fmt;
 use std::net::Ipv4Addr;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    DWordFull,
+    QWordFull,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DWordFull => write!(f, "DWord full"),
+            Self::QWordFull => write!(f, "QWord full"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
 pub trait Byte {
     fn to_u16(&self, b: Self) -> u16;
     fn to_u32(&self, b: Self, c: Self, d: Self) -> u32;
@@ -53,6 +71,26 @@ pub fn bytes_to_u32(a: u8, b: u8, c: u8, d: u8, swapped: bool) -> u32 {
     }
 }
 
+pub fn u32_to_bytes(v: u32, swapped: bool) -> [u8;4] {
+    if swapped {
+        v.to_le_bytes()
+    } else {
+        v.to_be_bytes()
+    }
+}
+
+pub fn u16_to_bytes(v: u16, swapped: bool) -> [u8;2] {
+    if swapped {
+        v.to_le_bytes()
+    } else {
+        v.to_be_bytes()
+    }
+}
+
+pub fn qwords_to_u64(hi: u32, lo: u32) -> u64 {
+    ((hi as u64) << 32) | lo as u64
+}
+
 pub fn bytes_to_u16(a: u8, b: u8, swapped: bool) -> u16 {
     if swapped {
         ((b as u16) << 8) | a as u16
@@ -140,13 +178,13 @@ impl ByteParser {
         self.q_pos == 4
     }
 
-    pub fn set_q_byte(&mut self, b: u8) {
+    pub fn set_q_byte(&mut self, b: u8) -> Result<(), Error> {
         if self.q_pos >= 4 {
-            println!("QWord full.");
-        } else {
-            self.curr_qword[self.q_pos] = b;
-            self.q_pos += 1;
+            return Err(Error::QWordFull);
         }
+        self.curr_qword[self.q_pos] = b;
+        self.q_pos += 1;
+        Ok(())
     }
 
     pub fn reset_qword(&mut self) {
@@ -154,13 +192,13 @@ impl ByteParser {
         self.q_pos = 0;
     }
 
-    pub fn set_d_byte(&mut self, b: u8) {
+    pub fn set_d_byte(&mut self, b: u8) -> Result<(), Error> {
         if self.d_pos >= 2 {
-            println!("DWord full.");
-        } else {
-            self.curr_dword[self.d_pos] = b;
-            self.d_pos += 1;
+            return Err(Error::DWordFull);
         }
+        self.curr_dword[self.d_pos] = b;
+        self.d_pos += 1;
+        Ok(())
     }
 
     pub fn reset_dword(&mut self) {
@@ -261,13 +299,27 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_to_bytes() {
+        assert_eq!(u32_to_bytes(0xA1B2C3D4, false), [0xA1, 0xB2, 0xC3, 0xD4]);
+        assert_eq!(u32_to_bytes(0xA1B2C3D4, true), [0xD4, 0xC3, 0xB2, 0xA1]);
+        assert_eq!(u16_to_bytes(0xA1B2, false), [0xA1, 0xB2]);
+        assert_eq!(u16_to_bytes(0xA1B2, true), [0xB2, 0xA1]);
+    }
+
+    #[test]
+    fn test_qwords_to_u64() {
+        assert_eq!(qwords_to_u64(0xA1B2C3D4, 0x01020304), 0xA1B2C3D401020304);
+        assert_eq!(qwords_to_u64(0, 1), 1);
+    }
+
     #[test]
     fn test_parser_toggle_swap() {
         let mut parser = ByteParser::new(false);
         parser.toggle_swap();
         assert!(parser.b_swap);
         parser.toggle_swap();
-        assert_eq!(parser.b_swap, false);
+        assert!(!parser.b_swap);
 
     }
 
@@ -301,11 +353,11 @@ mod tests {
 
         for c in cases {
             let mut parser = ByteParser::new(c.2);
-            parser.set_d_byte(c.0);
-            parser.set_d_byte(c.1);
+            parser.set_d_byte(c.0).unwrap();
+            parser.set_d_byte(c.1).unwrap();
             assert!(parser.dword_done());
             assert_eq!(c.3, parser.dword_as_u16());
-            parser.set_d_byte(2);
+            assert_eq!(parser.set_d_byte(2), Err(Error::DWordFull));
             parser.reset_dword();
             assert_eq!(0, parser.dword_as_u16());
         }
@@ -320,13 +372,13 @@ mod tests {
 
         for c in cases {
             let mut parser = ByteParser::new(c.4);
-            parser.set_q_byte(c.0);
-            parser.set_q_byte(c.1);
-            parser.set_q_byte(c.2);
-            parser.set_q_byte(c.3);
+            parser.set_q_byte(c.0).unwrap();
+            parser.set_q_byte(c.1).unwrap();
+            parser.set_q_byte(c.2).unwrap();
+            parser.set_q_byte(c.3).unwrap();
             assert!(parser.qword_done());
             assert_eq!(c.5, parser.qword_as_u32());
-            parser.set_q_byte(2);
+            assert_eq!(parser.set_q_byte(2), Err(Error::QWordFull));
             parser.reset_qword();
             assert_eq!(0, parser.qword_as_u32());
         }
@@ -341,10 +393,10 @@ mod tests {
 
         for c in cases {
             let mut parser = ByteParser::new(c.4);
-            parser.set_q_byte(c.0);
-            parser.set_q_byte(c.1);
-            parser.set_q_byte(c.2);
-            parser.set_q_byte(c.3);
+            parser.set_q_byte(c.0).unwrap();
+            parser.set_q_byte(c.1).unwrap();
+            parser.set_q_byte(c.2).unwrap();
+            parser.set_q_byte(c.3).unwrap();
             assert_eq!(c.5, parser.qword_as_ipv4());
         }
         
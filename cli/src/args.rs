@@ -1,19 +1,116 @@
-use clap::Parser as clapParser;
+use std::net::SocketAddr;
+
+use clap::{ArgEnum, Parser as clapParser, Subcommand};
 
 #[derive(clapParser, Debug)]
 #[clap(name = "pcaprs")]
 #[clap(author = "philmish")]
 #[clap(version = "0.1")]
+#[clap(subcommand_negates_reqs = true)]
 pub struct Args {
 
-    /// Pcap file to load
+    /// Pcap or pcapng file to load, - reads from stdin
     #[clap(short, long)]
     file: String,
 
     /// Record index to print
-    #[clap(short, long)]
-    idx: usize,
+    #[clap(short, long, required = true)]
+    idx: Option<usize>,
+
+    #[clap(subcommand)]
+    command: Option<Command>,
+
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Reassemble one conversation and print its payload
+    FOLLOW(FollowArgs),
+    /// List LLDP and CDP neighbors by source MAC address
+    NEIGHBORS,
+}
+
+#[derive(ArgEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FollowProtocol {
+    TCP,
+    UDP,
+}
+
+#[derive(ArgEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FollowFormat {
+    ASCII,
+    HEX,
+    RAW,
+}
+
+#[derive(ArgEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FollowDirection {
+    BOTH,
+    CLIENT,
+    SERVER,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct FollowArgs {
+
+    /// Transport protocol of the conversation
+    #[clap(arg_enum)]
+    protocol: FollowProtocol,
+
+    /// Conversation index, in order of first appearance
+    #[clap(short, long, required_unless_present = "tuple")]
+    stream: Option<usize>,
+
+    /// Conversation endpoints as ADDR:PORT,ADDR:PORT
+    #[clap(short, long, conflicts_with = "stream", value_parser = parse_tuple)]
+    tuple: Option<(SocketAddr, SocketAddr)>,
+
+    /// Output format
+    #[clap(short, long, arg_enum, default_value = "ascii")]
+    output: FollowFormat,
 
+    /// Only print bytes sent by one side
+    #[clap(short, long, arg_enum, default_value = "both")]
+    direction: FollowDirection,
+
+    /// Write output to a file instead of stdout, required for raw output
+    #[clap(short, long, required_if_eq("output", "raw"))]
+    write: Option<String>,
+
+}
+
+fn parse_tuple(s: &str) -> Result<(SocketAddr, SocketAddr), String> {
+    let (a, b) = s.split_once(',').ok_or("expected ADDR:PORT,ADDR:PORT")?;
+    let a = a.trim().parse::<SocketAddr>().map_err(|e| e.to_string())?;
+    let b = b.trim().parse::<SocketAddr>().map_err(|e| e.to_string())?;
+    Ok((a, b))
+}
+
+impl FollowArgs {
+
+    pub fn protocol(&self) -> FollowProtocol {
+        self.protocol
+    }
+
+    pub fn stream(&self) -> Option<usize> {
+        self.stream
+    }
+
+    pub fn tuple(&self) -> Option<(SocketAddr, SocketAddr)> {
+        self.tuple
+    }
+
+    pub fn output(&self) -> FollowFormat {
+        self.output
+    }
+
+    pub fn direction(&self) -> FollowDirection {
+        self.direction
+    }
+
+    pub fn write(&self) -> Option<&str> {
+        self.write.as_deref()
+    }
 }
 
 impl Args {
@@ -22,13 +119,21 @@ impl Args {
         self.file.to_string()
     }
 
-    pub fn index(&self) -> usize {
+    pub fn index(&self) -> Option<usize> {
         self.idx
     }
 
+    pub fn command(&self) -> Option<&Command> {
+        self.command.as_ref()
+    }
+
+    pub fn into_command(self) -> Option<Command> {
+        self.command
+    }
+
     pub fn init() -> Self {
         Self::parse()
-    } 
+    }
 }
 
 #[cfg(test)]
@@ -37,8 +142,31 @@ mod tests {
 
     #[test]
     fn test_args() {
-        let args = Args{file: "test.file".to_string(), idx: 2, };
+        let args = Args{file: "test.file".to_string(), idx: Some(2), command: None };
         assert_eq!(args.f_name(), "test.file".to_string());
-        assert_eq!(args.index(), 2);
+        assert_eq!(args.index(), Some(2));
+    }
+
+    #[test]
+    fn test_follow_args() {
+        let args = Args::try_parse_from(["pcaprs", "-f", "a.pcap", "follow", "tcp", "--stream", "3", "-o", "hex"]).unwrap();
+        assert_eq!(args.index(), None);
+        let Some(Command::FOLLOW(follow)) = args.command() else { panic!("expected follow") };
+        assert_eq!(follow.protocol(), FollowProtocol::TCP);
+        assert_eq!(follow.stream(), Some(3));
+        assert_eq!(follow.output(), FollowFormat::HEX);
+        assert_eq!(follow.direction(), FollowDirection::BOTH);
+
+        let args = Args::try_parse_from(["pcaprs", "-f", "a.pcap", "follow", "udp", "-t", "10.0.0.1:53,[::1]:5353"]).unwrap();
+        let Some(Command::FOLLOW(follow)) = args.command() else { panic!("expected follow") };
+        assert_eq!(follow.tuple(), Some(("10.0.0.1:53".parse().unwrap(), "[::1]:5353".parse().unwrap())));
+
+        assert!(Args::try_parse_from(["pcaprs", "-f", "a.pcap"]).is_err());
+        assert!(Args::try_parse_from(["pcaprs", "-f", "a.pcap", "follow", "tcp"]).is_err());
+        assert!(Args::try_parse_from(["pcaprs", "-f", "a.pcap", "follow", "tcp", "-s", "0", "-o", "raw"]).is_err());
+        assert!(Args::try_parse_from(["pcaprs", "-f", "a.pcap", "follow", "tcp", "-t", "nonsense"]).is_err());
+
+        let args = Args::try_parse_from(["pcaprs", "-f", "a.pcap", "neighbors"]).unwrap();
+        assert!(matches!(args.command(), Some(Command::NEIGHBORS)));
     }
 }
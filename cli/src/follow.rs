@@ -0,0 +1,407 @@
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::net::{IpAddr, SocketAddr};
+use std::time::Duration;
+
+use network::dissect::DissectedPacket;
+use network::error::Layer;
+use network::reassembly::ipv4::Ipv4Reassembler;
+use network::reassembly::ipv6::Ipv6Reassembler;
+use network::reassembly::tcp::{CloseReason, Direction, Endpoint, StreamEvent, TcpReassembler};
+
+use crate::args::{FollowArgs, FollowDirection, FollowFormat, FollowProtocol};
+
+#[derive(Default)]
+pub struct UdpConversations {
+    conversations: HashMap<(Endpoint, Endpoint), (usize, Endpoint)>,
+    events: Vec<StreamEvent>,
+}
+
+impl UdpConversations {
+
+    pub fn push(&mut self, src: Endpoint, dst: Endpoint, payload: &[u8]) {
+        let key = if src <= dst { (src, dst) } else { (dst, src) };
+        let next_id = self.conversations.len();
+        let (id, client) = *self.conversations.entry(key).or_insert_with(|| {
+            self.events.push(StreamEvent::OPEN { id: next_id, client: src, server: dst });
+            (next_id, src)
+        });
+        let direction = if src == client { Direction::CLIENT } else { Direction::SERVER };
+        if !payload.is_empty() {
+            self.events.push(StreamEvent::DATA { id, direction, data: payload.to_vec() });
+        }
+    }
+
+    pub fn events(&mut self) -> std::vec::Drain<'_, StreamEvent> {
+        self.events.drain(..)
+    }
+
+    /// Closes every conversation at the end of the capture.
+    pub fn flush(&mut self) {
+        let mut ids: Vec<usize> = self.conversations.values().map(|(id, _)| *id).collect();
+        ids.sort();
+        self.events.extend(ids.into_iter().map(|id| StreamEvent::CLOSE { id, reason: CloseReason::END }));
+    }
+}
+
+fn endpoint_matches(tuple: &(SocketAddr, SocketAddr), client: &Endpoint, server: &Endpoint) -> bool {
+    let a = Endpoint { addr: tuple.0.ip(), port: tuple.0.port() };
+    let b = Endpoint { addr: tuple.1.ip(), port: tuple.1.port() };
+    (a == *client && b == *server) || (a == *server && b == *client)
+}
+
+pub fn ascii(data: &[u8]) -> String {
+    data.iter().map(|b| match b {
+        b'\n' | b'\r' | b'\t' => *b as char,
+        0x20..=0x7E => *b as char,
+        _ => '.',
+    }).collect()
+}
+
+pub fn hex_dump(data: &[u8], offset: u64, indent: &str) -> String {
+    let mut out = String::new();
+    for (i, chunk) in data.chunks(16).enumerate() {
+        let hex: Vec<String> = chunk.iter().map(|b| format!("{:02x}", b)).collect();
+        let text: String = chunk.iter().map(|b| if (0x20..=0x7E).contains(b) { *b as char } else { '.' }).collect();
+        out.push_str(&format!(
+            "{}{:08x}  {:<47}  |{}|\n",
+            indent,
+            offset + (i * 16) as u64,
+            hex.join(" "),
+            text
+        ));
+    }
+    out
+}
+
+pub struct StreamPrinter<W: Write> {
+    out: W,
+    protocol: FollowProtocol,
+    stream: Option<usize>,
+    tuple: Option<(SocketAddr, SocketAddr)>,
+    format: FollowFormat,
+    direction: FollowDirection,
+    selected: Option<(usize, Endpoint, Endpoint)>,
+    offsets: [u64;2],
+    done: bool,
+}
+
+impl<W: Write> StreamPrinter<W> {
+
+    pub fn new(args: &FollowArgs, out: W) -> Self {
+        Self {
+            out,
+            protocol: args.protocol(),
+            stream: args.stream(),
+            tuple: args.tuple(),
+            format: args.output(),
+            direction: args.direction(),
+            selected: None,
+            offsets: [0;2],
+            done: false,
+        }
+    }
+
+    pub fn found(&self) -> bool {
+        self.selected.is_some()
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+
+    fn shows(&self, direction: Direction) -> bool {
+        matches!(
+            (self.direction, direction),
+            (FollowDirection::BOTH, _)
+                | (FollowDirection::CLIENT, Direction::CLIENT)
+                | (FollowDirection::SERVER, Direction::SERVER)
+        )
+    }
+
+    fn open(&mut self, id: usize, client: Endpoint, server: Endpoint) -> io::Result<()> {
+        let wanted = match (&self.stream, &self.tuple) {
+            (Some(s), _) => *s == id,
+            (None, Some(t)) => endpoint_matches(t, &client, &server),
+            _ => false,
+        };
+        if !wanted || self.selected.is_some() {
+            return Ok(());
+        }
+        self.selected = Some((id, client, server));
+        if self.format == FollowFormat::RAW {
+            return Ok(());
+        }
+        let protocol = match self.protocol {
+            FollowProtocol::TCP => "tcp",
+            FollowProtocol::UDP => "udp",
+        };
+        writeln!(self.out, "===================================================================")?;
+        writeln!(self.out, "Follow: {} stream {}", protocol, id)?;
+        writeln!(self.out, "Client: {}", client)?;
+        writeln!(self.out, "Server: {}", server)?;
+        writeln!(self.out, "===================================================================")
+    }
+
+    fn data(&mut self, direction: Direction, data: &[u8]) -> io::Result<()> {
+        let index = match direction {
+            Direction::CLIENT => 0,
+            Direction::SERVER => 1,
+        };
+        let offset = self.offsets[index];
+        self.offsets[index] += data.len() as u64;
+        if !self.shows(direction) {
+            return Ok(());
+        }
+        let (arrow, name) = match direction {
+            Direction::CLIENT => (">>>", "client"),
+            Direction::SERVER => ("<<<", "server"),
+        };
+        match self.format {
+            FollowFormat::RAW => self.out.write_all(data),
+            FollowFormat::ASCII => {
+                writeln!(self.out, "{} {} ({} bytes)", arrow, name, data.len())?;
+                let text = ascii(data);
+                if text.ends_with('\n') {
+                    write!(self.out, "{}", text)
+                } else {
+                    writeln!(self.out, "{}", text)
+                }
+            },
+            FollowFormat::HEX => {
+                let indent = if direction == Direction::SERVER { "    " } else { "" };
+                write!(self.out, "{}", hex_dump(data, offset, indent))
+            },
+        }
+    }
+
+    pub fn event(&mut self, event: &StreamEvent) -> io::Result<()> {
+        if let StreamEvent::OPEN { id, client, server } = event {
+            return self.open(*id, *client, *server);
+        }
+        match self.selected {
+            Some((id, _, _)) if id == event.id() && !self.done => (),
+            _ => return Ok(()),
+        }
+        match event {
+            StreamEvent::DATA { direction, data, .. } => self.data(*direction, data),
+            StreamEvent::GAP { direction, len, .. } => {
+                let index = if *direction == Direction::CLIENT { 0 } else { 1 };
+                self.offsets[index] += len;
+                if self.format == FollowFormat::RAW || !self.shows(*direction) {
+                    return Ok(());
+                }
+                writeln!(self.out, "[{} bytes missing]", len)
+            },
+            StreamEvent::CLOSE { reason, .. } => {
+                self.done = true;
+                if self.format == FollowFormat::RAW {
+                    return Ok(());
+                }
+                let reason = match reason {
+                    CloseReason::FIN => "closed",
+                    CloseReason::RESET => "reset",
+                    CloseReason::TIMEOUT => "timed out",
+                    CloseReason::EVICTED => "evicted",
+                    CloseReason::END => "end of capture",
+                };
+                writeln!(self.out, "===================================================================")?;
+                writeln!(self.out, "Client: {} bytes, Server: {} bytes ({})", self.offsets[0], self.offsets[1], reason)
+            },
+            StreamEvent::OPEN { .. } => Ok(()),
+        }
+    }
+}
+
+pub struct Follower<W: Write> {
+    printer: StreamPrinter<W>,
+    tcp: TcpReassembler,
+    udp: UdpConversations,
+    ipv4: Ipv4Reassembler,
+    ipv6: Ipv6Reassembler,
+}
+
+impl<W: Write> Follower<W> {
+
+    pub fn new(args: &FollowArgs, out: W) -> Self {
+        Self {
+            printer: StreamPrinter::new(args, out),
+            tcp: TcpReassembler::default(),
+            udp: UdpConversations::default(),
+            ipv4: Ipv4Reassembler::default(),
+            ipv6: Ipv6Reassembler::default(),
+        }
+    }
+
+    pub fn printer(&self) -> &StreamPrinter<W> {
+        &self.printer
+    }
+
+    pub fn push(&mut self, packet: &DissectedPacket, now: Duration) -> io::Result<()> {
+        if let Some(ip) = packet.ipv4() {
+            if ip.is_fragment() == Some(true) {
+                let payload = packet.layer(Layer::IPv4).map_or(&[][..], |l| l.payload());
+                if let (Ok(Some(d)), Some(src), Some(dst)) = (self.ipv4.push(ip, payload, now), ip.src(), ip.dst()) {
+                    self.transport(IpAddr::V4(src), IpAddr::V4(dst), &d.dissect(), now);
+                }
+                return self.drain();
+            }
+            if let (Some(src), Some(dst)) = (ip.src(), ip.dst()) {
+                self.transport(IpAddr::V4(src), IpAddr::V4(dst), packet, now);
+            }
+        } else if let Some(ip) = packet.ipv6() {
+            let (src, dst) = match (ip.src(), ip.dst()) {
+                (Some(s), Some(d)) => (IpAddr::V6(s), IpAddr::V6(d)),
+                _ => return Ok(()),
+            };
+            let fragmented = ip.extensions().and_then(|c| c.fragment()).is_some_and(|(o, m, _)| o != 0 || m);
+            if fragmented {
+                let start = packet.layer(Layer::IPv6).map_or(0, |l| l.range().start);
+                if let Ok(Some(d)) = self.ipv6.push(ip, &packet.data()[start..], now) {
+                    self.transport(src, dst, &d.dissect(), now);
+                }
+                return self.drain();
+            }
+            self.transport(src, dst, packet, now);
+        }
+        self.drain()
+    }
+
+    pub fn finish(mut self) -> io::Result<StreamPrinter<W>> {
+        match self.printer.protocol {
+            FollowProtocol::TCP => self.tcp.flush(),
+            FollowProtocol::UDP => self.udp.flush(),
+        }
+        self.drain()?;
+        Ok(self.printer)
+    }
+
+    fn transport(&mut self, src: IpAddr, dst: IpAddr, packet: &DissectedPacket, now: Duration) {
+        match self.printer.protocol {
+            FollowProtocol::TCP => {
+                let (header, layer) = match (packet.tcp(), packet.layer(Layer::TCP)) {
+                    (Some(h), Some(l)) if !l.is_truncated() => (h, l),
+                    _ => return,
+                };
+                let _ = self.tcp.push(src, dst, header, layer.payload(), now);
+            },
+            FollowProtocol::UDP => {
+                let (sport, dport) = match packet.udp() {
+                    Some(h) => (h.src_port(), h.dst_port()),
+                    None => return,
+                };
+                if let (Some(sport), Some(dport)) = (sport, dport) {
+                    self.udp.push(
+                        Endpoint { addr: src, port: sport },
+                        Endpoint { addr: dst, port: dport },
+                        packet.payload(),
+                    );
+                }
+            },
+        }
+    }
+
+    fn drain(&mut self) -> io::Result<()> {
+        let events: Vec<StreamEvent> = match self.printer.protocol {
+            FollowProtocol::TCP => self.tcp.events().collect(),
+            FollowProtocol::UDP => self.udp.events().collect(),
+        };
+        for event in events.iter() {
+            self.printer.event(event)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+    use crate::args::{Args, Command};
+
+    fn endpoint(s: &str) -> Endpoint {
+        let a: SocketAddr = s.parse().unwrap();
+        Endpoint { addr: a.ip(), port: a.port() }
+    }
+
+    fn follow_args(args: &[&str]) -> FollowArgs {
+        let line = ["pcaprs", "-f", "a.pcap", "follow"].iter().chain(args);
+        match Args::try_parse_from(line).unwrap().into_command() {
+            Some(Command::FOLLOW(args)) => args,
+            _ => panic!("expected follow"),
+        }
+    }
+
+    #[test]
+    fn test_hex_dump() {
+        assert_eq!(
+            hex_dump(b"GET / HTTP/1.1\r\nHost", 16, "    "),
+            "    00000010  47 45 54 20 2f 20 48 54 54 50 2f 31 2e 31 0d 0a  |GET / HTTP/1.1..|\n    \
+             00000020  48 6f 73 74                                      |Host|\n"
+        );
+        assert_eq!(ascii(b"ok\r\n\x00\xff"), "ok\r\n..");
+    }
+
+    #[test]
+    fn test_udp_conversations_and_printer() {
+        let mut udp = UdpConversations::default();
+        let (c, s) = (endpoint("10.0.0.1:5000"), endpoint("10.0.0.2:53"));
+        udp.push(endpoint("10.0.0.9:1"), s, b"other");
+        udp.push(c, s, b"query");
+        udp.push(s, c, b"answer");
+
+        let args = follow_args(&["udp", "-t", "10.0.0.2:53,10.0.0.1:5000"]);
+        let mut printer = StreamPrinter::new(&args, vec![]);
+        for e in udp.events() {
+            printer.event(&e).unwrap();
+        }
+        printer.event(&StreamEvent::CLOSE { id: 1, reason: CloseReason::END }).unwrap();
+        assert!(printer.found());
+        let out = String::from_utf8(printer.into_inner()).unwrap();
+        assert!(out.contains("Follow: udp stream 1\nClient: 10.0.0.1:5000\nServer: 10.0.0.2:53\n"));
+        assert!(out.contains(">>> client (5 bytes)\nquery\n<<< server (6 bytes)\nanswer\n"));
+        assert!(!out.contains("other"));
+        assert!(out.ends_with("Client: 5 bytes, Server: 6 bytes (end of capture)\n"));
+
+        let args = follow_args(&["udp", "-s", "1", "-o", "raw", "-d", "server", "-w", "out.bin"]);
+        let mut printer = StreamPrinter::new(&args, vec![]);
+        udp.push(c, s, b"again");
+        udp.push(s, c, b"reply");
+        for e in [
+            StreamEvent::OPEN { id: 1, client: c, server: s },
+            StreamEvent::DATA { id: 1, direction: Direction::CLIENT, data: b"query".to_vec() },
+            StreamEvent::DATA { id: 1, direction: Direction::SERVER, data: b"answer".to_vec() },
+        ] {
+            printer.event(&e).unwrap();
+        }
+        for e in udp.events() {
+            printer.event(&e).unwrap();
+        }
+        assert_eq!(printer.into_inner(), b"answerreply".to_vec());
+    }
+
+    #[test]
+    fn test_finish_prints_one_footer() {
+        let (c, s) = (endpoint("10.0.0.1:5000"), endpoint("10.0.0.2:53"));
+        let mut follower = Follower::new(&follow_args(&["udp", "-s", "0"]), vec![]);
+        follower.udp.push(c, s, b"query");
+        follower.drain().unwrap();
+        let out = String::from_utf8(follower.finish().unwrap().into_inner()).unwrap();
+        assert_eq!(out.matches("Client: 5 bytes, Server: 0 bytes (end of capture)").count(), 1);
+
+        let mut follower = Follower::new(&follow_args(&["tcp", "-s", "0"]), vec![]);
+        let mut parser = network::transport::tcp::TcpHeaderParser::new(false);
+        for b in [0x9C, 0x40, 0, 80, 0, 0, 0, 1, 0, 0, 0, 0, 0x50, 0x18, 0xFF, 0xFF, 0, 0, 0, 0] {
+            parser.parse(b).unwrap();
+        }
+        follower.tcp.push(c.addr, s.addr, &parser.get_header(), b"hello", Duration::ZERO).unwrap();
+        follower.drain().unwrap();
+        let out = String::from_utf8(follower.finish().unwrap().into_inner()).unwrap();
+        assert!(out.contains(">>> client (5 bytes)\nhello\n"));
+        assert_eq!(out.matches("(end of capture)").count(), 1);
+    }
+}
@@ -1,49 +1,123 @@
 pub mod args;
+pub mod follow;
+pub mod neighbors;
 
-use std::{fs::File, io::Read};
+use std::fs::File;
+use std::io::{self, BufReader, Cursor, Read, Write};
 
-use args::Args;
-use pcap::parser::RecordParser;
-use pcap::file::FileHeader;
+use args::{Args, FollowArgs};
+use follow::Follower;
+use neighbors::NeighborTable;
+use pcap::error::Error;
+use pcap::file::MagicNumber;
+use pcap::pcapng::reader::PcapNgReader;
+use pcap::reader::PcapReader;
 use pcap::record::Record;
 
+enum Capture {
+    Pcap(PcapReader<Box<dyn Read>>),
+    PcapNg(PcapNgReader<Box<dyn Read>>),
+}
+
 pub struct App {
-    header: FileHeader,
-    records: Vec<Record>,
+    capture: Capture,
 }
 
-fn read_file(f_name: String) -> File {
-    File::open(f_name).unwrap()
+fn open_input(f_name: &str) -> Result<Box<dyn Read>, Error> {
+    if f_name == "-" {
+        Ok(Box::new(io::stdin()))
+    } else {
+        Ok(Box::new(BufReader::new(File::open(f_name)?)))
+    }
 }
 
 impl App {
 
-    pub fn from_file(f_name: String) -> Self {
-        let mut data = read_file(f_name);
-        let mut bytes = Vec::new();
-        data.read_to_end(&mut bytes).unwrap();
-        let file_h = FileHeader::new(bytes.to_vec()).unwrap();
-        let mut parser = RecordParser::new(file_h.is_swapped());
-        let records = parser.parse_records(bytes.to_vec(), 24);
-        Self{
-            header: file_h,
-            records
-        }
+    pub fn from_file(f_name: String) -> Result<Self, Error> {
+        let mut input = open_input(&f_name)?;
+        let mut magic = [0u8;4];
+        input.read_exact(&mut magic)?;
+        let input: Box<dyn Read> = Box::new(Cursor::new(magic).chain(input));
+        let capture = match MagicNumber::from_bytes(&magic) {
+            MagicNumber::PCAPNG => {
+                let mut reader = PcapNgReader::new(input);
+                reader.read_header()?;
+                Capture::PcapNg(reader)
+            },
+            _ => Capture::Pcap(PcapReader::new(input)?),
+        };
+        Ok(Self{ capture })
     }
 
     pub fn show_header(&mut self) {
-        println!("{}", self.header);
+        match &self.capture {
+            Capture::Pcap(reader) => println!("{}", reader.header()),
+            Capture::PcapNg(reader) => {
+                if let Some(section) = reader.section() {
+                    print!("{}", section);
+                }
+                for interface in reader.interfaces() {
+                    print!("{}", interface);
+                }
+                println!();
+            },
+        }
+    }
+
+    pub fn print_packet(&mut self, n: usize) {
+        let record = match &mut self.capture {
+            Capture::Pcap(reader) => reader.nth(n),
+            Capture::PcapNg(reader) => reader.nth(n),
+        };
+        match record {
+            Some(Ok(r)) => println!("{}", r),
+            Some(Err(e)) => println!("{}", e),
+            None => println!("Index out of bounds"),
+        }
+    }
+
+    fn records(&mut self) -> Box<dyn Iterator<Item = Result<Record, Error>> + '_> {
+        match &mut self.capture {
+            Capture::Pcap(reader) => Box::new(reader.by_ref()),
+            Capture::PcapNg(reader) => Box::new(reader.by_ref()),
+        }
+    }
+
+    /// Returns false when no conversation matched.
+    pub fn follow<W: Write>(&mut self, args: &FollowArgs, out: W) -> Result<bool, Error> {
+        let mut follower = Follower::new(args, out);
+        for record in self.records() {
+            let record = record?;
+            let packet = match record.dissect() {
+                Ok(p) => p,
+                Err(_) => continue,
+            };
+            follower.push(&packet, record.header().timestamp())?;
+            if follower.printer().is_done() {
+                break;
+            }
+        }
+        let printer = follower.finish()?;
+        let found = printer.found();
+        printer.into_inner().flush()?;
+        Ok(found)
     }
 
-    pub fn print_packet(&self, n: usize) {
-        if n + 1  > self.records.len() {
-            println!("Index out of bounds")
-        } else {
-            println!("{}", self.records[n]);
+    /// Returns the number of neighbors found.
+    pub fn neighbors<W: Write>(&mut self, mut out: W) -> Result<usize, Error> {
+        let mut table = NeighborTable::new();
+        for record in self.records() {
+            let record = record?;
+            if let Ok(packet) = record.dissect() {
+                table.push(&packet);
+            }
         }
+        table.write_report(&mut out)?;
+        out.flush()?;
+        Ok(table.neighbors().len())
     }
 }
 
-pub fn run_app(args: Args) -> App {
+pub fn run_app(args: &Args) -> Result<App, Error> {
     App::from_file(args.f_name())
 }
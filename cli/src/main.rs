@@ -1,9 +1,53 @@
-use cli::{args::Args, run_app};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::process;
+
+use cli::{args::{Args, Command}, run_app};
 
 fn main() {
     let a = Args::init();
-    let idx = a.index();
-    let mut app = run_app(a);
-    app.show_header();
-    app.print_packet(idx);
+    let mut app = match run_app(&a) {
+        Ok(app) => app,
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(1);
+        },
+    };
+    match a.command() {
+        Some(Command::FOLLOW(follow)) => {
+            let out: Box<dyn Write> = match follow.write() {
+                Some(path) => match File::create(path) {
+                    Ok(f) => Box::new(BufWriter::new(f)),
+                    Err(e) => {
+                        eprintln!("{}: {}", path, e);
+                        process::exit(1);
+                    },
+                },
+                None => Box::new(io::stdout().lock()),
+            };
+            match app.follow(follow, out) {
+                Ok(true) => (),
+                Ok(false) => {
+                    eprintln!("No matching conversation");
+                    process::exit(1);
+                },
+                Err(e) => {
+                    eprintln!("{}", e);
+                    process::exit(1);
+                },
+            }
+        },
+        Some(Command::NEIGHBORS) => match app.neighbors(io::stdout().lock()) {
+            Ok(0) => println!("No neighbors found"),
+            Ok(_) => (),
+            Err(e) => {
+                eprintln!("{}", e);
+                process::exit(1);
+            },
+        },
+        None => {
+            app.show_header();
+            app.print_packet(a.index().unwrap_or(0));
+        },
+    }
 }
@@ -0,0 +1,274 @@
+use std::io::{self, Write};
+use std::net::IpAddr;
+
+use network::dissect::DissectedPacket;
+use network::ethernet_frame::MacAddress;
+use network::link::{cdp, lldp};
+
+/// What a device announced about itself over LLDP or CDP.
+pub struct Neighbor {
+    mac: MacAddress,
+    protocols: Vec<&'static str>,
+    chassis_id: Option<String>,
+    port_id: Option<String>,
+    system_name: Option<String>,
+    description: Option<String>,
+    capabilities: Vec<&'static str>,
+    addresses: Vec<IpAddr>,
+    vlan: Option<u16>,
+    ttl: Option<u16>,
+    packets: usize,
+}
+
+impl Neighbor {
+
+    fn new(mac: MacAddress) -> Self {
+        Self {
+            mac,
+            protocols: vec![],
+            chassis_id: None,
+            port_id: None,
+            system_name: None,
+            description: None,
+            capabilities: vec![],
+            addresses: vec![],
+            vlan: None,
+            ttl: None,
+            packets: 0,
+        }
+    }
+
+    pub fn mac(&self) -> &MacAddress {
+        &self.mac
+    }
+
+    pub fn protocols(&self) -> &[&'static str] {
+        &self.protocols
+    }
+
+    pub fn chassis_id(&self) -> Option<&str> {
+        self.chassis_id.as_deref()
+    }
+
+    pub fn port_id(&self) -> Option<&str> {
+        self.port_id.as_deref()
+    }
+
+    pub fn system_name(&self) -> Option<&str> {
+        self.system_name.as_deref()
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    pub fn capabilities(&self) -> &[&'static str] {
+        &self.capabilities
+    }
+
+    pub fn addresses(&self) -> &[IpAddr] {
+        &self.addresses
+    }
+
+    pub fn vlan(&self) -> Option<u16> {
+        self.vlan
+    }
+
+    pub fn ttl(&self) -> Option<u16> {
+        self.ttl
+    }
+
+    pub fn packets(&self) -> usize {
+        self.packets
+    }
+
+    fn seen(&mut self, protocol: &'static str) {
+        if !self.protocols.contains(&protocol) {
+            self.protocols.push(protocol);
+        }
+        self.packets += 1;
+    }
+
+    // Later announcements overwrite earlier ones, addresses and capabilities accumulate.
+    fn update_lldp(&mut self, pdu: &lldp::LldpPdu) {
+        self.seen("LLDP");
+        if let Some(id) = pdu.chassis_id() {
+            self.chassis_id = Some(id.to_string());
+        }
+        if let Some(id) = pdu.port_id() {
+            self.port_id = Some(id.to_string());
+        }
+        if let Some(name) = pdu.system_name() {
+            self.system_name = Some(name.to_string());
+        }
+        if let Some(desc) = pdu.system_description() {
+            self.description = Some(desc.to_string());
+        }
+        if let Some((system, _)) = pdu.capabilities() {
+            self.add_capabilities(lldp::capability_names(system));
+        }
+        self.add_addresses(pdu.management_addresses());
+        self.vlan = pdu.port_vlan().or(self.vlan);
+        self.ttl = pdu.ttl().or(self.ttl);
+    }
+
+    fn update_cdp(&mut self, packet: &cdp::CdpPacket) {
+        self.seen("CDP");
+        if let Some(id) = packet.device_id() {
+            self.chassis_id = Some(id.to_string());
+            self.system_name.get_or_insert_with(|| id.to_string());
+        }
+        if let Some(id) = packet.port_id() {
+            self.port_id = Some(id.to_string());
+        }
+        if let Some(platform) = packet.platform() {
+            self.description = Some(platform.to_string());
+        }
+        if let Some(caps) = packet.capabilities() {
+            self.add_capabilities(cdp::capability_names(caps));
+        }
+        self.add_addresses(packet.addresses());
+        self.vlan = packet.native_vlan().or(self.vlan);
+        self.ttl = Some(packet.ttl() as u16);
+    }
+
+    fn add_capabilities(&mut self, names: Vec<&'static str>) {
+        for name in names {
+            if !self.capabilities.contains(&name) {
+                self.capabilities.push(name);
+            }
+        }
+    }
+
+    fn add_addresses(&mut self, addrs: Vec<IpAddr>) {
+        for addr in addrs {
+            if !self.addresses.contains(&addr) {
+                self.addresses.push(addr);
+            }
+        }
+    }
+}
+
+/// Neighbors keyed by the source MAC address of their announcements, in order of first appearance.
+#[derive(Default)]
+pub struct NeighborTable {
+    neighbors: Vec<Neighbor>,
+}
+
+impl NeighborTable {
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns false when the packet carries no LLDP or CDP announcement.
+    pub fn push(&mut self, packet: &DissectedPacket) -> bool {
+        let Some(frame) = packet.ethernet_frame() else {
+            return false;
+        };
+        if packet.lldp().is_none() && packet.cdp().is_none() {
+            return false;
+        }
+        let mac = frame.src_mac();
+        let idx = match self.neighbors.iter().position(|n| &n.mac == mac) {
+            Some(idx) => idx,
+            None => {
+                self.neighbors.push(Neighbor::new(mac.clone()));
+                self.neighbors.len() - 1
+            },
+        };
+        let neighbor = &mut self.neighbors[idx];
+        if let Some(pdu) = packet.lldp() {
+            neighbor.update_lldp(pdu);
+        }
+        if let Some(cdp) = packet.cdp() {
+            neighbor.update_cdp(cdp);
+        }
+        true
+    }
+
+    pub fn neighbors(&self) -> &[Neighbor] {
+        &self.neighbors
+    }
+
+    pub fn write_report<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        for n in self.neighbors.iter() {
+            writeln!(out, "{} ({}), {} packets", n.mac, n.protocols.join(", "), n.packets)?;
+            let fields = [
+                ("Chassis ID", n.chassis_id.clone()),
+                ("Port ID", n.port_id.clone()),
+                ("System Name", n.system_name.clone()),
+                ("Description", n.description.clone()),
+                ("Capabilities", Some(n.capabilities.join(", ")).filter(|s| !s.is_empty())),
+                ("Addresses", Some(n.addresses.iter().map(|a| a.to_string()).collect::<Vec<_>>().join(" ")).filter(|s| !s.is_empty())),
+                ("VLAN", n.vlan.map(|v| v.to_string())),
+                ("TTL", n.ttl.map(|t| format!("{}s", t))),
+            ];
+            for (name, value) in fields {
+                if let Some(value) = value {
+                    writeln!(out, "    {}: {}", name, value)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(src: u8, ether_type: [u8;2]) -> Vec<u8> {
+        let mut data = vec![0x01, 0x80, 0xC2, 0x00, 0x00, 0x0E];
+        data.extend_from_slice(&[src;6]);
+        data.extend_from_slice(&ether_type);
+        data
+    }
+
+    #[test]
+    fn test_neighbor_table() {
+        let mut lldp = frame(0xB1, [0x88, 0xCC]);
+        lldp.extend_from_slice(&[0x02, 0x07, 0x04, 0xB1, 0xB1, 0xB1, 0xB1, 0xB1, 0xB1]);
+        lldp.extend_from_slice(&[0x04, 0x03, 0x05, b'p', b'1']);
+        lldp.extend_from_slice(&[0x06, 0x02, 0x00, 0x78]);
+        lldp.extend_from_slice(&[0x0A, 0x03, b's', b'w', b'1']);
+        lldp.extend_from_slice(&[0x0E, 0x04, 0x00, 0x14, 0x00, 0x04]);
+        lldp.extend_from_slice(&[0x10, 0x0C, 5, 1, 10, 0, 0, 1, 2, 0, 0, 0, 3, 0]);
+        lldp.extend_from_slice(&[0x00, 0x00]);
+
+        let mut cdp = frame(0xC1, [0x00, 0x22]);
+        cdp.extend_from_slice(&[0xAA, 0xAA, 0x03, 0x00, 0x00, 0x0C, 0x20, 0x00]);
+        cdp.extend_from_slice(&[0x02, 0xB4, 0x00, 0x00]);
+        cdp.extend_from_slice(&[0x00, 0x01, 0x00, 0x06, b'r', b'1']);
+        cdp.extend_from_slice(&[0x00, 0x06, 0x00, 0x08, b'C', b'9', b'K', b'!']);
+        cdp.extend_from_slice(&[0x00, 0x04, 0x00, 0x08, 0x00, 0x00, 0x00, 0x01]);
+
+        let mut other = frame(0xD1, [0x08, 0x00]);
+        other.extend_from_slice(&[0;20]);
+
+        let mut table = NeighborTable::new();
+        assert!(table.push(&DissectedPacket::from_ethernet(&lldp)));
+        assert!(table.push(&DissectedPacket::from_ethernet(&cdp)));
+        assert!(!table.push(&DissectedPacket::from_ethernet(&other)));
+        assert!(table.push(&DissectedPacket::from_ethernet(&lldp)));
+        assert_eq!(table.neighbors().len(), 2);
+        let sw = &table.neighbors()[0];
+        assert_eq!(sw.packets(), 2);
+        assert_eq!(sw.system_name(), Some("sw1"));
+        assert_eq!(sw.capabilities(), &["Bridge", "Router"]);
+        let router = &table.neighbors()[1];
+        assert_eq!(router.protocols(), &["CDP"]);
+        assert_eq!(router.system_name(), Some("r1"));
+        assert_eq!(router.description(), Some("C9K!"));
+
+        let mut out = vec![];
+        table.write_report(&mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "B1:B1:B1:B1:B1:B1 (LLDP), 2 packets\n    Chassis ID: B1:B1:B1:B1:B1:B1\n    Port ID: p1\n    \
+             System Name: sw1\n    Capabilities: Bridge, Router\n    Addresses: 10.0.0.1\n    TTL: 120s\n\
+             C1:C1:C1:C1:C1:C1 (CDP), 1 packets\n    Chassis ID: r1\n    System Name: r1\n    \
+             Description: C9K!\n    Capabilities: Router\n    TTL: 180s\n"
+        );
+    }
+}
@@ -0,0 +1,1447 @@
+use std::fmt::Display;
+use std::ops::Range;
+
+use crate::error::{Error, Layer};
+use crate::ethernet_frame::{EthernetFrame, EthernetFrameParser, PacketType};
+use crate::ip::{IPv4Header, IPv4HeaderParser};
+use crate::link::arp::{ARPHeader, ARPHeaderParser};
+use crate::link::can::CanHeader;
+use crate::link::cdp::{CdpPacket, CDP_PID};
+use crate::link::ieee80211::Ieee80211Header;
+use crate::link::llc::{LlcHeader, LlcHeaderParser, OUI_CISCO, OUI_ENCAPSULATED, SAP_IPX, SAP_STP};
+use crate::link::lldp::LldpPdu;
+use crate::link::loopback::LoopbackHeader;
+use crate::link::ppp::{PppHeader, PPP_IP, PPP_IPV6, PPP_IPX};
+use crate::link::radiotap::RadiotapHeader;
+use crate::link::sll::{SllHeader, PROTO_802_2, PROTO_802_3, PROTO_CAN, PROTO_CANFD};
+use crate::link::stp::Bpdu;
+use crate::link::usb::UsbHeader;
+use crate::netw::ipv6::{IPv6ExtensionChain, IPv6Header, IPv6HeaderParser};
+use crate::netw::ipx::{IpxHeader, IpxHeaderParser, IpxRip, IpxSap, SpxHeader, IPX_HEADER_LEN};
+use crate::transport::icmp::{internet_checksum, IcmpHeader, IcmpHeaderParser, IcmpQuote};
+use crate::transport::icmpv6::{icmpv6_checksum, Icmpv6Header, Icmpv6HeaderParser, Icmpv6Message};
+use crate::transport::protocols::TransportProtocol;
+use crate::transport::tcp::{TcpHeader, TcpHeaderParser};
+use crate::transport::udp::{UdpHeader, UdpHeaderParser};
+
+/// Outermost header of a capture, selected by the capture's link type.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LinkLayer {
+    ETHERNET,
+    /// BSD loopback, address family in host byte order.
+    NULL,
+    /// OpenBSD loopback, address family in network byte order.
+    LOOP,
+    /// IPv4 or IPv6, told apart by the version field.
+    RAW,
+    IPV4,
+    IPV6,
+    SLL,
+    SLL2,
+    PPP,
+    PPPWITHDIR,
+    IEEE80211,
+    RADIOTAP,
+    CAN,
+    USB,
+    USBMMAPPED,
+}
+
+pub enum Header {
+    Ethernet(EthernetFrame),
+    Loopback(LoopbackHeader),
+    SLL(SllHeader),
+    PPP(PppHeader),
+    Radiotap(RadiotapHeader),
+    IEEE80211(Ieee80211Header),
+    CAN(CanHeader),
+    USB(UsbHeader),
+    ARP(ARPHeader),
+    LLC(LlcHeader),
+    STP(Bpdu),
+    LLDP(LldpPdu),
+    CDP(CdpPacket),
+    IPX(IpxHeader),
+    SPX(SpxHeader),
+    IPXRIP(IpxRip),
+    IPXSAP(IpxSap),
+    IPv4(IPv4Header),
+    IPv6(IPv6Header),
+    TCP(TcpHeader),
+    UDP(UdpHeader),
+    ICMP(IcmpHeader),
+    ICMPv6(Icmpv6Header),
+}
+
+impl Header {
+
+    pub fn layer(&self) -> Layer {
+        match self {
+            Self::Ethernet(_) => Layer::Ethernet,
+            Self::Loopback(_) => Layer::Loopback,
+            Self::SLL(_) => Layer::SLL,
+            Self::PPP(_) => Layer::PPP,
+            Self::Radiotap(_) => Layer::Radiotap,
+            Self::IEEE80211(_) => Layer::IEEE80211,
+            Self::CAN(_) => Layer::CAN,
+            Self::USB(_) => Layer::USB,
+            Self::ARP(_) => Layer::ARP,
+            Self::LLC(_) => Layer::LLC,
+            Self::STP(_) => Layer::STP,
+            Self::LLDP(_) => Layer::LLDP,
+            Self::CDP(_) => Layer::CDP,
+            Self::IPX(_) => Layer::IPX,
+            Self::SPX(_) => Layer::SPX,
+            Self::IPXRIP(_) => Layer::IPXRIP,
+            Self::IPXSAP(_) => Layer::IPXSAP,
+            Self::IPv4(_) => Layer::IPv4,
+            Self::IPv6(_) => Layer::IPv6,
+            Self::TCP(_) => Layer::TCP,
+            Self::UDP(_) => Layer::UDP,
+            Self::ICMP(_) => Layer::ICMP,
+            Self::ICMPv6(_) => Layer::ICMPv6,
+        }
+    }
+}
+
+impl Display for Header {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Ethernet(h) => write!(f, "{}", h),
+            Self::Loopback(h) => write!(f, "{}", h),
+            Self::SLL(h) => write!(f, "{}", h),
+            Self::PPP(h) => write!(f, "{}", h),
+            Self::Radiotap(h) => write!(f, "{}", h),
+            Self::IEEE80211(h) => write!(f, "{}", h),
+            Self::CAN(h) => write!(f, "{}", h),
+            Self::USB(h) => write!(f, "{}", h),
+            Self::ARP(h) => write!(f, "{}", h),
+            Self::LLC(h) => write!(f, "{}", h),
+            Self::STP(h) => write!(f, "{}", h),
+            Self::LLDP(h) => write!(f, "{}", h),
+            Self::CDP(h) => write!(f, "{}", h),
+            Self::IPX(h) => write!(f, "{}", h),
+            Self::SPX(h) => write!(f, "{}", h),
+            Self::IPXRIP(h) => write!(f, "{}", h),
+            Self::IPXSAP(h) => write!(f, "{}", h),
+            Self::IPv4(h) => write!(f, "{}", h),
+            Self::IPv6(h) => write!(f, "{}", h),
+            Self::TCP(h) => write!(f, "{}", h),
+            Self::UDP(h) => write!(f, "{}", h),
+            Self::ICMP(h) => write!(f, "{}", h),
+            Self::ICMPv6(h) => write!(f, "{}", h),
+        }
+    }
+}
+
+pub struct DissectedLayer<'a> {
+    header: Header,
+    range: Range<usize>,
+    payload: &'a [u8],
+    truncated: bool,
+}
+
+impl<'a> DissectedLayer<'a> {
+
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+
+    pub fn layer(&self) -> Layer {
+        self.header.layer()
+    }
+
+    pub fn range(&self) -> Range<usize> {
+        self.range.clone()
+    }
+
+    pub fn payload(&self) -> &'a [u8] {
+        self.payload
+    }
+
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+}
+
+impl Display for DissectedLayer<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.truncated {
+            write!(f, "{}\n[Truncated {}]", self.header, self.layer())
+        } else {
+            write!(f, "{}", self.header)
+        }
+    }
+}
+
+pub struct DissectedPacket<'a> {
+    data: &'a [u8],
+    layers: Vec<DissectedLayer<'a>>,
+    error: Option<Error>,
+}
+
+impl Display for DissectedPacket<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for layer in self.layers.iter() {
+            writeln!(f, "{}", layer)?;
+        }
+        if let Some(e) = &self.error {
+            writeln!(f, "{}", e)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a> DissectedPacket<'a> {
+
+    pub fn from_ethernet(data: &'a [u8]) -> Self {
+        Self::from_link(LinkLayer::ETHERNET, data)
+    }
+
+    pub fn from_link(link: LinkLayer, data: &'a [u8]) -> Self {
+        let mut packet = Self { data, layers: vec![], error: None };
+        let limit = data.len();
+        let result = match link {
+            LinkLayer::ETHERNET => packet.dissect_ethernet(0),
+            LinkLayer::NULL => packet.dissect_loopback(0, limit, false),
+            LinkLayer::LOOP => packet.dissect_loopback(0, limit, true),
+            LinkLayer::RAW => packet.dissect_ip(0, limit),
+            LinkLayer::IPV4 => packet.dissect_ipv4(0, limit),
+            LinkLayer::IPV6 => packet.dissect_ipv6(0, limit),
+            LinkLayer::SLL => packet.dissect_sll(0, limit, false),
+            LinkLayer::SLL2 => packet.dissect_sll(0, limit, true),
+            LinkLayer::PPP => packet.dissect_ppp(0, limit, false),
+            LinkLayer::PPPWITHDIR => packet.dissect_ppp(0, limit, true),
+            LinkLayer::IEEE80211 => packet.dissect_ieee80211(0, limit),
+            LinkLayer::RADIOTAP => packet.dissect_radiotap(0, limit),
+            LinkLayer::CAN => packet.dissect_can(0, limit),
+            LinkLayer::USB => packet.dissect_usb(0, limit, false),
+            LinkLayer::USBMMAPPED => packet.dissect_usb(0, limit, true),
+        };
+        if let Err(e) = result {
+            packet.error = Some(e);
+        }
+        packet
+    }
+
+    pub fn from_transport(protocol: TransportProtocol, data: &'a [u8]) -> Self {
+        let mut packet = Self { data, layers: vec![], error: None };
+        if let Err(e) = packet.dissect_transport(protocol, 0, data.len()) {
+            packet.error = Some(e);
+        }
+        packet
+    }
+
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+
+    pub fn layers(&self) -> &[DissectedLayer<'a>] {
+        &self.layers
+    }
+
+    pub fn layer(&self, layer: Layer) -> Option<&DissectedLayer<'a>> {
+        self.layers.iter().find(|l| l.layer() == layer)
+    }
+
+    pub fn error(&self) -> Option<&Error> {
+        self.error.as_ref()
+    }
+
+    pub fn is_truncated(&self) -> bool {
+        self.layers.iter().any(|l| l.truncated)
+    }
+
+    pub fn payload(&self) -> &'a [u8] {
+        match self.layers.last() {
+            Some(l) => l.payload,
+            None => &[],
+        }
+    }
+
+    pub fn ethernet_frame(&self) -> Option<&EthernetFrame> {
+        self.layers.iter().find_map(|l| match &l.header {
+            Header::Ethernet(h) => Some(h),
+            _ => None,
+        })
+    }
+
+    pub fn loopback(&self) -> Option<&LoopbackHeader> {
+        self.layers.iter().find_map(|l| match &l.header {
+            Header::Loopback(h) => Some(h),
+            _ => None,
+        })
+    }
+
+    pub fn sll(&self) -> Option<&SllHeader> {
+        self.layers.iter().find_map(|l| match &l.header {
+            Header::SLL(h) => Some(h),
+            _ => None,
+        })
+    }
+
+    pub fn ppp(&self) -> Option<&PppHeader> {
+        self.layers.iter().find_map(|l| match &l.header {
+            Header::PPP(h) => Some(h),
+            _ => None,
+        })
+    }
+
+    pub fn radiotap(&self) -> Option<&RadiotapHeader> {
+        self.layers.iter().find_map(|l| match &l.header {
+            Header::Radiotap(h) => Some(h),
+            _ => None,
+        })
+    }
+
+    pub fn ieee80211(&self) -> Option<&Ieee80211Header> {
+        self.layers.iter().find_map(|l| match &l.header {
+            Header::IEEE80211(h) => Some(h),
+            _ => None,
+        })
+    }
+
+    pub fn can(&self) -> Option<&CanHeader> {
+        self.layers.iter().find_map(|l| match &l.header {
+            Header::CAN(h) => Some(h),
+            _ => None,
+        })
+    }
+
+    pub fn usb(&self) -> Option<&UsbHeader> {
+        self.layers.iter().find_map(|l| match &l.header {
+            Header::USB(h) => Some(h),
+            _ => None,
+        })
+    }
+
+    pub fn arp(&self) -> Option<&ARPHeader> {
+        self.layers.iter().find_map(|l| match &l.header {
+            Header::ARP(h) => Some(h),
+            _ => None,
+        })
+    }
+
+    pub fn llc(&self) -> Option<&LlcHeader> {
+        self.layers.iter().find_map(|l| match &l.header {
+            Header::LLC(h) => Some(h),
+            _ => None,
+        })
+    }
+
+    pub fn stp(&self) -> Option<&Bpdu> {
+        self.layers.iter().find_map(|l| match &l.header {
+            Header::STP(h) => Some(h),
+            _ => None,
+        })
+    }
+
+    pub fn lldp(&self) -> Option<&LldpPdu> {
+        self.layers.iter().find_map(|l| match &l.header {
+            Header::LLDP(h) => Some(h),
+            _ => None,
+        })
+    }
+
+    pub fn cdp(&self) -> Option<&CdpPacket> {
+        self.layers.iter().find_map(|l| match &l.header {
+            Header::CDP(h) => Some(h),
+            _ => None,
+        })
+    }
+
+    pub fn ipx(&self) -> Option<&IpxHeader> {
+        self.layers.iter().find_map(|l| match &l.header {
+            Header::IPX(h) => Some(h),
+            _ => None,
+        })
+    }
+
+    pub fn spx(&self) -> Option<&SpxHeader> {
+        self.layers.iter().find_map(|l| match &l.header {
+            Header::SPX(h) => Some(h),
+            _ => None,
+        })
+    }
+
+    pub fn ipx_rip(&self) -> Option<&IpxRip> {
+        self.layers.iter().find_map(|l| match &l.header {
+            Header::IPXRIP(h) => Some(h),
+            _ => None,
+        })
+    }
+
+    pub fn ipx_sap(&self) -> Option<&IpxSap> {
+        self.layers.iter().find_map(|l| match &l.header {
+            Header::IPXSAP(h) => Some(h),
+            _ => None,
+        })
+    }
+
+    pub fn ipv4(&self) -> Option<&IPv4Header> {
+        self.layers.iter().find_map(|l| match &l.header {
+            Header::IPv4(h) => Some(h),
+            _ => None,
+        })
+    }
+
+    pub fn ipv6(&self) -> Option<&IPv6Header> {
+        self.layers.iter().find_map(|l| match &l.header {
+            Header::IPv6(h) => Some(h),
+            _ => None,
+        })
+    }
+
+    pub fn tcp(&self) -> Option<&TcpHeader> {
+        self.layers.iter().find_map(|l| match &l.header {
+            Header::TCP(h) => Some(h),
+            _ => None,
+        })
+    }
+
+    pub fn udp(&self) -> Option<&UdpHeader> {
+        self.layers.iter().find_map(|l| match &l.header {
+            Header::UDP(h) => Some(h),
+            _ => None,
+        })
+    }
+
+    pub fn icmp(&self) -> Option<&IcmpHeader> {
+        self.layers.iter().find_map(|l| match &l.header {
+            Header::ICMP(h) => Some(h),
+            _ => None,
+        })
+    }
+
+    pub fn icmpv6(&self) -> Option<&Icmpv6Header> {
+        self.layers.iter().find_map(|l| match &l.header {
+            Header::ICMPv6(h) => Some(h),
+            _ => None,
+        })
+    }
+
+    fn feed<F>(&self, start: usize, len: usize, limit: usize, mut put: F) -> Result<(), Error>
+    where
+        F: FnMut(u8) -> Result<(), Error>,
+    {
+        let stop = (start + len).min(limit);
+        for b in self.data[start.min(stop)..stop].iter() {
+            put(*b)?;
+        }
+        Ok(())
+    }
+
+    // Like feed, for parsers whose header length grows with the bytes already seen.
+    fn feed_while<P, L, F>(&self, start: usize, limit: usize, parser: &mut P, len: L, mut put: F) -> Result<(), Error>
+    where
+        L: Fn(&P) -> usize,
+        F: FnMut(&mut P, u8) -> Result<(), Error>,
+    {
+        let mut pos = start;
+        while pos < limit && pos < start + len(parser) {
+            put(parser, self.data[pos])?;
+            pos += 1;
+        }
+        Ok(())
+    }
+
+    fn push(&mut self, header: Header, start: usize, end: usize, payload_end: usize, limit: usize) -> bool {
+        let truncated = end > limit;
+        let payload = if truncated {
+            &self.data[limit..limit]
+        } else {
+            &self.data[end..payload_end.clamp(end, limit)]
+        };
+        self.layers.push(DissectedLayer {
+            header,
+            range: start..end.min(limit),
+            payload,
+            truncated,
+        });
+        truncated
+    }
+
+    // Slice parsers reject a header the capture cut short. Their `parse_partial` keeps
+    // only what was captured: fixed fields past the cut stay unset, lists keep their
+    // whole entries. The layer is flagged as truncated and errors move to their frame offset.
+    fn partial<P>(&mut self, e: Error, offset: usize, limit: usize, bytes: &[u8], parse: P) -> Error
+    where
+        P: Fn(&[u8]) -> Result<Header, Error>,
+    {
+        if matches!(e, Error::Truncated(..)) {
+            if let Ok(header) = parse(bytes) {
+                self.layers.push(DissectedLayer {
+                    header,
+                    range: offset..limit,
+                    payload: &self.data[limit..limit],
+                    truncated: true,
+                });
+            }
+        }
+        e.at(offset)
+    }
+
+    fn dissect_ethernet(&mut self, offset: usize) -> Result<(), Error> {
+        let limit = self.data.len();
+        let mut parser = EthernetFrameParser::new();
+        self.feed_while(offset, limit, &mut parser, |p| p.header_len(), |p, b| p.put_byte(b))?;
+        let frame = parser.parse();
+        let p_type = frame.packet_type();
+        let end = offset + parser.header_len();
+        if self.push(Header::Ethernet(frame), offset, end, limit, limit) {
+            return Ok(());
+        }
+        self.dissect_ethertype(p_type, end, limit)
+    }
+
+    fn dissect_loopback(&mut self, offset: usize, limit: usize, network_order: bool) -> Result<(), Error> {
+        let header = LoopbackHeader::parse(&self.data[offset..limit], network_order).map_err(|e| e.at(offset))?;
+        let end = offset + header.header_len();
+        self.push(Header::Loopback(header), offset, end, limit, limit);
+        if header.is_ipv4() {
+            self.dissect_ipv4(end, limit)
+        } else if header.is_ipv6() {
+            self.dissect_ipv6(end, limit)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn dissect_sll(&mut self, offset: usize, limit: usize, v2: bool) -> Result<(), Error> {
+        let bytes = &self.data[offset..limit];
+        let header = if v2 { SllHeader::parse_v2(bytes) } else { SllHeader::parse(bytes) };
+        let header = header.map_err(|e| e.at(offset))?;
+        let end = offset + header.header_len();
+        let protocol = header.protocol();
+        self.push(Header::SLL(header), offset, end, limit, limit);
+        match protocol {
+            PROTO_802_2 => self.dissect_llc(end, limit),
+            PROTO_802_3 => self.dissect_ipx(end, limit),
+            PROTO_CAN | PROTO_CANFD => self.dissect_can(end, limit),
+            p if p > 1500 => self.dissect_ethertype(PacketType::new(p), end, limit),
+            _ => Ok(()),
+        }
+    }
+
+    fn dissect_ppp(&mut self, offset: usize, limit: usize, with_direction: bool) -> Result<(), Error> {
+        let header = PppHeader::parse(&self.data[offset..limit], with_direction).map_err(|e| e.at(offset))?;
+        let end = offset + header.header_len();
+        self.push(Header::PPP(header), offset, end, limit, limit);
+        match header.protocol() {
+            PPP_IP => self.dissect_ipv4(end, limit),
+            PPP_IPV6 => self.dissect_ipv6(end, limit),
+            PPP_IPX => self.dissect_ipx(end, limit),
+            _ => Ok(()),
+        }
+    }
+
+    fn dissect_radiotap(&mut self, offset: usize, limit: usize) -> Result<(), Error> {
+        let data = self.data;
+        let header = match RadiotapHeader::parse(&data[offset..limit]) {
+            Ok(h) => h,
+            Err(e) => return Err(self.partial(e, offset, limit, &data[offset..limit], |b| {
+                RadiotapHeader::parse_partial(b).map(Header::Radiotap)
+            })),
+        };
+        let end = offset + header.header_len();
+        let fcs = header.has_fcs();
+        self.push(Header::Radiotap(header), offset, end, limit, limit);
+        let limit = if fcs && limit >= end + 4 { limit - 4 } else { limit };
+        self.dissect_ieee80211(end, limit)
+    }
+
+    fn dissect_ieee80211(&mut self, offset: usize, limit: usize) -> Result<(), Error> {
+        let data = self.data;
+        let header = match Ieee80211Header::parse(&data[offset..limit]) {
+            Ok(h) => h,
+            Err(e) => return Err(self.partial(e, offset, limit, &data[offset..limit], |b| {
+                Ieee80211Header::parse_partial(b).map(Header::IEEE80211)
+            })),
+        };
+        let end = offset + header.header_len();
+        let llc = header.has_llc();
+        self.push(Header::IEEE80211(header), offset, end, limit, limit);
+        if llc {
+            self.dissect_llc(end, limit)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn dissect_can(&mut self, offset: usize, limit: usize) -> Result<(), Error> {
+        let header = CanHeader::parse(&self.data[offset..limit]).map_err(|e| e.at(offset))?;
+        let end = offset + header.header_len();
+        self.push(Header::CAN(header), offset, end, end + header.data_len(), limit);
+        Ok(())
+    }
+
+    fn dissect_usb(&mut self, offset: usize, limit: usize, mmapped: bool) -> Result<(), Error> {
+        let header = UsbHeader::parse(&self.data[offset..limit], mmapped).map_err(|e| e.at(offset))?;
+        let end = offset + header.header_len();
+        let data_end = end + header.data_len() as usize;
+        self.push(Header::USB(header), offset, end, data_end, limit);
+        Ok(())
+    }
+
+    fn dissect_ip(&mut self, offset: usize, limit: usize) -> Result<(), Error> {
+        match self.data.get(offset).map(|b| b >> 4) {
+            Some(6) => self.dissect_ipv6(offset, limit),
+            _ => self.dissect_ipv4(offset, limit),
+        }
+    }
+
+    fn dissect_ethertype(&mut self, p_type: PacketType, offset: usize, limit: usize) -> Result<(), Error> {
+        match p_type {
+            PacketType::IPv4 => self.dissect_ipv4(offset, limit),
+            PacketType::IPv6 => self.dissect_ipv6(offset, limit),
+            PacketType::ARP => self.dissect_arp(offset, limit),
+            PacketType::LLDP => self.dissect_lldp(offset, limit),
+            PacketType::IPX => self.dissect_ipx(offset, limit),
+            // Novell "raw" 802.3 puts IPX right after the length, recognizable by its 0xFFFF checksum.
+            PacketType::LENGTH(len) if self.data[offset.min(limit)..limit].starts_with(&[0xFF, 0xFF])
+                => self.dissect_ipx(offset, limit.min(offset + len as usize)),
+            PacketType::LENGTH(len) => self.dissect_llc(offset, limit.min(offset + len as usize)),
+            _ => Ok(()),
+        }
+    }
+
+    fn dissect_llc(&mut self, offset: usize, limit: usize) -> Result<(), Error> {
+        let mut parser = LlcHeaderParser::new();
+        self.feed_while(offset, limit, &mut parser, |p| p.header_len(), |p, b| p.parse(b))?;
+        let header = parser.get_header();
+        let end = offset + header.header_len();
+        let ethertype = match (header.oui(), header.protocol_id()) {
+            (Some(OUI_ENCAPSULATED), Some(pid)) if pid > 1500 => Some(PacketType::new(pid)),
+            _ => None,
+        };
+        let stp = header.dsap() == Some(SAP_STP) && header.ssap() == Some(SAP_STP);
+        let ipx = header.dsap() == Some(SAP_IPX) && header.ssap() == Some(SAP_IPX);
+        let cdp = header.oui() == Some(OUI_CISCO) && header.protocol_id() == Some(CDP_PID);
+        if self.push(Header::LLC(header), offset, end, limit, limit) {
+            return Ok(());
+        }
+        match ethertype {
+            Some(p_type) => self.dissect_ethertype(p_type, end, limit),
+            None if stp => self.dissect_stp(end, limit),
+            None if cdp => self.dissect_cdp(end, limit),
+            None if ipx => self.dissect_ipx(end, limit),
+            None => Ok(()),
+        }
+    }
+
+    fn dissect_lldp(&mut self, offset: usize, limit: usize) -> Result<(), Error> {
+        let data = self.data;
+        let pdu = match LldpPdu::parse(&data[offset..limit]) {
+            Ok(p) => p,
+            Err(e) => return Err(self.partial(e, offset, limit, &data[offset..limit], |b| {
+                LldpPdu::parse_partial(b).map(Header::LLDP)
+            })),
+        };
+        let end = offset + pdu.header_len();
+        self.push(Header::LLDP(pdu), offset, end, limit, limit);
+        Ok(())
+    }
+
+    fn dissect_cdp(&mut self, offset: usize, limit: usize) -> Result<(), Error> {
+        let data = self.data;
+        let cdp = match CdpPacket::parse(&data[offset..limit]) {
+            Ok(p) => p,
+            Err(e) => return Err(self.partial(e, offset, limit, &data[offset..limit], |b| {
+                CdpPacket::parse_partial(b).map(Header::CDP)
+            })),
+        };
+        let end = offset + cdp.header_len();
+        self.push(Header::CDP(cdp), offset, end, limit, limit);
+        Ok(())
+    }
+
+    fn dissect_stp(&mut self, offset: usize, limit: usize) -> Result<(), Error> {
+        let data = self.data;
+        let bpdu = match Bpdu::parse(&data[offset..limit]) {
+            Ok(b) => b,
+            Err(e) => return Err(self.partial(e, offset, limit, &data[offset..limit], |b| {
+                Bpdu::parse_partial(b).map(Header::STP)
+            })),
+        };
+        let end = offset + bpdu.header_len();
+        self.push(Header::STP(bpdu), offset, end, limit, limit);
+        Ok(())
+    }
+
+    fn dissect_ipx(&mut self, offset: usize, limit: usize) -> Result<(), Error> {
+        let mut parser = IpxHeaderParser::new();
+        self.feed(offset, IPX_HEADER_LEN, limit, |b| parser.parse(b))?;
+        let header = parser.get_header();
+        let end = offset + IPX_HEADER_LEN;
+        let length = header.length().unwrap_or(0) as usize;
+        let (spx, rip, sap) = (header.is_spx(), header.is_rip(), header.is_sap());
+        if self.push(Header::IPX(header), offset, end, limit, limit) {
+            return Ok(());
+        }
+        if length < IPX_HEADER_LEN {
+            return Err(Error::Malformed(Layer::IPX, offset + 2, "length shorter than header"));
+        }
+        // A capture shorter than the IPX length leaves the upper layer cut short.
+        let cut = limit < offset + length;
+        let limit = limit.min(offset + length);
+        let data = self.data;
+        let bytes = &data[end..limit];
+        let (header, len) = if spx {
+            let spx = match SpxHeader::parse(bytes) {
+                Ok(s) => s,
+                Err(e) => return Err(self.partial(e, end, limit, bytes, |b| {
+                    SpxHeader::parse_partial(b).map(Header::SPX)
+                })),
+            };
+            let len = spx.header_len();
+            (Header::SPX(spx), len)
+        } else if rip {
+            if cut {
+                let e = Error::Truncated(Layer::IPXRIP, bytes.len());
+                return Err(self.partial(e, end, limit, bytes, |b| IpxRip::parse_partial(b).map(Header::IPXRIP)));
+            }
+            let rip = IpxRip::parse(bytes).map_err(|e| e.at(end))?;
+            let len = rip.header_len();
+            (Header::IPXRIP(rip), len)
+        } else if sap {
+            if cut {
+                let e = Error::Truncated(Layer::IPXSAP, bytes.len());
+                return Err(self.partial(e, end, limit, bytes, |b| IpxSap::parse_partial(b).map(Header::IPXSAP)));
+            }
+            let sap = IpxSap::parse(bytes).map_err(|e| e.at(end))?;
+            let len = sap.header_len();
+            (Header::IPXSAP(sap), len)
+        } else {
+            return Ok(());
+        };
+        self.push(header, end, end + len, limit, limit);
+        Ok(())
+    }
+
+    fn dissect_arp(&mut self, offset: usize, limit: usize) -> Result<(), Error> {
+        let mut parser = ARPHeaderParser::new(false);
+        self.feed(offset, 28, limit, |b| parser.parse(b))?;
+        self.push(Header::ARP(parser.get_header()), offset, offset + 28, limit, limit);
+        Ok(())
+    }
+
+    fn dissect_ipv4(&mut self, offset: usize, limit: usize) -> Result<(), Error> {
+        let mut parser = IPv4HeaderParser::new(false).with_offset(offset);
+        self.feed(offset, 20, limit, |b| parser.step(b))?;
+        let mut header = parser.get_header();
+        if offset + 20 > limit {
+            self.push(Header::IPv4(header), offset, offset + 20, limit, limit);
+            return Ok(());
+        }
+        let hlen = header.ihl().unwrap_or(0) as usize * 4;
+        let total = header.total_len().unwrap_or(0) as usize;
+        let proto = header.protocol();
+        let fragment = header.fragment_offset().unwrap_or(0);
+        if hlen < 20 {
+            self.push(Header::IPv4(header), offset, offset + 20, limit, limit);
+            return Err(Error::Malformed(Layer::IPv4, offset, "header length below 20 bytes"));
+        }
+        if total < hlen {
+            self.push(Header::IPv4(header), offset, offset + hlen, limit, limit);
+            return Err(Error::Malformed(Layer::IPv4, offset, "total length shorter than header"));
+        }
+        if let Err(e) = self.feed(offset + 20, hlen - 20, limit, |b| parser.step(b)) {
+            self.push(Header::IPv4(header), offset, offset + hlen, limit, limit);
+            return Err(e);
+        }
+        header = parser.get_header();
+        let end = offset + hlen;
+        let limit = limit.min(offset + total);
+        if self.push(Header::IPv4(header), offset, end, limit, limit) || fragment != 0 {
+            return Ok(());
+        }
+        match proto {
+            Some(p) => self.dissect_transport(p, end, limit),
+            None => Ok(()),
+        }
+    }
+
+    fn dissect_ipv6(&mut self, offset: usize, limit: usize) -> Result<(), Error> {
+        let mut parser = IPv6HeaderParser::new(false);
+        self.feed(offset, 40, limit, |b| parser.parse(b))?;
+        let mut header = parser.get_header();
+        let end = offset + 40;
+        if end > limit {
+            self.push(Header::IPv6(header), offset, end, limit, limit);
+            return Ok(());
+        }
+        let limit = match header.payload_len() {
+            Some(len) => limit.min(end + len as usize),
+            None => limit,
+        };
+        let next_header = header.next_header().map_or(59, |p| p.to_u8());
+        let chain = match IPv6ExtensionChain::parse(next_header, &self.data[end..limit], end) {
+            Ok(c) => c,
+            Err(e) => {
+                self.push(Header::IPv6(header), offset, end, limit, limit);
+                return Err(e);
+            },
+        };
+        let later_fragment = chain.fragment().is_some_and(|(o, _, _)| o != 0);
+        let proto = chain.upper_layer();
+        let end = offset + chain.payload_offset();
+        header.set_extensions(chain);
+        if self.push(Header::IPv6(header), offset, end, limit, limit) || later_fragment {
+            return Ok(());
+        }
+        self.dissect_transport(proto, end, limit)
+    }
+
+    fn dissect_transport(&mut self, proto: TransportProtocol, offset: usize, limit: usize) -> Result<(), Error> {
+        match proto {
+            TransportProtocol::TCP => self.dissect_tcp(offset, limit),
+            TransportProtocol::UDP => self.dissect_udp(offset, limit),
+            TransportProtocol::ICMP => self.dissect_icmp(offset, limit),
+            TransportProtocol::ICMPv6 => self.dissect_icmpv6(offset, limit),
+            _ => Ok(()),
+        }
+    }
+
+    fn dissect_tcp(&mut self, offset: usize, limit: usize) -> Result<(), Error> {
+        let mut parser = TcpHeaderParser::new(false).with_offset(offset);
+        self.feed(offset, 20, limit, |b| parser.parse(b))?;
+        let header = parser.get_header();
+        if offset + 20 > limit {
+            self.push(Header::TCP(header), offset, offset + 20, limit, limit);
+            return Ok(());
+        }
+        let hlen = header.hlen().unwrap_or(0) as usize * 4;
+        if hlen < 20 {
+            self.push(Header::TCP(header), offset, offset + 20, limit, limit);
+            return Err(Error::Malformed(Layer::TCP, offset, "data offset below 20 bytes"));
+        }
+        let options = self.feed(offset + 20, hlen - 20, limit, |b| parser.parse(b));
+        self.push(Header::TCP(parser.get_header()), offset, offset + hlen, limit, limit);
+        options
+    }
+
+    fn dissect_udp(&mut self, offset: usize, limit: usize) -> Result<(), Error> {
+        let mut parser = UdpHeaderParser::new(false);
+        self.feed(offset, 8, limit, |b| parser.parse(b))?;
+        let header = parser.get_header();
+        let payload_end = match header.length() {
+            Some(len) if len >= 8 => offset + len as usize,
+            _ => limit,
+        };
+        self.push(Header::UDP(header), offset, offset + 8, payload_end, limit);
+        Ok(())
+    }
+
+    fn dissect_icmp(&mut self, offset: usize, limit: usize) -> Result<(), Error> {
+        let mut parser = IcmpHeaderParser::new();
+        self.feed(offset, 8, limit, |b| parser.parse(b))?;
+        let mut header = parser.get_header();
+        let end = offset + 8;
+        if end <= limit {
+            if self.is_complete(limit) {
+                header.set_checksum_valid(internet_checksum(&self.data[offset..limit]) == 0);
+            }
+            if header.is_error() {
+                if let Ok(quote) = IcmpQuote::parse(&self.data[end..limit]) {
+                    header.set_quote(quote);
+                }
+            }
+        }
+        self.push(Header::ICMP(header), offset, end, limit, limit);
+        Ok(())
+    }
+
+    fn dissect_icmpv6(&mut self, offset: usize, limit: usize) -> Result<(), Error> {
+        let mut parser = Icmpv6HeaderParser::new();
+        self.feed(offset, 4, limit, |b| parser.parse(b))?;
+        let mut header = parser.get_header();
+        if offset + 4 > limit {
+            self.push(Header::ICMPv6(header), offset, offset + 4, limit, limit);
+            return Ok(());
+        }
+        if self.is_complete(limit) {
+            if let Some((src, dst)) = self.ipv6().and_then(|h| Some((h.src()?, h.dst()?))) {
+                header.set_checksum_valid(icmpv6_checksum(src, dst, &self.data[offset..limit]) == 0);
+            }
+        }
+        match Icmpv6Message::parse(header.icmp_type().unwrap_or(0), &self.data[offset + 4..limit], offset + 4) {
+            Ok(message) => {
+                let end = message.header_len().map_or(limit, |l| offset + l);
+                header.set_message(message);
+                self.push(Header::ICMPv6(header), offset, end, limit, limit);
+                Ok(())
+            },
+            Err(e) => {
+                self.push(Header::ICMPv6(header), offset, limit, limit, limit);
+                Err(e)
+            },
+        }
+    }
+
+    // Whether the capture holds everything the enclosing IP header claims.
+    fn is_complete(&self, limit: usize) -> bool {
+        match self.layers.last().map(|l| (&l.header, l.range.start)) {
+            Some((Header::IPv4(h), start)) => h.total_len().is_some_and(|t| start + t as usize <= limit),
+            Some((Header::IPv6(h), start)) => h.payload_len().is_some_and(|t| start + 40 + t as usize <= limit),
+            _ => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::Ipv6Addr;
+
+    use crate::ethernet_frame::MacAddress;
+    use crate::ip::IPv4Option;
+    use crate::link::stp::PortRole;
+
+    fn ethernet(ether_type: [u8;2]) -> Vec<u8> {
+        let mut data = vec![
+            0xA1, 0xA1, 0xA1, 0xA1, 0xA1, 0xA1,
+            0xB1, 0xB1, 0xB1, 0xB1, 0xB1, 0xB1,
+        ];
+        data.extend_from_slice(&ether_type);
+        data
+    }
+
+    fn ipv4_tcp_with_options() -> Vec<u8> {
+        let mut data = ethernet([0x08, 0x00]);
+        // IHL 6: one word of options (router alert)
+        data.extend_from_slice(&[
+            0x46, 0x00, 0x00, 0x3B, 0x1C, 0x46, 0x40, 0x00,
+            0x40, 0x06, 0x00, 0x00, 0xC0, 0xA8, 0x00, 0x01,
+            0xC0, 0xA8, 0x00, 0xC7, 0x94, 0x04, 0x00, 0x00,
+        ]);
+        // data offset 8: 12 bytes of options
+        data.extend_from_slice(&[
+            0xD4, 0x31, 0x00, 0x50, 0x2A, 0x4A, 0x8E, 0x01,
+            0x00, 0x00, 0x00, 0x00, 0x80, 0x18, 0xFA, 0xF0,
+            0x00, 0x00, 0x00, 0x00, 0x01, 0x01, 0x08, 0x0A,
+            0x00, 0xA7, 0xCE, 0x4F, 0x00, 0x00, 0x00, 0x00,
+        ]);
+        data.extend_from_slice(b"GET");
+        // Ethernet trailer padding
+        data.extend_from_slice(&[0x00, 0x00, 0x00]);
+        data
+    }
+
+    #[test]
+    fn test_ipv4_tcp_options() {
+        let data = ipv4_tcp_with_options();
+        let packet = DissectedPacket::from_ethernet(&data);
+        assert!(packet.error().is_none());
+        assert!(!packet.is_truncated());
+        let layers: Vec<Layer> = packet.layers().iter().map(|l| l.layer()).collect();
+        assert_eq!(layers, vec![Layer::Ethernet, Layer::IPv4, Layer::TCP]);
+        assert_eq!(packet.layers()[0].range(), 0..14);
+        assert_eq!(packet.layers()[1].range(), 14..38);
+        assert_eq!(packet.layers()[2].range(), 38..70);
+        assert_eq!(packet.layers()[1].payload().len(), 35);
+        assert_eq!(packet.payload(), b"GET");
+        assert!(packet.ipv4().unwrap().to_string().contains("Source: 192.168.0.1"));
+        assert!(packet.tcp().unwrap().to_string().starts_with("Src Port: 54321\nDest Port: 80"));
+        assert!(packet.udp().is_none());
+
+        let mut bad = data.clone();
+        bad[35] = 0x09;
+        let packet = DissectedPacket::from_ethernet(&bad);
+        assert_eq!(packet.error(), Some(&Error::Malformed(Layer::IPv4, 34, "option length out of bounds")));
+        let mut bad = data.clone();
+        bad[61] = 0x0B;
+        let packet = DissectedPacket::from_ethernet(&bad);
+        assert_eq!(packet.error(), Some(&Error::Malformed(Layer::TCP, 60, "option length out of bounds")));
+    }
+
+    #[test]
+    fn test_typed_accessors() {
+        let data = ipv4_tcp_with_options();
+        let packet = DissectedPacket::from_ethernet(&data);
+        let frame = packet.ethernet_frame().unwrap();
+        assert_eq!(frame.dest_mac().octets(), [0xA1;6]);
+        assert_eq!(frame.src_mac().octets(), [0xB1;6]);
+        assert!(frame.dest_mac().is_multicast());
+        assert!(!frame.src_mac().is_broadcast());
+
+        let ip = packet.ipv4().unwrap();
+        assert_eq!(ip.version(), Some(4));
+        assert_eq!(ip.ihl(), Some(6));
+        assert_eq!(ip.header_len(), Some(24));
+        assert_eq!(ip.tos(), Some(0));
+        assert_eq!(ip.total_len(), Some(59));
+        assert_eq!(ip.id(), Some(0x1C46));
+        assert_eq!(ip.flags(), Some(0b010));
+        assert_eq!(ip.dont_fragment(), Some(true));
+        assert_eq!(ip.options(), Some(&[IPv4Option::RA(0)][..]));
+        assert_eq!(ip.fragment_offset(), Some(0));
+        assert_eq!(ip.ttl(), Some(64));
+        assert!(matches!(ip.protocol(), Some(TransportProtocol::TCP)));
+        assert_eq!(ip.checksum(), Some(0));
+        assert_eq!(ip.src(), Some("192.168.0.1".parse().unwrap()));
+        assert_eq!(ip.dst(), Some("192.168.0.199".parse().unwrap()));
+
+        let tcp = packet.tcp().unwrap();
+        assert_eq!(tcp.src_port(), Some(54321));
+        assert_eq!(tcp.dst_port(), Some(80));
+        assert_eq!(tcp.seq(), Some(0x2A4A8E01));
+        assert_eq!(tcp.ack(), Some(0));
+        assert_eq!(tcp.hlen(), Some(8));
+        assert_eq!(tcp.header_len(), Some(32));
+        assert_eq!(tcp.flags().unwrap().summary(), "[PSH, ACK]".to_string());
+        assert_eq!(tcp.timestamps(), Some((0x00A7CE4F, 0)));
+        assert_eq!(tcp.window(), Some(64240));
+        assert_eq!(tcp.checksum(), Some(0));
+        assert_eq!(tcp.urgent_ptr(), Some(0));
+        assert_eq!(TcpHeader::new().src_port(), None);
+    }
+
+    #[test]
+    fn test_ipv6_udp() {
+        let mut data = ethernet([0x86, 0xDD]);
+        data.extend_from_slice(&[0x60, 0x00, 0x00, 0x00, 0x00, 0x0A, 0x11, 0x40]);
+        data.extend_from_slice(&[0xFE, 0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x01]);
+        data.extend_from_slice(&[0xFE, 0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x02]);
+        data.extend_from_slice(&[0x02, 0x22, 0x02, 0x23, 0x00, 0x0A, 0x00, 0x00, 0xAB, 0xCD]);
+        let packet = DissectedPacket::from_ethernet(&data);
+        assert!(packet.error().is_none());
+        assert_eq!(packet.layers()[1].range(), 14..54);
+        assert_eq!(packet.layers()[2].range(), 54..62);
+        assert!(packet.ipv6().unwrap().to_string().contains("Source: fe80::1"));
+        assert!(packet.udp().unwrap().to_string().starts_with("Src Port: 546\nDest Port: 547"));
+        assert_eq!(packet.payload(), &[0xAB, 0xCD]);
+        assert!(packet.tcp().is_none());
+
+        let ip = packet.ipv6().unwrap();
+        assert_eq!(ip.version(), Some(6));
+        assert_eq!(ip.traffic_class(), Some(0));
+        assert_eq!(ip.flow_label(), Some(0));
+        assert_eq!(ip.payload_len(), Some(10));
+        assert!(matches!(ip.next_header(), Some(TransportProtocol::UDP)));
+        assert_eq!(ip.hop_limit(), Some(64));
+        assert_eq!(ip.src(), Some("fe80::1".parse().unwrap()));
+        assert_eq!(ip.dst(), Some("fe80::2".parse().unwrap()));
+        let udp = packet.udp().unwrap();
+        assert_eq!(udp.src_port(), Some(546));
+        assert_eq!(udp.dst_port(), Some(547));
+        assert_eq!(udp.length(), Some(10));
+        assert_eq!(udp.checksum(), Some(0));
+    }
+
+    #[test]
+    fn test_ipv6_extension_headers() {
+        let mut data = ethernet([0x86, 0xDD]);
+        data.extend_from_slice(&[0x60, 0x00, 0x00, 0x00, 0x00, 0x18, 0x00, 0x40]);
+        data.extend_from_slice(&[0xFE, 0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x01]);
+        data.extend_from_slice(&[0xFE, 0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x02]);
+        data.extend_from_slice(&[0x2C, 0x00, 0x05, 0x02, 0x00, 0x00, 0x01, 0x00]);
+        data.extend_from_slice(&[0x11, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x2A]);
+        data.extend_from_slice(&[0x02, 0x22, 0x02, 0x23, 0x00, 0x08, 0x00, 0x00]);
+        let packet = DissectedPacket::from_ethernet(&data);
+        assert!(packet.error().is_none());
+        assert_eq!(packet.layers()[1].range(), 14..70);
+        assert_eq!(packet.layers()[2].range(), 70..78);
+        let ip = packet.ipv6().unwrap();
+        assert_eq!(ip.payload_offset(), 56);
+        assert!(matches!(ip.upper_layer(), Some(TransportProtocol::UDP)));
+        assert_eq!(ip.extensions().unwrap().fragment(), Some((0, false, 42)));
+        assert!(ip.to_string().ends_with("Upper Layer: UDP"));
+        assert_eq!(packet.udp().unwrap().dst_port(), Some(547));
+        let offsets: Vec<usize> = ip.extensions().unwrap().headers().iter().map(|h| h.offset()).collect();
+        assert_eq!(offsets, vec![54, 62]);
+
+        let mut bad = data.clone();
+        bad[57] = 0x09;
+        let packet = DissectedPacket::from_ethernet(&bad);
+        assert_eq!(packet.error(), Some(&Error::Malformed(Layer::IPv6, 56, "option length out of bounds")));
+
+        data[65] = 0x08;
+        let packet = DissectedPacket::from_ethernet(&data);
+        assert_eq!(packet.layers().len(), 2);
+        assert_eq!(packet.payload(), &data[70..]);
+    }
+
+    #[test]
+    fn test_ipv4_icmp() {
+        let mut data = ethernet([0x08, 0x00]);
+        data.extend_from_slice(&[
+            0x45, 0x00, 0x00, 0x1C, 0x00, 0x01, 0x00, 0x00,
+            0x40, 0x01, 0x00, 0x00, 0x0A, 0x00, 0x00, 0x01,
+            0x0A, 0x00, 0x00, 0x02,
+            0x08, 0x00, 0xF7, 0xFE, 0x00, 0x01, 0x00, 0x00,
+        ]);
+        let packet = DissectedPacket::from_ethernet(&data);
+        assert!(packet.tcp().is_none());
+        assert_eq!(packet.layer(Layer::ICMP).unwrap().range(), 34..42);
+        assert!(packet.payload().is_empty());
+        let icmp = packet.icmp().unwrap();
+        assert_eq!(icmp.icmp_type(), Some(8));
+        assert_eq!(icmp.code(), Some(0));
+        assert_eq!(icmp.checksum(), Some(0xF7FE));
+        assert_eq!(icmp.checksum_valid(), Some(true));
+        assert_eq!(icmp.identifier(), Some(1));
+        assert_eq!(icmp.sequence(), Some(0));
+    }
+
+    #[test]
+    fn test_vlan_tagged_frame() {
+        let mut data = ethernet([0x81, 0x00]);
+        data.extend_from_slice(&[0x20, 0x2A, 0x08, 0x00]);
+        data.extend_from_slice(&[
+            0x45, 0x00, 0x00, 0x1C, 0x00, 0x01, 0x00, 0x00,
+            0x40, 0x01, 0x00, 0x00, 0x0A, 0x00, 0x00, 0x01,
+            0x0A, 0x00, 0x00, 0x02,
+            0x08, 0x00, 0xF7, 0xFE, 0x00, 0x01, 0x00, 0x00,
+        ]);
+        let packet = DissectedPacket::from_ethernet(&data);
+        assert!(packet.error().is_none());
+        assert_eq!(packet.layers()[0].range(), 0..18);
+        assert_eq!(packet.layer(Layer::ICMP).unwrap().range(), 38..46);
+        let Header::Ethernet(frame) = packet.layers()[0].header() else { panic!("expected ethernet") };
+        assert_eq!(frame.vlan_id(), Some(42));
+        assert_eq!(frame.vlan_tags()[0].pcp(), 1);
+
+        let packet = DissectedPacket::from_ethernet(&data[..16]);
+        assert!(packet.layers()[0].is_truncated());
+        assert_eq!(packet.layers().len(), 1);
+    }
+
+    #[test]
+    fn test_llc_snap_frames() {
+        let mut data = ethernet([0x00, 0x07]);
+        data.extend_from_slice(&[0xF0, 0xF0, 0x03, 0x00, 0x00, 0x00, 0x00, 0xEE, 0xEE]);
+        let packet = DissectedPacket::from_ethernet(&data);
+        assert!(packet.error().is_none());
+        assert_eq!(packet.layers().len(), 2);
+        assert_eq!(packet.layer(Layer::LLC).unwrap().range(), 14..17);
+        assert_eq!(packet.llc().unwrap().dsap(), Some(0xF0));
+        assert_eq!(packet.payload(), &[0x00, 0x00, 0x00, 0x00]);
+
+        let mut data = ethernet([0x00, 0x24]);
+        data.extend_from_slice(&[0xAA, 0xAA, 0x03, 0x00, 0x00, 0x00, 0x08, 0x00]);
+        data.extend_from_slice(&[
+            0x45, 0x00, 0x00, 0x1C, 0x00, 0x01, 0x00, 0x00,
+            0x40, 0x01, 0x00, 0x00, 0x0A, 0x00, 0x00, 0x01,
+            0x0A, 0x00, 0x00, 0x02,
+            0x08, 0x00, 0xF7, 0xFE, 0x00, 0x01, 0x00, 0x00,
+        ]);
+        let packet = DissectedPacket::from_ethernet(&data);
+        assert!(packet.error().is_none());
+        assert!(packet.llc().unwrap().is_snap());
+        assert_eq!(packet.layer(Layer::LLC).unwrap().range(), 14..22);
+        assert_eq!(packet.layer(Layer::ICMP).unwrap().range(), 42..50);
+
+        let packet = DissectedPacket::from_ethernet(&data[..19]);
+        assert!(packet.layer(Layer::LLC).unwrap().is_truncated());
+    }
+
+    #[test]
+    fn test_stp_bpdu() {
+        let mut data = vec![
+            0x01, 0x80, 0xC2, 0x00, 0x00, 0x00,
+            0xB1, 0xB1, 0xB1, 0xB1, 0xB1, 0xB1,
+            0x00, 0x27, 0x42, 0x42, 0x03,
+            0x00, 0x00, 0x02, 0x02, 0x3C,
+            0x80, 0x00, 0xB1, 0xB1, 0xB1, 0xB1, 0xB1, 0xB1,
+            0x00, 0x00, 0x00, 0x00,
+            0x80, 0x00, 0xB1, 0xB1, 0xB1, 0xB1, 0xB1, 0xB1,
+            0x80, 0x01, 0x00, 0x00, 0x14, 0x00, 0x02, 0x00, 0x0F, 0x00,
+            0x00,
+        ];
+        data.extend_from_slice(&[0;7]);
+        let packet = DissectedPacket::from_ethernet(&data);
+        assert!(packet.error().is_none());
+        assert_eq!(packet.layer(Layer::STP).unwrap().range(), 17..53);
+        assert!(packet.payload().is_empty());
+        let bpdu = packet.stp().unwrap();
+        assert_eq!(bpdu.root(), bpdu.bridge());
+        assert_eq!(bpdu.port_role(), Some(PortRole::DESIGNATED));
+
+        let packet = DissectedPacket::from_ethernet(&data[..40]);
+        assert_eq!(packet.error(), Some(&Error::Truncated(Layer::STP, 40)));
+        let layer = packet.layer(Layer::STP).unwrap();
+        assert!(layer.is_truncated());
+        assert_eq!(layer.range(), 17..40);
+        let bpdu = packet.stp().unwrap();
+        assert_eq!(bpdu.root(), DissectedPacket::from_ethernet(&data).stp().unwrap().root());
+        assert_eq!(bpdu.bridge(), None);
+        assert_eq!(bpdu.port_id(), None);
+
+        let packet = DissectedPacket::from_ethernet(&data[..19]);
+        assert_eq!(packet.error(), Some(&Error::Truncated(Layer::STP, 19)));
+        assert!(packet.stp().is_none());
+    }
+
+    #[test]
+    fn test_lldp_and_cdp() {
+        let mut data = ethernet([0x88, 0xCC]);
+        data.extend_from_slice(&[0x02, 0x07, 0x04, 0xB1, 0xB1, 0xB1, 0xB1, 0xB1, 0xB1]);
+        data.extend_from_slice(&[0x04, 0x03, 0x05, b'p', b'1']);
+        data.extend_from_slice(&[0x06, 0x02, 0x00, 0x78]);
+        data.extend_from_slice(&[0x0A, 0x03, b's', b'w', b'1']);
+        data.extend_from_slice(&[0x00, 0x00, 0x00, 0x00]);
+        let packet = DissectedPacket::from_ethernet(&data);
+        assert!(packet.error().is_none());
+        assert_eq!(packet.layer(Layer::LLDP).unwrap().range(), 14..39);
+        let lldp = packet.lldp().unwrap();
+        assert_eq!(lldp.system_name(), Some("sw1"));
+        assert_eq!(lldp.ttl(), Some(120));
+
+        let packet = DissectedPacket::from_ethernet(&data[..30]);
+        assert_eq!(packet.error(), Some(&Error::Truncated(Layer::LLDP, 30)));
+        let layer = packet.layer(Layer::LLDP).unwrap();
+        assert!(layer.is_truncated());
+        assert_eq!(layer.range(), 14..30);
+        assert_eq!(packet.lldp().unwrap().tlvs().len(), 2);
+        assert_eq!(packet.lldp().unwrap().ttl(), None);
+
+        let mut data = ethernet([0x00, 0x1A]);
+        data.extend_from_slice(&[0xAA, 0xAA, 0x03, 0x00, 0x00, 0x0C, 0x20, 0x00]);
+        data.extend_from_slice(&[0x02, 0xB4, 0x00, 0x00]);
+        data.extend_from_slice(&[0x00, 0x01, 0x00, 0x06, b'r', b'1']);
+        data.extend_from_slice(&[0x00, 0x03, 0x00, 0x08, b'G', b'i', b'0', b'1']);
+        let packet = DissectedPacket::from_ethernet(&data);
+        assert!(packet.error().is_none());
+        assert_eq!(packet.layer(Layer::CDP).unwrap().range(), 22..40);
+        assert_eq!(packet.cdp().unwrap().device_id(), Some("r1"));
+        assert_eq!(packet.cdp().unwrap().port_id(), Some("Gi01"));
+
+        let packet = DissectedPacket::from_ethernet(&data[..36]);
+        assert_eq!(packet.error(), Some(&Error::Truncated(Layer::CDP, 36)));
+        assert!(packet.layer(Layer::CDP).unwrap().is_truncated());
+        assert_eq!(packet.cdp().unwrap().device_id(), Some("r1"));
+        assert_eq!(packet.cdp().unwrap().port_id(), None);
+    }
+
+    #[test]
+    fn test_radiotap_80211() {
+        let mut data = vec![0x00, 0x00, 0x09, 0x00, 0x02, 0x00, 0x00, 0x00, 0x10];
+        data.extend_from_slice(&[0x08, 0x01, 0x2C, 0x00]);
+        for b in [0xA1, 0xB1, 0xC1] {
+            data.extend_from_slice(&[b;6]);
+        }
+        data.extend_from_slice(&[0x00, 0x00]);
+        data.extend_from_slice(&[0xAA, 0xAA, 0x03, 0x00, 0x00, 0x00, 0x08, 0x00]);
+        data.extend_from_slice(&ipv4_tcp_with_options()[14..]);
+        data.extend_from_slice(&[0xDE, 0xAD, 0xBE, 0xEF]);
+        let packet = DissectedPacket::from_link(LinkLayer::RADIOTAP, &data);
+        assert!(packet.error().is_none());
+        assert!(packet.radiotap().unwrap().has_fcs());
+        assert_eq!(packet.layer(Layer::IEEE80211).unwrap().range(), 9..33);
+        assert_eq!(packet.ieee80211().unwrap().bssid(), Some(packet.ieee80211().unwrap().receiver()));
+        assert_eq!(packet.layer(Layer::LLC).unwrap().range(), 33..41);
+        assert!(packet.tcp().is_some());
+        assert!(!packet.payload().ends_with(&[0xDE, 0xAD, 0xBE, 0xEF]));
+
+        // Without the FCS flag, so the cut is not taken for the FCS.
+        data[8] = 0;
+        let packet = DissectedPacket::from_link(LinkLayer::RADIOTAP, &data[..20]);
+        assert_eq!(packet.error(), Some(&Error::Truncated(Layer::IEEE80211, 20)));
+        let layer = packet.layer(Layer::IEEE80211).unwrap();
+        assert!(layer.is_truncated());
+        assert_eq!(layer.range(), 9..20);
+        assert_eq!(packet.ieee80211().unwrap().receiver(), &MacAddress::new([0xA1;6]));
+
+        assert_eq!(packet.ieee80211().unwrap().transmitter(), None);
+
+        let packet = DissectedPacket::from_link(LinkLayer::RADIOTAP, &data[..8]);
+        assert_eq!(packet.error(), Some(&Error::Truncated(Layer::Radiotap, 8)));
+        assert_eq!(packet.layer(Layer::Radiotap).unwrap().range(), 0..8);
+        assert_eq!(packet.radiotap().unwrap().flags(), None);
+    }
+
+    #[test]
+    fn test_usb_payload() {
+        let mut data = vec![0;64];
+        data[8] = b'C';
+        data[9] = 3;
+        data[10] = 0x81;
+        data[14] = b'-';
+        data[36] = 4;
+        data.extend_from_slice(&[1, 2, 3, 4]);
+        let packet = DissectedPacket::from_link(LinkLayer::USBMMAPPED, &data);
+        assert_eq!(packet.usb().unwrap().event_name(), "Complete");
+        assert_eq!(packet.payload(), &[1, 2, 3, 4]);
+        assert!(!packet.is_truncated());
+
+        let packet = DissectedPacket::from_link(LinkLayer::USB, &data[..50]);
+        assert_eq!(packet.layer(Layer::USB).unwrap().range(), 0..48);
+        assert_eq!(packet.payload(), &[0, 0]);
+
+        let packet = DissectedPacket::from_link(LinkLayer::USBMMAPPED, &data[..40]);
+        assert_eq!(packet.error(), Some(&Error::Truncated(Layer::USB, 40)));
+        assert!(packet.usb().is_none());
+    }
+
+    fn ipx(length: u16, packet_type: u8, dst_socket: u16) -> Vec<u8> {
+        let mut data = vec![0xFF, 0xFF];
+        data.extend_from_slice(&length.to_be_bytes());
+        data.extend_from_slice(&[0x00, packet_type, 0x00, 0x00, 0x00, 0x01]);
+        data.extend_from_slice(&[0xFF;6]);
+        data.extend_from_slice(&dst_socket.to_be_bytes());
+        data.extend_from_slice(&[0x00, 0x00, 0x00, 0x02, 0xB1, 0xB1, 0xB1, 0xB1, 0xB1, 0xB1, 0x40, 0x01]);
+        data
+    }
+
+    #[test]
+    fn test_ipx_frames() {
+        let mut data = ethernet([0x81, 0x37]);
+        data.extend(ipx(44, 5, 0x4005));
+        data.extend_from_slice(&[0x50, 0x00, 0x12, 0x34, 0x56, 0x78, 0x00, 0x01, 0x00, 0x00, 0x00, 0x04]);
+        data.extend_from_slice(&[0xAB, 0xCD]);
+        let packet = DissectedPacket::from_ethernet(&data);
+        assert!(packet.error().is_none());
+        assert_eq!(packet.ipx().unwrap().source().unwrap().to_string(), "00000002.B1:B1:B1:B1:B1:B1.4001");
+        assert_eq!(packet.layer(Layer::SPX).unwrap().range(), 44..56);
+        assert!(packet.spx().unwrap().is_end_of_message());
+        assert_eq!(packet.payload(), &[0xAB, 0xCD]);
+
+        // Novell raw 802.3, padded to the minimum frame size.
+        let mut data = ethernet([0x00, 0x28]);
+        data.extend(ipx(40, 1, 0x0453));
+        data.extend_from_slice(&[0x00, 0x02, 0x00, 0x00, 0x00, 0x10, 0x00, 0x01, 0x00, 0x02]);
+        data.extend_from_slice(&[0;6]);
+        let packet = DissectedPacket::from_ethernet(&data);
+        assert!(packet.error().is_none());
+        assert!(packet.llc().is_none());
+        assert_eq!(packet.ipx_rip().unwrap().routes()[0].network, 0x10);
+        assert!(packet.payload().is_empty());
+
+        let mut data = ethernet([0x00, 0x25]);
+        data.extend_from_slice(&[0xE0, 0xE0, 0x03]);
+        data.extend(ipx(34, 4, 0x0452));
+        data.extend_from_slice(&[0x00, 0x03, 0x00, 0x04]);
+        let packet = DissectedPacket::from_ethernet(&data);
+        assert!(packet.error().is_none());
+        assert_eq!(packet.layer(Layer::IPX).unwrap().range(), 17..47);
+        assert_eq!(packet.ipx_sap().unwrap().query_type(), Some(0x0004));
+
+        let mut data = ethernet([0x81, 0x37]);
+        data.extend(ipx(20, 4, 0x0451));
+        let packet = DissectedPacket::from_ethernet(&data);
+        assert_eq!(packet.error(), Some(&Error::Malformed(Layer::IPX, 16, "length shorter than header")));
+        assert!(packet.ipx().is_some());
+
+        let mut data = ethernet([0x81, 0x37]);
+        data.extend(ipx(44, 5, 0x4005));
+        data.extend_from_slice(&[0x50, 0x00, 0x12, 0x34, 0x56, 0x78]);
+        let packet = DissectedPacket::from_ethernet(&data);
+        assert_eq!(packet.error(), Some(&Error::Truncated(Layer::SPX, 50)));
+        let layer = packet.layer(Layer::SPX).unwrap();
+        assert!(layer.is_truncated());
+        assert_eq!(layer.range(), 44..50);
+        assert_eq!(packet.spx().unwrap().dst_connection(), Some(0x5678));
+        assert_eq!(packet.spx().unwrap().sequence(), None);
+
+        let mut data = ethernet([0x81, 0x37]);
+        data.extend(ipx(58, 1, 0x0453));
+        data.extend_from_slice(&[0x00, 0x02, 0x00, 0x00, 0x00, 0x10, 0x00, 0x01, 0x00, 0x02, 0x00, 0x00]);
+        let packet = DissectedPacket::from_ethernet(&data);
+        assert_eq!(packet.error(), Some(&Error::Truncated(Layer::IPXRIP, 56)));
+        assert!(packet.layer(Layer::IPXRIP).unwrap().is_truncated());
+        assert_eq!(packet.ipx_rip().unwrap().routes().len(), 1);
+    }
+
+    #[test]
+    fn test_icmp_time_exceeded() {
+        let mut data = ethernet([0x08, 0x00]);
+        data.extend_from_slice(&[
+            0x45, 0x00, 0x00, 0x38, 0x00, 0x02, 0x00, 0x00,
+            0x40, 0x01, 0x00, 0x00, 0x0A, 0x00, 0x00, 0xFE,
+            0x0A, 0x00, 0x00, 0x01,
+            0x0B, 0x00, 0xF4, 0xFF, 0x00, 0x00, 0x00, 0x00,
+            0x45, 0x00, 0x00, 0x3C, 0x1C, 0x46, 0x40, 0x00,
+            0x01, 0x06, 0x00, 0x00, 0x0A, 0x00, 0x00, 0x01,
+            0x5D, 0xB8, 0xD8, 0x22, 0xD4, 0x31, 0x00, 0x50,
+            0x2A, 0x4A, 0x8E, 0x01,
+        ]);
+        let packet = DissectedPacket::from_ethernet(&data);
+        let icmp = packet.icmp().unwrap();
+        assert!(icmp.is_error());
+        assert_eq!(icmp.code_name(), Some("TTL Exceeded in Transit"));
+        let quote = icmp.quote().unwrap();
+        assert_eq!(quote.header().ttl(), Some(1));
+        assert_eq!(quote.header().dst(), Some("93.184.216.34".parse().unwrap()));
+        assert_eq!(quote.src_port(), Some(54321));
+        assert_eq!(quote.dst_port(), Some(80));
+        assert!(icmp.to_string().contains("Quoted: 10.0.0.1:54321 -> 93.184.216.34:80 (TCP)"));
+
+        let packet = DissectedPacket::from_ethernet(&data[..50]);
+        assert_eq!(packet.icmp().unwrap().checksum_valid(), None);
+        assert!(packet.icmp().unwrap().quote().is_none());
+    }
+
+    #[test]
+    fn test_icmpv6_neighbor_solicitation() {
+        let mut data = ethernet([0x86, 0xDD]);
+        data.extend_from_slice(&[0x60, 0x00, 0x00, 0x00, 0x00, 0x20, 0x3A, 0xFF]);
+        data.extend_from_slice(&[0xFE, 0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x01]);
+        data.extend_from_slice(&[0xFF, 0x02, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x01, 0xFF, 0, 0, 0x02]);
+        data.extend_from_slice(&[0x87, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]);
+        data.extend_from_slice(&[0xFE, 0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x02]);
+        data.extend_from_slice(&[0x01, 0x01, 0xB1, 0xB1, 0xB1, 0xB1, 0xB1, 0xB1]);
+        let src = Ipv6Addr::from(<[u8; 16]>::try_from(&data[22..38]).unwrap());
+        let dst = Ipv6Addr::from(<[u8; 16]>::try_from(&data[38..54]).unwrap());
+        let check = icmpv6_checksum(src, dst, &data[54..]);
+        data[56..58].copy_from_slice(&check.to_be_bytes());
+
+        let packet = DissectedPacket::from_ethernet(&data);
+        assert!(packet.error().is_none());
+        assert_eq!(packet.layer(Layer::ICMPv6).unwrap().range(), 54..86);
+        let icmp = packet.icmpv6().unwrap();
+        assert_eq!(icmp.checksum_valid(), Some(true));
+        assert!(icmp.is_ndp());
+        assert_eq!(icmp.link_layer_address().unwrap().octets(), [0xB1; 6]);
+        assert!(icmp.to_string().starts_with("Type: 135 (Neighbor Solicitation)\nCode: 0\n"));
+        assert!(icmp.to_string().ends_with("Target: fe80::2\nSource Link-Layer Address: B1:B1:B1:B1:B1:B1"));
+
+        data[85] = 0xB2;
+        let packet = DissectedPacket::from_ethernet(&data);
+        assert_eq!(packet.icmpv6().unwrap().checksum_valid(), Some(false));
+        data[79] = 0;
+        let packet = DissectedPacket::from_ethernet(&data);
+        assert_eq!(packet.error(), Some(&Error::Malformed(Layer::ICMPv6, 78, "option length out of bounds")));
+    }
+
+    #[test]
+    fn test_non_first_fragment() {
+        let mut data = ethernet([0x08, 0x00]);
+        data.extend_from_slice(&[
+            0x45, 0x00, 0x00, 0x18, 0x00, 0x01, 0x00, 0xB9,
+            0x40, 0x11, 0x00, 0x00, 0x0A, 0x00, 0x00, 0x01,
+            0x0A, 0x00, 0x00, 0x02, 0x01, 0x02, 0x03, 0x04,
+        ]);
+        let packet = DissectedPacket::from_ethernet(&data);
+        assert_eq!(packet.layers().len(), 2);
+        assert_eq!(packet.payload(), &[0x01, 0x02, 0x03, 0x04]);
+    }
+
+    #[test]
+    fn test_truncated() {
+        let data = ipv4_tcp_with_options();
+        for (len, layers) in [(0, 1), (10, 1), (14, 2), (30, 2), (36, 2), (38, 3), (50, 3), (69, 3)] {
+            let packet = DissectedPacket::from_ethernet(&data[..len]);
+            assert!(packet.error().is_none());
+            assert!(packet.is_truncated());
+            assert_eq!(packet.layers().len(), layers);
+            assert!(packet.layers().last().unwrap().range().end <= len);
+            assert!(packet.payload().is_empty());
+        }
+        let packet = DissectedPacket::from_ethernet(&data[..71]);
+        assert!(!packet.is_truncated());
+        assert_eq!(packet.payload(), b"G");
+    }
+
+    #[test]
+    fn test_malformed_ihl() {
+        let mut data = ethernet([0x08, 0x00]);
+        data.extend_from_slice(&[
+            0x44, 0x00, 0x00, 0x14, 0x00, 0x01, 0x00, 0x00,
+            0x40, 0x06, 0x00, 0x00, 0x0A, 0x00, 0x00, 0x01,
+            0x0A, 0x00, 0x00, 0x02,
+        ]);
+        let packet = DissectedPacket::from_ethernet(&data);
+        assert_eq!(packet.layers().len(), 2);
+        assert_eq!(packet.error(), Some(&Error::Malformed(Layer::IPv4, 14, "header length below 20 bytes")));
+    }
+}
@@ -0,0 +1,115 @@
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layer {
+    Ethernet,
+    Mac,
+    Loopback,
+    SLL,
+    PPP,
+    Radiotap,
+    IEEE80211,
+    CAN,
+    USB,
+    LLC,
+    STP,
+    LLDP,
+    CDP,
+    ARP,
+    IPv4,
+    IPv6,
+    TCP,
+    UDP,
+    ICMP,
+    ICMPv6,
+    IPX,
+    SPX,
+    IPXRIP,
+    IPXSAP,
+}
+
+impl fmt::Display for Layer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Ethernet => write!(f, "Ethernet"),
+            Self::Mac => write!(f, "MAC address"),
+            Self::Loopback => write!(f, "Loopback"),
+            Self::SLL => write!(f, "Linux cooked capture"),
+            Self::PPP => write!(f, "PPP"),
+            Self::Radiotap => write!(f, "Radiotap"),
+            Self::IEEE80211 => write!(f, "802.11"),
+            Self::CAN => write!(f, "CAN"),
+            Self::USB => write!(f, "USB"),
+            Self::LLC => write!(f, "LLC"),
+            Self::STP => write!(f, "STP"),
+            Self::LLDP => write!(f, "LLDP"),
+            Self::CDP => write!(f, "CDP"),
+            Self::ARP => write!(f, "ARP"),
+            Self::IPv4 => write!(f, "IPv4"),
+            Self::IPv6 => write!(f, "IPv6"),
+            Self::TCP => write!(f, "TCP"),
+            Self::UDP => write!(f, "UDP"),
+            Self::ICMP => write!(f, "ICMP"),
+            Self::ICMPv6 => write!(f, "ICMPv6"),
+            Self::IPX => write!(f, "IPX"),
+            Self::SPX => write!(f, "SPX"),
+            Self::IPXRIP => write!(f, "IPX RIP"),
+            Self::IPXSAP => write!(f, "IPX SAP"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    Byte(Layer, byte::Error),
+    HeaderComplete(Layer),
+    InvalidPosition(Layer, usize),
+    Truncated(Layer, usize),
+    Malformed(Layer, usize, &'static str),
+    Reassembly(Layer, &'static str),
+}
+
+impl Error {
+
+    pub fn layer(&self) -> Layer {
+        match self {
+            Self::Byte(l, _) => *l,
+            Self::HeaderComplete(l) => *l,
+            Self::InvalidPosition(l, _) => *l,
+            Self::Truncated(l, _) => *l,
+            Self::Malformed(l, _, _) => *l,
+            Self::Reassembly(l, _) => *l,
+        }
+    }
+
+    /// Moves the offset of an error from a parser that starts at `offset` into the frame.
+    pub fn at(self, offset: usize) -> Self {
+        match self {
+            Self::Truncated(l, o) => Self::Truncated(l, offset + o),
+            Self::Malformed(l, o, r) => Self::Malformed(l, offset + o, r),
+            e => e,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Byte(l, e) => write!(f, "{} header: {}", l, e),
+            Self::HeaderComplete(l) => write!(f, "{} header already complete", l),
+            Self::InvalidPosition(l, p) => write!(f, "{}: invalid position {}", l, p),
+            Self::Truncated(l, o) => write!(f, "{} header truncated at offset {}", l, o),
+            Self::Malformed(l, o, r) => write!(f, "Malformed {} header at offset {}: {}", l, o, r),
+            Self::Reassembly(l, r) => write!(f, "{} reassembly failed: {}", l, r),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Byte(_, e) => Some(e),
+            _ => None,
+        }
+    }
+}
@@ -1,7 +1,9 @@
 use std::fmt;
 use byte::bytes_to_u16;
 
-#[derive(Clone)]
+use crate::error::{Error, Layer};
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
 pub struct MacAddress {
     bytes: [u8;6]
 }
@@ -16,12 +18,24 @@ impl MacAddress {
         Self{ bytes: [0;6]  }
     }
 
-    pub fn set_octet(&mut self, b: u8, idx: usize) {
+    pub fn octets(&self) -> [u8;6] {
+        self.bytes
+    }
+
+    pub fn is_broadcast(&self) -> bool {
+        self.bytes == [0xFF;6]
+    }
+
+    pub fn is_multicast(&self) -> bool {
+        self.bytes[0] & 0x01 != 0
+    }
+
+    pub fn set_octet(&mut self, b: u8, idx: usize) -> Result<(), Error> {
         if idx > 5 {
-            println!("Invalid Mac Address octet position {}", idx)
-        } else {
-            self.bytes[idx] = b
+            return Err(Error::InvalidPosition(Layer::Mac, idx));
         }
+        self.bytes[idx] = b;
+        Ok(())
     }
 }
 
@@ -45,7 +59,9 @@ pub enum PacketType {
     IPv6,
     ARP,
     IPX,
+    LLDP,
     LENGTH(u16),
+    VLAN(u16),
     UNKNWON
 }
 
@@ -56,7 +72,9 @@ impl Clone for PacketType {
             Self::IPv6 => Self::IPv6,
             Self::ARP => Self::ARP,
             Self::IPX => Self::IPX,
+            Self::LLDP => Self::LLDP,
             Self::LENGTH(b) => Self::LENGTH(*b),
+            Self::VLAN(b) => Self::VLAN(*b),
             Self::UNKNWON => Self::UNKNWON,
         }
     }
@@ -73,6 +91,8 @@ impl PacketType {
             0x0806 => PacketType::ARP,
             0x8137 => PacketType::IPX,
             0x86dd => PacketType::IPv6,
+            0x88cc => PacketType::LLDP,
+            0x8100 | 0x88a8 | 0x9100 => PacketType::VLAN(bytes),
             _ => PacketType::UNKNWON,
         }
     }
@@ -85,16 +105,71 @@ impl fmt::Display for PacketType {
             PacketType::ARP => write!(f, "ARP"),
             PacketType::IPX => write!(f, "IPX"),
             PacketType::IPv6 => write!(f, "IPv6"),
+            PacketType::LLDP => write!(f, "LLDP"),
             PacketType::LENGTH(len) => write!(f, "IEEE 802.3 ({} Bytes)", len),
+            PacketType::VLAN(tpid) => write!(f, "{} ({:#06x})", VlanTag::tpid_name(*tpid), tpid),
             PacketType::UNKNWON => write!(f, "Unknown"),
         }
     }
 }
 
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct VlanTag {
+    tpid: u16,
+    tci: u16,
+}
+
+impl VlanTag {
+
+    pub fn new(tpid: u16, tci: u16) -> Self {
+        Self { tpid, tci }
+    }
+
+    pub fn tpid_name(tpid: u16) -> &'static str {
+        match tpid {
+            0x8100 => "802.1Q Virtual LAN",
+            0x88a8 => "802.1ad Service VLAN",
+            0x9100 => "QinQ Service VLAN",
+            _ => "Unknown VLAN",
+        }
+    }
+
+    pub fn tpid(&self) -> u16 {
+        self.tpid
+    }
+
+    pub fn pcp(&self) -> u8 {
+        (self.tci >> 13) as u8
+    }
+
+    pub fn dei(&self) -> bool {
+        self.tci & 0x1000 != 0
+    }
+
+    pub fn vid(&self) -> u16 {
+        self.tci & 0x0FFF
+    }
+}
+
+impl fmt::Display for VlanTag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: PCP {}, DEI {}, VID {}",
+            Self::tpid_name(self.tpid),
+            self.pcp(),
+            self.dei() as u8,
+            self.vid(),
+        )
+    }
+}
+
+#[derive(Clone)]
 pub struct EthernetFrame {
     dest: MacAddress,
     src: MacAddress,
     p_type: PacketType,
+    tags: Vec<VlanTag>,
 }
 
 impl EthernetFrame {
@@ -103,21 +178,49 @@ impl EthernetFrame {
         Self{
             dest,
             src,
-            p_type
+            p_type,
+            tags: vec![],
         }
     }
 
+    pub fn set_vlan_tags(&mut self, tags: Vec<VlanTag>) {
+        self.tags = tags;
+    }
+
+    /// Tags in wire order, the outermost (service) tag first.
+    pub fn vlan_tags(&self) -> &[VlanTag] {
+        &self.tags
+    }
+
+    pub fn vlan_id(&self) -> Option<u16> {
+        self.tags.last().map(|t| t.vid())
+    }
+
+    pub fn header_len(&self) -> usize {
+        14 + 4 * self.tags.len()
+    }
+
     pub fn is_802_3(&self) -> bool {
         match self.p_type {
             PacketType::LENGTH(_) => true,
             PacketType::IPv4 => false,
             PacketType::IPv6 => false,
             PacketType::IPX => false,
+            PacketType::LLDP => false,
+            PacketType::VLAN(_) => false,
             PacketType::UNKNWON => false,
             PacketType::ARP => false,
         }
     }
 
+    pub fn dest_mac(&self) -> &MacAddress {
+        &self.dest
+    }
+
+    pub fn src_mac(&self) -> &MacAddress {
+        &self.src
+    }
+
     pub fn packet_type(&self) -> PacketType {
         self.p_type.clone()
     }
@@ -128,7 +231,9 @@ impl EthernetFrame {
             PacketType::IPv6 => false,
             PacketType::IPX => false,
             PacketType::ARP => true,
+            PacketType::LLDP => false,
             PacketType::LENGTH(_) => false,
+            PacketType::VLAN(_) => false,
             PacketType::UNKNWON => false,
 
         }
@@ -137,36 +242,54 @@ impl EthernetFrame {
 
 impl fmt::Display for EthernetFrame {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "Destination: {}\nSource: {}\nType: {}\n", self.dest, self.src, self.p_type)
+        write!(f, "Destination: {}\nSource: {}\n", self.dest, self.src)?;
+        for tag in self.tags.iter() {
+            writeln!(f, "{}", tag)?;
+        }
+        writeln!(f, "Type: {}", self.p_type)
     }
 }
 
+#[allow(clippy::upper_case_acronyms)]
 enum ParserState {
     DEST,
     SRC,
     T,
+    TCI,
     END,
 }
 
 impl ParserState {
     
-    fn step(&self, pos: usize) -> Self {
+    // `hlen` grows by four bytes for every VLAN tag seen so far.
+    fn step(&self, pos: usize, hlen: usize) -> Self {
         if pos < 6 {
             ParserState::DEST
         } else if pos < 12 {
             ParserState::SRC
-        } else if pos <= 13 {
+        } else if pos >= hlen {
+            ParserState::END
+        } else if (pos - 12) % 4 < 2 {
             ParserState::T
         } else {
-            ParserState::END
+            ParserState::TCI
         }
     } 
 }
 
+impl Default for EthernetFrameParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 pub struct EthernetFrameParser {
     dest: MacAddress,
     src: MacAddress,
     p_type: [u8;2],
+    tci: [u8;2],
+    tags: Vec<VlanTag>,
+    hlen: usize,
     curr_pos: usize,
     state: ParserState,
 }
@@ -178,50 +301,78 @@ impl EthernetFrameParser {
             dest: MacAddress::empty(),
             src: MacAddress::empty(),
             p_type: [0;2],
+            tci: [0;2],
+            tags: vec![],
+            hlen: 14,
             curr_pos: 0,
             state: ParserState::DEST,
         }
     }
 
-    fn put_d_byte(&mut self, b: u8) {
-        self.dest.set_octet(b, self.curr_pos);
+    fn put_d_byte(&mut self, b: u8) -> Result<(), Error> {
+        self.dest.set_octet(b, self.curr_pos)?;
         self.curr_pos += 1;
+        Ok(())
     }
 
-    fn put_s_byte(&mut self, b: u8) {
+    fn put_s_byte(&mut self, b: u8) -> Result<(), Error> {
         let tmp = self.curr_pos - 6;
-        self.src.set_octet(b, tmp);
+        self.src.set_octet(b, tmp)?;
         self.curr_pos += 1;
+        Ok(())
     }
 
-    fn put_t_byte(&mut self, b: u8) {
-        self.p_type[self.curr_pos - 12] = b;
+    fn put_t_byte(&mut self, b: u8) -> Result<(), Error> {
+        let idx = (self.curr_pos - 12) % 4;
+        self.p_type[idx] = b;
+        if idx == 1 && matches!(self.packet_type(), PacketType::VLAN(_)) {
+            self.hlen += 4;
+        }
         self.curr_pos += 1;
+        Ok(())
+    }
+
+    fn put_tci_byte(&mut self, b: u8) -> Result<(), Error> {
+        let idx = (self.curr_pos - 12) % 4 - 2;
+        self.tci[idx] = b;
+        if idx == 1 {
+            let tpid = bytes_to_u16(self.p_type[0], self.p_type[1], false);
+            let tci = bytes_to_u16(self.tci[0], self.tci[1], false);
+            self.tags.push(VlanTag::new(tpid, tci));
+        }
+        self.curr_pos += 1;
+        Ok(())
+    }
+
+    /// Bytes the header needs, including every VLAN tag announced so far.
+    pub fn header_len(&self) -> usize {
+        self.hlen
+    }
+
+    fn packet_type(&self) -> PacketType {
+        PacketType::new(bytes_to_u16(self.p_type[0], self.p_type[1], false))
     }
 
-    pub fn put_byte(&mut self, byte: u8) {
-        self.state = self.state.step(self.curr_pos);
+    pub fn put_byte(&mut self, byte: u8) -> Result<(), Error> {
+        self.state = self.state.step(self.curr_pos, self.hlen);
 
         match self.state {
            ParserState::DEST => self.put_d_byte(byte),
            ParserState::SRC => self.put_s_byte(byte),
            ParserState::T => self.put_t_byte(byte),
-           ParserState::END => println!("Parser is done.")
+           ParserState::TCI => self.put_tci_byte(byte),
+           ParserState::END => Err(Error::HeaderComplete(Layer::Ethernet)),
         }
     }
 
     pub fn parse(&self) -> EthernetFrame {
-        EthernetFrame::new(
+        let mut frame = EthernetFrame::new(
             self.dest.clone(),
             self.src.clone(),
-            PacketType::new(
-                bytes_to_u16(
-                    self.p_type[0],
-                    self.p_type[1],
-                    false,
-                )
-            )
-        )
+            self.packet_type(),
+        );
+        frame.set_vlan_tags(self.tags.clone());
+        frame
     }
 }
 
@@ -236,15 +387,21 @@ mod tests {
         assert!(matches!(PacketType::new(0x0806), PacketType::ARP));
         assert!(matches!(PacketType::new(0x8137), PacketType::IPX));
         assert!(matches!(PacketType::new(0x86dd), PacketType::IPv6));
+        assert!(matches!(PacketType::new(0x88cc), PacketType::LLDP));
         assert!(matches!(PacketType::new(1500), PacketType::LENGTH(1500)));
         assert!(matches!(PacketType::new(0xAAAA), PacketType::UNKNWON));
+        assert!(matches!(PacketType::new(0x8100), PacketType::VLAN(0x8100)));
+        assert!(matches!(PacketType::new(0x88a8), PacketType::VLAN(0x88a8)));
+        assert!(matches!(PacketType::new(0x9100), PacketType::VLAN(0x9100)));
 
         assert!(matches!(PacketType::IPv4.clone(), PacketType::IPv4));
         assert!(matches!(PacketType::ARP.clone(), PacketType::ARP));
         assert!(matches!(PacketType::IPX.clone(), PacketType::IPX));
         assert!(matches!(PacketType::IPv6.clone(), PacketType::IPv6));
+        assert!(matches!(PacketType::LLDP.clone(), PacketType::LLDP));
         assert!(matches!(PacketType::LENGTH(1500).clone(), PacketType::LENGTH(1500)));
         assert!(matches!(PacketType::UNKNWON.clone(), PacketType::UNKNWON));
+        assert!(matches!(PacketType::VLAN(0x8100).clone(), PacketType::VLAN(0x8100)));
 
     }
 
@@ -252,11 +409,22 @@ mod tests {
     fn test_parser_state_enum() {
 
         let state = ParserState::T;
-        assert!(matches!(state.step(0), ParserState::DEST));
-        assert!(matches!(state.step(7), ParserState::SRC));
-        assert!(matches!(state.step(13), ParserState::T));
-        assert!(matches!(state.step(14), ParserState::END));
+        assert!(matches!(state.step(0, 14), ParserState::DEST));
+        assert!(matches!(state.step(7, 14), ParserState::SRC));
+        assert!(matches!(state.step(13, 14), ParserState::T));
+        assert!(matches!(state.step(14, 14), ParserState::END));
+        assert!(matches!(state.step(14, 18), ParserState::TCI));
+        assert!(matches!(state.step(16, 18), ParserState::T));
+        assert!(matches!(state.step(18, 18), ParserState::END));
+
+    }
 
+    #[test]
+    fn test_mac_set_octet() {
+        let mut mac = MacAddress::empty();
+        assert!(mac.set_octet(0xAB, 5).is_ok());
+        assert_eq!(mac.set_octet(0xAB, 6), Err(Error::InvalidPosition(Layer::Mac, 6)));
+        assert_eq!(mac.to_string(), "00:00:00:00:00:AB".to_string());
     }
 
     #[test]
@@ -270,14 +438,49 @@ mod tests {
         ];
 
         for b in bytes {
-            parser.put_byte(b);
+            parser.put_byte(b).unwrap();
         }
+        assert_eq!(parser.put_byte(0), Err(Error::HeaderComplete(Layer::Ethernet)));
         let frame = parser.parse();
         assert!(matches!(frame.packet_type(), PacketType::IPv4));
         assert_eq!(frame.dest.to_string(), "A1:A1:A1:A1:A1:A1".to_string());
-        assert_eq!(frame.is_arp(), false);
-        assert_eq!(frame.is_802_3(), false);
+        assert!(!frame.is_arp());
+        assert!(!frame.is_802_3());
 
     }
+
+    #[test]
+    fn test_parser_vlan_stack() {
+        let mut parser = EthernetFrameParser::new();
+        let bytes: [u8;22] = [
+            0xA1, 0xA1, 0xA1, 0xA1,
+            0xA1, 0xA1, 0xB1, 0xB1,
+            0xB1, 0xB1, 0xB1, 0xB1,
+            0x88, 0xA8, 0x00, 0x0A,
+            0x81, 0x00, 0xB0, 0x64,
+            0x86, 0xDD,
+        ];
+
+        for b in bytes {
+            assert!(parser.header_len() > 0);
+            parser.put_byte(b).unwrap();
+        }
+        assert_eq!(parser.header_len(), 22);
+        assert_eq!(parser.put_byte(0), Err(Error::HeaderComplete(Layer::Ethernet)));
+        let frame = parser.parse();
+        assert!(matches!(frame.packet_type(), PacketType::IPv6));
+        assert_eq!(frame.header_len(), 22);
+        assert_eq!(frame.vlan_tags().len(), 2);
+        assert_eq!(frame.vlan_tags()[0], VlanTag::new(0x88a8, 10));
+        let inner = frame.vlan_tags()[1];
+        assert_eq!((inner.tpid(), inner.pcp(), inner.dei(), inner.vid()), (0x8100, 5, true, 100));
+        assert_eq!(frame.vlan_id(), Some(100));
+        assert_eq!(
+            frame.to_string(),
+            "Destination: A1:A1:A1:A1:A1:A1\nSource: B1:B1:B1:B1:B1:B1\n\
+             802.1ad Service VLAN: PCP 0, DEI 0, VID 10\n\
+             802.1Q Virtual LAN: PCP 5, DEI 1, VID 100\nType: IPv6\n"
+        );
+    }
 }
 
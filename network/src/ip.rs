@@ -1,21 +1,134 @@
 use std::fmt::Display;
 use std::net::Ipv4Addr;
 
-use byte::ByteParser;
+use byte::{bytes_to_u16, bytes_to_u32, ByteParser};
+use crate::error::{Error, Layer};
+use crate::tlv::OptionFormat;
 use crate::transport::protocols::TransportProtocol;
 
+pub const FLAG_RESERVED: u8 = 0b100;
+pub const FLAG_DF: u8 = 0b010;
+pub const FLAG_MF: u8 = 0b001;
+
+const OPTION_FORMAT: OptionFormat = OptionFormat {
+    layer: Layer::IPv4,
+    single: &[0, 1],
+    end: Some(0),
+    inclusive_len: true,
+};
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum IPv4Option {
+    EOL,
+    NOP,
+    RR(u8, Vec<Ipv4Addr>),
+    TS(u8, u8, u8, Vec<(Option<Ipv4Addr>, u32)>),
+    LSRR(u8, Vec<Ipv4Addr>),
+    SSRR(u8, Vec<Ipv4Addr>),
+    RA(u16),
+    SEC(u8, Vec<u8>),
+    UNKNOWN(u8, Vec<u8>),
+}
+
+impl IPv4Option {
+
+    pub fn kind(&self) -> u8 {
+        match self {
+            Self::EOL => 0,
+            Self::NOP => 1,
+            Self::RR(_, _) => 7,
+            Self::TS(_, _, _, _) => 68,
+            Self::SEC(_, _) => 130,
+            Self::LSRR(_, _) => 131,
+            Self::SSRR(_, _) => 137,
+            Self::RA(_) => 148,
+            Self::UNKNOWN(k, _) => *k,
+        }
+    }
+
+    pub fn parse_all(bytes: &[u8], base: usize) -> Result<Vec<Self>, Error> {
+        OPTION_FORMAT.parse(bytes, base, |kind, value, offset| match (kind, value) {
+            (0, _) => Ok(Self::EOL),
+            (1, _) => Ok(Self::NOP),
+            (_, value) => Self::parse(kind, value.unwrap_or_default(), offset),
+        })
+    }
+
+    fn route(value: &[u8], offset: usize) -> Result<(u8, Vec<Ipv4Addr>), Error> {
+        if value.is_empty() || !(value.len() - 1).is_multiple_of(4) {
+            return Err(Error::Malformed(Layer::IPv4, offset, "invalid route option length"));
+        }
+        let routes = value[1..].chunks(4).map(|a| Ipv4Addr::new(a[0], a[1], a[2], a[3])).collect();
+        Ok((value[0], routes))
+    }
+
+    fn timestamp(value: &[u8], offset: usize) -> Result<Self, Error> {
+        if value.len() < 2 {
+            return Err(Error::Malformed(Layer::IPv4, offset, "invalid timestamp option length"));
+        }
+        let (pointer, overflow, flag) = (value[0], value[1] >> 4, value[1] & 0x0F);
+        let entry_len = if flag == 0 { 4 } else { 8 };
+        if !(value.len() - 2).is_multiple_of(entry_len) {
+            return Err(Error::Malformed(Layer::IPv4, offset, "invalid timestamp option length"));
+        }
+        let entries = value[2..].chunks(entry_len).map(|e| match flag {
+            0 => (None, bytes_to_u32(e[0], e[1], e[2], e[3], false)),
+            _ => (Some(Ipv4Addr::new(e[0], e[1], e[2], e[3])), bytes_to_u32(e[4], e[5], e[6], e[7], false)),
+        }).collect();
+        Ok(Self::TS(pointer, overflow, flag, entries))
+    }
+
+    fn parse(kind: u8, value: &[u8], offset: usize) -> Result<Self, Error> {
+        match kind {
+            7 => Self::route(value, offset).map(|(p, r)| Self::RR(p, r)),
+            68 => Self::timestamp(value, offset),
+            130 if !value.is_empty() => Ok(Self::SEC(value[0], value[1..].to_vec())),
+            131 => Self::route(value, offset).map(|(p, r)| Self::LSRR(p, r)),
+            137 => Self::route(value, offset).map(|(p, r)| Self::SSRR(p, r)),
+            148 if value.len() == 2 => Ok(Self::RA(bytes_to_u16(value[0], value[1], false))),
+            130|148 => Err(Error::Malformed(Layer::IPv4, offset, "invalid option length")),
+            _ => Ok(Self::UNKNOWN(kind, value.to_vec())),
+        }
+    }
+}
+
+impl Display for IPv4Option {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let routes = |r: &Vec<Ipv4Addr>| r.iter().map(|a| a.to_string()).collect::<Vec<String>>().join(" ");
+        match self {
+            Self::EOL => write!(f, "EOL"),
+            Self::NOP => write!(f, "NOP"),
+            Self::RR(_, r) => write!(f, "Record Route: {}", routes(r)),
+            Self::TS(_, overflow, flag, entries) => {
+                let entries: Vec<String> = entries.iter().map(|(a, ts)| match a {
+                    Some(a) => format!("{}@{}", a, ts),
+                    None => ts.to_string(),
+                }).collect();
+                write!(f, "Timestamp (flag {}, overflow {}): {}", flag, overflow, entries.join(" "))
+            },
+            Self::LSRR(_, r) => write!(f, "Loose Source Route: {}", routes(r)),
+            Self::SSRR(_, r) => write!(f, "Strict Source Route: {}", routes(r)),
+            Self::RA(b) => write!(f, "Router Alert: {}", b),
+            Self::SEC(level, _) => write!(f, "Security: level {:#04x}", level),
+            Self::UNKNOWN(kind, value) => write!(f, "Unknown kind={} len={}", kind, value.len() + 2),
+        }
+    }
+}
+
 pub enum IPv4HeaderField {
     V(u8),
     IHL(u8),
     TOS(u8),
     LEN(u16),
     ID(u16),
-    FF(u16),
+    FLAGS(u8),
+    FRAG(u16),
     TTL(u8),
     PRT(TransportProtocol),
     CHECK(u16),
     SRC(Ipv4Addr),
     DST(Ipv4Addr),
+    OPTS(Vec<IPv4Option>),
     UNSET,
 }
 
@@ -28,12 +141,14 @@ impl Clone for IPv4HeaderField {
             IPv4HeaderField::TOS(b) => IPv4HeaderField::TOS(*b),
             IPv4HeaderField::LEN(b) => IPv4HeaderField::LEN(*b),
             IPv4HeaderField::ID(b) => IPv4HeaderField::ID(*b),
-            IPv4HeaderField::FF(b) => IPv4HeaderField::FF(*b),
+            IPv4HeaderField::FLAGS(b) => IPv4HeaderField::FLAGS(*b),
+            IPv4HeaderField::FRAG(b) => IPv4HeaderField::FRAG(*b),
             IPv4HeaderField::TTL(b) => IPv4HeaderField::TTL(*b),
-            IPv4HeaderField::PRT(b) => IPv4HeaderField::PRT(b.clone()),
+            IPv4HeaderField::PRT(b) => IPv4HeaderField::PRT(*b),
             IPv4HeaderField::CHECK(b) => IPv4HeaderField::CHECK(*b),
             IPv4HeaderField::SRC(b) => IPv4HeaderField::SRC(*b),
             IPv4HeaderField::DST(b) =>IPv4HeaderField::DST(*b),
+            IPv4HeaderField::OPTS(b) => IPv4HeaderField::OPTS(b.clone()),
             IPv4HeaderField::UNSET => IPv4HeaderField::UNSET,
         }
     }
@@ -47,12 +162,29 @@ impl Display for IPv4HeaderField {
             IPv4HeaderField::TOS(b) => write!(f, "ToS: {:#04b}", b),
             IPv4HeaderField::LEN(b) => write!(f, "Packet Len: {}", b),
             IPv4HeaderField::ID(b) => write!(f, "ID: {:#06x}", b),
-            IPv4HeaderField::FF(b) => write!(f, "FF: {:#018b}", b),
+            IPv4HeaderField::FLAGS(b) => {
+                let mut names = vec![];
+                if b & FLAG_RESERVED != 0 {
+                    names.push("RSV");
+                }
+                if b & FLAG_DF != 0 {
+                    names.push("DF");
+                }
+                if b & FLAG_MF != 0 {
+                    names.push("MF");
+                }
+                write!(f, "Flags: {:#05b} [{}]", b, names.join(", "))
+            },
+            IPv4HeaderField::FRAG(b) => write!(f, "Fragment Offset: {}", b),
             IPv4HeaderField::TTL(b) => write!(f, "TTL: {}", b),
             IPv4HeaderField::PRT(b) => write!(f, "Protocol: {}", b.to_str()),
             IPv4HeaderField::CHECK(b) => write!(f, "Checksum: {:#018b}", b),
             IPv4HeaderField::SRC(b) => write!(f, "Source: {}", b),
             IPv4HeaderField::DST(b) => write!(f, "Destination: {}", b),
+            IPv4HeaderField::OPTS(b) => {
+                let opts: Vec<String> = b.iter().map(|o| o.to_string()).collect();
+                write!(f, "Options: {}", opts.join(", "))
+            },
             IPv4HeaderField::UNSET => write!(f, "UNSET"),
         }
     }
@@ -65,12 +197,14 @@ pub struct IPv4Header {
     tos: IPv4HeaderField,
     length: IPv4HeaderField,
     id: IPv4HeaderField,
-    ff: IPv4HeaderField,
+    flags: IPv4HeaderField,
+    frag: IPv4HeaderField,
     ttl: IPv4HeaderField,
     proto: IPv4HeaderField,
     checksum: IPv4HeaderField,
     src: IPv4HeaderField,
-    dst: IPv4HeaderField
+    dst: IPv4HeaderField,
+    options: IPv4HeaderField,
 }
 
 impl IPv4Header {
@@ -81,12 +215,14 @@ impl IPv4Header {
             tos: IPv4HeaderField::UNSET,
             length: IPv4HeaderField::UNSET,
             id: IPv4HeaderField::UNSET,
-            ff: IPv4HeaderField::UNSET,
+            flags: IPv4HeaderField::UNSET,
+            frag: IPv4HeaderField::UNSET,
             ttl: IPv4HeaderField::UNSET,
             proto: IPv4HeaderField::UNSET,
             checksum: IPv4HeaderField::UNSET,
             src: IPv4HeaderField::UNSET,
             dst: IPv4HeaderField::UNSET, 
+            options: IPv4HeaderField::UNSET,
         }
     }
 
@@ -97,15 +233,128 @@ impl IPv4Header {
             IPv4HeaderField::TOS(_) => self.tos = field,
             IPv4HeaderField::LEN(_) => self.length = field,
             IPv4HeaderField::ID(_) => self.id = field,
-            IPv4HeaderField::FF(_) => self.ff = field,
+            IPv4HeaderField::FLAGS(_) => self.flags = field,
+            IPv4HeaderField::FRAG(_) => self.frag = field,
             IPv4HeaderField::TTL(_) => self.ttl = field,
             IPv4HeaderField::PRT(_) => self.proto = field,
             IPv4HeaderField::CHECK(_) => self.checksum = field,
             IPv4HeaderField::SRC(_) => self.src = field,
             IPv4HeaderField::DST(_) => self.dst = field,
+            IPv4HeaderField::OPTS(_) => self.options = field,
             IPv4HeaderField::UNSET => (),
         }
     }
+
+    pub fn version(&self) -> Option<u8> {
+        match self.version {
+            IPv4HeaderField::V(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn ihl(&self) -> Option<u8> {
+        match self.ihl {
+            IPv4HeaderField::IHL(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn header_len(&self) -> Option<usize> {
+        self.ihl().map(|b| b as usize * 4)
+    }
+
+    pub fn tos(&self) -> Option<u8> {
+        match self.tos {
+            IPv4HeaderField::TOS(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn total_len(&self) -> Option<u16> {
+        match self.length {
+            IPv4HeaderField::LEN(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn id(&self) -> Option<u16> {
+        match self.id {
+            IPv4HeaderField::ID(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn flags(&self) -> Option<u8> {
+        match self.flags {
+            IPv4HeaderField::FLAGS(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn dont_fragment(&self) -> Option<bool> {
+        self.flags().map(|b| b & FLAG_DF != 0)
+    }
+
+    pub fn more_fragments(&self) -> Option<bool> {
+        self.flags().map(|b| b & FLAG_MF != 0)
+    }
+
+    pub fn fragment_offset(&self) -> Option<u16> {
+        match self.frag {
+            IPv4HeaderField::FRAG(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn fragment_byte_offset(&self) -> Option<usize> {
+        self.fragment_offset().map(|b| b as usize * 8)
+    }
+
+    pub fn is_fragment(&self) -> Option<bool> {
+        Some(self.more_fragments()? || self.fragment_offset()? != 0)
+    }
+
+    pub fn ttl(&self) -> Option<u8> {
+        match self.ttl {
+            IPv4HeaderField::TTL(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn protocol(&self) -> Option<TransportProtocol> {
+        match &self.proto {
+            IPv4HeaderField::PRT(p) => Some(*p),
+            _ => None,
+        }
+    }
+
+    pub fn checksum(&self) -> Option<u16> {
+        match self.checksum {
+            IPv4HeaderField::CHECK(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn src(&self) -> Option<Ipv4Addr> {
+        match self.src {
+            IPv4HeaderField::SRC(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn dst(&self) -> Option<Ipv4Addr> {
+        match self.dst {
+            IPv4HeaderField::DST(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn options(&self) -> Option<&[IPv4Option]> {
+        match &self.options {
+            IPv4HeaderField::OPTS(b) => Some(b),
+            _ => None,
+        }
+    }
 }
 
 impl Display for IPv4Header {
@@ -113,19 +362,24 @@ impl Display for IPv4Header {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n",
+            "{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n",
             self.version,
             self.ihl,
             self.tos,
             self.length,
             self.id,
-            self.ff,
+            self.flags,
+            self.frag,
             self.ttl,
             self.proto,
             self.checksum,
             self.src,
             self.dst,
-        )
+        )?;
+        match self.options {
+            IPv4HeaderField::OPTS(_) => writeln!(f, "{}", self.options),
+            _ => Ok(()),
+        }
     }
 }
 
@@ -133,6 +387,9 @@ pub struct IPv4HeaderParser {
     parser: ByteParser,
     curr_state: IPv4HeaderField,
     header: IPv4Header,
+    opts_len: usize,
+    opts: Vec<u8>,
+    offset: usize,
 }
 
 impl IPv4HeaderParser {
@@ -142,32 +399,48 @@ impl IPv4HeaderParser {
             parser: ByteParser::new(b_swap),
             curr_state: IPv4HeaderField::V(0),
             header: IPv4Header::empty(),
+            opts_len: 0,
+            opts: vec![],
+            offset: 0,
         }
     }
 
-    fn set_byte(&mut self, b: u8) {
+    pub fn with_offset(mut self, offset: usize) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    fn set_byte(&mut self, b: u8) -> Result<(), Error> {
         match self.curr_state {
-            IPv4HeaderField::IHL(_) => println!(),
+            IPv4HeaderField::IHL(_) => Ok(()),
             IPv4HeaderField::V(_)|
                 IPv4HeaderField::TOS(_)|
                 IPv4HeaderField::TTL(_)|
                 IPv4HeaderField::PRT(_)
-                => self.parser.set_word(b),
+                => {
+                    self.parser.set_word(b);
+                    Ok(())
+                },
             IPv4HeaderField::LEN(_)|
                 IPv4HeaderField::ID(_)|
                 IPv4HeaderField::CHECK(_)|
-                IPv4HeaderField::FF(_)
-                => self.parser.set_d_byte(b),
+                IPv4HeaderField::FLAGS(_)|
+                IPv4HeaderField::FRAG(_)
+                => self.parser.set_d_byte(b).map_err(|e| Error::Byte(Layer::IPv4, e)),
             IPv4HeaderField::DST(_)| 
                 IPv4HeaderField::SRC(_)
-                => self.parser.set_q_byte(b),
+                => self.parser.set_q_byte(b).map_err(|e| Error::Byte(Layer::IPv4, e)),
+            IPv4HeaderField::OPTS(_) => {
+                self.opts.push(b);
+                Ok(())
+            },
             IPv4HeaderField::UNSET 
-                => println!("Cant set unset field with byte {}", b),
+                => Err(Error::HeaderComplete(Layer::IPv4)),
         }
     }
 
-    pub fn step(&mut self, b: u8) {
-        self.set_byte(b);
+    pub fn step(&mut self, b: u8) -> Result<(), Error> {
+        self.set_byte(b)?;
         match self.curr_state {
             IPv4HeaderField::V(_) => self.version(),
             IPv4HeaderField::IHL(_) => (),
@@ -175,13 +448,16 @@ impl IPv4HeaderParser {
             IPv4HeaderField::LEN(_) => self.length(),
             IPv4HeaderField::ID(_) => self.id(),
             IPv4HeaderField::TTL(_) => self.ttl(),
-            IPv4HeaderField::FF(_) => self.ff(),
+            IPv4HeaderField::FLAGS(_) => self.flags(b),
+            IPv4HeaderField::FRAG(_) => self.frag(),
             IPv4HeaderField::PRT(_) => self.proto(),
             IPv4HeaderField::CHECK(_) => self.checksum(),
             IPv4HeaderField::SRC(_) => self.src(),
             IPv4HeaderField::DST(_) => self.dst(),
-            IPv4HeaderField::UNSET => println!("Cant step in unset field with byte {}", b),
+            IPv4HeaderField::OPTS(_) => self.options()?,
+            IPv4HeaderField::UNSET => (),
         }
+        Ok(())
     }
 
     fn version(&mut self) {
@@ -191,6 +467,7 @@ impl IPv4HeaderParser {
         self.header.set_field(
             IPv4HeaderField::IHL(self.parser.word_r_nibble())
         );
+        self.opts_len = (self.parser.word_r_nibble() as usize * 4).saturating_sub(20);
         self.curr_state = IPv4HeaderField::TOS(0);
     }
 
@@ -203,11 +480,9 @@ impl IPv4HeaderParser {
 
     fn length(&mut self) {
         if self.parser.dword_done() {
-            self.parser.toggle_swap();
             self.header.set_field(
                 IPv4HeaderField::LEN(self.parser.dword_as_u16())
             );
-            self.parser.toggle_swap();
             self.parser.reset_dword();
             self.curr_state = IPv4HeaderField::ID(0);
         }
@@ -219,7 +494,7 @@ impl IPv4HeaderParser {
                 IPv4HeaderField::ID(self.parser.dword_as_u16())
             );
             self.parser.reset_dword();
-            self.curr_state = IPv4HeaderField::FF(0);
+            self.curr_state = IPv4HeaderField::FLAGS(0);
         }
     }
 
@@ -230,11 +505,15 @@ impl IPv4HeaderParser {
         self.curr_state = IPv4HeaderField::PRT(TransportProtocol::UNKNOWN(254))
     }
 
-    fn ff(&mut self) {
+    /// The flags are the top three bits of the byte, the rest starts the fragment offset.
+    fn flags(&mut self, b: u8) {
+        self.header.set_field(IPv4HeaderField::FLAGS(b >> 5));
+        self.curr_state = IPv4HeaderField::FRAG(0);
+    }
+
+    fn frag(&mut self) {
         if self.parser.dword_done() {
-            self.header.set_field(
-                IPv4HeaderField::FF(self.parser.dword_as_u16())
-            );
+            self.header.set_field(IPv4HeaderField::FRAG(self.parser.dword_as_u16() & 0x1FFF));
             self.parser.reset_dword();
             self.curr_state = IPv4HeaderField::TTL(0);
         }
@@ -273,11 +552,101 @@ impl IPv4HeaderParser {
                 IPv4HeaderField::DST(self.parser.qword_as_ipv4())
             );
             self.parser.reset_qword();
+            self.curr_state = if self.opts_len > 0 {
+                IPv4HeaderField::OPTS(vec![])
+            } else {
+                IPv4HeaderField::UNSET
+            };
+        }
+    }
+
+    fn options(&mut self) -> Result<(), Error> {
+        if self.opts.len() == self.opts_len {
+            self.header.set_field(IPv4HeaderField::OPTS(IPv4Option::parse_all(&self.opts, self.offset + 20)?));
             self.curr_state = IPv4HeaderField::UNSET;
         }
+        Ok(())
     }
 
     pub fn get_header(&self) -> IPv4Header {
         self.header.clone()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(bytes: &[u8]) -> Result<IPv4Header, Error> {
+        let mut parser = IPv4HeaderParser::new(false);
+        for b in bytes {
+            parser.step(*b)?;
+        }
+        Ok(parser.get_header())
+    }
+
+    fn header(ihl: u8, ff: [u8;2]) -> Vec<u8> {
+        vec![
+            0x40 | ihl, 0x00, 0x00, 0x3C, 0x1C, 0x46, ff[0], ff[1],
+            0x40, 0x11, 0x00, 0x00, 0x0A, 0x00, 0x00, 0x01,
+            0x0A, 0x00, 0x00, 0x02,
+        ]
+    }
+
+    #[test]
+    fn test_flags_and_fragment_offset() {
+        let h = parse(&header(5, [0x40, 0x00])).unwrap();
+        assert_eq!(h.flags(), Some(FLAG_DF));
+        assert_eq!(h.dont_fragment(), Some(true));
+        assert_eq!(h.more_fragments(), Some(false));
+        assert_eq!(h.fragment_offset(), Some(0));
+        assert_eq!(h.is_fragment(), Some(false));
+        assert!(h.to_string().contains("Flags: 0b010 [DF]\nFragment Offset: 0\n"));
+        assert_eq!(h.options(), None);
+
+        let h = parse(&header(5, [0x20, 0xB9])).unwrap();
+        assert_eq!(h.dont_fragment(), Some(false));
+        assert_eq!(h.more_fragments(), Some(true));
+        assert_eq!(h.fragment_offset(), Some(185));
+        assert_eq!(h.fragment_byte_offset(), Some(1480));
+        assert_eq!(h.is_fragment(), Some(true));
+
+        assert_eq!(IPv4Header::empty().is_fragment(), None);
+    }
+
+    #[test]
+    fn test_options() {
+        let mut bytes = header(15, [0x00, 0x00]);
+        bytes.extend_from_slice(&[
+            0x94, 0x04, 0x00, 0x00,
+            0x07, 0x07, 0x04, 0xC0, 0xA8, 0x00, 0x01,
+            0x01,
+            0x83, 0x07, 0x04, 0x0A, 0x00, 0x00, 0x09,
+            0x44, 0x0C, 0x05, 0x01, 0x0A, 0x00, 0x00, 0x01, 0x00, 0x00, 0x01, 0x00,
+            0x82, 0x04, 0xAB, 0xCD,
+            0x89, 0x03, 0x04,
+            0x00, 0x00,
+        ]);
+        assert_eq!(bytes.len(), 60);
+        let h = parse(&bytes).unwrap();
+        assert_eq!(h.options().unwrap(), &[
+            IPv4Option::RA(0),
+            IPv4Option::RR(4, vec![Ipv4Addr::new(192, 168, 0, 1)]),
+            IPv4Option::NOP,
+            IPv4Option::LSRR(4, vec![Ipv4Addr::new(10, 0, 0, 9)]),
+            IPv4Option::TS(5, 0, 1, vec![(Some(Ipv4Addr::new(10, 0, 0, 1)), 256)]),
+            IPv4Option::SEC(0xAB, vec![0xCD]),
+            IPv4Option::SSRR(4, vec![]),
+            IPv4Option::EOL,
+        ]);
+        assert!(h.to_string().contains("Options: Router Alert: 0, Record Route: 192.168.0.1, NOP"));
+        assert!(matches!(parse(&[bytes.clone(), vec![0]].concat()), Err(Error::HeaderComplete(Layer::IPv4))));
+
+        bytes[20..24].copy_from_slice(&[0x94, 0x03, 0x00, 0x00]);
+        assert_eq!(parse(&bytes).err(), Some(Error::Malformed(Layer::IPv4, 20, "invalid option length")));
+        bytes[20..24].copy_from_slice(&[0x07, 0x05, 0x00, 0x00]);
+        assert_eq!(parse(&bytes).err(), Some(Error::Malformed(Layer::IPv4, 20, "invalid route option length")));
+        bytes[20..24].copy_from_slice(&[0x07, 0x40, 0x00, 0x00]);
+        assert_eq!(parse(&bytes).err(), Some(Error::Malformed(Layer::IPv4, 20, "option length out of bounds")));
+    }
+}
@@ -1,5 +1,9 @@
+pub mod error;
 pub mod ethernet_frame;
 pub mod ip;
 pub mod transport;
 pub mod netw;
 pub mod link;
+pub mod dissect;
+pub mod reassembly;
+mod tlv;
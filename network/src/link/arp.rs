@@ -2,6 +2,8 @@ use std::{net::Ipv4Addr, fmt::Display};
 
 use byte::ByteParser;
 
+use crate::error::{Error, Layer};
+
 use super::mac::{MacAddress, MacAddressParser};
 
 pub enum ARPHeaderField {
@@ -64,6 +66,12 @@ pub struct ARPHeader {
     dst_ip: ARPHeaderField,
 }
 
+impl Default for ARPHeader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl ARPHeader {
     
     pub fn new() -> Self {
@@ -80,21 +88,6 @@ impl ARPHeader {
         }
     }
 
-    pub fn to_string(&self) -> String {
-        format!(
-            "{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n",
-            self.mac_type,
-            self.proto_type,
-            self.hardware_addr_s,
-            self.proto_addr_s,
-            self.operation,
-            self.src_mac,
-            self.src_ip,
-            self.dst_mac,
-            self.dst_ip,
-        )
-    }
-
     pub fn set_field(&mut self, field: ARPHeaderField) {
         match field {
             ARPHeaderField::MACT(_) => self.mac_type = field,
@@ -106,7 +99,7 @@ impl ARPHeader {
             ARPHeaderField::SRCIP(_) => self.src_ip = field,
             ARPHeaderField::DSTMAC(_) => self.dst_mac = field,
             ARPHeaderField::DSTIP(_) => self.dst_ip = field,
-            ARPHeaderField::UNSET => println!("Cant set unset ARP Header field"),
+            ARPHeaderField::UNSET => (),
         }
     }
 
@@ -125,11 +118,86 @@ impl ARPHeader {
         }
 
     }
+
+    pub fn hw_type(&self) -> Option<u16> {
+        match self.mac_type {
+            ARPHeaderField::MACT(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn proto_type(&self) -> Option<u16> {
+        match self.proto_type {
+            ARPHeaderField::PROTT(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn hw_addr_size(&self) -> Option<u8> {
+        match self.hardware_addr_s {
+            ARPHeaderField::HWS(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn proto_addr_size(&self) -> Option<u8> {
+        match self.proto_addr_s {
+            ARPHeaderField::PAS(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn operation(&self) -> Option<u16> {
+        match self.operation {
+            ARPHeaderField::OP(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn src_mac(&self) -> Option<&MacAddress> {
+        match &self.src_mac {
+            ARPHeaderField::SRCMAC(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn src_ip(&self) -> Option<Ipv4Addr> {
+        match self.src_ip {
+            ARPHeaderField::SRCIP(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn dst_mac(&self) -> Option<&MacAddress> {
+        match &self.dst_mac {
+            ARPHeaderField::DSTMAC(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn dst_ip(&self) -> Option<Ipv4Addr> {
+        match self.dst_ip {
+            ARPHeaderField::DSTIP(b) => Some(b),
+            _ => None,
+        }
+    }
 }
 
 impl Display for ARPHeader {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.to_string())
+        write!(
+            f,
+            "{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n",
+            self.mac_type,
+            self.proto_type,
+            self.hardware_addr_s,
+            self.proto_addr_s,
+            self.operation,
+            self.src_mac,
+            self.src_ip,
+            self.dst_mac,
+            self.dst_ip,
+        )
     }
 }
 
@@ -151,8 +219,8 @@ impl ARPHeaderParser {
         }
     }
 
-    pub fn parse(&mut self, b: u8) {
-        self.step(b);
+    pub fn parse(&mut self, b: u8) -> Result<(), Error> {
+        self.step(b)?;
         match self.curr_field {
             ARPHeaderField::MACT(_) => self.mac_type(),
             ARPHeaderField::PROTT(_) => self.proto_type(),
@@ -163,26 +231,30 @@ impl ARPHeaderParser {
             ARPHeaderField::SRCIP(_) => self.src_ip(),
             ARPHeaderField::DSTMAC(_) => self.dst_mac(),
             ARPHeaderField::DSTIP(_) => self.dst_ip(),
-            ARPHeaderField::UNSET => println!("Cant parse byte into unset arp header field"),
+            ARPHeaderField::UNSET => (),
         }
+        Ok(())
     }
 
-    fn step(&mut self, b: u8) {
+    fn step(&mut self, b: u8) -> Result<(), Error> {
         match self.curr_field {
             ARPHeaderField::MACT(_)|
                 ARPHeaderField::PROTT(_)|
                 ARPHeaderField::OP(_)
-                => self.b_parser.set_d_byte(b),
+                => self.b_parser.set_d_byte(b).map_err(|e| Error::Byte(Layer::ARP, e)),
             ARPHeaderField::HWS(_)|
                 ARPHeaderField::PAS(_)
-                => self.b_parser.set_word(b),
+                => {
+                    self.b_parser.set_word(b);
+                    Ok(())
+                },
             ARPHeaderField::DSTIP(_)|
                 ARPHeaderField::SRCIP(_)
-                => self.b_parser.set_q_byte(b),
+                => self.b_parser.set_q_byte(b).map_err(|e| Error::Byte(Layer::ARP, e)),
             ARPHeaderField::DSTMAC(_)|
                 ARPHeaderField::SRCMAC(_)
                 => self.m_parser.set_byte(b),
-            ARPHeaderField::UNSET => println!("Cant step into unset ARP header field"),
+            ARPHeaderField::UNSET => Err(Error::HeaderComplete(Layer::ARP)),
         }
     }
 
@@ -314,4 +386,28 @@ mod tests {
             )
         );
     }
+
+    #[test]
+    fn test_typed_accessors() {
+        let mut parser = ARPHeaderParser::new(false);
+        let bytes: [u8;28] = [
+            0x00, 0x01, 0x08, 0x00, 0x06, 0x04, 0x00, 0x01,
+            0xA1, 0xA2, 0xA3, 0xA4, 0xA5, 0xA6, 0xC0, 0xA8, 0x00, 0x01,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xC0, 0xA8, 0x00, 0x02,
+        ];
+        for b in bytes {
+            parser.parse(b).unwrap();
+        }
+        let header = parser.get_header();
+        assert_eq!(header.hw_type(), Some(1));
+        assert_eq!(header.proto_type(), Some(0x0800));
+        assert_eq!(header.hw_addr_size(), Some(6));
+        assert_eq!(header.proto_addr_size(), Some(4));
+        assert_eq!(header.operation(), Some(1));
+        assert_eq!(header.src_mac().unwrap().octets(), [0xA1, 0xA2, 0xA3, 0xA4, 0xA5, 0xA6]);
+        assert_eq!(header.src_ip(), Some(Ipv4Addr::new(192, 168, 0, 1)));
+        assert_eq!(header.dst_mac().unwrap().octets(), [0;6]);
+        assert_eq!(header.dst_ip(), Some(Ipv4Addr::new(192, 168, 0, 2)));
+        assert_eq!(ARPHeader::new().src_ip(), None);
+    }
 }
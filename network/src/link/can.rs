@@ -0,0 +1,121 @@
+use std::fmt::Display;
+
+use byte::bytes_to_u32;
+
+use crate::error::{Error, Layer};
+
+pub const CAN_HEADER_LEN: usize = 8;
+
+const CAN_EFF_FLAG: u32 = 0x8000_0000;
+const CAN_RTR_FLAG: u32 = 0x4000_0000;
+const CAN_ERR_FLAG: u32 = 0x2000_0000;
+const CAN_EFF_MASK: u32 = 0x1FFF_FFFF;
+const CAN_SFF_MASK: u32 = 0x0000_07FF;
+
+const CANFD_BRS: u8 = 0x01;
+const CANFD_ESI: u8 = 0x02;
+const CANFD_FDF: u8 = 0x04;
+
+/// SocketCAN frame header as written for LINKTYPE_CAN_SOCKETCAN, the data follows it.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct CanHeader {
+    can_id: u32,
+    length: u8,
+    fd_flags: u8,
+}
+
+impl CanHeader {
+
+    pub fn parse(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() < CAN_HEADER_LEN {
+            return Err(Error::Truncated(Layer::CAN, bytes.len()));
+        }
+        if bytes[4] > 64 {
+            return Err(Error::Malformed(Layer::CAN, 4, "invalid data length"));
+        }
+        Ok(Self {
+            can_id: bytes_to_u32(bytes[0], bytes[1], bytes[2], bytes[3], false),
+            length: bytes[4],
+            fd_flags: bytes[5],
+        })
+    }
+
+    /// 29-bit identifier for extended frames, 11-bit otherwise.
+    pub fn id(&self) -> u32 {
+        if self.is_extended() {
+            self.can_id & CAN_EFF_MASK
+        } else {
+            self.can_id & CAN_SFF_MASK
+        }
+    }
+
+    pub fn is_extended(&self) -> bool {
+        self.can_id & CAN_EFF_FLAG != 0
+    }
+
+    pub fn is_remote(&self) -> bool {
+        self.can_id & CAN_RTR_FLAG != 0
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.can_id & CAN_ERR_FLAG != 0
+    }
+
+    pub fn is_fd(&self) -> bool {
+        self.fd_flags & CANFD_FDF != 0 || self.length > 8
+    }
+
+    pub fn data_len(&self) -> usize {
+        self.length as usize
+    }
+
+    pub fn header_len(&self) -> usize {
+        CAN_HEADER_LEN
+    }
+
+    fn flag_names(&self) -> Vec<&'static str> {
+        [
+            (self.is_extended(), "Extended"),
+            (self.is_remote(), "Remote"),
+            (self.is_error(), "Error"),
+            (self.is_fd(), "FD"),
+            (self.fd_flags & CANFD_BRS != 0, "Bit Rate Switch"),
+            (self.fd_flags & CANFD_ESI != 0, "Error State Indicator"),
+        ].into_iter().filter(|(set, _)| *set).map(|(_, name)| name).collect()
+    }
+}
+
+impl Display for CanHeader {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Identifier: {:#x}", self.id())?;
+        let flags = self.flag_names();
+        if !flags.is_empty() {
+            write!(f, "\nFlags: {}", flags.join(", "))?;
+        }
+        write!(f, "\nLength: {}", self.length)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_can() {
+        let can = CanHeader::parse(&[0x00, 0x00, 0x01, 0x23, 0x08, 0x00, 0x00, 0x00]).unwrap();
+        assert_eq!(can.id(), 0x123);
+        assert!(!can.is_extended() && !can.is_fd());
+        assert_eq!(can.data_len(), 8);
+        assert_eq!(can.to_string(), "Identifier: 0x123\nLength: 8");
+
+        let can = CanHeader::parse(&[0x98, 0xDA, 0xF1, 0x10, 0x40, 0x05, 0x00, 0x00]).unwrap();
+        assert_eq!(can.id(), 0x18DAF110);
+        assert!(can.is_extended() && can.is_fd());
+        assert_eq!(can.to_string(), "Identifier: 0x18daf110\nFlags: Extended, FD, Bit Rate Switch\nLength: 64");
+
+        assert_eq!(
+            CanHeader::parse(&[0x00, 0x00, 0x01, 0x23, 0x48, 0x00, 0x00, 0x00]),
+            Err(Error::Malformed(Layer::CAN, 4, "invalid data length"))
+        );
+    }
+}
@@ -0,0 +1,320 @@
+use std::fmt::Display;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use byte::{bytes_to_u16, bytes_to_u32};
+
+use crate::error::{Error, Layer};
+
+/// SNAP protocol ID of CDP under the Cisco OUI.
+pub const CDP_PID: u16 = 0x2000;
+
+pub fn capability_names(bits: u32) -> Vec<&'static str> {
+    const NAMES: [&str; 10] = [
+        "Router", "Transparent Bridge", "Source Route Bridge", "Switch", "Host",
+        "IGMP", "Repeater", "VoIP Phone", "Remotely Managed", "CVTA",
+    ];
+    NAMES.iter().enumerate().filter(|(i, _)| bits & (1 << i) != 0).map(|(_, n)| *n).collect()
+}
+
+fn text(b: &[u8]) -> String {
+    String::from_utf8_lossy(b).trim_end_matches('\0').to_string()
+}
+
+// NLPID 0xCC for IPv4, 802.2 SNAP header with EtherType 0x86DD for IPv6.
+fn addresses(b: &[u8], offset: usize) -> Result<Vec<IpAddr>, Error> {
+    let short = Error::Malformed(Layer::CDP, offset, "address list too short");
+    if b.len() < 4 {
+        return Err(short);
+    }
+    let count = bytes_to_u32(b[0], b[1], b[2], b[3], false);
+    let mut addrs = vec![];
+    let mut i = 4;
+    for _ in 0..count {
+        if i + 2 > b.len() {
+            return Err(short);
+        }
+        let proto_len = b[i + 1] as usize;
+        let proto = b.get(i + 2..i + 2 + proto_len).ok_or(short.clone())?;
+        i += 2 + proto_len;
+        if i + 2 > b.len() {
+            return Err(short);
+        }
+        let addr_len = bytes_to_u16(b[i], b[i + 1], false) as usize;
+        let addr = b.get(i + 2..i + 2 + addr_len).ok_or(short.clone())?;
+        i += 2 + addr_len;
+        match (proto, addr.len()) {
+            ([0xCC], 4) => addrs.push(IpAddr::V4(Ipv4Addr::new(addr[0], addr[1], addr[2], addr[3]))),
+            ([0xAA, 0xAA, 0x03, 0, 0, 0, 0x86, 0xDD], 16) => {
+                let mut octets = [0u8;16];
+                octets.copy_from_slice(addr);
+                addrs.push(IpAddr::V6(Ipv6Addr::from(octets)));
+            },
+            _ => (),
+        }
+    }
+    Ok(addrs)
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum CdpTlv {
+    DEVICEID(String),
+    ADDRESSES(Vec<IpAddr>),
+    PORTID(String),
+    CAPABILITIES(u32),
+    VERSION(String),
+    PLATFORM(String),
+    NATIVEVLAN(u16),
+    DUPLEX(bool),
+    MANAGEMENT(Vec<IpAddr>),
+    UNKNOWN(u16, Vec<u8>),
+}
+
+impl CdpTlv {
+
+    pub fn kind(&self) -> u16 {
+        match self {
+            Self::DEVICEID(_) => 0x0001,
+            Self::ADDRESSES(_) => 0x0002,
+            Self::PORTID(_) => 0x0003,
+            Self::CAPABILITIES(_) => 0x0004,
+            Self::VERSION(_) => 0x0005,
+            Self::PLATFORM(_) => 0x0006,
+            Self::NATIVEVLAN(_) => 0x000A,
+            Self::DUPLEX(_) => 0x000B,
+            Self::MANAGEMENT(_) => 0x0016,
+            Self::UNKNOWN(k, _) => *k,
+        }
+    }
+
+    fn parse(kind: u16, b: &[u8], offset: usize) -> Result<Self, Error> {
+        let tlv = match (kind, b.len()) {
+            (0x0001, _) => Self::DEVICEID(text(b)),
+            (0x0002, _) => Self::ADDRESSES(addresses(b, offset)?),
+            (0x0003, _) => Self::PORTID(text(b)),
+            (0x0004, 4) => Self::CAPABILITIES(bytes_to_u32(b[0], b[1], b[2], b[3], false)),
+            (0x0005, _) => Self::VERSION(text(b)),
+            (0x0006, _) => Self::PLATFORM(text(b)),
+            (0x000A, 2) => Self::NATIVEVLAN(bytes_to_u16(b[0], b[1], false)),
+            (0x000B, 1) => Self::DUPLEX(b[0] != 0),
+            (0x0016, _) => Self::MANAGEMENT(addresses(b, offset)?),
+            _ => Self::UNKNOWN(kind, b.to_vec()),
+        };
+        Ok(tlv)
+    }
+}
+
+impl Display for CdpTlv {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let join = |a: &[IpAddr]| a.iter().map(|a| a.to_string()).collect::<Vec<_>>().join(" ");
+        match self {
+            Self::DEVICEID(s) => write!(f, "Device ID: {}", s),
+            Self::ADDRESSES(a) => write!(f, "Addresses: {}", join(a)),
+            Self::PORTID(s) => write!(f, "Port ID: {}", s),
+            Self::CAPABILITIES(c) => write!(f, "Capabilities: {}", capability_names(*c).join(", ")),
+            Self::VERSION(s) => write!(f, "Software Version: {}", s),
+            Self::PLATFORM(s) => write!(f, "Platform: {}", s),
+            Self::NATIVEVLAN(v) => write!(f, "Native VLAN: {}", v),
+            Self::DUPLEX(full) => write!(f, "Duplex: {}", if *full { "Full" } else { "Half" }),
+            Self::MANAGEMENT(a) => write!(f, "Management Addresses: {}", join(a)),
+            Self::UNKNOWN(kind, value) => write!(f, "Unknown TLV type={:#06x} len={}", kind, value.len() + 4),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct CdpPacket {
+    version: u8,
+    ttl: u8,
+    checksum: u16,
+    tlvs: Vec<CdpTlv>,
+    len: usize,
+}
+
+impl CdpPacket {
+
+    /// `bytes` starts after the SNAP header.
+    pub fn parse(bytes: &[u8]) -> Result<Self, Error> {
+        Self::parse_tlvs(bytes, false)
+    }
+
+    pub fn parse_partial(bytes: &[u8]) -> Result<Self, Error> {
+        Self::parse_tlvs(bytes, true)
+    }
+
+    fn parse_tlvs(bytes: &[u8], partial: bool) -> Result<Self, Error> {
+        if bytes.len() < 4 {
+            return Err(Error::Truncated(Layer::CDP, bytes.len()));
+        }
+        let mut tlvs = vec![];
+        let mut i = 4;
+        while i < bytes.len() {
+            let (kind, len) = match bytes.get(i..i + 4) {
+                Some(h) => (bytes_to_u16(h[0], h[1], false), bytes_to_u16(h[2], h[3], false) as usize),
+                None => (0, 4),
+            };
+            if len < 4 {
+                return Err(Error::Malformed(Layer::CDP, i, "TLV length below 4 bytes"));
+            }
+            if i + len > bytes.len() {
+                if partial {
+                    break;
+                }
+                return Err(Error::Truncated(Layer::CDP, bytes.len()));
+            }
+            tlvs.push(CdpTlv::parse(kind, &bytes[i + 4..i + len], i)?);
+            i += len;
+        }
+        Ok(Self {
+            version: bytes[0],
+            ttl: bytes[1],
+            checksum: bytes_to_u16(bytes[2], bytes[3], false),
+            tlvs,
+            len: i,
+        })
+    }
+
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+
+    pub fn ttl(&self) -> u8 {
+        self.ttl
+    }
+
+    pub fn checksum(&self) -> u16 {
+        self.checksum
+    }
+
+    pub fn tlvs(&self) -> &[CdpTlv] {
+        &self.tlvs
+    }
+
+    pub fn header_len(&self) -> usize {
+        self.len
+    }
+
+    pub fn device_id(&self) -> Option<&str> {
+        self.tlvs.iter().find_map(|t| match t {
+            CdpTlv::DEVICEID(s) => Some(s.as_str()),
+            _ => None,
+        })
+    }
+
+    pub fn port_id(&self) -> Option<&str> {
+        self.tlvs.iter().find_map(|t| match t {
+            CdpTlv::PORTID(s) => Some(s.as_str()),
+            _ => None,
+        })
+    }
+
+    pub fn platform(&self) -> Option<&str> {
+        self.tlvs.iter().find_map(|t| match t {
+            CdpTlv::PLATFORM(s) => Some(s.as_str()),
+            _ => None,
+        })
+    }
+
+    pub fn software_version(&self) -> Option<&str> {
+        self.tlvs.iter().find_map(|t| match t {
+            CdpTlv::VERSION(s) => Some(s.as_str()),
+            _ => None,
+        })
+    }
+
+    pub fn capabilities(&self) -> Option<u32> {
+        self.tlvs.iter().find_map(|t| match t {
+            CdpTlv::CAPABILITIES(c) => Some(*c),
+            _ => None,
+        })
+    }
+
+    pub fn native_vlan(&self) -> Option<u16> {
+        self.tlvs.iter().find_map(|t| match t {
+            CdpTlv::NATIVEVLAN(v) => Some(*v),
+            _ => None,
+        })
+    }
+
+    /// Interface addresses followed by management addresses.
+    pub fn addresses(&self) -> Vec<IpAddr> {
+        let mut addrs: Vec<IpAddr> = vec![];
+        for tlv in self.tlvs.iter() {
+            if let CdpTlv::ADDRESSES(a) | CdpTlv::MANAGEMENT(a) = tlv {
+                addrs.extend(a.iter().filter(|a| !addrs.contains(a)).collect::<Vec<_>>());
+            }
+        }
+        addrs
+    }
+}
+
+impl Display for CdpPacket {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Version: {}\nTTL: {}\nChecksum: {:#06x}", self.version, self.ttl, self.checksum)?;
+        for tlv in self.tlvs.iter() {
+            write!(f, "\n{}", tlv)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tlv(kind: u16, value: &[u8]) -> Vec<u8> {
+        let mut data = kind.to_be_bytes().to_vec();
+        data.extend_from_slice(&(value.len() as u16 + 4).to_be_bytes());
+        data.extend_from_slice(value);
+        data
+    }
+
+    #[test]
+    fn test_cdp() {
+        let mut data = vec![0x02, 0xB4, 0x12, 0x34];
+        data.extend(tlv(0x0001, b"router1"));
+        data.extend(tlv(0x0002, &[0, 0, 0, 2, 1, 1, 0xCC, 0, 4, 192, 168, 1, 1, 9, 1, 0xFF, 0, 1, 0xAB]));
+        data.extend(tlv(0x0003, b"GigabitEthernet0/2"));
+        data.extend(tlv(0x0004, &[0, 0, 0, 0x29]));
+        data.extend(tlv(0x0005, b"IOS 15.2"));
+        data.extend(tlv(0x0006, b"cisco C2960"));
+        data.extend(tlv(0x000A, &[0, 20]));
+        data.extend(tlv(0x000B, &[1]));
+        let mut mgmt = vec![0, 0, 0, 1, 2, 8, 0xAA, 0xAA, 0x03, 0, 0, 0, 0x86, 0xDD, 0, 16];
+        mgmt.extend_from_slice(&"2001:db8::1".parse::<Ipv6Addr>().unwrap().octets());
+        data.extend(tlv(0x0016, &mgmt));
+        data.extend(tlv(0x1234, &[7]));
+        let cdp = CdpPacket::parse(&data).unwrap();
+        assert_eq!((cdp.version(), cdp.ttl(), cdp.checksum()), (2, 180, 0x1234));
+        assert_eq!(cdp.header_len(), data.len());
+        assert_eq!(cdp.device_id(), Some("router1"));
+        assert_eq!(cdp.port_id(), Some("GigabitEthernet0/2"));
+        assert_eq!(capability_names(cdp.capabilities().unwrap()), vec!["Router", "Switch", "IGMP"]);
+        assert_eq!(cdp.software_version(), Some("IOS 15.2"));
+        assert_eq!(cdp.platform(), Some("cisco C2960"));
+        assert_eq!(cdp.native_vlan(), Some(20));
+        assert_eq!(cdp.tlvs()[7], CdpTlv::DUPLEX(true));
+        assert_eq!(cdp.tlvs()[9], CdpTlv::UNKNOWN(0x1234, vec![7]));
+        assert_eq!(
+            cdp.addresses(),
+            vec!["192.168.1.1".parse::<IpAddr>().unwrap(), "2001:db8::1".parse::<IpAddr>().unwrap()]
+        );
+        assert!(cdp.to_string().starts_with("Version: 2\nTTL: 180\nChecksum: 0x1234\nDevice ID: router1\nAddresses: 192.168.1.1\n"));
+    }
+
+    #[test]
+    fn test_cdp_errors() {
+        let mut data = vec![0x02, 0xB4, 0x12, 0x34];
+        data.extend(tlv(0x0001, b"router1"));
+        assert_eq!(CdpPacket::parse(&data[..2]), Err(Error::Truncated(Layer::CDP, 2)));
+        assert_eq!(CdpPacket::parse(&data[..10]), Err(Error::Truncated(Layer::CDP, 10)));
+        assert_eq!(CdpPacket::parse(&data[..6]), Err(Error::Truncated(Layer::CDP, 6)));
+        assert!(CdpPacket::parse_partial(&data[..10]).unwrap().tlvs().is_empty());
+        assert_eq!(
+            CdpPacket::parse(&[0x02, 0xB4, 0x12, 0x34, 0x00, 0x01, 0x00, 0x02]),
+            Err(Error::Malformed(Layer::CDP, 4, "TLV length below 4 bytes"))
+        );
+        let mut data = vec![0x02, 0xB4, 0x12, 0x34];
+        data.extend(tlv(0x0002, &[0, 0, 0, 1, 1, 1]));
+        assert_eq!(CdpPacket::parse(&data), Err(Error::Malformed(Layer::CDP, 4, "address list too short")));
+    }
+}
@@ -0,0 +1,347 @@
+use std::fmt::Display;
+
+use byte::bytes_to_u16;
+
+use crate::error::{Error, Layer};
+use crate::ethernet_frame::MacAddress;
+
+pub const TYPE_MANAGEMENT: u8 = 0;
+pub const TYPE_CONTROL: u8 = 1;
+pub const TYPE_DATA: u8 = 2;
+
+const FLAG_TO_DS: u8 = 0x01;
+const FLAG_FROM_DS: u8 = 0x02;
+const FLAG_MORE_FRAGMENTS: u8 = 0x04;
+const FLAG_RETRY: u8 = 0x08;
+const FLAG_POWER_MGMT: u8 = 0x10;
+const FLAG_MORE_DATA: u8 = 0x20;
+const FLAG_PROTECTED: u8 = 0x40;
+const FLAG_ORDER: u8 = 0x80;
+
+fn u16_le(b: &[u8], i: usize) -> u16 {
+    bytes_to_u16(b[i], b[i + 1], true)
+}
+
+fn mac_at(b: &[u8], i: usize) -> MacAddress {
+    MacAddress::new([b[i], b[i + 1], b[i + 2], b[i + 3], b[i + 4], b[i + 5]])
+}
+
+pub fn subtype_name(frame_type: u8, subtype: u8) -> &'static str {
+    match (frame_type, subtype) {
+        (TYPE_MANAGEMENT, 0) => "Association Request",
+        (TYPE_MANAGEMENT, 1) => "Association Response",
+        (TYPE_MANAGEMENT, 2) => "Reassociation Request",
+        (TYPE_MANAGEMENT, 3) => "Reassociation Response",
+        (TYPE_MANAGEMENT, 4) => "Probe Request",
+        (TYPE_MANAGEMENT, 5) => "Probe Response",
+        (TYPE_MANAGEMENT, 6) => "Timing Advertisement",
+        (TYPE_MANAGEMENT, 8) => "Beacon",
+        (TYPE_MANAGEMENT, 9) => "ATIM",
+        (TYPE_MANAGEMENT, 10) => "Disassociation",
+        (TYPE_MANAGEMENT, 11) => "Authentication",
+        (TYPE_MANAGEMENT, 12) => "Deauthentication",
+        (TYPE_MANAGEMENT, 13) => "Action",
+        (TYPE_MANAGEMENT, 14) => "Action No Ack",
+        (TYPE_CONTROL, 7) => "Control Wrapper",
+        (TYPE_CONTROL, 8) => "Block Ack Request",
+        (TYPE_CONTROL, 9) => "Block Ack",
+        (TYPE_CONTROL, 10) => "PS-Poll",
+        (TYPE_CONTROL, 11) => "RTS",
+        (TYPE_CONTROL, 12) => "CTS",
+        (TYPE_CONTROL, 13) => "ACK",
+        (TYPE_CONTROL, 14) => "CF-End",
+        (TYPE_CONTROL, 15) => "CF-End + CF-Ack",
+        (TYPE_DATA, 0) => "Data",
+        (TYPE_DATA, 4) => "Null",
+        (TYPE_DATA, 8) => "QoS Data",
+        (TYPE_DATA, 12) => "QoS Null",
+        (TYPE_DATA, _) => "Data",
+        _ => "Reserved",
+    }
+}
+
+/// IEEE 802.11 MAC header. Addresses are kept in wire order, their meaning depends on
+/// the To DS and From DS flags.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Ieee80211Header {
+    frame_control: u16,
+    duration: u16,
+    addresses: Vec<MacAddress>,
+    sequence_control: Option<u16>,
+    qos_control: Option<u16>,
+    len: usize,
+}
+
+impl Ieee80211Header {
+
+    pub fn parse(bytes: &[u8]) -> Result<Self, Error> {
+        Self::parse_fields(bytes, false)
+    }
+
+    pub fn parse_partial(bytes: &[u8]) -> Result<Self, Error> {
+        Self::parse_fields(bytes, true)
+    }
+
+    fn parse_fields(bytes: &[u8], partial: bool) -> Result<Self, Error> {
+        if bytes.len() < 10 {
+            return Err(Error::Truncated(Layer::IEEE80211, bytes.len()));
+        }
+        let frame_control = u16_le(bytes, 0);
+        let (version, frame_type, subtype) = (frame_control & 0x03, (frame_control >> 2) as u8 & 0x03, (frame_control >> 4) as u8 & 0x0F);
+        if version != 0 {
+            return Err(Error::Malformed(Layer::IEEE80211, 0, "unknown protocol version"));
+        }
+        let flags = (frame_control >> 8) as u8;
+        let (addresses, sequenced, qos) = match frame_type {
+            TYPE_MANAGEMENT => (3, true, false),
+            TYPE_CONTROL => match subtype {
+                12 | 13 => (1, false, false),
+                _ => (2, false, false),
+            },
+            TYPE_DATA => {
+                let four = flags & (FLAG_TO_DS | FLAG_FROM_DS) == FLAG_TO_DS | FLAG_FROM_DS;
+                (if four { 4 } else { 3 }, true, subtype & 0x08 != 0)
+            },
+            _ => return Err(Error::Malformed(Layer::IEEE80211, 0, "reserved frame type")),
+        };
+        let mut len = 4 + 6 * addresses + if sequenced { 2 } else { 0 } + if qos { 2 } else { 0 };
+        if qos && flags & FLAG_ORDER != 0 {
+            len += 4;
+        }
+        if bytes.len() < len && !partial {
+            return Err(Error::Truncated(Layer::IEEE80211, bytes.len()));
+        }
+        // The sequence control field sits between the third and fourth address.
+        let mut pos = 4;
+        let mut header = Self {
+            frame_control,
+            duration: u16_le(bytes, 2),
+            addresses: vec![],
+            sequence_control: None,
+            qos_control: None,
+            len,
+        };
+        for i in 0..addresses {
+            if i == 3 {
+                pos += 2;
+            }
+            if pos + 6 > bytes.len() {
+                break;
+            }
+            header.addresses.push(mac_at(bytes, pos));
+            pos += 6;
+        }
+        if sequenced && bytes.len() >= 24 {
+            header.sequence_control = Some(u16_le(bytes, 22));
+            pos = pos.max(24);
+        }
+        if qos && header.addresses.len() == addresses && pos + 2 <= bytes.len() {
+            header.qos_control = Some(u16_le(bytes, pos));
+        }
+        Ok(header)
+    }
+
+    pub fn frame_control(&self) -> u16 {
+        self.frame_control
+    }
+
+    pub fn frame_type(&self) -> u8 {
+        (self.frame_control >> 2) as u8 & 0x03
+    }
+
+    pub fn subtype(&self) -> u8 {
+        (self.frame_control >> 4) as u8 & 0x0F
+    }
+
+    pub fn type_name(&self) -> &'static str {
+        match self.frame_type() {
+            TYPE_MANAGEMENT => "Management",
+            TYPE_CONTROL => "Control",
+            TYPE_DATA => "Data",
+            _ => "Reserved",
+        }
+    }
+
+    pub fn subtype_name(&self) -> &'static str {
+        subtype_name(self.frame_type(), self.subtype())
+    }
+
+    pub fn flags(&self) -> u8 {
+        (self.frame_control >> 8) as u8
+    }
+
+    pub fn to_ds(&self) -> bool {
+        self.flags() & FLAG_TO_DS != 0
+    }
+
+    pub fn from_ds(&self) -> bool {
+        self.flags() & FLAG_FROM_DS != 0
+    }
+
+    pub fn is_retry(&self) -> bool {
+        self.flags() & FLAG_RETRY != 0
+    }
+
+    /// The frame body is encrypted.
+    pub fn is_protected(&self) -> bool {
+        self.flags() & FLAG_PROTECTED != 0
+    }
+
+    pub fn duration(&self) -> u16 {
+        self.duration
+    }
+
+    pub fn addresses(&self) -> &[MacAddress] {
+        &self.addresses
+    }
+
+    pub fn receiver(&self) -> &MacAddress {
+        &self.addresses[0]
+    }
+
+    pub fn transmitter(&self) -> Option<&MacAddress> {
+        self.addresses.get(1)
+    }
+
+    pub fn destination(&self) -> Option<&MacAddress> {
+        match (self.frame_type(), self.to_ds()) {
+            (TYPE_CONTROL, _) => None,
+            (_, false) => self.addresses.first(),
+            (_, true) => self.addresses.get(2),
+        }
+    }
+
+    pub fn source(&self) -> Option<&MacAddress> {
+        match (self.frame_type(), self.to_ds(), self.from_ds()) {
+            (TYPE_CONTROL, _, _) => None,
+            (_, _, false) => self.addresses.get(1),
+            (_, false, true) => self.addresses.get(2),
+            (_, true, true) => self.addresses.get(3),
+        }
+    }
+
+    /// Only defined outside of wireless distribution system frames.
+    pub fn bssid(&self) -> Option<&MacAddress> {
+        match (self.frame_type(), self.to_ds(), self.from_ds()) {
+            (TYPE_CONTROL, _, _) | (_, true, true) => None,
+            (_, false, false) => self.addresses.get(2),
+            (_, false, true) => self.addresses.get(1),
+            (_, true, false) => self.addresses.first(),
+        }
+    }
+
+    pub fn sequence_number(&self) -> Option<u16> {
+        self.sequence_control.map(|s| s >> 4)
+    }
+
+    pub fn fragment_number(&self) -> Option<u8> {
+        self.sequence_control.map(|s| (s & 0x0F) as u8)
+    }
+
+    /// Traffic identifier of QoS data frames.
+    pub fn tid(&self) -> Option<u8> {
+        self.qos_control.map(|q| (q & 0x0F) as u8)
+    }
+
+    /// Data frames that carry an unencrypted LLC payload.
+    pub fn has_llc(&self) -> bool {
+        self.frame_type() == TYPE_DATA && self.subtype() & 0x04 == 0 && !self.is_protected()
+    }
+
+    pub fn header_len(&self) -> usize {
+        self.len
+    }
+
+    fn flag_names(&self) -> Vec<&'static str> {
+        [
+            (FLAG_TO_DS, "To DS"),
+            (FLAG_FROM_DS, "From DS"),
+            (FLAG_MORE_FRAGMENTS, "More Fragments"),
+            (FLAG_RETRY, "Retry"),
+            (FLAG_POWER_MGMT, "Power Management"),
+            (FLAG_MORE_DATA, "More Data"),
+            (FLAG_PROTECTED, "Protected"),
+            (FLAG_ORDER, "Order"),
+        ].into_iter().filter(|(f, _)| self.flags() & f != 0).map(|(_, name)| name).collect()
+    }
+}
+
+impl Display for Ieee80211Header {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Type: {} ({})", self.type_name(), self.subtype_name())?;
+        writeln!(f, "Flags: {:#04x} ({})", self.flags(), self.flag_names().join(", "))?;
+        write!(f, "Duration: {}", self.duration)?;
+        let named = [
+            ("Receiver", Some(self.receiver())),
+            ("Transmitter", self.transmitter()),
+            ("Destination", self.destination()),
+            ("Source", self.source()),
+            ("BSSID", self.bssid()),
+        ];
+        for (name, addr) in named {
+            if let Some(addr) = addr {
+                write!(f, "\n{}: {}", name, addr)?;
+            }
+        }
+        if let (Some(seq), Some(frag)) = (self.sequence_number(), self.fragment_number()) {
+            write!(f, "\nSequence Number: {}, Fragment {}", seq, frag)?;
+        }
+        if let Some(tid) = self.tid() {
+            write!(f, "\nTID: {}", tid)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_qos_data_from_ds() {
+        let mut bytes = vec![0x88, 0x02, 0x2C, 0x00];
+        bytes.extend_from_slice(&[0xA1;6]);
+        bytes.extend_from_slice(&[0xB1;6]);
+        bytes.extend_from_slice(&[0xC1;6]);
+        bytes.extend_from_slice(&[0x40, 0x06, 0x05, 0x00]);
+        let header = Ieee80211Header::parse(&bytes).unwrap();
+        assert_eq!(header.subtype_name(), "QoS Data");
+        assert!(header.from_ds() && !header.to_ds());
+        assert_eq!(header.header_len(), 26);
+        assert_eq!(header.destination(), Some(&MacAddress::new([0xA1;6])));
+        assert_eq!(header.bssid(), Some(&MacAddress::new([0xB1;6])));
+        assert_eq!(header.source(), Some(&MacAddress::new([0xC1;6])));
+        assert_eq!(header.sequence_number(), Some(100));
+        assert_eq!(header.tid(), Some(5));
+        assert!(header.has_llc());
+        assert!(header.to_string().starts_with("Type: Data (QoS Data)\nFlags: 0x02 (From DS)\nDuration: 44\n"));
+        assert_eq!(Ieee80211Header::parse(&bytes[..25]), Err(Error::Truncated(Layer::IEEE80211, 25)));
+        let partial = Ieee80211Header::parse_partial(&bytes[..20]).unwrap();
+        assert_eq!(partial.addresses().len(), 2);
+        assert_eq!((partial.source(), partial.sequence_number(), partial.tid()), (None, None, None));
+    }
+
+    #[test]
+    fn test_control_and_wds() {
+        let mut ack = vec![0xD4, 0x00, 0x00, 0x00];
+        ack.extend_from_slice(&[0xA1;6]);
+        let header = Ieee80211Header::parse(&ack).unwrap();
+        assert_eq!(header.subtype_name(), "ACK");
+        assert_eq!(header.header_len(), 10);
+        assert_eq!(header.source(), None);
+        assert_eq!(header.transmitter(), None);
+
+        let mut wds = vec![0x08, 0x03, 0x00, 0x00];
+        for b in [0xA1, 0xB1, 0xC1] {
+            wds.extend_from_slice(&[b;6]);
+        }
+        wds.extend_from_slice(&[0x10, 0x00]);
+        wds.extend_from_slice(&[0xD1;6]);
+        let header = Ieee80211Header::parse(&wds).unwrap();
+        assert_eq!(header.header_len(), 30);
+        assert_eq!(header.source(), Some(&MacAddress::new([0xD1;6])));
+        assert_eq!(header.destination(), Some(&MacAddress::new([0xC1;6])));
+        assert_eq!(header.bssid(), None);
+        assert_eq!(header.sequence_number(), Some(1));
+    }
+}
@@ -0,0 +1,352 @@
+use std::fmt::Display;
+
+use byte::ByteParser;
+
+use crate::error::{Error, Layer};
+
+pub const SAP_NULL: u8 = 0x00;
+pub const SAP_IP: u8 = 0x06;
+pub const SAP_STP: u8 = 0x42;
+pub const SAP_SNAP: u8 = 0xAA;
+pub const SAP_IPX: u8 = 0xE0;
+pub const SAP_NETBIOS: u8 = 0xF0;
+pub const SAP_ISO: u8 = 0xFE;
+
+pub const OUI_ENCAPSULATED: u32 = 0x000000;
+pub const OUI_CISCO: u32 = 0x00000C;
+pub const OUI_IEEE_8021: u32 = 0x0080C2;
+
+pub fn sap_name(sap: u8) -> &'static str {
+    match sap & 0xFE {
+        SAP_NULL => "Null SAP",
+        SAP_IP => "IP",
+        SAP_STP => "Spanning Tree BPDU",
+        SAP_SNAP => "SNAP",
+        SAP_IPX => "NetWare",
+        SAP_NETBIOS => "NetBIOS",
+        SAP_ISO => "ISO Network Layer",
+        _ => "Unknown",
+    }
+}
+
+pub fn oui_name(oui: u32) -> &'static str {
+    match oui {
+        OUI_ENCAPSULATED => "Encapsulated Ethernet",
+        OUI_CISCO => "Cisco",
+        OUI_IEEE_8021 => "IEEE 802.1",
+        _ => "Unknown",
+    }
+}
+
+pub enum LlcHeaderField {
+    DSAP(u8),
+    SSAP(u8),
+    CTRL(u16),
+    OUI(u32),
+    PID(u16),
+    UNSET,
+}
+
+impl Display for LlcHeaderField {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::DSAP(b) => write!(f, "DSAP: {:#04x} ({})", b, sap_name(*b)),
+            Self::SSAP(b) => write!(f, "SSAP: {:#04x} ({})", b, sap_name(*b)),
+            Self::CTRL(b) => write!(f, "Control: {:#04x}", b),
+            Self::OUI(b) => write!(f, "Organization Code: {:#08x} ({})", b, oui_name(*b)),
+            Self::PID(b) => write!(f, "PID: {:#06x}", b),
+            Self::UNSET => write!(f, "UNSET"),
+        }
+    }
+}
+
+impl Clone for LlcHeaderField {
+    fn clone(&self) -> Self {
+        match self {
+            Self::DSAP(b) => Self::DSAP(*b),
+            Self::SSAP(b) => Self::SSAP(*b),
+            Self::CTRL(b) => Self::CTRL(*b),
+            Self::OUI(b) => Self::OUI(*b),
+            Self::PID(b) => Self::PID(*b),
+            Self::UNSET => Self::UNSET,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct LlcHeader {
+    dsap: LlcHeaderField,
+    ssap: LlcHeaderField,
+    control: LlcHeaderField,
+    oui: LlcHeaderField,
+    pid: LlcHeaderField,
+    control_len: usize,
+}
+
+impl Display for LlcHeader {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}\n{}\n{}", self.dsap, self.ssap, self.control)?;
+        if let Some(format) = self.frame_format() {
+            write!(f, " ({}-format)", format)?;
+        }
+        if self.is_snap() {
+            write!(f, "\n{}\n{}", self.oui, self.pid)?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for LlcHeader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LlcHeader {
+
+    pub fn new() -> Self {
+        Self {
+            dsap: LlcHeaderField::UNSET,
+            ssap: LlcHeaderField::UNSET,
+            control: LlcHeaderField::UNSET,
+            oui: LlcHeaderField::UNSET,
+            pid: LlcHeaderField::UNSET,
+            control_len: 1,
+        }
+    }
+
+    pub fn set_field(&mut self, field: LlcHeaderField) {
+        match field {
+            LlcHeaderField::DSAP(_) => self.dsap = field,
+            LlcHeaderField::SSAP(_) => self.ssap = field,
+            LlcHeaderField::CTRL(_) => self.control = field,
+            LlcHeaderField::OUI(_) => self.oui = field,
+            LlcHeaderField::PID(_) => self.pid = field,
+            LlcHeaderField::UNSET => (),
+        }
+    }
+
+    pub fn dsap(&self) -> Option<u8> {
+        match self.dsap {
+            LlcHeaderField::DSAP(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn ssap(&self) -> Option<u8> {
+        match self.ssap {
+            LlcHeaderField::SSAP(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn control(&self) -> Option<u16> {
+        match self.control {
+            LlcHeaderField::CTRL(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn oui(&self) -> Option<u32> {
+        match self.oui {
+            LlcHeaderField::OUI(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn protocol_id(&self) -> Option<u16> {
+        match self.pid {
+            LlcHeaderField::PID(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn is_group(&self) -> bool {
+        self.dsap().is_some_and(|b| b & 0x01 != 0)
+    }
+
+    pub fn is_response(&self) -> bool {
+        self.ssap().is_some_and(|b| b & 0x01 != 0)
+    }
+
+    pub fn is_snap(&self) -> bool {
+        self.dsap() == Some(SAP_SNAP) && self.ssap() == Some(SAP_SNAP)
+    }
+
+    /// "I" (information), "S" (supervisory) or "U" (unnumbered).
+    pub fn frame_format(&self) -> Option<&'static str> {
+        let control = self.control()?;
+        let first = if self.control_len == 2 { (control >> 8) as u8 } else { control as u8 };
+        match first & 0x03 {
+            0x03 => Some("U"),
+            0x01 => Some("S"),
+            _ => Some("I"),
+        }
+    }
+
+    pub fn header_len(&self) -> usize {
+        2 + self.control_len + if self.is_snap() { 5 } else { 0 }
+    }
+}
+
+pub struct LlcHeaderParser {
+    parser: ByteParser,
+    curr_field: LlcHeaderField,
+    header: LlcHeader,
+    // First byte of a two-byte control field.
+    control: Option<u8>,
+    oui: [u8;3],
+    pos: usize,
+}
+
+impl Default for LlcHeaderParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LlcHeaderParser {
+
+    pub fn new() -> Self {
+        Self {
+            parser: ByteParser::new(false),
+            curr_field: LlcHeaderField::DSAP(0),
+            header: LlcHeader::new(),
+            control: None,
+            oui: [0;3],
+            pos: 0,
+        }
+    }
+
+    /// Bytes the header needs, known once DSAP, SSAP and the first control byte are read.
+    pub fn header_len(&self) -> usize {
+        self.header.header_len()
+    }
+
+    pub fn parse(&mut self, b: u8) -> Result<(), Error> {
+        self.step(b)?;
+        match self.curr_field {
+            LlcHeaderField::DSAP(_) => {
+                self.header.set_field(LlcHeaderField::DSAP(self.parser.word()));
+                self.curr_field = LlcHeaderField::SSAP(0);
+            },
+            LlcHeaderField::SSAP(_) => {
+                self.header.set_field(LlcHeaderField::SSAP(self.parser.word()));
+                self.curr_field = LlcHeaderField::CTRL(0);
+            },
+            LlcHeaderField::CTRL(_) => self.control(b),
+            LlcHeaderField::OUI(_) => {
+                self.oui[self.pos] = b;
+                self.pos += 1;
+                if self.pos == 3 {
+                    let oui = u32::from_be_bytes([0, self.oui[0], self.oui[1], self.oui[2]]);
+                    self.header.set_field(LlcHeaderField::OUI(oui));
+                    self.curr_field = LlcHeaderField::PID(0);
+                }
+            },
+            LlcHeaderField::PID(_) => {
+                if self.parser.dword_done() {
+                    self.header.set_field(LlcHeaderField::PID(self.parser.dword_as_u16()));
+                    self.parser.reset_dword();
+                    self.curr_field = LlcHeaderField::UNSET;
+                }
+            },
+            LlcHeaderField::UNSET => (),
+        }
+        Ok(())
+    }
+
+    pub fn get_header(&self) -> LlcHeader {
+        self.header.clone()
+    }
+
+    // U-format frames carry a single control byte, I- and S-format frames two.
+    fn control(&mut self, b: u8) {
+        let control = match self.control.take() {
+            Some(first) => u16::from_be_bytes([first, b]),
+            None if b & 0x03 != 0x03 => {
+                self.header.control_len = 2;
+                self.control = Some(b);
+                return;
+            },
+            None => b as u16,
+        };
+        self.header.set_field(LlcHeaderField::CTRL(control));
+        self.curr_field = if self.header.is_snap() { LlcHeaderField::OUI(0) } else { LlcHeaderField::UNSET };
+    }
+
+    fn step(&mut self, b: u8) -> Result<(), Error> {
+        match self.curr_field {
+            LlcHeaderField::DSAP(_)|
+                LlcHeaderField::SSAP(_)
+                => {
+                    self.parser.set_word(b);
+                    Ok(())
+                },
+            LlcHeaderField::CTRL(_)|
+                LlcHeaderField::OUI(_)
+                => Ok(()),
+            LlcHeaderField::PID(_)
+                => self.parser.set_d_byte(b).map_err(|e| Error::Byte(Layer::LLC, e)),
+            LlcHeaderField::UNSET => Err(Error::HeaderComplete(Layer::LLC)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(bytes: &[u8]) -> LlcHeaderParser {
+        let mut parser = LlcHeaderParser::new();
+        for b in bytes {
+            parser.parse(*b).unwrap();
+        }
+        parser
+    }
+
+    #[test]
+    fn test_llc_u_format() {
+        let mut parser = parse(&[0x42, 0x42, 0x03]);
+        assert_eq!(parser.parse(0), Err(Error::HeaderComplete(Layer::LLC)));
+        let header = parser.get_header();
+        assert_eq!(header.dsap(), Some(SAP_STP));
+        assert_eq!(header.control(), Some(0x03));
+        assert_eq!(header.frame_format(), Some("U"));
+        assert_eq!(header.header_len(), 3);
+        assert!(!header.is_snap());
+        assert_eq!(
+            header.to_string(),
+            "DSAP: 0x42 (Spanning Tree BPDU)\nSSAP: 0x42 (Spanning Tree BPDU)\nControl: 0x03 (U-format)"
+        );
+    }
+
+    #[test]
+    fn test_llc_i_format() {
+        let parser = parse(&[0xF0, 0xF1, 0x00, 0x02]);
+        let header = parser.get_header();
+        assert_eq!(header.control(), Some(0x0002));
+        assert_eq!(header.frame_format(), Some("I"));
+        assert_eq!(header.header_len(), 4);
+        assert!(header.is_response());
+        assert!(!header.is_group());
+    }
+
+    #[test]
+    fn test_snap() {
+        let mut parser = LlcHeaderParser::new();
+        for b in [0xAA, 0xAA, 0x03, 0x00, 0x00] {
+            parser.parse(b).unwrap();
+            assert!(parser.header_len() <= 8);
+        }
+        assert_eq!(parser.header_len(), 8);
+        for b in [0x0C, 0x20, 0x00] {
+            parser.parse(b).unwrap();
+        }
+        let header = parser.get_header();
+        assert!(header.is_snap());
+        assert_eq!(header.oui(), Some(OUI_CISCO));
+        assert_eq!(header.protocol_id(), Some(0x2000));
+        assert!(header.to_string().ends_with("Organization Code: 0x00000c (Cisco)\nPID: 0x2000"));
+    }
+}
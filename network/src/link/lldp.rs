@@ -0,0 +1,433 @@
+use std::fmt::Display;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use byte::{bytes_to_u16, bytes_to_u32};
+
+use crate::error::{Error, Layer};
+use crate::ethernet_frame::MacAddress;
+
+pub const OUI_IEEE_8021: u32 = 0x0080C2;
+pub const OUI_IEEE_8023: u32 = 0x00120F;
+
+pub fn capability_names(bits: u16) -> Vec<&'static str> {
+    const NAMES: [&str; 11] = [
+        "Other", "Repeater", "Bridge", "WLAN Access Point", "Router", "Telephone",
+        "DOCSIS Cable Device", "Station Only", "C-VLAN", "S-VLAN", "Two-port MAC Relay",
+    ];
+    NAMES.iter().enumerate().filter(|(i, _)| bits & (1 << i) != 0).map(|(_, n)| *n).collect()
+}
+
+fn text(b: &[u8]) -> String {
+    String::from_utf8_lossy(b).trim_end_matches('\0').to_string()
+}
+
+fn mac(b: &[u8]) -> Option<MacAddress> {
+    let octets: [u8;6] = b.try_into().ok()?;
+    Some(MacAddress::new(octets))
+}
+
+/// Address family numbers (IANA) used by LLDP network address fields.
+fn network_address(b: &[u8]) -> Option<IpAddr> {
+    match (b.first()?, b.len()) {
+        (1, 5) => Some(IpAddr::V4(Ipv4Addr::new(b[1], b[2], b[3], b[4]))),
+        (2, 17) => {
+            let octets: [u8;16] = b[1..].try_into().ok()?;
+            Some(IpAddr::V6(Ipv6Addr::from(octets)))
+        },
+        _ => None,
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum LldpId {
+    MAC(MacAddress),
+    ADDR(IpAddr),
+    NAME(String),
+}
+
+impl LldpId {
+
+    fn chassis(subtype: u8, b: &[u8]) -> Self {
+        match subtype {
+            4 => mac(b).map(Self::MAC),
+            5 => network_address(b).map(Self::ADDR),
+            _ => None,
+        }.unwrap_or_else(|| Self::NAME(text(b)))
+    }
+
+    fn port(subtype: u8, b: &[u8]) -> Self {
+        match subtype {
+            3 => mac(b).map(Self::MAC),
+            4 => network_address(b).map(Self::ADDR),
+            _ => None,
+        }.unwrap_or_else(|| Self::NAME(text(b)))
+    }
+}
+
+impl Display for LldpId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MAC(m) => write!(f, "{}", m),
+            Self::ADDR(a) => write!(f, "{}", a),
+            Self::NAME(n) => write!(f, "{}", n),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum LldpTlv {
+    END,
+    CHASSISID(u8, LldpId),
+    PORTID(u8, LldpId),
+    TTL(u16),
+    PORTDESC(String),
+    SYSNAME(String),
+    SYSDESC(String),
+    CAPABILITIES {
+        system: u16,
+        enabled: u16,
+    },
+    MANAGEMENT {
+        address: Option<IpAddr>,
+        if_subtype: u8,
+        if_number: u32,
+        oid: Vec<u8>,
+    },
+    PORTVLAN(u16),
+    VLANNAME {
+        vid: u16,
+        name: String,
+    },
+    MACPHY {
+        autoneg: u8,
+        pmd_capability: u16,
+        mau_type: u16,
+    },
+    LINKAGG {
+        status: u8,
+        port: u32,
+    },
+    MAXFRAME(u16),
+    ORG {
+        oui: u32,
+        subtype: u8,
+        info: Vec<u8>,
+    },
+    UNKNOWN(u8, Vec<u8>),
+}
+
+impl LldpTlv {
+
+    pub fn kind(&self) -> u8 {
+        match self {
+            Self::END => 0,
+            Self::CHASSISID(..) => 1,
+            Self::PORTID(..) => 2,
+            Self::TTL(_) => 3,
+            Self::PORTDESC(_) => 4,
+            Self::SYSNAME(_) => 5,
+            Self::SYSDESC(_) => 6,
+            Self::CAPABILITIES { .. } => 7,
+            Self::MANAGEMENT { .. } => 8,
+            Self::PORTVLAN(_) |
+                Self::VLANNAME { .. } |
+                Self::MACPHY { .. } |
+                Self::LINKAGG { .. } |
+                Self::MAXFRAME(_) |
+                Self::ORG { .. }
+                => 127,
+            Self::UNKNOWN(k, _) => *k,
+        }
+    }
+
+    fn parse(kind: u8, b: &[u8], offset: usize) -> Result<Self, Error> {
+        let short = || Error::Malformed(Layer::LLDP, offset, "TLV too short");
+        let tlv = match kind {
+            0 => Self::END,
+            1 | 2 => {
+                let (subtype, id) = b.split_first().ok_or_else(short)?;
+                match kind {
+                    1 => Self::CHASSISID(*subtype, LldpId::chassis(*subtype, id)),
+                    _ => Self::PORTID(*subtype, LldpId::port(*subtype, id)),
+                }
+            },
+            3 if b.len() >= 2 => Self::TTL(bytes_to_u16(b[0], b[1], false)),
+            4 => Self::PORTDESC(text(b)),
+            5 => Self::SYSNAME(text(b)),
+            6 => Self::SYSDESC(text(b)),
+            7 if b.len() >= 4 => Self::CAPABILITIES {
+                system: bytes_to_u16(b[0], b[1], false),
+                enabled: bytes_to_u16(b[2], b[3], false),
+            },
+            8 => {
+                let addr_len = *b.first().ok_or_else(short)? as usize;
+                let i = 1 + addr_len;
+                if addr_len == 0 || b.len() < i + 6 {
+                    return Err(short());
+                }
+                let oid_len = b[i + 5] as usize;
+                if b.len() < i + 6 + oid_len {
+                    return Err(short());
+                }
+                Self::MANAGEMENT {
+                    address: network_address(&b[1..i]),
+                    if_subtype: b[i],
+                    if_number: bytes_to_u32(b[i + 1], b[i + 2], b[i + 3], b[i + 4], false),
+                    oid: b[i + 6..i + 6 + oid_len].to_vec(),
+                }
+            },
+            127 if b.len() >= 4 => Self::org(u32::from_be_bytes([0, b[0], b[1], b[2]]), b[3], &b[4..]),
+            3 | 7 | 127 => return Err(short()),
+            _ => Self::UNKNOWN(kind, b.to_vec()),
+        };
+        Ok(tlv)
+    }
+
+    fn org(oui: u32, subtype: u8, b: &[u8]) -> Self {
+        match (oui, subtype, b.len()) {
+            (OUI_IEEE_8021, 1, 2) => Self::PORTVLAN(bytes_to_u16(b[0], b[1], false)),
+            (OUI_IEEE_8021, 3, l) if l >= 3 && l >= 3 + b[2] as usize => Self::VLANNAME {
+                vid: bytes_to_u16(b[0], b[1], false),
+                name: text(&b[3..3 + b[2] as usize]),
+            },
+            (OUI_IEEE_8023, 1, 5) => Self::MACPHY {
+                autoneg: b[0],
+                pmd_capability: bytes_to_u16(b[1], b[2], false),
+                mau_type: bytes_to_u16(b[3], b[4], false),
+            },
+            (OUI_IEEE_8023, 3, 5) => Self::LINKAGG {
+                status: b[0],
+                port: bytes_to_u32(b[1], b[2], b[3], b[4], false),
+            },
+            (OUI_IEEE_8023, 4, 2) => Self::MAXFRAME(bytes_to_u16(b[0], b[1], false)),
+            _ => Self::ORG { oui, subtype, info: b.to_vec() },
+        }
+    }
+}
+
+impl Display for LldpTlv {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::END => write!(f, "End of LLDPDU"),
+            Self::CHASSISID(subtype, id) => write!(f, "Chassis ID: {} (subtype {})", id, subtype),
+            Self::PORTID(subtype, id) => write!(f, "Port ID: {} (subtype {})", id, subtype),
+            Self::TTL(t) => write!(f, "Time To Live: {}", t),
+            Self::PORTDESC(s) => write!(f, "Port Description: {}", s),
+            Self::SYSNAME(s) => write!(f, "System Name: {}", s),
+            Self::SYSDESC(s) => write!(f, "System Description: {}", s),
+            Self::CAPABILITIES { system, enabled } => write!(
+                f,
+                "Capabilities: {} (enabled: {})",
+                capability_names(*system).join(", "),
+                capability_names(*enabled).join(", ")
+            ),
+            Self::MANAGEMENT { address, if_number, .. } => match address {
+                Some(a) => write!(f, "Management Address: {} (interface {})", a, if_number),
+                None => write!(f, "Management Address: unknown family (interface {})", if_number),
+            },
+            Self::PORTVLAN(v) => write!(f, "Port VLAN ID: {}", v),
+            Self::VLANNAME { vid, name } => write!(f, "VLAN Name: {} {}", vid, name),
+            Self::MACPHY { autoneg, mau_type, .. } => write!(
+                f,
+                "MAC/PHY Configuration: Auto-negotiation {}, MAU Type {}",
+                if autoneg & 0x02 != 0 { "enabled" } else { "disabled" },
+                mau_type
+            ),
+            Self::LINKAGG { status, port } => write!(f, "Link Aggregation: Status {:#04x}, Port {}", status, port),
+            Self::MAXFRAME(s) => write!(f, "Maximum Frame Size: {}", s),
+            Self::ORG { oui, subtype, info } => write!(f, "Organization Specific: OUI {:#08x}, subtype {}, {} bytes", oui, subtype, info.len()),
+            Self::UNKNOWN(kind, value) => write!(f, "Unknown TLV type={} len={}", kind, value.len()),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct LldpPdu {
+    tlvs: Vec<LldpTlv>,
+    len: usize,
+}
+
+impl LldpPdu {
+
+    pub fn parse(bytes: &[u8]) -> Result<Self, Error> {
+        Self::parse_tlvs(bytes, false)
+    }
+
+    pub fn parse_partial(bytes: &[u8]) -> Result<Self, Error> {
+        Self::parse_tlvs(bytes, true)
+    }
+
+    fn parse_tlvs(bytes: &[u8], partial: bool) -> Result<Self, Error> {
+        let mut tlvs = vec![];
+        let mut i = 0;
+        while i < bytes.len() {
+            let (kind, len) = match bytes.get(i..i + 2) {
+                Some(head) => {
+                    let head = bytes_to_u16(head[0], head[1], false);
+                    ((head >> 9) as u8, (head & 0x01FF) as usize)
+                },
+                None => (0, 0),
+            };
+            if i + 2 + len > bytes.len() {
+                if partial {
+                    break;
+                }
+                return Err(Error::Truncated(Layer::LLDP, bytes.len()));
+            }
+            let tlv = LldpTlv::parse(kind, &bytes[i + 2..i + 2 + len], i)?;
+            i += 2 + len;
+            if matches!(tlv, LldpTlv::END) {
+                break;
+            }
+            tlvs.push(tlv);
+        }
+        if !matches!(tlvs.first(), Some(LldpTlv::CHASSISID(..))) {
+            return Err(Error::Malformed(Layer::LLDP, 0, "missing chassis ID"));
+        }
+        Ok(Self { tlvs, len: i })
+    }
+
+    pub fn tlvs(&self) -> &[LldpTlv] {
+        &self.tlvs
+    }
+
+    pub fn header_len(&self) -> usize {
+        self.len
+    }
+
+    pub fn chassis_id(&self) -> Option<&LldpId> {
+        self.tlvs.iter().find_map(|t| match t {
+            LldpTlv::CHASSISID(_, id) => Some(id),
+            _ => None,
+        })
+    }
+
+    pub fn port_id(&self) -> Option<&LldpId> {
+        self.tlvs.iter().find_map(|t| match t {
+            LldpTlv::PORTID(_, id) => Some(id),
+            _ => None,
+        })
+    }
+
+    pub fn ttl(&self) -> Option<u16> {
+        self.tlvs.iter().find_map(|t| match t {
+            LldpTlv::TTL(ttl) => Some(*ttl),
+            _ => None,
+        })
+    }
+
+    pub fn port_description(&self) -> Option<&str> {
+        self.tlvs.iter().find_map(|t| match t {
+            LldpTlv::PORTDESC(s) => Some(s.as_str()),
+            _ => None,
+        })
+    }
+
+    pub fn system_name(&self) -> Option<&str> {
+        self.tlvs.iter().find_map(|t| match t {
+            LldpTlv::SYSNAME(s) => Some(s.as_str()),
+            _ => None,
+        })
+    }
+
+    pub fn system_description(&self) -> Option<&str> {
+        self.tlvs.iter().find_map(|t| match t {
+            LldpTlv::SYSDESC(s) => Some(s.as_str()),
+            _ => None,
+        })
+    }
+
+    /// System and enabled capability bits.
+    pub fn capabilities(&self) -> Option<(u16, u16)> {
+        self.tlvs.iter().find_map(|t| match t {
+            LldpTlv::CAPABILITIES { system, enabled } => Some((*system, *enabled)),
+            _ => None,
+        })
+    }
+
+    pub fn management_addresses(&self) -> Vec<IpAddr> {
+        self.tlvs.iter().filter_map(|t| match t {
+            LldpTlv::MANAGEMENT { address, .. } => *address,
+            _ => None,
+        }).collect()
+    }
+
+    pub fn port_vlan(&self) -> Option<u16> {
+        self.tlvs.iter().find_map(|t| match t {
+            LldpTlv::PORTVLAN(v) => Some(*v),
+            _ => None,
+        })
+    }
+}
+
+impl Display for LldpPdu {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let lines: Vec<String> = self.tlvs.iter().map(|t| t.to_string()).collect();
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tlv(kind: u8, value: &[u8]) -> Vec<u8> {
+        let head = ((kind as u16) << 9) | value.len() as u16;
+        let mut data = head.to_be_bytes().to_vec();
+        data.extend_from_slice(value);
+        data
+    }
+
+    fn lldpdu() -> Vec<u8> {
+        let mut data = tlv(1, &[4, 0x00, 0x11, 0x22, 0x33, 0x44, 0x55]);
+        data.extend(tlv(2, b"\x05Gi0/1"));
+        data.extend(tlv(3, &[0x00, 0x78]));
+        data.extend(tlv(4, b"uplink"));
+        data.extend(tlv(5, b"sw1.example"));
+        data.extend(tlv(6, b"Switch OS 1.0"));
+        data.extend(tlv(7, &[0x00, 0x14, 0x00, 0x04]));
+        data.extend(tlv(8, &[5, 1, 10, 0, 0, 1, 2, 0, 0, 0, 3, 0]));
+        data.extend(tlv(127, &[0x00, 0x80, 0xC2, 1, 0x00, 0x0A]));
+        data.extend(tlv(127, &[0x00, 0x80, 0xC2, 3, 0x00, 0x0A, 4, b'u', b's', b'e', b'r']));
+        data.extend(tlv(127, &[0x00, 0x12, 0x0F, 4, 0x05, 0xEE]));
+        data.extend(tlv(127, &[0x00, 0x12, 0x0F, 1, 0x03, 0x6C, 0x00, 0x00, 0x1E]));
+        data.extend(tlv(127, &[0x00, 0x00, 0x5E, 9, 0xFF]));
+        data.extend(tlv(0, &[]));
+        data
+    }
+
+    #[test]
+    fn test_lldpdu() {
+        let data = lldpdu();
+        let pdu = LldpPdu::parse(&data).unwrap();
+        assert_eq!(pdu.header_len(), data.len());
+        assert_eq!(pdu.tlvs().len(), 13);
+        assert_eq!(pdu.chassis_id(), Some(&LldpId::MAC(MacAddress::new([0x00, 0x11, 0x22, 0x33, 0x44, 0x55]))));
+        assert_eq!(pdu.port_id(), Some(&LldpId::NAME("Gi0/1".to_string())));
+        assert_eq!(pdu.ttl(), Some(120));
+        assert_eq!(pdu.port_description(), Some("uplink"));
+        assert_eq!(pdu.system_name(), Some("sw1.example"));
+        assert_eq!(pdu.system_description(), Some("Switch OS 1.0"));
+        assert_eq!(pdu.capabilities(), Some((0x14, 0x04)));
+        assert_eq!(capability_names(0x14), vec!["Bridge", "Router"]);
+        assert_eq!(pdu.management_addresses(), vec![IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1))]);
+        assert_eq!(pdu.port_vlan(), Some(10));
+        assert_eq!(pdu.tlvs()[9], LldpTlv::VLANNAME { vid: 10, name: "user".to_string() });
+        assert_eq!(pdu.tlvs()[10], LldpTlv::MAXFRAME(1518));
+        assert_eq!(pdu.tlvs()[11], LldpTlv::MACPHY { autoneg: 3, pmd_capability: 0x6C00, mau_type: 30 });
+        assert_eq!(pdu.tlvs()[12], LldpTlv::ORG { oui: 0x00005E, subtype: 9, info: vec![0xFF] });
+        assert!(pdu.to_string().starts_with(
+            "Chassis ID: 00:11:22:33:44:55 (subtype 4)\nPort ID: Gi0/1 (subtype 5)\nTime To Live: 120"
+        ));
+        assert!(pdu.to_string().contains("Capabilities: Bridge, Router (enabled: Bridge)\nManagement Address: 10.0.0.1 (interface 3)"));
+    }
+
+    #[test]
+    fn test_lldpdu_errors() {
+        let data = lldpdu();
+        assert_eq!(LldpPdu::parse(&data[..12]), Err(Error::Truncated(Layer::LLDP, 12)));
+        assert_eq!(LldpPdu::parse_partial(&data[..12]).unwrap().tlvs().len(), 1);
+        assert_eq!(LldpPdu::parse(&data[9..]), Err(Error::Malformed(Layer::LLDP, 0, "missing chassis ID")));
+        assert_eq!(LldpPdu::parse(&tlv(3, &[0x01])), Err(Error::Malformed(Layer::LLDP, 0, "TLV too short")));
+    }
+}
@@ -0,0 +1,83 @@
+use std::fmt::Display;
+
+use byte::bytes_to_u32;
+
+use crate::error::{Error, Layer};
+
+pub const AF_INET: u32 = 2;
+pub const AF_INET6_BSD: u32 = 24;
+pub const AF_INET6_FREEBSD: u32 = 28;
+pub const AF_INET6_DARWIN: u32 = 30;
+
+pub const LOOPBACK_HEADER_LEN: usize = 4;
+
+/// The 4-byte address family header of BSD loopback captures.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct LoopbackHeader {
+    family: u32,
+}
+
+impl LoopbackHeader {
+
+    /// LINKTYPE_LOOP stores the family in network byte order, LINKTYPE_NULL in the
+    /// byte order of the capturing host, which is guessed from the value.
+    pub fn parse(bytes: &[u8], network_order: bool) -> Result<Self, Error> {
+        if bytes.len() < LOOPBACK_HEADER_LEN {
+            return Err(Error::Truncated(Layer::Loopback, bytes.len()));
+        }
+        let mut family = bytes_to_u32(bytes[0], bytes[1], bytes[2], bytes[3], !network_order);
+        if !network_order && family > 0xFFFF {
+            family = family.swap_bytes();
+        }
+        Ok(Self { family })
+    }
+
+    pub fn family(&self) -> u32 {
+        self.family
+    }
+
+    pub fn is_ipv4(&self) -> bool {
+        self.family == AF_INET
+    }
+
+    pub fn is_ipv6(&self) -> bool {
+        matches!(self.family, AF_INET6_BSD | AF_INET6_FREEBSD | AF_INET6_DARWIN)
+    }
+
+    pub fn family_name(&self) -> &'static str {
+        if self.is_ipv4() {
+            "IPv4"
+        } else if self.is_ipv6() {
+            "IPv6"
+        } else {
+            "Unknown"
+        }
+    }
+
+    pub fn header_len(&self) -> usize {
+        LOOPBACK_HEADER_LEN
+    }
+}
+
+impl Display for LoopbackHeader {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Family: {} ({})", self.family, self.family_name())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_loopback_byte_order() {
+        let little = LoopbackHeader::parse(&[0x02, 0x00, 0x00, 0x00], false).unwrap();
+        let big = LoopbackHeader::parse(&[0x00, 0x00, 0x00, 0x1E], false).unwrap();
+        let lo = LoopbackHeader::parse(&[0x00, 0x00, 0x00, 0x18], true).unwrap();
+        assert!(little.is_ipv4());
+        assert_eq!(big.family(), AF_INET6_DARWIN);
+        assert!(lo.is_ipv6());
+        assert_eq!(lo.to_string(), "Family: 24 (IPv6)");
+        assert_eq!(LoopbackHeader::parse(&[0x02], false), Err(Error::Truncated(Layer::Loopback, 1)));
+    }
+}
@@ -1,5 +1,7 @@
 use std::fmt::Display;
 
+use crate::error::{Error, Layer};
+
 pub struct MacAddress {
     bytes: [u8;6],
 }
@@ -20,8 +22,15 @@ impl MacAddress {
         Self{bytes}
     }
 
-    pub fn to_string(&self) -> String {
-        format!(
+    pub fn octets(&self) -> [u8;6] {
+        self.bytes
+    }
+}
+
+impl Display for MacAddress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
             "{:02X?}:{:02X?}:{:02X?}:{:02X?}:{:02X?}:{:02X?}",
             self.bytes[0],
             self.bytes[1],
@@ -33,17 +42,17 @@ impl MacAddress {
     }
 }
 
-impl Display for MacAddress {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.to_string())
-    }
-}
-
 pub struct MacAddressParser {
     buf: [u8;6],
     pos: usize,
 }
 
+impl Default for MacAddressParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl MacAddressParser {
     
     pub fn new() -> Self {
@@ -57,13 +66,13 @@ impl MacAddressParser {
         self.pos == 6
     }
 
-    pub fn set_byte(&mut self, b: u8) {
+    pub fn set_byte(&mut self, b: u8) -> Result<(), Error> {
         if self.done() {
-            println!("Mac Address buffer full");
-        } else {
-            self.buf[self.pos] = b;
-            self.pos += 1;
+            return Err(Error::InvalidPosition(Layer::Mac, self.pos));
         }
+        self.buf[self.pos] = b;
+        self.pos += 1;
+        Ok(())
     }
 
     pub fn get_adress(&self) -> MacAddress {
@@ -95,9 +104,10 @@ mod tests {
         let b = [0xDE, 0xAD, 0xBE, 0xEF, 0x00, 0x00];
 
         for i in b {
-            parser.set_byte(i);
+            parser.set_byte(i).unwrap();
         }
         assert!(parser.done());
+        assert_eq!(parser.set_byte(0), Err(Error::InvalidPosition(Layer::Mac, 6)));
         let addr = MacAddress::new([0xDE, 0xAD, 0xBE, 0xEF, 0x00, 0x00]);
         assert_eq!(addr.to_string(), parser.get_adress().to_string());
         parser.reset();
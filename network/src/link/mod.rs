@@ -1,2 +1,13 @@
 pub mod arp;
+pub mod can;
+pub mod cdp;
+pub mod ieee80211;
+pub mod llc;
+pub mod lldp;
+pub mod loopback;
+pub mod ppp;
+pub mod radiotap;
+pub mod sll;
+pub mod stp;
+pub mod usb;
 pub mod mac;
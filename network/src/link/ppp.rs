@@ -0,0 +1,116 @@
+use std::fmt::Display;
+
+use byte::bytes_to_u16;
+
+use crate::error::{Error, Layer};
+
+pub const PPP_IP: u16 = 0x0021;
+pub const PPP_IPX: u16 = 0x002B;
+pub const PPP_IPV6: u16 = 0x0057;
+pub const PPP_IPCP: u16 = 0x8021;
+pub const PPP_IPV6CP: u16 = 0x8057;
+pub const PPP_LCP: u16 = 0xC021;
+pub const PPP_PAP: u16 = 0xC023;
+pub const PPP_CHAP: u16 = 0xC223;
+
+pub fn protocol_name(protocol: u16) -> &'static str {
+    match protocol {
+        PPP_IP => "IPv4",
+        PPP_IPX => "IPX",
+        PPP_IPV6 => "IPv6",
+        PPP_IPCP => "IP Control Protocol",
+        PPP_IPV6CP => "IPv6 Control Protocol",
+        PPP_LCP => "Link Control Protocol",
+        PPP_PAP => "Password Authentication Protocol",
+        PPP_CHAP => "Challenge Handshake Authentication Protocol",
+        _ => "Unknown",
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct PppHeader {
+    direction: Option<u8>,
+    framed: bool,
+    protocol: u16,
+    len: usize,
+}
+
+impl PppHeader {
+
+    /// Address and control bytes are optional, and the protocol field may be compressed
+    /// to a single byte. `with_direction` expects the leading byte of LINKTYPE_PPP_WITH_DIR.
+    pub fn parse(bytes: &[u8], with_direction: bool) -> Result<Self, Error> {
+        let mut pos = 0;
+        let direction = if with_direction {
+            pos += 1;
+            Some(*bytes.first().ok_or(Error::Truncated(Layer::PPP, 0))?)
+        } else {
+            None
+        };
+        let framed = bytes[pos..].starts_with(&[0xFF, 0x03]);
+        if framed {
+            pos += 2;
+        }
+        let protocol = match bytes.get(pos) {
+            Some(b) if b & 0x01 == 1 => {
+                pos += 1;
+                *b as u16
+            },
+            Some(_) if pos + 2 <= bytes.len() => {
+                pos += 2;
+                bytes_to_u16(bytes[pos - 2], bytes[pos - 1], false)
+            },
+            _ => return Err(Error::Truncated(Layer::PPP, bytes.len())),
+        };
+        Ok(Self { direction, framed, protocol, len: pos })
+    }
+
+    /// True for packets sent by the capturing host.
+    pub fn is_sent(&self) -> Option<bool> {
+        self.direction.map(|d| d != 0)
+    }
+
+    /// Whether the HDLC address and control bytes were present.
+    pub fn is_framed(&self) -> bool {
+        self.framed
+    }
+
+    pub fn protocol(&self) -> u16 {
+        self.protocol
+    }
+
+    pub fn header_len(&self) -> usize {
+        self.len
+    }
+}
+
+impl Display for PppHeader {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(sent) = self.is_sent() {
+            writeln!(f, "Direction: {}", if sent { "Sent" } else { "Received" })?;
+        }
+        write!(f, "Protocol: {:#06x} ({})", self.protocol, protocol_name(self.protocol))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ppp() {
+        let ppp = PppHeader::parse(&[0xFF, 0x03, 0x00, 0x21, 0x45], false).unwrap();
+        assert!(ppp.is_framed());
+        assert_eq!(ppp.protocol(), PPP_IP);
+        assert_eq!(ppp.header_len(), 4);
+        assert_eq!(ppp.is_sent(), None);
+
+        let ppp = PppHeader::parse(&[0x01, 0x57, 0x60], true).unwrap();
+        assert!(!ppp.is_framed());
+        assert_eq!(ppp.protocol(), PPP_IPV6);
+        assert_eq!(ppp.header_len(), 2);
+        assert_eq!(ppp.to_string(), "Direction: Sent\nProtocol: 0x0057 (IPv6)");
+
+        assert_eq!(PppHeader::parse(&[0xFF, 0x03, 0xC0], false), Err(Error::Truncated(Layer::PPP, 3)));
+    }
+}
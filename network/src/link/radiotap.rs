@@ -0,0 +1,214 @@
+use std::fmt::Display;
+
+use byte::{bytes_to_u16, bytes_to_u32};
+
+use crate::error::{Error, Layer};
+
+const PRESENT_TSFT: u32 = 1 << 0;
+const PRESENT_FLAGS: u32 = 1 << 1;
+const PRESENT_RATE: u32 = 1 << 2;
+const PRESENT_CHANNEL: u32 = 1 << 3;
+const PRESENT_FHSS: u32 = 1 << 4;
+const PRESENT_SIGNAL: u32 = 1 << 5;
+const PRESENT_NOISE: u32 = 1 << 6;
+const PRESENT_EXT: u32 = 1 << 31;
+
+/// The 802.11 frame is followed by its 4-byte FCS.
+pub const FLAG_FCS: u8 = 0x10;
+
+/// Radiotap capture header. Only the fields up to the antenna noise are decoded, the
+/// rest is skipped using the header length.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct RadiotapHeader {
+    length: u16,
+    present: Vec<u32>,
+    tsft: Option<u64>,
+    flags: Option<u8>,
+    rate: Option<u8>,
+    channel: Option<(u16, u16)>,
+    signal: Option<i8>,
+    noise: Option<i8>,
+}
+
+impl RadiotapHeader {
+
+    pub fn parse(bytes: &[u8]) -> Result<Self, Error> {
+        Self::parse_fields(bytes, false)
+    }
+
+    pub fn parse_partial(bytes: &[u8]) -> Result<Self, Error> {
+        Self::parse_fields(bytes, true)
+    }
+
+    fn parse_fields(bytes: &[u8], partial: bool) -> Result<Self, Error> {
+        if bytes.len() < 8 {
+            return Err(Error::Truncated(Layer::Radiotap, bytes.len()));
+        }
+        if bytes[0] != 0 {
+            return Err(Error::Malformed(Layer::Radiotap, 0, "unsupported version"));
+        }
+        let length = bytes_to_u16(bytes[2], bytes[3], true);
+        let cut = bytes.len() < length as usize;
+        if cut && !partial {
+            return Err(Error::Truncated(Layer::Radiotap, bytes.len()));
+        }
+        let b = &bytes[..bytes.len().min(length as usize)];
+        let mut present = vec![];
+        let mut pos = 4;
+        loop {
+            if pos + 4 > b.len() && cut {
+                return Err(Error::Truncated(Layer::Radiotap, bytes.len()));
+            }
+            if pos + 4 > b.len() {
+                return Err(Error::Malformed(Layer::Radiotap, pos, "present bitmap out of bounds"));
+            }
+            let word = bytes_to_u32(b[pos], b[pos + 1], b[pos + 2], b[pos + 3], true);
+            present.push(word);
+            pos += 4;
+            if word & PRESENT_EXT == 0 {
+                break;
+            }
+        }
+        let mut header = Self {
+            length,
+            present,
+            tsft: None,
+            flags: None,
+            rate: None,
+            channel: None,
+            signal: None,
+            noise: None,
+        };
+        let first = header.present[0];
+        let mut missing = false;
+        // Each field is aligned to its natural size, relative to the start of the header.
+        let mut field = |bit: u32, size: usize, align: usize| -> Result<Option<&[u8]>, Error> {
+            if first & bit == 0 || missing {
+                return Ok(None);
+            }
+            pos = pos.next_multiple_of(align);
+            if pos + size > b.len() && cut {
+                missing = true;
+                return Ok(None);
+            }
+            if pos + size > b.len() {
+                return Err(Error::Malformed(Layer::Radiotap, pos, "field out of bounds"));
+            }
+            pos += size;
+            Ok(Some(&b[pos - size..pos]))
+        };
+        header.tsft = field(PRESENT_TSFT, 8, 8)?.map(|f| u64::from_le_bytes(f.try_into().unwrap()));
+        header.flags = field(PRESENT_FLAGS, 1, 1)?.map(|f| f[0]);
+        header.rate = field(PRESENT_RATE, 1, 1)?.map(|f| f[0]);
+        header.channel = field(PRESENT_CHANNEL, 4, 2)?
+            .map(|f| (bytes_to_u16(f[0], f[1], true), bytes_to_u16(f[2], f[3], true)));
+        field(PRESENT_FHSS, 2, 1)?;
+        header.signal = field(PRESENT_SIGNAL, 1, 1)?.map(|f| f[0] as i8);
+        header.noise = field(PRESENT_NOISE, 1, 1)?.map(|f| f[0] as i8);
+        Ok(header)
+    }
+
+    pub fn present(&self) -> &[u32] {
+        &self.present
+    }
+
+    /// Microseconds on the MAC's timer when the first bit arrived.
+    pub fn tsft(&self) -> Option<u64> {
+        self.tsft
+    }
+
+    pub fn flags(&self) -> Option<u8> {
+        self.flags
+    }
+
+    pub fn has_fcs(&self) -> bool {
+        self.flags.is_some_and(|f| f & FLAG_FCS != 0)
+    }
+
+    /// Data rate in units of 500 kb/s.
+    pub fn rate(&self) -> Option<u8> {
+        self.rate
+    }
+
+    /// Channel frequency in MHz.
+    pub fn frequency(&self) -> Option<u16> {
+        self.channel.map(|(freq, _)| freq)
+    }
+
+    pub fn channel_flags(&self) -> Option<u16> {
+        self.channel.map(|(_, flags)| flags)
+    }
+
+    /// Antenna signal in dBm.
+    pub fn signal(&self) -> Option<i8> {
+        self.signal
+    }
+
+    /// Antenna noise in dBm.
+    pub fn noise(&self) -> Option<i8> {
+        self.noise
+    }
+
+    pub fn header_len(&self) -> usize {
+        self.length as usize
+    }
+}
+
+impl Display for RadiotapHeader {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Length: {}\nPresent: {:#010x}", self.length, self.present[0])?;
+        if let Some(tsft) = self.tsft {
+            write!(f, "\nTSFT: {} us", tsft)?;
+        }
+        if let Some(flags) = self.flags {
+            write!(f, "\nFlags: {:#04x}", flags)?;
+        }
+        if let Some(rate) = self.rate {
+            write!(f, "\nRate: {:.1} Mb/s", rate as f64 / 2.0)?;
+        }
+        if let Some(freq) = self.frequency() {
+            write!(f, "\nChannel: {} MHz", freq)?;
+        }
+        if let Some(signal) = self.signal {
+            write!(f, "\nAntenna Signal: {} dBm", signal)?;
+        }
+        if let Some(noise) = self.noise {
+            write!(f, "\nAntenna Noise: {} dBm", noise)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_radiotap() {
+        let bytes = [
+            0x00, 0x00, 0x12, 0x00, 0x6E, 0x00, 0x00, 0x00,
+            0x10, 0x0C, 0x6C, 0x09, 0xA0, 0x00, 0xD8, 0xA1,
+            0x00, 0x00,
+        ];
+        let header = RadiotapHeader::parse(&bytes).unwrap();
+        assert_eq!(header.header_len(), 18);
+        assert!(header.has_fcs());
+        assert_eq!(header.rate(), Some(12));
+        assert_eq!(header.frequency(), Some(2412));
+        assert_eq!(header.signal(), Some(-40));
+        assert_eq!(header.noise(), Some(-95));
+        assert_eq!(header.tsft(), None);
+        assert_eq!(
+            header.to_string(),
+            "Length: 18\nPresent: 0x0000006e\nFlags: 0x10\nRate: 6.0 Mb/s\nChannel: 2412 MHz\nAntenna Signal: -40 dBm\nAntenna Noise: -95 dBm"
+        );
+        assert_eq!(RadiotapHeader::parse(&bytes[..12]), Err(Error::Truncated(Layer::Radiotap, 12)));
+        let partial = RadiotapHeader::parse_partial(&bytes[..12]).unwrap();
+        assert_eq!((partial.flags(), partial.rate()), (Some(0x10), Some(12)));
+        assert_eq!((partial.frequency(), partial.signal()), (None, None));
+        assert_eq!(
+            RadiotapHeader::parse(&[0x00, 0x00, 0x08, 0x00, 0x01, 0x00, 0x00, 0x00]),
+            Err(Error::Malformed(Layer::Radiotap, 8, "field out of bounds"))
+        );
+    }
+}
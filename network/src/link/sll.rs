@@ -0,0 +1,164 @@
+use std::fmt::Display;
+
+use byte::{bytes_to_u16, bytes_to_u32};
+
+use crate::error::{Error, Layer};
+use crate::ethernet_frame::MacAddress;
+
+pub const SLL_HEADER_LEN: usize = 16;
+pub const SLL2_HEADER_LEN: usize = 20;
+
+/// Protocol values below 1536 name a framing instead of an ethertype.
+pub const PROTO_802_3: u16 = 0x0001;
+pub const PROTO_802_2: u16 = 0x0004;
+pub const PROTO_CAN: u16 = 0x000C;
+pub const PROTO_CANFD: u16 = 0x000D;
+
+fn u16_at(b: &[u8], i: usize) -> u16 {
+    bytes_to_u16(b[i], b[i + 1], false)
+}
+
+pub fn packet_type_name(packet_type: u16) -> &'static str {
+    match packet_type {
+        0 => "Unicast to us",
+        1 => "Broadcast",
+        2 => "Multicast",
+        3 => "Unicast to another host",
+        4 => "Sent by us",
+        _ => "Unknown",
+    }
+}
+
+/// Linux "cooked" capture header, written for `any` and other non-Ethernet devices.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SllHeader {
+    version: u8,
+    packet_type: u16,
+    arphrd_type: u16,
+    address: Vec<u8>,
+    protocol: u16,
+    if_index: Option<u32>,
+}
+
+impl SllHeader {
+
+    pub fn parse(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() < SLL_HEADER_LEN {
+            return Err(Error::Truncated(Layer::SLL, bytes.len()));
+        }
+        let addr_len = (u16_at(bytes, 4) as usize).min(8);
+        Ok(Self {
+            version: 1,
+            packet_type: u16_at(bytes, 0),
+            arphrd_type: u16_at(bytes, 2),
+            address: bytes[6..6 + addr_len].to_vec(),
+            protocol: u16_at(bytes, 14),
+            if_index: None,
+        })
+    }
+
+    pub fn parse_v2(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() < SLL2_HEADER_LEN {
+            return Err(Error::Truncated(Layer::SLL, bytes.len()));
+        }
+        let addr_len = (bytes[11] as usize).min(8);
+        Ok(Self {
+            version: 2,
+            packet_type: bytes[10] as u16,
+            arphrd_type: u16_at(bytes, 8),
+            address: bytes[12..12 + addr_len].to_vec(),
+            protocol: u16_at(bytes, 0),
+            if_index: Some(bytes_to_u32(bytes[4], bytes[5], bytes[6], bytes[7], false)),
+        })
+    }
+
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+
+    pub fn packet_type(&self) -> u16 {
+        self.packet_type
+    }
+
+    /// ARPHRD_* type of the capturing device, 1 for Ethernet.
+    pub fn arphrd_type(&self) -> u16 {
+        self.arphrd_type
+    }
+
+    pub fn address(&self) -> &[u8] {
+        &self.address
+    }
+
+    pub fn mac(&self) -> Option<MacAddress> {
+        let a: [u8;6] = self.address.as_slice().try_into().ok()?;
+        Some(MacAddress::new(a))
+    }
+
+    /// Ethertype, or one of the PROTO_* framings.
+    pub fn protocol(&self) -> u16 {
+        self.protocol
+    }
+
+    pub fn if_index(&self) -> Option<u32> {
+        self.if_index
+    }
+
+    pub fn header_len(&self) -> usize {
+        if self.version == 2 { SLL2_HEADER_LEN } else { SLL_HEADER_LEN }
+    }
+}
+
+impl Display for SllHeader {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Packet Type: {} ({})", packet_type_name(self.packet_type), self.packet_type)?;
+        writeln!(f, "Link-Layer Address Type: {}", self.arphrd_type)?;
+        match self.mac() {
+            Some(mac) => writeln!(f, "Link-Layer Address: {}", mac)?,
+            None => {
+                let hex: Vec<String> = self.address.iter().map(|b| format!("{:02X}", b)).collect();
+                writeln!(f, "Link-Layer Address: {}", hex.join(":"))?;
+            },
+        }
+        if let Some(index) = self.if_index {
+            writeln!(f, "Interface Index: {}", index)?;
+        }
+        write!(f, "Protocol: {:#06x}", self.protocol)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sll() {
+        let sll = SllHeader::parse(&[
+            0x00, 0x04, 0x00, 0x01, 0x00, 0x06,
+            0xB1, 0xB1, 0xB1, 0xB1, 0xB1, 0xB1, 0x00, 0x00,
+            0x08, 0x00,
+        ]).unwrap();
+        assert_eq!(sll.packet_type(), 4);
+        assert_eq!(sll.protocol(), 0x0800);
+        assert_eq!(sll.mac(), Some(MacAddress::new([0xB1;6])));
+        assert_eq!(
+            sll.to_string(),
+            "Packet Type: Sent by us (4)\nLink-Layer Address Type: 1\nLink-Layer Address: B1:B1:B1:B1:B1:B1\nProtocol: 0x0800"
+        );
+        assert_eq!(SllHeader::parse(&[0;15]), Err(Error::Truncated(Layer::SLL, 15)));
+    }
+
+    #[test]
+    fn test_sll2() {
+        let sll = SllHeader::parse_v2(&[
+            0x86, 0xDD, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03,
+            0x03, 0x04, 0x00, 0x02, 0xAB, 0xCD, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00,
+        ]).unwrap();
+        assert_eq!(sll.protocol(), 0x86DD);
+        assert_eq!(sll.if_index(), Some(3));
+        assert_eq!(sll.arphrd_type(), 0x0304);
+        assert_eq!(sll.mac(), None);
+        assert_eq!(sll.header_len(), 20);
+        assert!(sll.to_string().contains("Link-Layer Address: AB:CD\nInterface Index: 3\n"));
+    }
+}
@@ -0,0 +1,542 @@
+use std::fmt::Display;
+
+use byte::{bytes_to_u16, bytes_to_u32};
+
+use crate::error::{Error, Layer};
+use crate::ethernet_frame::MacAddress;
+
+pub const BPDU_CONFIG: u8 = 0x00;
+pub const BPDU_TCN: u8 = 0x80;
+pub const BPDU_RST: u8 = 0x02;
+
+pub const VERSION_STP: u8 = 0;
+pub const VERSION_RSTP: u8 = 2;
+pub const VERSION_MSTP: u8 = 3;
+
+/// Length of a rapid spanning tree BPDU, the longest without the MST extension.
+pub const RST_BPDU_LEN: usize = 36;
+
+// Offsets of the MST extension, counted from the protocol identifier.
+const MST_CONFIG_ID: usize = 38;
+const MST_CIST_COST: usize = 89;
+const MST_CIST_BRIDGE: usize = 93;
+const MST_REMAINING_HOPS: usize = 101;
+const MST_MSTI: usize = 102;
+const MSTI_LEN: usize = 16;
+
+fn u16_at(b: &[u8], i: usize) -> u16 {
+    bytes_to_u16(b[i], b[i + 1], false)
+}
+
+fn u32_at(b: &[u8], i: usize) -> u32 {
+    bytes_to_u32(b[i], b[i + 1], b[i + 2], b[i + 3], false)
+}
+
+fn seconds(raw: u16) -> f64 {
+    raw as f64 / 256.0
+}
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct BridgeId {
+    priority: u16,
+    mac: MacAddress,
+}
+
+impl BridgeId {
+
+    pub fn new(priority: u16, mac: MacAddress) -> Self {
+        Self { priority, mac }
+    }
+
+    fn parse(b: &[u8]) -> Self {
+        Self::new(u16_at(b, 0), MacAddress::new([b[2], b[3], b[4], b[5], b[6], b[7]]))
+    }
+
+    /// Bridge priority without the system ID extension.
+    pub fn priority(&self) -> u16 {
+        self.priority & 0xF000
+    }
+
+    /// System ID extension, the VLAN or MST instance the ID belongs to.
+    pub fn system_id(&self) -> u16 {
+        self.priority & 0x0FFF
+    }
+
+    pub fn mac(&self) -> &MacAddress {
+        &self.mac
+    }
+}
+
+impl Display for BridgeId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} / {} / {}", self.priority(), self.system_id(), self.mac)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PortRole {
+    /// Master port in MSTI records, unknown in RSTP BPDUs.
+    MASTER,
+    ALTERNATE,
+    ROOT,
+    DESIGNATED,
+}
+
+impl Display for PortRole {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MASTER => write!(f, "Master"),
+            Self::ALTERNATE => write!(f, "Alternate/Backup"),
+            Self::ROOT => write!(f, "Root"),
+            Self::DESIGNATED => write!(f, "Designated"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct BpduFlags(u8);
+
+impl BpduFlags {
+
+    pub fn new(b: u8) -> Self {
+        Self(b)
+    }
+
+    pub fn bits(&self) -> u8 {
+        self.0
+    }
+
+    pub fn topology_change(&self) -> bool {
+        self.0 & 0x01 != 0
+    }
+
+    pub fn proposal(&self) -> bool {
+        self.0 & 0x02 != 0
+    }
+
+    pub fn port_role(&self) -> PortRole {
+        match (self.0 >> 2) & 0x03 {
+            1 => PortRole::ALTERNATE,
+            2 => PortRole::ROOT,
+            3 => PortRole::DESIGNATED,
+            _ => PortRole::MASTER,
+        }
+    }
+
+    pub fn learning(&self) -> bool {
+        self.0 & 0x10 != 0
+    }
+
+    pub fn forwarding(&self) -> bool {
+        self.0 & 0x20 != 0
+    }
+
+    pub fn agreement(&self) -> bool {
+        self.0 & 0x40 != 0
+    }
+
+    pub fn topology_change_ack(&self) -> bool {
+        self.0 & 0x80 != 0
+    }
+}
+
+impl Display for BpduFlags {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut names = vec![];
+        if self.topology_change_ack() {
+            names.push("Topology Change Ack".to_string());
+        }
+        if self.agreement() {
+            names.push("Agreement".to_string());
+        }
+        if self.forwarding() {
+            names.push("Forwarding".to_string());
+        }
+        if self.learning() {
+            names.push("Learning".to_string());
+        }
+        if self.proposal() {
+            names.push("Proposal".to_string());
+        }
+        if self.topology_change() {
+            names.push("Topology Change".to_string());
+        }
+        names.push(format!("Port Role: {}", self.port_role()));
+        write!(f, "{:#04x} ({})", self.0, names.join(", "))
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct MstiRecord {
+    pub flags: BpduFlags,
+    pub regional_root: BridgeId,
+    pub internal_root_path_cost: u32,
+    pub bridge_priority: u8,
+    pub port_priority: u8,
+    pub remaining_hops: u8,
+}
+
+impl MstiRecord {
+
+    pub fn instance(&self) -> u16 {
+        self.regional_root.system_id()
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct MstConfig {
+    pub selector: u8,
+    pub name: String,
+    pub revision: u16,
+    pub digest: [u8;16],
+    pub cist_internal_root_path_cost: u32,
+    pub cist_bridge: BridgeId,
+    pub cist_remaining_hops: u8,
+    pub mstis: Vec<MstiRecord>,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Bpdu {
+    version: u8,
+    bpdu_type: u8,
+    flags: Option<BpduFlags>,
+    root: Option<BridgeId>,
+    root_path_cost: Option<u32>,
+    bridge: Option<BridgeId>,
+    port_id: Option<u16>,
+    message_age: Option<u16>,
+    max_age: Option<u16>,
+    hello_time: Option<u16>,
+    forward_delay: Option<u16>,
+    mst: Option<MstConfig>,
+    len: usize,
+}
+
+impl Bpdu {
+
+    /// `bytes` starts at the protocol identifier, right after the LLC header.
+    pub fn parse(bytes: &[u8]) -> Result<Self, Error> {
+        Self::parse_fields(bytes, false)
+    }
+
+    pub fn parse_partial(bytes: &[u8]) -> Result<Self, Error> {
+        Self::parse_fields(bytes, true)
+    }
+
+    fn parse_fields(bytes: &[u8], partial: bool) -> Result<Self, Error> {
+        if bytes.len() < 4 {
+            return Err(Error::Truncated(Layer::STP, bytes.len()));
+        }
+        if u16_at(bytes, 0) != 0 {
+            return Err(Error::Malformed(Layer::STP, 0, "unknown protocol identifier"));
+        }
+        let mut bpdu = Self {
+            version: bytes[2],
+            bpdu_type: bytes[3],
+            flags: None,
+            root: None,
+            root_path_cost: None,
+            bridge: None,
+            port_id: None,
+            message_age: None,
+            max_age: None,
+            hello_time: None,
+            forward_delay: None,
+            mst: None,
+            len: 4,
+        };
+        let len = match bpdu.bpdu_type {
+            BPDU_TCN => return Ok(bpdu),
+            BPDU_CONFIG => 35,
+            BPDU_RST => RST_BPDU_LEN,
+            _ => return Err(Error::Malformed(Layer::STP, 3, "unknown BPDU type")),
+        };
+        if bytes.len() < len && !partial {
+            return Err(Error::Truncated(Layer::STP, bytes.len()));
+        }
+        let u16_field = |i: usize| bytes.get(i..i + 2).map(|b| u16_at(b, 0));
+        bpdu.flags = bytes.get(4).map(|b| BpduFlags::new(*b));
+        bpdu.root = bytes.get(5..13).map(BridgeId::parse);
+        bpdu.root_path_cost = bytes.get(13..17).map(|b| u32_at(b, 0));
+        bpdu.bridge = bytes.get(17..25).map(BridgeId::parse);
+        bpdu.port_id = u16_field(25);
+        bpdu.message_age = u16_field(27);
+        bpdu.max_age = u16_field(29);
+        bpdu.hello_time = u16_field(31);
+        bpdu.forward_delay = u16_field(33);
+        bpdu.len = len;
+        if bpdu.bpdu_type == BPDU_RST && bpdu.version >= VERSION_MSTP && bytes.len() > len {
+            match bpdu.parse_mst(bytes) {
+                Err(Error::Truncated(..)) if partial => (),
+                r => r?,
+            }
+        }
+        Ok(bpdu)
+    }
+
+    fn parse_mst(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        if bytes.len() < MST_MSTI {
+            return Err(Error::Truncated(Layer::STP, bytes.len()));
+        }
+        let v3_len = u16_at(bytes, 36) as usize;
+        if v3_len < MST_MSTI - MST_CONFIG_ID || !(v3_len - (MST_MSTI - MST_CONFIG_ID)).is_multiple_of(MSTI_LEN) {
+            return Err(Error::Malformed(Layer::STP, 36, "invalid version 3 length"));
+        }
+        let end = MST_CONFIG_ID + v3_len;
+        if bytes.len() < end {
+            return Err(Error::Truncated(Layer::STP, bytes.len()));
+        }
+        let name = &bytes[MST_CONFIG_ID + 1..MST_CONFIG_ID + 33];
+        let name = String::from_utf8_lossy(name).trim_end_matches('\0').to_string();
+        let mut digest = [0u8;16];
+        digest.copy_from_slice(&bytes[MST_CONFIG_ID + 35..MST_CIST_COST]);
+        let mstis = bytes[MST_MSTI..end]
+            .chunks_exact(MSTI_LEN)
+            .map(|r| MstiRecord {
+                flags: BpduFlags::new(r[0]),
+                regional_root: BridgeId::parse(&r[1..]),
+                internal_root_path_cost: u32_at(r, 9),
+                bridge_priority: r[13],
+                port_priority: r[14],
+                remaining_hops: r[15],
+            })
+            .collect();
+        self.mst = Some(MstConfig {
+            selector: bytes[MST_CONFIG_ID],
+            name,
+            revision: u16_at(bytes, MST_CONFIG_ID + 33),
+            digest,
+            cist_internal_root_path_cost: u32_at(bytes, MST_CIST_COST),
+            cist_bridge: BridgeId::parse(&bytes[MST_CIST_BRIDGE..]),
+            cist_remaining_hops: bytes[MST_REMAINING_HOPS],
+            mstis,
+        });
+        self.len = end;
+        Ok(())
+    }
+
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+
+    pub fn version_name(&self) -> &'static str {
+        match self.version {
+            VERSION_STP => "Spanning Tree",
+            VERSION_RSTP => "Rapid Spanning Tree",
+            VERSION_MSTP => "Multiple Spanning Tree",
+            _ => "Unknown",
+        }
+    }
+
+    pub fn bpdu_type(&self) -> u8 {
+        self.bpdu_type
+    }
+
+    pub fn is_tcn(&self) -> bool {
+        self.bpdu_type == BPDU_TCN
+    }
+
+    /// Whether the BPDU announces or acknowledges a topology change.
+    pub fn topology_change(&self) -> bool {
+        self.is_tcn() || self.flags.is_some_and(|f| f.topology_change() || f.topology_change_ack())
+    }
+
+    pub fn flags(&self) -> Option<BpduFlags> {
+        self.flags
+    }
+
+    pub fn port_role(&self) -> Option<PortRole> {
+        match self.bpdu_type {
+            BPDU_RST => self.flags.map(|f| f.port_role()),
+            _ => None,
+        }
+    }
+
+    pub fn root(&self) -> Option<&BridgeId> {
+        self.root.as_ref()
+    }
+
+    pub fn root_path_cost(&self) -> Option<u32> {
+        self.root_path_cost
+    }
+
+    pub fn bridge(&self) -> Option<&BridgeId> {
+        self.bridge.as_ref()
+    }
+
+    pub fn port_id(&self) -> Option<u16> {
+        self.port_id
+    }
+
+    /// Timers are carried in units of 1/256 second.
+    pub fn message_age(&self) -> Option<u16> {
+        self.message_age
+    }
+
+    pub fn max_age(&self) -> Option<u16> {
+        self.max_age
+    }
+
+    pub fn hello_time(&self) -> Option<u16> {
+        self.hello_time
+    }
+
+    pub fn forward_delay(&self) -> Option<u16> {
+        self.forward_delay
+    }
+
+    pub fn mst(&self) -> Option<&MstConfig> {
+        self.mst.as_ref()
+    }
+
+    pub fn header_len(&self) -> usize {
+        self.len
+    }
+}
+
+impl Display for Bpdu {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let type_name = match self.bpdu_type {
+            BPDU_CONFIG => "Configuration",
+            BPDU_TCN => "Topology Change Notification",
+            _ => "Rapid/Multiple Spanning Tree",
+        };
+        write!(f, "Protocol Version: {} ({})\nBPDU Type: {:#04x} ({})", self.version, self.version_name(), self.bpdu_type, type_name)?;
+        if let Some(flags) = self.flags {
+            write!(f, "\nFlags: {}", flags)?;
+        }
+        if let Some(root) = &self.root {
+            write!(f, "\nRoot Identifier: {}", root)?;
+        }
+        if let Some(cost) = self.root_path_cost {
+            write!(f, "\nRoot Path Cost: {}", cost)?;
+        }
+        if let Some(bridge) = &self.bridge {
+            write!(f, "\nBridge Identifier: {}", bridge)?;
+        }
+        if let Some(port) = self.port_id {
+            write!(f, "\nPort Identifier: {:#06x}", port)?;
+        }
+        for (name, timer) in [
+            ("Message Age", self.message_age),
+            ("Max Age", self.max_age),
+            ("Hello Time", self.hello_time),
+            ("Forward Delay", self.forward_delay),
+        ] {
+            if let Some(t) = timer {
+                write!(f, "\n{}: {}", name, seconds(t))?;
+            }
+        }
+        if let Some(mst) = &self.mst {
+            write!(
+                f,
+                "\nMST Config Name: {}\nMST Config Revision: {}\nCIST Internal Root Path Cost: {}\nCIST Bridge Identifier: {}\nCIST Remaining Hops: {}",
+                mst.name, mst.revision, mst.cist_internal_root_path_cost, mst.cist_bridge, mst.cist_remaining_hops
+            )?;
+            for msti in mst.mstis.iter() {
+                write!(
+                    f,
+                    "\nMSTI {}: Regional Root {}, Internal Root Path Cost {}, Role {}, Remaining Hops {}",
+                    msti.instance(), msti.regional_root, msti.internal_root_path_cost, msti.flags.port_role(), msti.remaining_hops
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(version: u8, bpdu_type: u8, flags: u8) -> Vec<u8> {
+        let mut data = vec![0x00, 0x00, version, bpdu_type, flags];
+        data.extend_from_slice(&[0x80, 0x00, 0x00, 0x11, 0x22, 0x33, 0x44, 0x55]);
+        data.extend_from_slice(&[0x00, 0x00, 0x4E, 0x20]);
+        data.extend_from_slice(&[0x80, 0x01, 0x00, 0xAA, 0xBB, 0xCC, 0xDD, 0xEE]);
+        data.extend_from_slice(&[0x80, 0x02, 0x01, 0x00, 0x14, 0x00, 0x02, 0x00, 0x0F, 0x00]);
+        data
+    }
+
+    #[test]
+    fn test_config_and_tcn() {
+        let bpdu = Bpdu::parse(&config(VERSION_STP, BPDU_CONFIG, 0x01)).unwrap();
+        assert_eq!(bpdu.header_len(), 35);
+        assert_eq!(bpdu.root().unwrap().priority(), 32768);
+        assert_eq!(bpdu.root().unwrap().mac().octets(), [0x00, 0x11, 0x22, 0x33, 0x44, 0x55]);
+        assert_eq!(bpdu.bridge().unwrap().system_id(), 1);
+        assert_eq!(bpdu.root_path_cost(), Some(20000));
+        assert_eq!(bpdu.port_id(), Some(0x8002));
+        assert_eq!((bpdu.message_age(), bpdu.max_age()), (Some(256), Some(5120)));
+        assert!(bpdu.topology_change());
+        assert_eq!(bpdu.port_role(), None);
+        assert!(bpdu.to_string().contains("Root Identifier: 32768 / 0 / 00:11:22:33:44:55\nRoot Path Cost: 20000"));
+        assert!(bpdu.to_string().contains("Message Age: 1\nMax Age: 20\nHello Time: 2\nForward Delay: 15"));
+
+        let bpdu = Bpdu::parse(&[0x00, 0x00, 0x00, 0x80]).unwrap();
+        assert!(bpdu.is_tcn());
+        assert!(bpdu.topology_change());
+        assert_eq!(bpdu.header_len(), 4);
+        assert_eq!(bpdu.to_string(), "Protocol Version: 0 (Spanning Tree)\nBPDU Type: 0x80 (Topology Change Notification)");
+
+        assert_eq!(Bpdu::parse(&config(0, 0, 0)[..20]), Err(Error::Truncated(Layer::STP, 20)));
+        let partial = Bpdu::parse_partial(&config(0, 0, 0)[..20]).unwrap();
+        assert_eq!(partial.root_path_cost(), Some(20000));
+        assert_eq!((partial.bridge(), partial.port_id()), (None, None));
+        assert!(partial.to_string().ends_with("Root Path Cost: 20000"));
+        assert_eq!(Bpdu::parse(&[0x00, 0x01, 0x00, 0x00]), Err(Error::Malformed(Layer::STP, 0, "unknown protocol identifier")));
+        assert_eq!(Bpdu::parse(&[0x00, 0x00, 0x00, 0x07]), Err(Error::Malformed(Layer::STP, 3, "unknown BPDU type")));
+    }
+
+    #[test]
+    fn test_rstp_flags() {
+        let mut data = config(VERSION_RSTP, BPDU_RST, 0x7C);
+        data.push(0);
+        let bpdu = Bpdu::parse(&data).unwrap();
+        assert_eq!(bpdu.header_len(), 36);
+        let flags = bpdu.flags().unwrap();
+        assert!(flags.agreement() && flags.forwarding() && flags.learning());
+        assert!(!flags.proposal() && !flags.topology_change());
+        assert_eq!(bpdu.port_role(), Some(PortRole::DESIGNATED));
+        assert!(!bpdu.topology_change());
+        assert!(bpdu.to_string().contains("Flags: 0x7c (Agreement, Forwarding, Learning, Port Role: Designated)"));
+    }
+
+    #[test]
+    fn test_mstp() {
+        let mut data = config(VERSION_MSTP, BPDU_RST, 0x08);
+        data.push(0);
+        data.extend_from_slice(&[0x00, 0x50]);
+        data.push(0);
+        let mut name = b"region1".to_vec();
+        name.resize(32, 0);
+        data.extend_from_slice(&name);
+        data.extend_from_slice(&[0x00, 0x02]);
+        data.extend_from_slice(&[0xAC;16]);
+        data.extend_from_slice(&[0x00, 0x00, 0x07, 0xD0]);
+        data.extend_from_slice(&[0x80, 0x00, 0x00, 0xAA, 0xBB, 0xCC, 0xDD, 0xEE]);
+        data.push(20);
+        data.push(0x7C);
+        data.extend_from_slice(&[0x80, 0x05, 0x00, 0x11, 0x22, 0x33, 0x44, 0x55]);
+        data.extend_from_slice(&[0x00, 0x00, 0x00, 0x00, 0x80, 0x80, 19]);
+        let bpdu = Bpdu::parse(&data).unwrap();
+        assert_eq!(bpdu.header_len(), 118);
+        assert_eq!(bpdu.port_role(), Some(PortRole::ROOT));
+        let mst = bpdu.mst().unwrap();
+        assert_eq!(mst.name, "region1");
+        assert_eq!(mst.revision, 2);
+        assert_eq!(mst.cist_internal_root_path_cost, 2000);
+        assert_eq!(mst.cist_remaining_hops, 20);
+        assert_eq!(mst.mstis.len(), 1);
+        assert_eq!(mst.mstis[0].instance(), 5);
+        assert_eq!(mst.mstis[0].flags.port_role(), PortRole::DESIGNATED);
+        assert!(bpdu.to_string().ends_with(
+            "MSTI 5: Regional Root 32768 / 5 / 00:11:22:33:44:55, Internal Root Path Cost 0, Role Designated, Remaining Hops 19"
+        ));
+
+        data[37] = 0x51;
+        assert_eq!(Bpdu::parse(&data), Err(Error::Malformed(Layer::STP, 36, "invalid version 3 length")));
+        data[37] = 0x50;
+        assert_eq!(Bpdu::parse(&data[..110]), Err(Error::Truncated(Layer::STP, 110)));
+    }
+}
@@ -0,0 +1,167 @@
+use std::fmt::Display;
+
+use byte::{bytes_to_u16, bytes_to_u32};
+
+use crate::error::{Error, Layer};
+
+pub const USB_HEADER_LEN: usize = 48;
+pub const USB_MMAPPED_HEADER_LEN: usize = 64;
+
+pub const TRANSFER_ISOCHRONOUS: u8 = 0;
+pub const TRANSFER_INTERRUPT: u8 = 1;
+pub const TRANSFER_CONTROL: u8 = 2;
+pub const TRANSFER_BULK: u8 = 3;
+
+fn u32_le(b: &[u8], i: usize) -> u32 {
+    bytes_to_u32(b[i], b[i + 1], b[i + 2], b[i + 3], true)
+}
+
+pub fn transfer_type_name(transfer_type: u8) -> &'static str {
+    match transfer_type {
+        TRANSFER_ISOCHRONOUS => "Isochronous",
+        TRANSFER_INTERRUPT => "Interrupt",
+        TRANSFER_CONTROL => "Control",
+        TRANSFER_BULK => "Bulk",
+        _ => "Unknown",
+    }
+}
+
+/// Linux usbmon packet header. It is written in the byte order of the capturing host,
+/// which is assumed to be little-endian.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct UsbHeader {
+    id: u64,
+    event: u8,
+    transfer_type: u8,
+    endpoint: u8,
+    device: u8,
+    bus: u16,
+    setup: Option<[u8;8]>,
+    status: i32,
+    urb_len: u32,
+    data_len: u32,
+    len: usize,
+}
+
+impl UsbHeader {
+
+    /// `mmapped` selects the 64-byte header of LINKTYPE_USB_LINUX_MMAPPED.
+    pub fn parse(bytes: &[u8], mmapped: bool) -> Result<Self, Error> {
+        let len = if mmapped { USB_MMAPPED_HEADER_LEN } else { USB_HEADER_LEN };
+        if bytes.len() < len {
+            return Err(Error::Truncated(Layer::USB, bytes.len()));
+        }
+        Ok(Self {
+            id: u64::from_le_bytes(bytes[0..8].try_into().unwrap()),
+            event: bytes[8],
+            transfer_type: bytes[9],
+            endpoint: bytes[10],
+            device: bytes[11],
+            bus: bytes_to_u16(bytes[12], bytes[13], true),
+            setup: if bytes[14] == 0 { Some(bytes[40..48].try_into().unwrap()) } else { None },
+            status: u32_le(bytes, 28) as i32,
+            urb_len: u32_le(bytes, 32),
+            data_len: u32_le(bytes, 36),
+            len,
+        })
+    }
+
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// 'S' for submission, 'C' for completion and 'E' for errors.
+    pub fn event(&self) -> char {
+        self.event as char
+    }
+
+    pub fn event_name(&self) -> &'static str {
+        match self.event {
+            b'S' => "Submit",
+            b'C' => "Complete",
+            b'E' => "Error",
+            _ => "Unknown",
+        }
+    }
+
+    pub fn transfer_type(&self) -> u8 {
+        self.transfer_type
+    }
+
+    pub fn endpoint(&self) -> u8 {
+        self.endpoint
+    }
+
+    pub fn is_in(&self) -> bool {
+        self.endpoint & 0x80 != 0
+    }
+
+    pub fn device(&self) -> u8 {
+        self.device
+    }
+
+    pub fn bus(&self) -> u16 {
+        self.bus
+    }
+
+    /// Setup packet of control transfer submissions.
+    pub fn setup(&self) -> Option<&[u8;8]> {
+        self.setup.as_ref()
+    }
+
+    pub fn status(&self) -> i32 {
+        self.status
+    }
+
+    pub fn urb_len(&self) -> u32 {
+        self.urb_len
+    }
+
+    /// Bytes of transfer data captured after the header.
+    pub fn data_len(&self) -> u32 {
+        self.data_len
+    }
+
+    pub fn header_len(&self) -> usize {
+        self.len
+    }
+}
+
+impl Display for UsbHeader {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "URB ID: {:#018x}", self.id)?;
+        writeln!(f, "Event: {} ({})", self.event_name(), self.event())?;
+        writeln!(f, "Transfer Type: {}", transfer_type_name(self.transfer_type))?;
+        writeln!(f, "Endpoint: {:#04x} ({})", self.endpoint, if self.is_in() { "IN" } else { "OUT" })?;
+        writeln!(f, "Device: {}\nBus: {}", self.device, self.bus)?;
+        if let Some(setup) = self.setup {
+            let hex: Vec<String> = setup.iter().map(|b| format!("{:02X}", b)).collect();
+            writeln!(f, "Setup: {}", hex.join(" "))?;
+        }
+        write!(f, "Status: {}\nURB Length: {}\nData Length: {}", self.status, self.urb_len, self.data_len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_usb_control_submit() {
+        let mut bytes = vec![0x00, 0x8E, 0x2C, 0x36, 0x00, 0x88, 0xFF, 0xFF];
+        bytes.extend_from_slice(&[b'S', TRANSFER_CONTROL, 0x80, 0x03, 0x01, 0x00, 0x00, 0x3C]);
+        bytes.extend_from_slice(&[0;12]);
+        bytes.extend_from_slice(&[0x8D, 0xFF, 0xFF, 0xFF, 0x12, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]);
+        bytes.extend_from_slice(&[0x80, 0x06, 0x00, 0x01, 0x00, 0x00, 0x12, 0x00]);
+        let usb = UsbHeader::parse(&bytes, false).unwrap();
+        assert_eq!(usb.event_name(), "Submit");
+        assert!(usb.is_in());
+        assert_eq!(usb.device(), 3);
+        assert_eq!(usb.bus(), 1);
+        assert_eq!(usb.status(), -115);
+        assert_eq!(usb.urb_len(), 18);
+        assert_eq!(usb.setup(), Some(&[0x80, 0x06, 0x00, 0x01, 0x00, 0x00, 0x12, 0x00]));
+        assert!(usb.to_string().contains("Transfer Type: Control\nEndpoint: 0x80 (IN)\nDevice: 3\nBus: 1\nSetup: 80 06"));
+        assert_eq!(UsbHeader::parse(&bytes, true), Err(Error::Truncated(Layer::USB, 48)));
+    }
+}
@@ -1,7 +1,9 @@
 use std::{net::Ipv6Addr, fmt::Display};
 
-use byte::ByteParser;
+use byte::{bytes_to_u16, bytes_to_u32, ByteParser};
 
+use crate::error::{Error, Layer};
+use crate::tlv::OptionFormat;
 use crate::transport::protocols::TransportProtocol;
 
 pub struct Ipv6AddressParser {
@@ -24,13 +26,13 @@ impl Ipv6AddressParser {
         self.pos = 0;
     }
 
-    fn set_bytes(&mut self, b: u16) {
-        if !self.done() {
-            self.buf[self.pos] = b;
-            self.pos += 1;
-        } else {
-            println!("IPv6 Address Parser Buffer full");
+    fn set_bytes(&mut self, b: u16) -> Result<(), Error> {
+        if self.done() {
+            return Err(Error::InvalidPosition(Layer::IPv6, self.pos));
         }
+        self.buf[self.pos] = b;
+        self.pos += 1;
+        Ok(())
     }
 
     fn get_address(&self) -> Ipv6Addr {
@@ -47,6 +49,321 @@ impl Ipv6AddressParser {
     }
 }
 
+pub const EXT_HOP_BY_HOP: u8 = 0;
+pub const EXT_ROUTING: u8 = 43;
+pub const EXT_FRAGMENT: u8 = 44;
+pub const EXT_AH: u8 = 51;
+pub const EXT_DEST_OPTS: u8 = 60;
+pub const EXT_MOBILITY: u8 = 135;
+
+const OPTION_FORMAT: OptionFormat = OptionFormat {
+    layer: Layer::IPv6,
+    single: &[0],
+    end: None,
+    inclusive_len: false,
+};
+
+fn ipv6_addr(b: &[u8]) -> Ipv6Addr {
+    let mut octets = [0u8;16];
+    octets.copy_from_slice(&b[..16]);
+    Ipv6Addr::from(octets)
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum IPv6Option {
+    PAD1,
+    PADN(u8),
+    RA(u16),
+    JUMBO(u32),
+    UNKNOWN(u8, Vec<u8>),
+}
+
+impl IPv6Option {
+
+    pub fn kind(&self) -> u8 {
+        match self {
+            Self::PAD1 => 0,
+            Self::PADN(_) => 1,
+            Self::RA(_) => 5,
+            Self::JUMBO(_) => 0xC2,
+            Self::UNKNOWN(k, _) => *k,
+        }
+    }
+
+    pub fn parse_all(bytes: &[u8], base: usize) -> Result<Vec<Self>, Error> {
+        OPTION_FORMAT.parse(bytes, base, |kind, value, _| {
+            let value = value.unwrap_or_default();
+            Ok(match (kind, value.len()) {
+                (0, _) => Self::PAD1,
+                (1, len) => Self::PADN(len as u8),
+                (5, 2) => Self::RA(bytes_to_u16(value[0], value[1], false)),
+                (0xC2, 4) => Self::JUMBO(bytes_to_u32(value[0], value[1], value[2], value[3], false)),
+                (k, _) => Self::UNKNOWN(k, value.to_vec()),
+            })
+        })
+    }
+}
+
+impl Display for IPv6Option {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::PAD1 => write!(f, "Pad1"),
+            Self::PADN(n) => write!(f, "PadN({})", n),
+            Self::RA(b) => write!(f, "Router Alert: {}", b),
+            Self::JUMBO(b) => write!(f, "Jumbo Payload: {}", b),
+            Self::UNKNOWN(kind, value) => write!(f, "Unknown type={:#04x} len={}", kind, value.len()),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum IPv6Routing {
+    SOURCE(Vec<Ipv6Addr>),
+    HOME(Ipv6Addr),
+    SRH {
+        last_entry: u8,
+        flags: u8,
+        tag: u16,
+        segments: Vec<Ipv6Addr>,
+        tlvs: Vec<u8>,
+    },
+    UNKNOWN(Vec<u8>),
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum IPv6ExtensionHeader {
+    HOPOPT(Vec<IPv6Option>),
+    ROUTING {
+        routing_type: u8,
+        segments_left: u8,
+        data: IPv6Routing,
+    },
+    FRAGMENT {
+        offset: u16,
+        more: bool,
+        id: u32,
+    },
+    DSTOPTS(Vec<IPv6Option>),
+    AH {
+        spi: u32,
+        seq: u32,
+        icv: Vec<u8>,
+    },
+    MOBILITY {
+        mh_type: u8,
+        data: Vec<u8>,
+    },
+}
+
+impl IPv6ExtensionHeader {
+
+    pub fn kind(&self) -> u8 {
+        match self {
+            Self::HOPOPT(_) => EXT_HOP_BY_HOP,
+            Self::ROUTING { .. } => EXT_ROUTING,
+            Self::FRAGMENT { .. } => EXT_FRAGMENT,
+            Self::DSTOPTS(_) => EXT_DEST_OPTS,
+            Self::AH { .. } => EXT_AH,
+            Self::MOBILITY { .. } => EXT_MOBILITY,
+        }
+    }
+
+    pub fn is_extension(next_header: u8) -> bool {
+        matches!(
+            next_header,
+            EXT_HOP_BY_HOP | EXT_ROUTING | EXT_FRAGMENT | EXT_AH | EXT_DEST_OPTS | EXT_MOBILITY
+        )
+    }
+
+    fn header_len(next_header: u8, bytes: &[u8]) -> usize {
+        match next_header {
+            EXT_FRAGMENT => 8,
+            EXT_AH => (bytes[1] as usize + 2) * 4,
+            _ => (bytes[1] as usize + 1) * 8,
+        }
+    }
+
+    fn parse(next_header: u8, bytes: &[u8], base: usize) -> Result<Self, Error> {
+        match next_header {
+            EXT_HOP_BY_HOP => Ok(Self::HOPOPT(IPv6Option::parse_all(&bytes[2..], base + 2)?)),
+            EXT_DEST_OPTS => Ok(Self::DSTOPTS(IPv6Option::parse_all(&bytes[2..], base + 2)?)),
+            EXT_ROUTING => Self::routing(bytes, base),
+            EXT_FRAGMENT => {
+                let frag = bytes_to_u16(bytes[2], bytes[3], false);
+                Ok(Self::FRAGMENT {
+                    offset: frag >> 3,
+                    more: frag & 1 == 1,
+                    id: bytes_to_u32(bytes[4], bytes[5], bytes[6], bytes[7], false),
+                })
+            },
+            EXT_AH => {
+                if bytes.len() < 12 {
+                    return Err(Error::Malformed(Layer::IPv6, base, "authentication header too short"));
+                }
+                Ok(Self::AH {
+                    spi: bytes_to_u32(bytes[4], bytes[5], bytes[6], bytes[7], false),
+                    seq: bytes_to_u32(bytes[8], bytes[9], bytes[10], bytes[11], false),
+                    icv: bytes[12..].to_vec(),
+                })
+            },
+            _ => Ok(Self::MOBILITY { mh_type: bytes[2], data: bytes[4..].to_vec() }),
+        }
+    }
+
+    fn routing(bytes: &[u8], base: usize) -> Result<Self, Error> {
+        let data = match bytes[2] {
+            0 => IPv6Routing::SOURCE(bytes[8..].chunks_exact(16).map(ipv6_addr).collect()),
+            2 if bytes.len() == 24 => IPv6Routing::HOME(ipv6_addr(&bytes[8..])),
+            2 => return Err(Error::Malformed(Layer::IPv6, base, "invalid type 2 routing header length")),
+            4 => {
+                let end = 8 + (bytes[4] as usize + 1) * 16;
+                if end > bytes.len() {
+                    return Err(Error::Malformed(Layer::IPv6, base + 4, "segment list exceeds header length"));
+                }
+                IPv6Routing::SRH {
+                    last_entry: bytes[4],
+                    flags: bytes[5],
+                    tag: bytes_to_u16(bytes[6], bytes[7], false),
+                    segments: bytes[8..end].chunks_exact(16).map(ipv6_addr).collect(),
+                    tlvs: bytes[end..].to_vec(),
+                }
+            },
+            _ => IPv6Routing::UNKNOWN(bytes[4..].to_vec()),
+        };
+        Ok(Self::ROUTING { routing_type: bytes[2], segments_left: bytes[3], data })
+    }
+}
+
+impl Display for IPv6ExtensionHeader {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let options = |o: &Vec<IPv6Option>| o.iter().map(|o| o.to_string()).collect::<Vec<String>>().join(", ");
+        let addrs = |a: &Vec<Ipv6Addr>| a.iter().map(|a| a.to_string()).collect::<Vec<String>>().join(" ");
+        match self {
+            Self::HOPOPT(o) => write!(f, "Hop-by-Hop Options: {}", options(o)),
+            Self::DSTOPTS(o) => write!(f, "Destination Options: {}", options(o)),
+            Self::ROUTING { routing_type, segments_left, data } => match data {
+                IPv6Routing::SOURCE(a) => write!(f, "Routing (Source Route): Segments Left {}, {}", segments_left, addrs(a)),
+                IPv6Routing::HOME(a) => write!(f, "Routing (Home Address): Segments Left {}, {}", segments_left, a),
+                IPv6Routing::SRH { last_entry, segments, .. } => write!(
+                    f,
+                    "Routing (SRv6): Segments Left {}, Last Entry {}, {}",
+                    segments_left, last_entry, addrs(segments)
+                ),
+                IPv6Routing::UNKNOWN(_) => write!(f, "Routing (Type {}): Segments Left {}", routing_type, segments_left),
+            },
+            Self::FRAGMENT { offset, more, id } => write!(
+                f,
+                "Fragment: Offset {}, More Fragments {}, ID {:#010x}",
+                offset, more, id
+            ),
+            Self::AH { spi, seq, .. } => write!(f, "Authentication Header: SPI {:#010x}, Seq {}", spi, seq),
+            Self::MOBILITY { mh_type, .. } => write!(f, "Mobility Header: Type {}", mh_type),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct IPv6Extension {
+    next_header: u8,
+    offset: usize,
+    len: usize,
+    header: IPv6ExtensionHeader,
+}
+
+impl IPv6Extension {
+
+    pub fn next_header(&self) -> TransportProtocol {
+        TransportProtocol::new(self.next_header)
+    }
+
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    pub fn header_len(&self) -> usize {
+        self.len
+    }
+
+    pub fn header(&self) -> &IPv6ExtensionHeader {
+        &self.header
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct IPv6ExtensionChain {
+    headers: Vec<IPv6Extension>,
+    protocol: u8,
+    len: usize,
+    truncated: bool,
+}
+
+impl IPv6ExtensionChain {
+
+    /// `base` is the offset of `bytes[0]` in the frame, used for header offsets and errors.
+    pub fn parse(next_header: u8, bytes: &[u8], base: usize) -> Result<Self, Error> {
+        let mut chain = Self { protocol: next_header, ..Default::default() };
+        while IPv6ExtensionHeader::is_extension(chain.protocol) {
+            let pos = chain.len;
+            if pos + 2 > bytes.len() {
+                chain.len = pos + 2;
+                chain.truncated = true;
+                break;
+            }
+            let len = IPv6ExtensionHeader::header_len(chain.protocol, &bytes[pos..]);
+            if pos + len > bytes.len() {
+                chain.len = pos + len;
+                chain.truncated = true;
+                break;
+            }
+            let header = IPv6ExtensionHeader::parse(chain.protocol, &bytes[pos..pos + len], base + pos)?;
+            let next_header = bytes[pos];
+            let later_fragment = matches!(header, IPv6ExtensionHeader::FRAGMENT { offset, .. } if offset != 0);
+            chain.headers.push(IPv6Extension { next_header, offset: base + pos, len, header });
+            chain.protocol = next_header;
+            chain.len += len;
+            if later_fragment {
+                break;
+            }
+        }
+        Ok(chain)
+    }
+
+    pub fn headers(&self) -> &[IPv6Extension] {
+        &self.headers
+    }
+
+    pub fn upper_layer(&self) -> TransportProtocol {
+        TransportProtocol::new(self.protocol)
+    }
+
+    pub fn header_len(&self) -> usize {
+        self.len
+    }
+
+    pub fn payload_offset(&self) -> usize {
+        40 + self.len
+    }
+
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+
+    pub fn fragment(&self) -> Option<(u16, bool, u32)> {
+        self.headers.iter().find_map(|h| match h.header {
+            IPv6ExtensionHeader::FRAGMENT { offset, more, id } => Some((offset, more, id)),
+            _ => None,
+        })
+    }
+
+    pub fn routing_segments(&self) -> Option<&[Ipv6Addr]> {
+        self.headers.iter().find_map(|h| match &h.header {
+            IPv6ExtensionHeader::ROUTING { data: IPv6Routing::SRH { segments, .. }, .. } => Some(&segments[..]),
+            IPv6ExtensionHeader::ROUTING { data: IPv6Routing::SOURCE(segments), .. } => Some(&segments[..]),
+            _ => None,
+        })
+    }
+}
+
 pub enum IPv6HeaderField {
     V(u8),
     FLOW(u32),
@@ -79,7 +396,7 @@ impl Clone for IPv6HeaderField {
             Self::V(b) => Self::V(*b),
             Self::FLOW(b) => Self::FLOW(*b),
             Self::LEN(b) => Self::LEN(*b),
-            Self::PRT(b) => Self::PRT(b.clone()),
+            Self::PRT(b) => Self::PRT(*b),
             Self::HOPL(b) => Self::HOPL(*b),
             Self::SRC(b) => Self::SRC(*b),
             Self::DST(b) => Self::DST(*b),
@@ -97,6 +414,7 @@ pub struct IPv6Header {
     hop_len: IPv6HeaderField,
     source: IPv6HeaderField,
     destination: IPv6HeaderField,
+    extensions: Option<IPv6ExtensionChain>,
 }
 
 impl Display for IPv6Header {
@@ -111,7 +429,22 @@ impl Display for IPv6Header {
             self.hop_len,
             self.source,
             self.destination,
-            )
+            )?;
+        if let Some(chain) = &self.extensions {
+            for ext in chain.headers() {
+                write!(f, "\n{}", ext.header())?;
+            }
+            if !chain.headers().is_empty() {
+                write!(f, "\nUpper Layer: {}", chain.upper_layer().to_str())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Default for IPv6Header {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
@@ -126,6 +459,7 @@ impl IPv6Header {
             hop_len: IPv6HeaderField::UNSET,
             source: IPv6HeaderField::UNSET,
             destination: IPv6HeaderField::UNSET,
+            extensions: None,
         }
     }
 
@@ -138,7 +472,7 @@ impl IPv6Header {
             IPv6HeaderField::HOPL(_) => self.hop_len = field,
             IPv6HeaderField::SRC(_) => self.source = field,
             IPv6HeaderField::DST(_) => self.destination = field,
-            IPv6HeaderField::UNSET => println!("Cant set unset field in Ipv6 header")
+            IPv6HeaderField::UNSET => (),
         }
     }
 
@@ -155,6 +489,81 @@ impl IPv6Header {
         }
 
     }
+
+    pub fn version(&self) -> Option<u8> {
+        match self.version {
+            IPv6HeaderField::V(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn traffic_class(&self) -> Option<u8> {
+        match self.flow {
+            IPv6HeaderField::FLOW(b) => Some((b >> 20) as u8),
+            _ => None,
+        }
+    }
+
+    pub fn flow_label(&self) -> Option<u32> {
+        match self.flow {
+            IPv6HeaderField::FLOW(b) => Some(b & 0xFFFFF),
+            _ => None,
+        }
+    }
+
+    pub fn payload_len(&self) -> Option<u16> {
+        match self.length {
+            IPv6HeaderField::LEN(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn next_header(&self) -> Option<TransportProtocol> {
+        match &self.proto {
+            IPv6HeaderField::PRT(p) => Some(*p),
+            _ => None,
+        }
+    }
+
+    pub fn hop_limit(&self) -> Option<u8> {
+        match self.hop_len {
+            IPv6HeaderField::HOPL(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn src(&self) -> Option<Ipv6Addr> {
+        match self.source {
+            IPv6HeaderField::SRC(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn dst(&self) -> Option<Ipv6Addr> {
+        match self.destination {
+            IPv6HeaderField::DST(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn set_extensions(&mut self, chain: IPv6ExtensionChain) {
+        self.extensions = Some(chain);
+    }
+
+    pub fn extensions(&self) -> Option<&IPv6ExtensionChain> {
+        self.extensions.as_ref()
+    }
+
+    pub fn upper_layer(&self) -> Option<TransportProtocol> {
+        match &self.extensions {
+            Some(chain) => Some(chain.upper_layer()),
+            None => self.next_header(),
+        }
+    }
+
+    pub fn payload_offset(&self) -> usize {
+        self.extensions.as_ref().map_or(40, |c| c.payload_offset())
+    }
 }
 
 pub struct IPv6HeaderParser {
@@ -175,45 +584,52 @@ impl IPv6HeaderParser {
         }
     }
 
-    pub fn parse(&mut self, b: u8) {
-        self.step(b);
+    pub fn parse(&mut self, b: u8) -> Result<(), Error> {
+        self.step(b)?;
         match self.curr_field {
-            IPv6HeaderField::V(_) => self.version(),
+            IPv6HeaderField::V(_) => self.version()?,
             IPv6HeaderField::FLOW(_) => self.flow(),
             IPv6HeaderField::LEN(_) => self.length(),
             IPv6HeaderField::PRT(_) => self.proto(),
             IPv6HeaderField::HOPL(_) => self.hop_len(),
-            IPv6HeaderField::SRC(_) => self.source(),
-            IPv6HeaderField::DST(_) => self.destination(),
-            IPv6HeaderField::UNSET => println!("IPv6 header parsing complete, cant set byte"),
+            IPv6HeaderField::SRC(_) => self.source()?,
+            IPv6HeaderField::DST(_) => self.destination()?,
+            IPv6HeaderField::UNSET => (),
         }
+        Ok(())
     }
 
     pub fn get_header(&self) -> IPv6Header {
         self.header.clone()
     }
 
-    fn step(&mut self, b: u8) {
+    fn step(&mut self, b: u8) -> Result<(), Error> {
         match self.curr_field {
             IPv6HeaderField::V(_)|
                 IPv6HeaderField::PRT(_)|
                 IPv6HeaderField::HOPL(_) 
-                => self.b_parser.set_word(b),
+                => {
+                    self.b_parser.set_word(b);
+                    Ok(())
+                },
             IPv6HeaderField::LEN(_)|
                 IPv6HeaderField::SRC(_)|
                 IPv6HeaderField::DST(_)
-                => self.b_parser.set_d_byte(b),
-            IPv6HeaderField::FLOW(_) => self.b_parser.set_q_byte(b),
-            IPv6HeaderField::UNSET => println!("Cant set byte for unset IPv6 Header field"),
+                => self.b_parser.set_d_byte(b).map_err(|e| Error::Byte(Layer::IPv6, e)),
+            IPv6HeaderField::FLOW(_)
+                => self.b_parser.set_q_byte(b).map_err(|e| Error::Byte(Layer::IPv6, e)),
+            IPv6HeaderField::UNSET => Err(Error::HeaderComplete(Layer::IPv6)),
         }
     }
 
-    fn version(&mut self) {
+    fn version(&mut self) -> Result<(), Error> {
         self.header.set_field(
             IPv6HeaderField::V(self.b_parser.word_l_nibble())
         );
-        self.b_parser.set_q_byte(self.b_parser.word_r_nibble());
+        self.b_parser.set_q_byte(self.b_parser.word_r_nibble())
+            .map_err(|e| Error::Byte(Layer::IPv6, e))?;
         self.curr_field = IPv6HeaderField::FLOW(0);
+        Ok(())
     }
 
     fn flow(&mut self) {
@@ -246,9 +662,9 @@ impl IPv6HeaderParser {
         self.curr_field = IPv6HeaderField::SRC(Ipv6Addr::new(0, 0, 0, 0, 0, 0, 0, 1));
     }
 
-    fn source(&mut self) {
+    fn source(&mut self) -> Result<(), Error> {
         if self.b_parser.dword_done() {
-            self.a_parser.set_bytes(self.b_parser.dword_as_u16());
+            self.a_parser.set_bytes(self.b_parser.dword_as_u16())?;
             self.b_parser.reset_dword();
         }
         if self.a_parser.done() {
@@ -258,11 +674,12 @@ impl IPv6HeaderParser {
             self.a_parser.clear();
             self.curr_field = IPv6HeaderField::DST(Ipv6Addr::new(0, 0, 0, 0, 0, 0, 0, 1));
         }
+        Ok(())
     }
     
-    fn destination(&mut self) {
+    fn destination(&mut self) -> Result<(), Error> {
         if self.b_parser.dword_done() {
-            self.a_parser.set_bytes(self.b_parser.dword_as_u16());
+            self.a_parser.set_bytes(self.b_parser.dword_as_u16())?;
             self.b_parser.reset_dword();
         }
         if self.a_parser.done() {
@@ -272,6 +689,7 @@ impl IPv6HeaderParser {
             self.a_parser.clear();
             self.curr_field = IPv6HeaderField::UNSET;
         }
+        Ok(())
     }
 }
 
@@ -285,11 +703,114 @@ mod tests {
         let bytes: [u16;8] = [0,0,0,0,0,0,0,1];
         let mut parser = Ipv6AddressParser::new();
         for i in bytes {
-            parser.set_bytes(i)
+            parser.set_bytes(i).unwrap();
         }
         assert!(parser.done());
         let addr = parser.get_address();
         assert_eq!("::1".parse(), Ok(addr));
         assert!(addr.is_loopback());
     }
+
+    fn extension_chain() -> Vec<u8> {
+        let mut data = vec![
+            // Hop-by-Hop: Router Alert + PadN(0)
+            60, 0, 5, 2, 0, 0, 1, 0,
+            // Destination Options: PadN(4)
+            43, 0, 1, 4, 0, 0, 0, 0,
+            // SRv6: two segments, one left
+            44, 4, 4, 1, 1, 0, 0, 7,
+        ];
+        data.extend_from_slice(&"2001:db8::2".parse::<Ipv6Addr>().unwrap().octets());
+        data.extend_from_slice(&"2001:db8::1".parse::<Ipv6Addr>().unwrap().octets());
+        // Fragment: offset 0, M set
+        data.extend_from_slice(&[51, 0, 0x00, 0x01, 0xDE, 0xAD, 0xBE, 0xEF]);
+        // AH: 24 bytes with a 12 byte ICV
+        data.extend_from_slice(&[17, 4, 0, 0, 0, 0, 1, 0, 0, 0, 0, 1]);
+        data.extend_from_slice(&[0xAA;12]);
+        data
+    }
+
+    #[test]
+    fn test_extension_chain() {
+        let data = extension_chain();
+        let chain = IPv6ExtensionChain::parse(EXT_HOP_BY_HOP, &data, 40).unwrap();
+        assert!(!chain.is_truncated());
+        assert!(matches!(chain.upper_layer(), TransportProtocol::UDP));
+        assert_eq!(chain.header_len(), 88);
+        assert_eq!(chain.payload_offset(), 128);
+        let kinds: Vec<u8> = chain.headers().iter().map(|h| h.header().kind()).collect();
+        assert_eq!(kinds, vec![EXT_HOP_BY_HOP, EXT_DEST_OPTS, EXT_ROUTING, EXT_FRAGMENT, EXT_AH]);
+        let offsets: Vec<usize> = chain.headers().iter().map(|h| h.offset()).collect();
+        assert_eq!(offsets, vec![40, 48, 56, 96, 104]);
+
+        let headers = chain.headers();
+        assert_eq!(headers[0].header(), &IPv6ExtensionHeader::HOPOPT(vec![IPv6Option::RA(0), IPv6Option::PADN(0)]));
+        assert_eq!(headers[1].header(), &IPv6ExtensionHeader::DSTOPTS(vec![IPv6Option::PADN(4)]));
+        match headers[2].header() {
+            IPv6ExtensionHeader::ROUTING { routing_type, segments_left, data: IPv6Routing::SRH { last_entry, tag, tlvs, .. } } => {
+                assert_eq!((*routing_type, *segments_left, *last_entry, *tag), (4, 1, 1, 7));
+                assert!(tlvs.is_empty());
+            },
+            h => panic!("unexpected {:?}", h),
+        }
+        assert_eq!(
+            chain.routing_segments(),
+            Some(&["2001:db8::2".parse().unwrap(), "2001:db8::1".parse().unwrap()][..])
+        );
+        assert_eq!(chain.fragment(), Some((0, true, 0xDEADBEEF)));
+        assert_eq!(
+            headers[4].header(),
+            &IPv6ExtensionHeader::AH { spi: 0x100, seq: 1, icv: vec![0xAA;12] }
+        );
+        assert!(matches!(headers[4].next_header(), TransportProtocol::UDP));
+        assert_eq!(
+            headers[2].header().to_string(),
+            "Routing (SRv6): Segments Left 1, Last Entry 1, 2001:db8::2 2001:db8::1"
+        );
+    }
+
+    #[test]
+    fn test_mobility_and_no_next_header() {
+        let data = [59, 0, 5, 0, 0, 0, 0, 0];
+        let chain = IPv6ExtensionChain::parse(EXT_MOBILITY, &data, 40).unwrap();
+        assert_eq!(chain.headers().len(), 1);
+        assert_eq!(chain.headers()[0].header(), &IPv6ExtensionHeader::MOBILITY { mh_type: 5, data: vec![0;4] });
+        assert!(matches!(chain.upper_layer(), TransportProtocol::IPv6NoNxt));
+        assert_eq!(chain.payload_offset(), 48);
+
+        let chain = IPv6ExtensionChain::parse(6, &data, 40).unwrap();
+        assert!(chain.headers().is_empty());
+        assert_eq!(chain.payload_offset(), 40);
+    }
+
+    #[test]
+    fn test_later_fragment_stops_walk() {
+        let data = [60, 0, 0x00, 0x08, 0, 0, 0, 1, 0xFF, 0xFF];
+        let chain = IPv6ExtensionChain::parse(EXT_FRAGMENT, &data, 40).unwrap();
+        assert_eq!(chain.fragment(), Some((1, false, 1)));
+        assert_eq!(chain.headers().len(), 1);
+        assert_eq!(chain.header_len(), 8);
+    }
+
+    #[test]
+    fn test_truncated_and_malformed_chain() {
+        let data = extension_chain();
+        let chain = IPv6ExtensionChain::parse(EXT_HOP_BY_HOP, &data[..87], 40).unwrap();
+        assert!(chain.is_truncated());
+        assert_eq!(chain.headers().len(), 4);
+        assert_eq!(chain.header_len(), 88);
+
+        let chain = IPv6ExtensionChain::parse(EXT_HOP_BY_HOP, &data[..1], 40).unwrap();
+        assert!(chain.is_truncated());
+        assert!(chain.headers().is_empty());
+
+        assert_eq!(
+            IPv6ExtensionChain::parse(EXT_HOP_BY_HOP, &[59, 0, 1, 7, 0, 0, 0, 0], 40),
+            Err(Error::Malformed(Layer::IPv6, 42, "option length out of bounds"))
+        );
+        assert_eq!(
+            IPv6ExtensionChain::parse(EXT_ROUTING, &[59, 0, 4, 0, 1, 0, 0, 0], 40),
+            Err(Error::Malformed(Layer::IPv6, 44, "segment list exceeds header length"))
+        );
+    }
 }
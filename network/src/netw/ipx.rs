@@ -0,0 +1,817 @@
+use std::fmt::Display;
+
+use byte::{bytes_to_u16, bytes_to_u32, ByteParser};
+
+use crate::error::{Error, Layer};
+use crate::ethernet_frame::MacAddress;
+
+pub const IPX_HEADER_LEN: usize = 30;
+pub const SPX_HEADER_LEN: usize = 12;
+
+pub const PT_UNKNOWN: u8 = 0;
+pub const PT_RIP: u8 = 1;
+pub const PT_ECHO: u8 = 2;
+pub const PT_ERROR: u8 = 3;
+pub const PT_PEP: u8 = 4;
+pub const PT_SPX: u8 = 5;
+pub const PT_NCP: u8 = 17;
+pub const PT_NETBIOS: u8 = 20;
+
+pub const SOCKET_NCP: u16 = 0x0451;
+pub const SOCKET_SAP: u16 = 0x0452;
+pub const SOCKET_RIP: u16 = 0x0453;
+pub const SOCKET_NETBIOS: u16 = 0x0455;
+pub const SOCKET_DIAGNOSTIC: u16 = 0x0456;
+
+const RIP_ENTRY_LEN: usize = 8;
+const SAP_ENTRY_LEN: usize = 64;
+const SAP_NAME_LEN: usize = 48;
+
+fn u16_at(b: &[u8], i: usize) -> u16 {
+    bytes_to_u16(b[i], b[i + 1], false)
+}
+
+fn u32_at(b: &[u8], i: usize) -> u32 {
+    bytes_to_u32(b[i], b[i + 1], b[i + 2], b[i + 3], false)
+}
+
+pub fn packet_type_name(pt: u8) -> &'static str {
+    match pt {
+        PT_UNKNOWN => "Unknown",
+        PT_RIP => "RIP",
+        PT_ECHO => "Echo",
+        PT_ERROR => "Error",
+        PT_PEP => "PEP",
+        PT_SPX => "SPX",
+        PT_NCP => "NCP",
+        PT_NETBIOS => "NetBIOS Broadcast",
+        _ => "Unknown",
+    }
+}
+
+pub fn socket_name(socket: u16) -> &'static str {
+    match socket {
+        SOCKET_NCP => "NCP",
+        SOCKET_SAP => "SAP",
+        SOCKET_RIP => "RIP",
+        SOCKET_NETBIOS => "NetBIOS",
+        SOCKET_DIAGNOSTIC => "Diagnostic",
+        0x4000..=0x7FFF => "Dynamic",
+        _ => "Unknown",
+    }
+}
+
+pub fn service_type_name(service: u16) -> &'static str {
+    match service {
+        0x0004 => "File Server",
+        0x0007 => "Print Server",
+        0x0047 => "Advertising Print Server",
+        0x0278 => "Directory Server",
+        0xFFFF => "Wildcard",
+        _ => "Unknown",
+    }
+}
+
+/// Network, node and socket, the full address of an IPX endpoint.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct IpxAddress {
+    network: u32,
+    node: MacAddress,
+    socket: u16,
+}
+
+impl IpxAddress {
+
+    pub fn new(network: u32, node: MacAddress, socket: u16) -> Self {
+        Self { network, node, socket }
+    }
+
+    fn parse(b: &[u8]) -> Self {
+        Self::new(u32_at(b, 0), MacAddress::new([b[4], b[5], b[6], b[7], b[8], b[9]]), u16_at(b, 10))
+    }
+
+    pub fn network(&self) -> u32 {
+        self.network
+    }
+
+    pub fn node(&self) -> &MacAddress {
+        &self.node
+    }
+
+    pub fn socket(&self) -> u16 {
+        self.socket
+    }
+}
+
+impl Display for IpxAddress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:08X}.{}.{:04X}", self.network, self.node, self.socket)
+    }
+}
+
+pub enum IpxHeaderField {
+    CHECK(u16),
+    LEN(u16),
+    TC(u8),
+    PT(u8),
+    DNET(u32),
+    DNODE(MacAddress),
+    DSOCK(u16),
+    SNET(u32),
+    SNODE(MacAddress),
+    SSOCK(u16),
+    UNSET,
+}
+
+impl Display for IpxHeaderField {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::CHECK(b) => write!(f, "Checksum: {:#06x}", b),
+            Self::LEN(b) => write!(f, "Length: {}", b),
+            Self::TC(b) => write!(f, "Transport Control: {} hops", b),
+            Self::PT(b) => write!(f, "Packet Type: {} ({})", b, packet_type_name(*b)),
+            Self::DNET(b) => write!(f, "Destination Network: {:#010x}", b),
+            Self::DNODE(b) => write!(f, "Destination Node: {}", b),
+            Self::DSOCK(b) => write!(f, "Destination Socket: {:#06x} ({})", b, socket_name(*b)),
+            Self::SNET(b) => write!(f, "Source Network: {:#010x}", b),
+            Self::SNODE(b) => write!(f, "Source Node: {}", b),
+            Self::SSOCK(b) => write!(f, "Source Socket: {:#06x} ({})", b, socket_name(*b)),
+            Self::UNSET => write!(f, "UNSET"),
+        }
+    }
+}
+
+impl Clone for IpxHeaderField {
+    fn clone(&self) -> Self {
+        match self {
+            Self::CHECK(b) => Self::CHECK(*b),
+            Self::LEN(b) => Self::LEN(*b),
+            Self::TC(b) => Self::TC(*b),
+            Self::PT(b) => Self::PT(*b),
+            Self::DNET(b) => Self::DNET(*b),
+            Self::DNODE(b) => Self::DNODE(b.clone()),
+            Self::DSOCK(b) => Self::DSOCK(*b),
+            Self::SNET(b) => Self::SNET(*b),
+            Self::SNODE(b) => Self::SNODE(b.clone()),
+            Self::SSOCK(b) => Self::SSOCK(*b),
+            Self::UNSET => Self::UNSET,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct IpxHeader {
+    checksum: IpxHeaderField,
+    length: IpxHeaderField,
+    transport_control: IpxHeaderField,
+    packet_type: IpxHeaderField,
+    dst_network: IpxHeaderField,
+    dst_node: IpxHeaderField,
+    dst_socket: IpxHeaderField,
+    src_network: IpxHeaderField,
+    src_node: IpxHeaderField,
+    src_socket: IpxHeaderField,
+}
+
+impl Display for IpxHeader {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}",
+            self.checksum,
+            self.length,
+            self.transport_control,
+            self.packet_type,
+            self.dst_network,
+            self.dst_node,
+            self.dst_socket,
+            self.src_network,
+            self.src_node,
+            self.src_socket,
+        )
+    }
+}
+
+impl Default for IpxHeader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IpxHeader {
+
+    pub fn new() -> Self {
+        Self {
+            checksum: IpxHeaderField::UNSET,
+            length: IpxHeaderField::UNSET,
+            transport_control: IpxHeaderField::UNSET,
+            packet_type: IpxHeaderField::UNSET,
+            dst_network: IpxHeaderField::UNSET,
+            dst_node: IpxHeaderField::UNSET,
+            dst_socket: IpxHeaderField::UNSET,
+            src_network: IpxHeaderField::UNSET,
+            src_node: IpxHeaderField::UNSET,
+            src_socket: IpxHeaderField::UNSET,
+        }
+    }
+
+    pub fn set_field(&mut self, field: IpxHeaderField) {
+        match field {
+            IpxHeaderField::CHECK(_) => self.checksum = field,
+            IpxHeaderField::LEN(_) => self.length = field,
+            IpxHeaderField::TC(_) => self.transport_control = field,
+            IpxHeaderField::PT(_) => self.packet_type = field,
+            IpxHeaderField::DNET(_) => self.dst_network = field,
+            IpxHeaderField::DNODE(_) => self.dst_node = field,
+            IpxHeaderField::DSOCK(_) => self.dst_socket = field,
+            IpxHeaderField::SNET(_) => self.src_network = field,
+            IpxHeaderField::SNODE(_) => self.src_node = field,
+            IpxHeaderField::SSOCK(_) => self.src_socket = field,
+            IpxHeaderField::UNSET => (),
+        }
+    }
+
+    /// 0xFFFF unless the sender opted into checksumming.
+    pub fn checksum(&self) -> Option<u16> {
+        match self.checksum {
+            IpxHeaderField::CHECK(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    /// Length of header and data in bytes.
+    pub fn length(&self) -> Option<u16> {
+        match self.length {
+            IpxHeaderField::LEN(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    /// Routers crossed so far.
+    pub fn hops(&self) -> Option<u8> {
+        match self.transport_control {
+            IpxHeaderField::TC(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn packet_type(&self) -> Option<u8> {
+        match self.packet_type {
+            IpxHeaderField::PT(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn destination(&self) -> Option<IpxAddress> {
+        match (&self.dst_network, &self.dst_node, &self.dst_socket) {
+            (IpxHeaderField::DNET(n), IpxHeaderField::DNODE(m), IpxHeaderField::DSOCK(s))
+                => Some(IpxAddress::new(*n, m.clone(), *s)),
+            _ => None,
+        }
+    }
+
+    pub fn source(&self) -> Option<IpxAddress> {
+        match (&self.src_network, &self.src_node, &self.src_socket) {
+            (IpxHeaderField::SNET(n), IpxHeaderField::SNODE(m), IpxHeaderField::SSOCK(s))
+                => Some(IpxAddress::new(*n, m.clone(), *s)),
+            _ => None,
+        }
+    }
+
+    pub fn dst_socket(&self) -> Option<u16> {
+        match self.dst_socket {
+            IpxHeaderField::DSOCK(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn src_socket(&self) -> Option<u16> {
+        match self.src_socket {
+            IpxHeaderField::SSOCK(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn is_spx(&self) -> bool {
+        self.packet_type() == Some(PT_SPX)
+    }
+
+    pub fn is_rip(&self) -> bool {
+        self.dst_socket() == Some(SOCKET_RIP) || self.src_socket() == Some(SOCKET_RIP)
+    }
+
+    pub fn is_sap(&self) -> bool {
+        self.dst_socket() == Some(SOCKET_SAP) || self.src_socket() == Some(SOCKET_SAP)
+    }
+}
+
+pub struct IpxHeaderParser {
+    parser: ByteParser,
+    curr_field: IpxHeaderField,
+    header: IpxHeader,
+    node: [u8;6],
+    pos: usize,
+}
+
+impl Default for IpxHeaderParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IpxHeaderParser {
+
+    pub fn new() -> Self {
+        Self {
+            parser: ByteParser::new(false),
+            curr_field: IpxHeaderField::CHECK(0),
+            header: IpxHeader::new(),
+            node: [0;6],
+            pos: 0,
+        }
+    }
+
+    pub fn parse(&mut self, b: u8) -> Result<(), Error> {
+        self.step(b)?;
+        match self.curr_field {
+            IpxHeaderField::CHECK(_) => self.dword(IpxHeaderField::CHECK, IpxHeaderField::LEN(0)),
+            IpxHeaderField::LEN(_) => self.dword(IpxHeaderField::LEN, IpxHeaderField::TC(0)),
+            IpxHeaderField::TC(_) => {
+                self.header.set_field(IpxHeaderField::TC(self.parser.word()));
+                self.curr_field = IpxHeaderField::PT(0);
+            },
+            IpxHeaderField::PT(_) => {
+                self.header.set_field(IpxHeaderField::PT(self.parser.word()));
+                self.curr_field = IpxHeaderField::DNET(0);
+            },
+            IpxHeaderField::DNET(_) => self.qword(IpxHeaderField::DNET, IpxHeaderField::DNODE(MacAddress::new([0;6]))),
+            IpxHeaderField::DNODE(_) => self.node(b, IpxHeaderField::DNODE, IpxHeaderField::DSOCK(0)),
+            IpxHeaderField::DSOCK(_) => self.dword(IpxHeaderField::DSOCK, IpxHeaderField::SNET(0)),
+            IpxHeaderField::SNET(_) => self.qword(IpxHeaderField::SNET, IpxHeaderField::SNODE(MacAddress::new([0;6]))),
+            IpxHeaderField::SNODE(_) => self.node(b, IpxHeaderField::SNODE, IpxHeaderField::SSOCK(0)),
+            IpxHeaderField::SSOCK(_) => self.dword(IpxHeaderField::SSOCK, IpxHeaderField::UNSET),
+            IpxHeaderField::UNSET => (),
+        }
+        Ok(())
+    }
+
+    pub fn get_header(&self) -> IpxHeader {
+        self.header.clone()
+    }
+
+    fn dword(&mut self, field: fn(u16) -> IpxHeaderField, next: IpxHeaderField) {
+        if self.parser.dword_done() {
+            self.header.set_field(field(self.parser.dword_as_u16()));
+            self.parser.reset_dword();
+            self.curr_field = next;
+        }
+    }
+
+    fn qword(&mut self, field: fn(u32) -> IpxHeaderField, next: IpxHeaderField) {
+        if self.parser.qword_done() {
+            self.header.set_field(field(self.parser.qword_as_u32()));
+            self.parser.reset_qword();
+            self.curr_field = next;
+        }
+    }
+
+    fn node(&mut self, b: u8, field: fn(MacAddress) -> IpxHeaderField, next: IpxHeaderField) {
+        self.node[self.pos] = b;
+        self.pos += 1;
+        if self.pos == 6 {
+            self.header.set_field(field(MacAddress::new(self.node)));
+            self.pos = 0;
+            self.curr_field = next;
+        }
+    }
+
+    fn step(&mut self, b: u8) -> Result<(), Error> {
+        match self.curr_field {
+            IpxHeaderField::TC(_)|
+                IpxHeaderField::PT(_)
+                => {
+                    self.parser.set_word(b);
+                    Ok(())
+                },
+            IpxHeaderField::CHECK(_)|
+                IpxHeaderField::LEN(_)|
+                IpxHeaderField::DSOCK(_)|
+                IpxHeaderField::SSOCK(_)
+                => self.parser.set_d_byte(b).map_err(|e| Error::Byte(Layer::IPX, e)),
+            IpxHeaderField::DNET(_)|
+                IpxHeaderField::SNET(_)
+                => self.parser.set_q_byte(b).map_err(|e| Error::Byte(Layer::IPX, e)),
+            IpxHeaderField::DNODE(_)|
+                IpxHeaderField::SNODE(_)
+                => Ok(()),
+            IpxHeaderField::UNSET => Err(Error::HeaderComplete(Layer::IPX)),
+        }
+    }
+}
+
+/// Sequenced Packet Exchange header, carried in IPX packets of type 5.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SpxHeader {
+    connection_control: u8,
+    datastream_type: u8,
+    src_connection: Option<u16>,
+    dst_connection: Option<u16>,
+    sequence: Option<u16>,
+    acknowledge: Option<u16>,
+    allocation: Option<u16>,
+}
+
+impl SpxHeader {
+
+    pub fn parse(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() < SPX_HEADER_LEN {
+            return Err(Error::Truncated(Layer::SPX, bytes.len()));
+        }
+        Self::parse_partial(bytes)
+    }
+
+    pub fn parse_partial(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() < 2 {
+            return Err(Error::Truncated(Layer::SPX, bytes.len()));
+        }
+        let field = |i: usize| bytes.get(i..i + 2).map(|b| u16_at(b, 0));
+        Ok(Self {
+            connection_control: bytes[0],
+            datastream_type: bytes[1],
+            src_connection: field(2),
+            dst_connection: field(4),
+            sequence: field(6),
+            acknowledge: field(8),
+            allocation: field(10),
+        })
+    }
+
+    pub fn connection_control(&self) -> u8 {
+        self.connection_control
+    }
+
+    pub fn is_system(&self) -> bool {
+        self.connection_control & 0x80 != 0
+    }
+
+    pub fn ack_requested(&self) -> bool {
+        self.connection_control & 0x40 != 0
+    }
+
+    pub fn is_attention(&self) -> bool {
+        self.connection_control & 0x20 != 0
+    }
+
+    pub fn is_end_of_message(&self) -> bool {
+        self.connection_control & 0x10 != 0
+    }
+
+    pub fn is_spx2(&self) -> bool {
+        self.connection_control & 0x08 != 0
+    }
+
+    pub fn datastream_type(&self) -> u8 {
+        self.datastream_type
+    }
+
+    pub fn src_connection(&self) -> Option<u16> {
+        self.src_connection
+    }
+
+    pub fn dst_connection(&self) -> Option<u16> {
+        self.dst_connection
+    }
+
+    pub fn sequence(&self) -> Option<u16> {
+        self.sequence
+    }
+
+    pub fn acknowledge(&self) -> Option<u16> {
+        self.acknowledge
+    }
+
+    /// Highest sequence number the receiver has buffers for.
+    pub fn allocation(&self) -> Option<u16> {
+        self.allocation
+    }
+
+    pub fn header_len(&self) -> usize {
+        SPX_HEADER_LEN
+    }
+
+    fn control_names(&self) -> Vec<&'static str> {
+        [
+            (self.is_system(), "System"),
+            (self.ack_requested(), "Send Ack"),
+            (self.is_attention(), "Attention"),
+            (self.is_end_of_message(), "End of Message"),
+            (self.is_spx2(), "SPX II"),
+        ].into_iter().filter(|(set, _)| *set).map(|(_, name)| name).collect()
+    }
+
+    fn datastream_name(&self) -> &'static str {
+        match self.datastream_type {
+            0xFE => "End of Connection",
+            0xFF => "End of Connection Ack",
+            _ => "Client Defined",
+        }
+    }
+}
+
+impl Display for SpxHeader {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Connection Control: {:#04x} ({})", self.connection_control, self.control_names().join(", "))?;
+        write!(f, "Datastream Type: {:#04x} ({})", self.datastream_type, self.datastream_name())?;
+        if let Some(id) = self.src_connection {
+            write!(f, "\nSource Connection ID: {:#06x}", id)?;
+        }
+        if let Some(id) = self.dst_connection {
+            write!(f, "\nDestination Connection ID: {:#06x}", id)?;
+        }
+        for (name, value) in [
+            ("Sequence Number", self.sequence),
+            ("Acknowledge Number", self.acknowledge),
+            ("Allocation Number", self.allocation),
+        ] {
+            if let Some(v) = value {
+                write!(f, "\n{}: {}", name, v)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct IpxRoute {
+    pub network: u32,
+    pub hops: u16,
+    /// Delay in ticks of about 1/18 s.
+    pub ticks: u16,
+}
+
+/// Routing Information Protocol as spoken on socket 0x0453.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct IpxRip {
+    operation: u16,
+    routes: Vec<IpxRoute>,
+}
+
+impl IpxRip {
+
+    pub fn parse(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() < 2 {
+            return Err(Error::Truncated(Layer::IPXRIP, bytes.len()));
+        }
+        let body = &bytes[2..];
+        if !body.len().is_multiple_of(RIP_ENTRY_LEN) {
+            return Err(Error::Malformed(Layer::IPXRIP, 2, "invalid RIP entry length"));
+        }
+        let routes = body.chunks(RIP_ENTRY_LEN).map(|e| IpxRoute {
+            network: u32_at(e, 0),
+            hops: u16_at(e, 4),
+            ticks: u16_at(e, 6),
+        }).collect();
+        Ok(Self { operation: u16_at(bytes, 0), routes })
+    }
+
+    pub fn parse_partial(bytes: &[u8]) -> Result<Self, Error> {
+        let whole = bytes.len().saturating_sub(2) / RIP_ENTRY_LEN * RIP_ENTRY_LEN;
+        Self::parse(&bytes[..bytes.len().min(2 + whole)])
+    }
+
+    pub fn operation(&self) -> u16 {
+        self.operation
+    }
+
+    pub fn is_request(&self) -> bool {
+        self.operation == 1
+    }
+
+    pub fn routes(&self) -> &[IpxRoute] {
+        &self.routes
+    }
+
+    pub fn header_len(&self) -> usize {
+        2 + RIP_ENTRY_LEN * self.routes.len()
+    }
+}
+
+impl Display for IpxRip {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self.operation {
+            1 => "Request",
+            2 => "Response",
+            _ => "Unknown",
+        };
+        write!(f, "Operation: {} ({})", name, self.operation)?;
+        for route in self.routes.iter() {
+            write!(f, "\nNetwork {:08X}: {} hops, {} ticks", route.network, route.hops, route.ticks)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SapService {
+    pub service_type: u16,
+    pub name: String,
+    pub address: IpxAddress,
+    pub hops: u16,
+}
+
+/// Service Advertising Protocol as spoken on socket 0x0452.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct IpxSap {
+    operation: u16,
+    query_type: Option<u16>,
+    services: Vec<SapService>,
+}
+
+impl IpxSap {
+
+    pub fn parse(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() < 2 {
+            return Err(Error::Truncated(Layer::IPXSAP, bytes.len()));
+        }
+        let operation = u16_at(bytes, 0);
+        let body = &bytes[2..];
+        if operation == 1 || operation == 3 {
+            if body.len() < 2 {
+                return Err(Error::Truncated(Layer::IPXSAP, bytes.len()));
+            }
+            return Ok(Self { operation, query_type: Some(u16_at(body, 0)), services: vec![] });
+        }
+        if !body.len().is_multiple_of(SAP_ENTRY_LEN) {
+            return Err(Error::Malformed(Layer::IPXSAP, 2, "invalid SAP entry length"));
+        }
+        let services = body.chunks(SAP_ENTRY_LEN).map(|e| {
+            let name = &e[2..2 + SAP_NAME_LEN];
+            let end = name.iter().position(|b| *b == 0).unwrap_or(SAP_NAME_LEN);
+            SapService {
+                service_type: u16_at(e, 0),
+                name: String::from_utf8_lossy(&name[..end]).into_owned(),
+                address: IpxAddress::parse(&e[50..62]),
+                hops: u16_at(e, 62),
+            }
+        }).collect();
+        Ok(Self { operation, query_type: None, services })
+    }
+
+    pub fn parse_partial(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() >= 2 && matches!(u16_at(bytes, 0), 1 | 3) {
+            return Self::parse(bytes);
+        }
+        let whole = bytes.len().saturating_sub(2) / SAP_ENTRY_LEN * SAP_ENTRY_LEN;
+        Self::parse(&bytes[..bytes.len().min(2 + whole)])
+    }
+
+    pub fn operation(&self) -> u16 {
+        self.operation
+    }
+
+    pub fn is_query(&self) -> bool {
+        self.query_type.is_some()
+    }
+
+    /// Service type asked for by general and nearest queries.
+    pub fn query_type(&self) -> Option<u16> {
+        self.query_type
+    }
+
+    pub fn services(&self) -> &[SapService] {
+        &self.services
+    }
+
+    pub fn header_len(&self) -> usize {
+        match self.query_type {
+            Some(_) => 4,
+            None => 2 + SAP_ENTRY_LEN * self.services.len(),
+        }
+    }
+}
+
+impl Display for IpxSap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self.operation {
+            1 => "General Query",
+            2 => "General Response",
+            3 => "Nearest Query",
+            4 => "Nearest Response",
+            _ => "Unknown",
+        };
+        write!(f, "Operation: {} ({})", name, self.operation)?;
+        if let Some(service) = self.query_type {
+            write!(f, "\nService Type: {:#06x} ({})", service, service_type_name(service))?;
+        }
+        for s in self.services.iter() {
+            write!(
+                f,
+                "\nService {:#06x} ({}): {} at {}, {} hops",
+                s.service_type,
+                service_type_name(s.service_type),
+                s.name,
+                s.address,
+                s.hops,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ipx_header() {
+        let bytes = [
+            0xFF, 0xFF, 0x00, 0x28, 0x01, 0x04,
+            0x00, 0x00, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x04, 0x52,
+            0x00, 0x00, 0x00, 0x0A, 0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x40, 0x01,
+        ];
+        let mut parser = IpxHeaderParser::new();
+        for b in bytes {
+            parser.parse(b).unwrap();
+        }
+        assert_eq!(parser.parse(0), Err(Error::HeaderComplete(Layer::IPX)));
+        let header = parser.get_header();
+        assert_eq!(header.checksum(), Some(0xFFFF));
+        assert_eq!(header.length(), Some(40));
+        assert_eq!(header.hops(), Some(1));
+        assert_eq!(header.packet_type(), Some(PT_PEP));
+        assert!(header.is_sap());
+        assert!(!header.is_rip());
+        let src = header.source().unwrap();
+        assert_eq!(src.network(), 0x0A);
+        assert_eq!(src.socket(), 0x4001);
+        assert_eq!(src.to_string(), "0000000A.00:11:22:33:44:55.4001");
+        assert!(header.to_string().contains("Destination Socket: 0x0452 (SAP)\nSource Network: 0x0000000a"));
+    }
+
+    #[test]
+    fn test_spx() {
+        let spx = SpxHeader::parse(&[0xC0, 0x00, 0x12, 0x34, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03]).unwrap();
+        assert!(spx.is_system());
+        assert!(spx.ack_requested());
+        assert!(!spx.is_end_of_message());
+        assert_eq!(spx.src_connection(), Some(0x1234));
+        assert_eq!(spx.allocation(), Some(3));
+        assert!(spx.to_string().starts_with("Connection Control: 0xc0 (System, Send Ack)\n"));
+        assert_eq!(SpxHeader::parse(&[0;11]), Err(Error::Truncated(Layer::SPX, 11)));
+        let partial = SpxHeader::parse_partial(&[0xC0, 0x00, 0x12, 0x34, 0xFF]).unwrap();
+        assert_eq!((partial.src_connection(), partial.dst_connection()), (Some(0x1234), None));
+        assert_eq!(partial.to_string().lines().count(), 3);
+    }
+
+    #[test]
+    fn test_rip() {
+        let rip = IpxRip::parse(&[
+            0x00, 0x02,
+            0x00, 0x00, 0x00, 0x10, 0x00, 0x01, 0x00, 0x02,
+            0x00, 0x00, 0x00, 0x20, 0x00, 0x03, 0x00, 0x07,
+        ]).unwrap();
+        assert!(!rip.is_request());
+        assert_eq!(rip.routes()[1], IpxRoute { network: 0x20, hops: 3, ticks: 7 });
+        assert_eq!(rip.header_len(), 18);
+        assert_eq!(
+            rip.to_string(),
+            "Operation: Response (2)\nNetwork 00000010: 1 hops, 2 ticks\nNetwork 00000020: 3 hops, 7 ticks"
+        );
+        assert_eq!(
+            IpxRip::parse(&[0x00, 0x01, 0x00]),
+            Err(Error::Malformed(Layer::IPXRIP, 2, "invalid RIP entry length"))
+        );
+        let partial = IpxRip::parse_partial(&[0x00, 0x02, 0x00, 0x00, 0x00, 0x10, 0x00, 0x01, 0x00, 0x02, 0x00]).unwrap();
+        assert_eq!(partial.routes().len(), 1);
+    }
+
+    #[test]
+    fn test_sap() {
+        let query = IpxSap::parse(&[0x00, 0x01, 0x00, 0x04]).unwrap();
+        assert!(query.is_query());
+        assert_eq!(query.query_type(), Some(0x0004));
+        assert_eq!(query.header_len(), 4);
+
+        let mut bytes = vec![0x00, 0x02, 0x00, 0x04];
+        let mut name = b"FS1".to_vec();
+        name.resize(SAP_NAME_LEN, 0);
+        bytes.extend_from_slice(&name);
+        bytes.extend_from_slice(&[0x00, 0x00, 0x00, 0x0A, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x04, 0x51]);
+        bytes.extend_from_slice(&[0x00, 0x01]);
+        let sap = IpxSap::parse(&bytes).unwrap();
+        assert!(!sap.is_query());
+        assert_eq!(sap.services()[0].name, "FS1");
+        assert_eq!(sap.services()[0].address.socket(), SOCKET_NCP);
+        assert_eq!(
+            sap.to_string(),
+            "Operation: General Response (2)\n\
+             Service 0x0004 (File Server): FS1 at 0000000A.00:00:00:00:00:01.0451, 1 hops"
+        );
+        assert_eq!(
+            IpxSap::parse(&bytes[..20]),
+            Err(Error::Malformed(Layer::IPXSAP, 2, "invalid SAP entry length"))
+        );
+        assert!(IpxSap::parse_partial(&bytes[..20]).unwrap().services().is_empty());
+        assert_eq!(IpxSap::parse_partial(&[0x00, 0x01, 0x00]), Err(Error::Truncated(Layer::IPXSAP, 3)));
+    }
+}
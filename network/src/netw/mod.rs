@@ -1 +1,2 @@
     pub mod ipv6;
+    pub mod ipx;
@@ -0,0 +1,246 @@
+use std::net::Ipv4Addr;
+use std::time::Duration;
+
+use crate::dissect::DissectedPacket;
+use crate::error::{Error, Layer};
+use crate::ip::IPv4Header;
+use crate::transport::protocols::TransportProtocol;
+
+use super::{FragmentTable, ReassemblyConfig};
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Ipv4FragmentKey {
+    pub src: Ipv4Addr,
+    pub dst: Ipv4Addr,
+    pub protocol: u8,
+    pub id: u16,
+}
+
+impl Ipv4FragmentKey {
+
+    pub fn from_header(header: &IPv4Header) -> Option<Self> {
+        Some(Self {
+            src: header.src()?,
+            dst: header.dst()?,
+            protocol: header.protocol()?.to_u8(),
+            id: header.id()?,
+        })
+    }
+}
+
+pub struct ReassembledDatagram {
+    key: Ipv4FragmentKey,
+    header: IPv4Header,
+    payload: Vec<u8>,
+    fragments: usize,
+    overlaps: usize,
+}
+
+impl ReassembledDatagram {
+
+    pub fn key(&self) -> &Ipv4FragmentKey {
+        &self.key
+    }
+
+    pub fn header(&self) -> &IPv4Header {
+        &self.header
+    }
+
+    pub fn payload(&self) -> &[u8] {
+        &self.payload
+    }
+
+    pub fn protocol(&self) -> TransportProtocol {
+        TransportProtocol::new(self.key.protocol)
+    }
+
+    pub fn fragments(&self) -> usize {
+        self.fragments
+    }
+
+    pub fn overlaps(&self) -> usize {
+        self.overlaps
+    }
+
+    pub fn dissect(&self) -> DissectedPacket<'_> {
+        DissectedPacket::from_transport(self.protocol(), &self.payload)
+    }
+}
+
+pub struct Ipv4Reassembler {
+    table: FragmentTable<Ipv4FragmentKey, IPv4Header>,
+}
+
+impl Default for Ipv4Reassembler {
+    fn default() -> Self {
+        Self::new(ReassemblyConfig::default())
+    }
+}
+
+impl Ipv4Reassembler {
+
+    pub fn new(config: ReassemblyConfig) -> Self {
+        Self { table: FragmentTable::new(config) }
+    }
+
+    pub fn config(&self) -> &ReassemblyConfig {
+        self.table.config()
+    }
+
+    pub fn pending(&self) -> usize {
+        self.table.pending()
+    }
+
+    pub fn memory(&self) -> usize {
+        self.table.memory()
+    }
+
+    pub fn evicted(&self) -> usize {
+        self.table.evicted()
+    }
+
+    pub fn expire(&mut self, now: Duration) -> Vec<Ipv4FragmentKey> {
+        self.table.expire(now)
+    }
+
+    pub fn push(
+        &mut self,
+        header: &IPv4Header,
+        payload: &[u8],
+        now: Duration,
+    ) -> Result<Option<ReassembledDatagram>, Error> {
+        let error = |reason| Error::Reassembly(Layer::IPv4, reason);
+        let key = Ipv4FragmentKey::from_header(header).ok_or(error("incomplete header"))?;
+        let (more, offset) = match (header.more_fragments(), header.fragment_byte_offset()) {
+            (Some(m), Some(o)) => (m, o),
+            _ => return Err(error("incomplete header")),
+        };
+        self.table.expire(now);
+        if !more && offset == 0 {
+            return Ok(Some(ReassembledDatagram {
+                key,
+                header: header.clone(),
+                payload: payload.to_vec(),
+                fragments: 1,
+                overlaps: 0,
+            }));
+        }
+        if more && !payload.len().is_multiple_of(8) {
+            return Err(error("fragment length not a multiple of 8"));
+        }
+        let first = if offset == 0 { Some(header.clone()) } else { None };
+        let buffer = match self.table.insert(key, first, offset, payload, !more, now).map_err(error)? {
+            Some(b) => b,
+            None => return Ok(None),
+        };
+        let (header, payload, fragments, overlaps) = buffer.into_parts();
+        Ok(Some(ReassembledDatagram {
+            key,
+            header: header.ok_or(error("missing first fragment"))?,
+            payload,
+            fragments,
+            overlaps,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ip::IPv4HeaderParser;
+    use crate::reassembly::OverlapPolicy;
+
+    fn header(id: u16, more: bool, offset: u16, len: usize) -> IPv4Header {
+        let ff = ((more as u16) << 13) | (offset / 8);
+        let total = (20 + len) as u16;
+        let bytes = [
+            0x45, 0x00, (total >> 8) as u8, total as u8, (id >> 8) as u8, id as u8, (ff >> 8) as u8, ff as u8,
+            0x40, 0x11, 0x00, 0x00, 0x0A, 0x00, 0x00, 0x01,
+            0x0A, 0x00, 0x00, 0x02,
+        ];
+        let mut parser = IPv4HeaderParser::new(false);
+        for b in bytes {
+            parser.step(b).unwrap();
+        }
+        parser.get_header()
+    }
+
+    fn udp_datagram() -> Vec<u8> {
+        let mut data = vec![0x00, 0x35, 0xD4, 0x31, 0x00, 0x20, 0x00, 0x00];
+        data.extend((0..24).map(|b| b as u8));
+        data
+    }
+
+    #[test]
+    fn test_unfragmented() {
+        let mut r = Ipv4Reassembler::default();
+        let data = udp_datagram();
+        let d = r.push(&header(1, false, 0, 32), &data, Duration::ZERO).unwrap().unwrap();
+        assert_eq!(d.payload(), &data[..]);
+        assert_eq!(d.fragments(), 1);
+        assert_eq!(r.pending(), 0);
+    }
+
+    #[test]
+    fn test_out_of_order_reassembly() {
+        let mut r = Ipv4Reassembler::default();
+        let data = udp_datagram();
+        let t = Duration::from_secs(1);
+        assert!(r.push(&header(7, false, 24, 8), &data[24..], t).unwrap().is_none());
+        assert!(r.push(&header(7, true, 8, 16), &data[8..24], t).unwrap().is_none());
+        assert_eq!(r.pending(), 1);
+        assert_eq!(r.memory(), 32);
+        let d = r.push(&header(7, true, 0, 8), &data[..8], t).unwrap().unwrap();
+        assert_eq!(d.payload(), &data[..]);
+        assert_eq!(d.fragments(), 3);
+        assert_eq!(d.overlaps(), 0);
+        assert_eq!(d.key().id, 7);
+        assert_eq!(d.header().fragment_offset(), Some(0));
+        assert!(matches!(d.protocol(), TransportProtocol::UDP));
+        assert_eq!(r.pending(), 0);
+        assert_eq!(r.memory(), 0);
+
+        let packet = d.dissect();
+        assert_eq!(packet.udp().unwrap().dst_port(), Some(54321));
+        assert_eq!(packet.payload(), &data[8..]);
+    }
+
+    #[test]
+    fn test_separate_keys_and_overlap() {
+        let config = ReassemblyConfig { overlap: OverlapPolicy::LAST, ..Default::default() };
+        let mut r = Ipv4Reassembler::new(config);
+        let t = Duration::ZERO;
+        assert!(r.push(&header(1, true, 0, 16), &[1;16], t).unwrap().is_none());
+        assert!(r.push(&header(2, true, 0, 16), &[9;16], t).unwrap().is_none());
+        assert_eq!(r.pending(), 2);
+        let d = r.push(&header(1, false, 8, 16), &[2;16], t).unwrap().unwrap();
+        assert_eq!(d.payload(), &[vec![1u8;8], vec![2;16]].concat()[..]);
+        assert_eq!(d.overlaps(), 1);
+        assert_eq!(r.pending(), 1);
+    }
+
+    #[test]
+    fn test_timeout_and_errors() {
+        let config = ReassemblyConfig { timeout: Duration::from_secs(5), ..Default::default() };
+        let mut r = Ipv4Reassembler::new(config);
+        assert!(r.push(&header(1, true, 0, 8), &[0;8], Duration::from_secs(1)).unwrap().is_none());
+        assert!(r.push(&header(1, false, 8, 8), &[0;8], Duration::from_secs(7)).unwrap().is_none());
+        assert_eq!(r.pending(), 1);
+        assert_eq!(r.expire(Duration::from_secs(20)).len(), 1);
+
+        assert_eq!(
+            r.push(&header(2, true, 0, 12), &[0;12], Duration::ZERO).err(),
+            Some(Error::Reassembly(Layer::IPv4, "fragment length not a multiple of 8"))
+        );
+        assert!(r.push(&header(3, false, 16, 8), &[0;8], Duration::ZERO).unwrap().is_none());
+        assert_eq!(
+            r.push(&header(3, true, 24, 8), &[0;8], Duration::ZERO).err(),
+            Some(Error::Reassembly(Layer::IPv4, "fragment beyond end of datagram"))
+        );
+        assert_eq!(r.pending(), 0);
+        assert_eq!(
+            r.push(&IPv4Header::empty(), &[], Duration::ZERO).err(),
+            Some(Error::Reassembly(Layer::IPv4, "incomplete header"))
+        );
+    }
+}
@@ -0,0 +1,361 @@
+use std::collections::{BTreeMap, HashMap};
+use std::net::Ipv6Addr;
+use std::time::Duration;
+
+use crate::dissect::DissectedPacket;
+use crate::error::{Error, Layer};
+use crate::netw::ipv6::{IPv6ExtensionChain, IPv6ExtensionHeader, IPv6Header};
+use crate::transport::protocols::TransportProtocol;
+
+use super::{FragmentTable, OverlapPolicy, ReassemblyConfig};
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Ipv6FragmentKey {
+    pub src: Ipv6Addr,
+    pub dst: Ipv6Addr,
+    pub id: u32,
+}
+
+pub struct ReassembledDatagram {
+    key: Option<Ipv6FragmentKey>,
+    header: IPv6Header,
+    next_header: u8,
+    payload: Vec<u8>,
+    fragments: usize,
+}
+
+impl ReassembledDatagram {
+
+    pub fn key(&self) -> Option<&Ipv6FragmentKey> {
+        self.key.as_ref()
+    }
+
+    pub fn header(&self) -> &IPv6Header {
+        &self.header
+    }
+
+    pub fn payload(&self) -> &[u8] {
+        &self.payload
+    }
+
+    pub fn next_header(&self) -> TransportProtocol {
+        TransportProtocol::new(self.next_header)
+    }
+
+    pub fn fragments(&self) -> usize {
+        self.fragments
+    }
+
+    pub fn is_atomic(&self) -> bool {
+        self.key.is_some() && self.fragments == 1
+    }
+
+    /// Offsets in the chain are relative to the reassembled payload.
+    pub fn extensions(&self) -> Result<IPv6ExtensionChain, Error> {
+        IPv6ExtensionChain::parse(self.next_header, &self.payload, 0)
+    }
+
+    pub fn dissect(&self) -> DissectedPacket<'_> {
+        match self.extensions() {
+            Ok(chain) if !chain.is_truncated() => {
+                DissectedPacket::from_transport(chain.upper_layer(), &self.payload[chain.header_len()..])
+            },
+            _ => DissectedPacket::from_transport(TransportProtocol::IPv6NoNxt, &[]),
+        }
+    }
+}
+
+pub struct Ipv6Reassembler {
+    // The first fragment's header and the Next Header of its Fragment header.
+    table: FragmentTable<Ipv6FragmentKey, (IPv6Header, u8)>,
+    // Datagrams dropped for overlapping, keyed to their timer like the fragment table.
+    discarded: HashMap<Ipv6FragmentKey, (Duration, usize)>,
+    discard_timers: BTreeMap<(Duration, usize), Ipv6FragmentKey>,
+    serial: usize,
+}
+
+impl Default for Ipv6Reassembler {
+    fn default() -> Self {
+        Self::new(ReassemblyConfig { overlap: OverlapPolicy::DISCARD, ..Default::default() })
+    }
+}
+
+impl Ipv6Reassembler {
+
+    pub fn new(config: ReassemblyConfig) -> Self {
+        Self {
+            table: FragmentTable::new(config),
+            discarded: HashMap::new(),
+            discard_timers: BTreeMap::new(),
+            serial: 0,
+        }
+    }
+
+    pub fn config(&self) -> &ReassemblyConfig {
+        self.table.config()
+    }
+
+    pub fn pending(&self) -> usize {
+        self.table.pending()
+    }
+
+    pub fn memory(&self) -> usize {
+        self.table.memory()
+    }
+
+    pub fn evicted(&self) -> usize {
+        self.table.evicted()
+    }
+
+    pub fn discarded(&self) -> usize {
+        self.discarded.len()
+    }
+
+    pub fn expire(&mut self, now: Duration) -> Vec<Ipv6FragmentKey> {
+        let timeout = self.table.config().timeout;
+        while let Some((&(seen, _), _)) = self.discard_timers.first_key_value() {
+            if now.saturating_sub(seen) < timeout {
+                break;
+            }
+            self.forget_oldest_discarded();
+        }
+        self.table.expire(now)
+    }
+
+    fn forget_oldest_discarded(&mut self) {
+        if let Some((_, key)) = self.discard_timers.pop_first() {
+            self.discarded.remove(&key);
+        }
+    }
+
+    fn discard(&mut self, key: Ipv6FragmentKey, now: Duration) {
+        if self.discarded.contains_key(&key) {
+            return;
+        }
+        while self.discarded.len() >= self.table.config().max_pending.max(1) {
+            self.forget_oldest_discarded();
+        }
+        let timer = (now, self.serial);
+        self.serial += 1;
+        self.discard_timers.insert(timer, key);
+        self.discarded.insert(key, timer);
+    }
+
+    /// `datagram` starts at the fixed IPv6 header.
+    pub fn push(
+        &mut self,
+        header: &IPv6Header,
+        datagram: &[u8],
+        now: Duration,
+    ) -> Result<Option<ReassembledDatagram>, Error> {
+        let error = |reason| Error::Reassembly(Layer::IPv6, reason);
+        let (src, dst, next_header) = match (header.src(), header.dst(), header.next_header()) {
+            (Some(s), Some(d), Some(n)) => (s, d, n.to_u8()),
+            _ => return Err(error("incomplete header")),
+        };
+        let end = 40 + header.payload_len().unwrap_or(0) as usize;
+        if datagram.len() < end {
+            return Err(error("truncated datagram"));
+        }
+        let chain = match header.extensions() {
+            Some(c) => c.clone(),
+            None => IPv6ExtensionChain::parse(next_header, &datagram[40..end], 40)?,
+        };
+        self.expire(now);
+
+        // Header offsets may be frame offsets, so measure from the fixed header instead.
+        let mut start = 40;
+        let fragment = chain.headers().iter().find_map(|h| {
+            start += h.header_len();
+            match h.header() {
+                IPv6ExtensionHeader::FRAGMENT { offset, more, id } => {
+                    Some((h.next_header().to_u8(), start, *offset as usize * 8, *more, *id))
+                },
+                _ => None,
+            }
+        });
+        let (next_header, start, offset, more, id) = match fragment {
+            Some(f) => f,
+            None => return Ok(Some(ReassembledDatagram {
+                key: None,
+                header: header.clone(),
+                next_header,
+                payload: datagram[40..end].to_vec(),
+                fragments: 1,
+            })),
+        };
+        let key = Ipv6FragmentKey { src, dst, id };
+        let payload = &datagram[start.min(end)..end];
+        if !more && offset == 0 {
+            return Ok(Some(ReassembledDatagram {
+                key: Some(key),
+                header: header.clone(),
+                next_header,
+                payload: payload.to_vec(),
+                fragments: 1,
+            }));
+        }
+        if self.discarded.contains_key(&key) {
+            return Err(error("datagram discarded after overlapping fragment"));
+        }
+        if more && !payload.len().is_multiple_of(8) {
+            return Err(error("fragment length not a multiple of 8"));
+        }
+
+        let first = if offset == 0 { Some((header.clone(), next_header)) } else { None };
+        let buffer = match self.table.insert(key, first, offset, payload, !more, now) {
+            Ok(Some(b)) => b,
+            Ok(None) => return Ok(None),
+            Err(e) => {
+                if self.table.config().overlap == OverlapPolicy::DISCARD && e == "overlapping fragment" {
+                    self.discard(key, now);
+                }
+                return Err(error(e));
+            },
+        };
+        let (header, payload, fragments, _) = buffer.into_parts();
+        let (header, next_header) = header.ok_or(error("missing first fragment"))?;
+        Ok(Some(ReassembledDatagram {
+            key: Some(key),
+            header,
+            next_header,
+            payload,
+            fragments,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::netw::ipv6::IPv6HeaderParser;
+
+    fn datagram(id: u32, more: bool, offset: u16, next: u8, payload: &[u8]) -> (IPv6Header, Vec<u8>) {
+        let len = (8 + payload.len()) as u16;
+        let mut data = vec![0x60, 0x00, 0x00, 0x00, (len >> 8) as u8, len as u8, 44, 0x40];
+        data.extend_from_slice(&"2001:db8::1".parse::<Ipv6Addr>().unwrap().octets());
+        data.extend_from_slice(&"2001:db8::2".parse::<Ipv6Addr>().unwrap().octets());
+        let frag = (offset & 0xFFF8) | more as u16;
+        data.extend_from_slice(&[next, 0, (frag >> 8) as u8, frag as u8]);
+        data.extend_from_slice(&id.to_be_bytes());
+        data.extend_from_slice(payload);
+        let mut parser = IPv6HeaderParser::new(false);
+        for b in data[..40].iter() {
+            parser.parse(*b).unwrap();
+        }
+        (parser.get_header(), data)
+    }
+
+    fn udp_datagram() -> Vec<u8> {
+        let mut data = vec![0x00, 0x35, 0xD4, 0x31, 0x00, 0x20, 0x00, 0x00];
+        data.extend((0..24).map(|b| b as u8));
+        data
+    }
+
+    #[test]
+    fn test_reassembly() {
+        let mut r = Ipv6Reassembler::default();
+        let data = udp_datagram();
+        let t = Duration::ZERO;
+        let (h, d) = datagram(9, false, 16, 17, &data[16..]);
+        assert!(r.push(&h, &d, t).unwrap().is_none());
+        let (h, d) = datagram(9, true, 0, 17, &data[..8]);
+        assert!(r.push(&h, &d, t).unwrap().is_none());
+        let (h, d) = datagram(10, true, 0, 17, &data[..16]);
+        assert!(r.push(&h, &d, t).unwrap().is_none());
+        assert_eq!(r.pending(), 2);
+
+        let (h, d) = datagram(9, true, 0, 17, &data[..16]);
+        assert_eq!(
+            r.push(&h, &d, t).err(),
+            Some(Error::Reassembly(Layer::IPv6, "overlapping fragment"))
+        );
+        assert_eq!(r.pending(), 1);
+        assert_eq!(r.discarded(), 1);
+        let (h, d) = datagram(9, true, 8, 17, &data[8..16]);
+        assert_eq!(
+            r.push(&h, &d, t).err(),
+            Some(Error::Reassembly(Layer::IPv6, "datagram discarded after overlapping fragment"))
+        );
+        r.expire(Duration::from_secs(60));
+        assert_eq!(r.discarded(), 0);
+        assert_eq!(r.pending(), 0);
+
+        let (h, d) = datagram(11, true, 0, 17, &data[..16]);
+        assert!(r.push(&h, &d, t).unwrap().is_none());
+        // Only the first fragment's Next Header counts.
+        let (h, d) = datagram(11, false, 16, 6, &data[16..]);
+        let reassembled = r.push(&h, &d, t).unwrap().unwrap();
+        assert_eq!(reassembled.payload(), &data[..]);
+        assert_eq!(reassembled.fragments(), 2);
+        assert!(!reassembled.is_atomic());
+        assert_eq!(reassembled.key().unwrap().id, 11);
+        assert!(matches!(reassembled.next_header(), TransportProtocol::UDP));
+        let packet = reassembled.dissect();
+        assert_eq!(packet.udp().unwrap().dst_port(), Some(54321));
+        assert_eq!(packet.payload(), &data[8..]);
+    }
+
+    #[test]
+    fn test_atomic_and_unfragmented() {
+        let mut r = Ipv6Reassembler::default();
+        let data = udp_datagram();
+        let (h, d) = datagram(1, true, 0, 17, &data[..16]);
+        assert!(r.push(&h, &d, Duration::ZERO).unwrap().is_none());
+        let (h, d) = datagram(1, false, 0, 17, &data);
+        let atomic = r.push(&h, &d, Duration::ZERO).unwrap().unwrap();
+        assert!(atomic.is_atomic());
+        assert_eq!(atomic.payload(), &data[..]);
+        assert_eq!(r.pending(), 1);
+
+        let (_, mut d) = datagram(1, false, 0, 17, &data);
+        d[6] = 17;
+        d.drain(40..48);
+        d[5] -= 8;
+        let mut parser = IPv6HeaderParser::new(false);
+        for b in d[..40].iter() {
+            parser.parse(*b).unwrap();
+        }
+        let h = parser.get_header();
+        let whole = r.push(&h, &d, Duration::ZERO).unwrap().unwrap();
+        assert!(whole.key().is_none());
+        assert_eq!(whole.dissect().udp().unwrap().src_port(), Some(53));
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut r = Ipv6Reassembler::default();
+        let (h, d) = datagram(1, true, 0, 17, &[0;12]);
+        assert_eq!(
+            r.push(&h, &d, Duration::ZERO).err(),
+            Some(Error::Reassembly(Layer::IPv6, "fragment length not a multiple of 8"))
+        );
+        assert_eq!(
+            r.push(&h, &d[..50], Duration::ZERO).err(),
+            Some(Error::Reassembly(Layer::IPv6, "truncated datagram"))
+        );
+        assert_eq!(
+            r.push(&IPv6Header::new(), &d, Duration::ZERO).err(),
+            Some(Error::Reassembly(Layer::IPv6, "incomplete header"))
+        );
+    }
+
+    #[test]
+    fn test_discarded_limit() {
+        let config = ReassemblyConfig { overlap: OverlapPolicy::DISCARD, max_pending: 2, ..Default::default() };
+        let mut r = Ipv6Reassembler::new(config);
+        let data = udp_datagram();
+        for id in 1..=3 {
+            let t = Duration::from_secs(id as u64);
+            let (h, d) = datagram(id, true, 0, 17, &data[..16]);
+            assert!(r.push(&h, &d, t).unwrap().is_none());
+            let (h, d) = datagram(id, true, 8, 17, &data[8..24]);
+            assert!(r.push(&h, &d, t).is_err());
+        }
+        assert_eq!(r.discarded(), 2);
+        assert_eq!(r.discard_timers.len(), 2);
+        assert!(r.discarded.keys().all(|k| k.id != 1));
+        r.expire(Duration::from_secs(32));
+        assert_eq!(r.discarded(), 1);
+    }
+}
@@ -0,0 +1,358 @@
+pub mod ipv4;
+pub mod ipv6;
+pub mod tcp;
+
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+use std::time::Duration;
+
+/// Which bytes win when data overlaps what is already buffered. `DISCARD` drops the
+/// whole datagram for fragments, and only the overlapping segment for TCP.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum OverlapPolicy {
+    FIRST,
+    LAST,
+    DISCARD,
+}
+
+#[derive(Clone, Debug)]
+pub struct ReassemblyConfig {
+    pub timeout: Duration,
+    pub max_datagram_len: usize,
+    pub max_memory: usize,
+    pub max_pending: usize,
+    pub overlap: OverlapPolicy,
+}
+
+impl Default for ReassemblyConfig {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(30),
+            max_datagram_len: 65535,
+            max_memory: 4 * 1024 * 1024,
+            max_pending: 1024,
+            overlap: OverlapPolicy::FIRST,
+        }
+    }
+}
+
+pub(crate) struct FragmentBuffer<H> {
+    header: Option<H>,
+    data: Vec<u8>,
+    filled: Vec<(usize, usize)>,
+    total_len: Option<usize>,
+    timer: (Duration, usize),
+    fragments: usize,
+    overlaps: usize,
+}
+
+impl<H> FragmentBuffer<H> {
+
+    fn new(timer: (Duration, usize)) -> Self {
+        Self {
+            header: None,
+            data: vec![],
+            filled: vec![],
+            total_len: None,
+            timer,
+            fragments: 0,
+            overlaps: 0,
+        }
+    }
+
+    pub(crate) fn into_parts(self) -> (Option<H>, Vec<u8>, usize, usize) {
+        (self.header, self.data, self.fragments, self.overlaps)
+    }
+
+    fn is_complete(&self) -> bool {
+        match self.total_len {
+            Some(0) => self.header.is_some(),
+            Some(t) => self.filled == [(0, t)],
+            None => false,
+        }
+    }
+
+    fn copy(&mut self, from: usize, to: usize, offset: usize, payload: &[u8]) {
+        self.data[from..to].copy_from_slice(&payload[from - offset..to - offset]);
+    }
+
+    fn insert(
+        &mut self,
+        offset: usize,
+        payload: &[u8],
+        last: bool,
+        policy: OverlapPolicy,
+    ) -> Result<(), &'static str> {
+        let end = offset + payload.len();
+        if let Some(t) = self.total_len {
+            if end > t {
+                return Err("fragment beyond end of datagram");
+            }
+            if last && end != t {
+                return Err("conflicting datagram length");
+            }
+        }
+        if last {
+            if self.filled.last().is_some_and(|(_, e)| *e > end) {
+                return Err("fragment beyond end of datagram");
+            }
+            self.total_len = Some(end);
+        }
+        let overlapping = self.filled.iter().any(|(s, e)| *s < end && offset < *e);
+        if overlapping && policy == OverlapPolicy::DISCARD {
+            return Err("overlapping fragment");
+        }
+        if self.data.len() < end {
+            self.data.resize(end, 0);
+        }
+
+        if !overlapping || policy == OverlapPolicy::LAST {
+            self.copy(offset, end, offset, payload);
+        } else {
+            let mut cursor = offset;
+            let filled = self.filled.clone();
+            for (s, e) in filled {
+                if s >= end {
+                    break;
+                }
+                if s > cursor {
+                    self.copy(cursor, s, offset, payload);
+                }
+                cursor = cursor.max(e);
+            }
+            if cursor < end {
+                self.copy(cursor, end, offset, payload);
+            }
+        }
+
+        self.filled.push((offset, end));
+        self.filled.sort();
+        let mut merged: Vec<(usize, usize)> = vec![];
+        for (s, e) in self.filled.iter() {
+            match merged.last_mut() {
+                Some(last) if *s <= last.1 => last.1 = last.1.max(*e),
+                _ => merged.push((*s, *e)),
+            }
+        }
+        self.filled = merged;
+        self.fragments += 1;
+        if overlapping {
+            self.overlaps += 1;
+        }
+        Ok(())
+    }
+}
+
+pub(crate) struct FragmentTable<K, H> {
+    config: ReassemblyConfig,
+    pending: HashMap<K, FragmentBuffer<H>>,
+    // Keyed by (first seen, serial) so the oldest datagram is always first.
+    timers: BTreeMap<(Duration, usize), K>,
+    serial: usize,
+    memory: usize,
+    evicted: usize,
+}
+
+impl<K: Hash + Eq + Clone, H> FragmentTable<K, H> {
+
+    pub(crate) fn new(config: ReassemblyConfig) -> Self {
+        Self {
+            config,
+            pending: HashMap::new(),
+            timers: BTreeMap::new(),
+            serial: 0,
+            memory: 0,
+            evicted: 0,
+        }
+    }
+
+    pub(crate) fn config(&self) -> &ReassemblyConfig {
+        &self.config
+    }
+
+    pub(crate) fn pending(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub(crate) fn memory(&self) -> usize {
+        self.memory
+    }
+
+    pub(crate) fn evicted(&self) -> usize {
+        self.evicted
+    }
+
+    pub(crate) fn expire(&mut self, now: Duration) -> Vec<K> {
+        let mut expired = vec![];
+        while let Some((&(seen, _), key)) = self.timers.first_key_value() {
+            if now.saturating_sub(seen) < self.config.timeout {
+                break;
+            }
+            let key = key.clone();
+            self.remove(&key);
+            expired.push(key);
+        }
+        expired
+    }
+
+    fn remove(&mut self, key: &K) -> Option<FragmentBuffer<H>> {
+        let buffer = self.pending.remove(key)?;
+        self.timers.remove(&buffer.timer);
+        self.memory -= buffer.data.len();
+        Some(buffer)
+    }
+
+    fn evict_oldest(&mut self, keep: &K) -> bool {
+        let oldest = self.timers.values().find(|k| *k != keep).cloned();
+        match oldest {
+            Some(k) => {
+                self.remove(&k);
+                self.evicted += 1;
+                true
+            },
+            None => false,
+        }
+    }
+
+    pub(crate) fn insert(
+        &mut self,
+        key: K,
+        header: Option<H>,
+        offset: usize,
+        payload: &[u8],
+        last: bool,
+        now: Duration,
+    ) -> Result<Option<FragmentBuffer<H>>, &'static str> {
+        let end = offset + payload.len();
+        if end > self.config.max_datagram_len {
+            self.remove(&key);
+            return Err("datagram exceeds maximum length");
+        }
+        let current = self.pending.get(&key).map(|b| b.data.len());
+        let growth = end.saturating_sub(current.unwrap_or(0));
+        while self.memory + growth > self.config.max_memory
+            || (current.is_none() && self.pending.len() >= self.config.max_pending)
+        {
+            if !self.evict_oldest(&key) {
+                self.remove(&key);
+                return Err("reassembly memory limit exceeded");
+            }
+        }
+
+        let policy = self.config.overlap;
+        let buffer = self.pending.entry(key.clone()).or_insert_with(|| {
+            let timer = (now, self.serial);
+            self.serial += 1;
+            self.timers.insert(timer, key.clone());
+            FragmentBuffer::new(timer)
+        });
+        let before = buffer.data.len();
+        if let Err(e) = buffer.insert(offset, payload, last, policy) {
+            self.remove(&key);
+            return Err(e);
+        }
+        if header.is_some() && (buffer.header.is_none() || policy == OverlapPolicy::LAST) {
+            buffer.header = header;
+        }
+        self.memory += buffer.data.len() - before;
+        if buffer.is_complete() {
+            return Ok(self.remove(&key));
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn buffer() -> FragmentBuffer<()> {
+        FragmentBuffer::new((Duration::ZERO, 0))
+    }
+
+    #[test]
+    fn test_out_of_order() {
+        let mut b = buffer();
+        b.insert(16, &[3;8], true, OverlapPolicy::FIRST).unwrap();
+        assert!(!b.is_complete());
+        b.insert(0, &[1;8], false, OverlapPolicy::FIRST).unwrap();
+        b.insert(8, &[2;8], false, OverlapPolicy::FIRST).unwrap();
+        b.header = Some(());
+        assert!(b.is_complete());
+        assert_eq!(b.filled, vec![(0, 24)]);
+        assert_eq!(b.data, [[1u8;8], [2;8], [3;8]].concat());
+        assert_eq!(b.overlaps, 0);
+    }
+
+    #[test]
+    fn test_overlap_policies() {
+        for (policy, expected) in [
+            (OverlapPolicy::FIRST, [[1u8;8], [1;8], [2;8]].concat()),
+            (OverlapPolicy::LAST, [[1u8;8], [2;8], [2;8]].concat()),
+        ] {
+            let mut b = buffer();
+            b.insert(0, &[1;16], false, policy).unwrap();
+            b.insert(8, &[2;16], true, policy).unwrap();
+            assert_eq!(b.data, expected);
+            assert_eq!(b.overlaps, 1);
+            assert_eq!(b.filled, vec![(0, 24)]);
+        }
+
+        let mut b = buffer();
+        b.insert(0, &[1;16], false, OverlapPolicy::DISCARD).unwrap();
+        assert_eq!(b.insert(8, &[2;16], true, OverlapPolicy::DISCARD), Err("overlapping fragment"));
+        assert_eq!(b.data, vec![1;16]);
+    }
+
+    #[test]
+    fn test_inconsistent_length() {
+        let mut b = buffer();
+        b.insert(0, &[1;16], true, OverlapPolicy::FIRST).unwrap();
+        assert_eq!(b.insert(16, &[1;8], false, OverlapPolicy::FIRST), Err("fragment beyond end of datagram"));
+        assert_eq!(b.insert(0, &[1;8], true, OverlapPolicy::FIRST), Err("conflicting datagram length"));
+
+        let mut b = buffer();
+        b.insert(16, &[1;8], false, OverlapPolicy::FIRST).unwrap();
+        assert_eq!(b.insert(0, &[1;8], true, OverlapPolicy::FIRST), Err("fragment beyond end of datagram"));
+    }
+
+    #[test]
+    fn test_table_limits() {
+        let config = ReassemblyConfig { max_memory: 32, max_pending: 2, ..Default::default() };
+        let mut table: FragmentTable<u32, ()> = FragmentTable::new(config);
+        table.insert(1, None, 0, &[0;16], false, Duration::from_secs(1)).unwrap();
+        table.insert(2, None, 0, &[0;16], false, Duration::from_secs(2)).unwrap();
+        assert_eq!(table.memory(), 32);
+        table.insert(3, None, 0, &[0;8], false, Duration::from_secs(3)).unwrap();
+        assert_eq!(table.pending(), 2);
+        assert_eq!(table.evicted(), 1);
+        assert!(!table.pending.contains_key(&1));
+        assert_eq!(table.memory(), 24);
+        assert_eq!(
+            table.insert(4, None, 0, &[0;40], false, Duration::from_secs(4)).err(),
+            Some("reassembly memory limit exceeded")
+        );
+        assert_eq!(table.pending(), 0);
+        assert_eq!(table.memory(), 0);
+        assert_eq!(
+            table.insert(5, None, 65528, &[0;16], false, Duration::from_secs(5)).err(),
+            Some("datagram exceeds maximum length")
+        );
+    }
+
+    #[test]
+    fn test_table_expire() {
+        let config = ReassemblyConfig { timeout: Duration::from_secs(10), ..Default::default() };
+        let mut table: FragmentTable<u32, ()> = FragmentTable::new(config);
+        table.insert(1, None, 8, &[0;8], true, Duration::from_secs(1)).unwrap();
+        table.insert(2, None, 8, &[0;8], true, Duration::from_secs(5)).unwrap();
+        assert!(table.expire(Duration::from_secs(10)).is_empty());
+        assert_eq!(table.expire(Duration::from_secs(11)), vec![1]);
+        assert_eq!(table.pending(), 1);
+        assert_eq!(table.memory(), 16);
+        table.insert(3, None, 8, &[0;8], true, Duration::from_secs(5)).unwrap();
+        table.insert(4, None, 8, &[0;8], true, Duration::from_secs(12)).unwrap();
+        assert_eq!(table.expire(Duration::from_secs(15)), vec![2, 3]);
+        assert_eq!(table.timers.len(), 1);
+    }
+}
@@ -0,0 +1,708 @@
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Display;
+use std::net::IpAddr;
+use std::time::Duration;
+
+use crate::dissect::DissectedPacket;
+use crate::error::{Error, Layer};
+use crate::transport::tcp::TcpHeader;
+
+use super::{OverlapPolicy, ReassemblyConfig};
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct Endpoint {
+    pub addr: IpAddr,
+    pub port: u16,
+}
+
+impl Display for Endpoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.addr {
+            IpAddr::V4(a) => write!(f, "{}:{}", a, self.port),
+            IpAddr::V6(a) => write!(f, "[{}]:{}", a, self.port),
+        }
+    }
+}
+
+/// The side that sent the bytes: the client opened the connection.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Direction {
+    CLIENT,
+    SERVER,
+}
+
+impl Direction {
+
+    fn index(&self) -> usize {
+        match self {
+            Self::CLIENT => 0,
+            Self::SERVER => 1,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CloseReason {
+    FIN,
+    RESET,
+    TIMEOUT,
+    EVICTED,
+    END,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum StreamEvent {
+    OPEN {
+        id: usize,
+        client: Endpoint,
+        server: Endpoint,
+    },
+    DATA {
+        id: usize,
+        direction: Direction,
+        data: Vec<u8>,
+    },
+    GAP {
+        id: usize,
+        direction: Direction,
+        len: u64,
+    },
+    CLOSE {
+        id: usize,
+        reason: CloseReason,
+    },
+}
+
+impl StreamEvent {
+
+    pub fn id(&self) -> usize {
+        match self {
+            Self::OPEN { id, .. } => *id,
+            Self::DATA { id, .. } => *id,
+            Self::GAP { id, .. } => *id,
+            Self::CLOSE { id, .. } => *id,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct TcpHalfStream {
+    isn: Option<u32>,
+    base: Option<u32>,
+    delivered: u64,
+    segments: BTreeMap<u64, Vec<u8>>,
+    buffered: usize,
+    fin: Option<u64>,
+    closed: bool,
+    retransmissions: usize,
+    overlaps: usize,
+    out_of_order: usize,
+    gaps: u64,
+}
+
+impl TcpHalfStream {
+
+    pub fn isn(&self) -> Option<u32> {
+        self.isn
+    }
+
+    pub fn bytes(&self) -> u64 {
+        self.delivered
+    }
+
+    pub fn buffered(&self) -> usize {
+        self.buffered
+    }
+
+    pub fn retransmissions(&self) -> usize {
+        self.retransmissions
+    }
+
+    pub fn overlaps(&self) -> usize {
+        self.overlaps
+    }
+
+    pub fn out_of_order(&self) -> usize {
+        self.out_of_order
+    }
+
+    pub fn gaps(&self) -> u64 {
+        self.gaps
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+
+    // Signed distance from the next expected byte, so wraparound is transparent.
+    fn offset(&self, seq: u32) -> i64 {
+        let expected = self.base.unwrap_or(seq).wrapping_add(self.delivered as u32);
+        self.delivered as i64 + seq.wrapping_sub(expected) as i32 as i64
+    }
+
+    fn insert(&mut self, offset: i64, data: &[u8], policy: OverlapPolicy) {
+        let end = offset + data.len() as i64;
+        if end <= self.delivered as i64 {
+            self.retransmissions += 1;
+            return;
+        }
+        let (start, data) = if offset < self.delivered as i64 {
+            self.overlaps += 1;
+            (self.delivered, &data[(self.delivered as i64 - offset) as usize..])
+        } else {
+            (offset as u64, data)
+        };
+        let end = end as u64;
+        if start > self.delivered {
+            self.out_of_order += 1;
+        }
+
+        let overlapping: Vec<(u64, u64)> = self.segments.range(..end)
+            .map(|(s, d)| (*s, *s + d.len() as u64))
+            .filter(|(_, e)| *e > start)
+            .collect();
+        if !overlapping.is_empty() {
+            self.overlaps += 1;
+            if policy == OverlapPolicy::DISCARD {
+                return;
+            }
+        }
+        if overlapping.is_empty() || policy == OverlapPolicy::LAST {
+            for (s, e) in overlapping {
+                let old = self.segments.remove(&s).unwrap_or_default();
+                self.buffered -= old.len();
+                if s < start {
+                    self.put(s, old[..(start - s) as usize].to_vec());
+                }
+                if e > end {
+                    self.put(end, old[(end - s) as usize..].to_vec());
+                }
+            }
+            self.put(start, data.to_vec());
+        } else {
+            let mut cursor = start;
+            for (s, e) in overlapping {
+                if s > cursor {
+                    self.put(cursor, data[(cursor - start) as usize..(s - start) as usize].to_vec());
+                }
+                cursor = cursor.max(e);
+            }
+            if cursor < end {
+                self.put(cursor, data[(cursor - start) as usize..].to_vec());
+            }
+        }
+    }
+
+    fn put(&mut self, start: u64, data: Vec<u8>) {
+        self.buffered += data.len();
+        self.segments.insert(start, data);
+    }
+
+    fn flush(&mut self) -> Vec<u8> {
+        let mut out = vec![];
+        while let Some((&start, _)) = self.segments.first_key_value() {
+            if start > self.delivered {
+                break;
+            }
+            let data = self.segments.remove(&start).unwrap_or_default();
+            self.buffered -= data.len();
+            let end = start + data.len() as u64;
+            if end > self.delivered {
+                out.extend_from_slice(&data[(self.delivered - start) as usize..]);
+                self.delivered = end;
+            }
+        }
+        if self.fin.is_some_and(|f| f <= self.delivered) {
+            self.closed = true;
+        }
+        out
+    }
+
+    fn skip_gap(&mut self) -> Option<u64> {
+        let start = *self.segments.first_key_value()?.0;
+        let len = start - self.delivered;
+        self.gaps += len;
+        self.delivered = start;
+        Some(len)
+    }
+}
+
+pub struct TcpConnection {
+    id: usize,
+    client: Endpoint,
+    server: Endpoint,
+    streams: [TcpHalfStream;2],
+    last_seen: Duration,
+}
+
+impl TcpConnection {
+
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+    pub fn client(&self) -> Endpoint {
+        self.client
+    }
+
+    pub fn server(&self) -> Endpoint {
+        self.server
+    }
+
+    pub fn stream(&self, direction: Direction) -> &TcpHalfStream {
+        &self.streams[direction.index()]
+    }
+
+    fn buffered(&self) -> usize {
+        self.streams.iter().map(|s| s.buffered).sum()
+    }
+
+    fn direction(&self, src: &Endpoint) -> Direction {
+        if *src == self.client { Direction::CLIENT } else { Direction::SERVER }
+    }
+
+    fn deliver(&mut self, direction: Direction, events: &mut Vec<StreamEvent>) {
+        let id = self.id;
+        let data = self.streams[direction.index()].flush();
+        if !data.is_empty() {
+            events.push(StreamEvent::DATA { id, direction, data });
+        }
+    }
+
+    fn drain(&mut self, events: &mut Vec<StreamEvent>) {
+        for direction in [Direction::CLIENT, Direction::SERVER] {
+            while let Some(len) = self.streams[direction.index()].skip_gap() {
+                events.push(StreamEvent::GAP { id: self.id, direction, len });
+                self.deliver(direction, events);
+            }
+        }
+    }
+}
+
+fn connection_key(a: Endpoint, b: Endpoint) -> (Endpoint, Endpoint) {
+    if a <= b { (a, b) } else { (b, a) }
+}
+
+pub struct TcpReassembler {
+    config: ReassemblyConfig,
+    connections: HashMap<(Endpoint, Endpoint), TcpConnection>,
+    ids: BTreeMap<usize, (Endpoint, Endpoint)>,
+    // Keyed by (last seen, id) so the idlest connection is always first.
+    timers: BTreeMap<(Duration, usize), (Endpoint, Endpoint)>,
+    memory: usize,
+    events: Vec<StreamEvent>,
+    next_id: usize,
+}
+
+impl Default for TcpReassembler {
+    fn default() -> Self {
+        Self::new(ReassemblyConfig { timeout: Duration::from_secs(300), ..Default::default() })
+    }
+}
+
+impl TcpReassembler {
+
+    pub fn new(config: ReassemblyConfig) -> Self {
+        Self {
+            config,
+            connections: HashMap::new(),
+            ids: BTreeMap::new(),
+            timers: BTreeMap::new(),
+            memory: 0,
+            events: vec![],
+            next_id: 0,
+        }
+    }
+
+    pub fn config(&self) -> &ReassemblyConfig {
+        &self.config
+    }
+
+    pub fn pending(&self) -> usize {
+        self.connections.len()
+    }
+
+    /// Bytes buffered out of order across all connections.
+    pub fn memory(&self) -> usize {
+        self.memory
+    }
+
+    pub fn connection(&self, id: usize) -> Option<&TcpConnection> {
+        self.connections.get(self.ids.get(&id)?)
+    }
+
+    pub fn connections(&self) -> impl Iterator<Item = &TcpConnection> {
+        self.connections.values()
+    }
+
+    pub fn events(&mut self) -> std::vec::Drain<'_, StreamEvent> {
+        self.events.drain(..)
+    }
+
+    pub fn expire(&mut self, now: Duration) -> Vec<usize> {
+        let mut expired = vec![];
+        while let Some((&(seen, _), &key)) = self.timers.first_key_value() {
+            if now.saturating_sub(seen) < self.config.timeout {
+                break;
+            }
+            expired.push(self.close(&key, CloseReason::TIMEOUT));
+        }
+        expired
+    }
+
+    pub fn flush(&mut self) {
+        while let Some((_, key)) = self.ids.first_key_value() {
+            let key = *key;
+            self.close(&key, CloseReason::END);
+        }
+    }
+
+    fn close(&mut self, key: &(Endpoint, Endpoint), reason: CloseReason) -> usize {
+        let mut conn = match self.connections.remove(key) {
+            Some(c) => c,
+            None => return 0,
+        };
+        self.ids.remove(&conn.id);
+        self.timers.remove(&(conn.last_seen, conn.id));
+        self.memory -= conn.buffered();
+        if reason != CloseReason::RESET {
+            conn.drain(&mut self.events);
+        }
+        self.events.push(StreamEvent::CLOSE { id: conn.id, reason });
+        conn.id
+    }
+
+    fn evict_oldest(&mut self, keep: &(Endpoint, Endpoint)) -> bool {
+        match self.timers.values().find(|k| *k != keep).copied() {
+            Some(key) => {
+                self.close(&key, CloseReason::EVICTED);
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// Keeps the bytes buffered by all connections under `max_memory`, first by
+    /// skipping the gap in front of the stream that grew, then by evicting the idlest
+    /// other connections.
+    fn limit_memory(&mut self, key: &(Endpoint, Endpoint), direction: Direction) {
+        while self.memory > self.config.max_memory {
+            let conn = match self.connections.get_mut(key) {
+                Some(c) => c,
+                None => return,
+            };
+            let before = conn.buffered();
+            if let Some(len) = conn.streams[direction.index()].skip_gap() {
+                self.events.push(StreamEvent::GAP { id: conn.id, direction, len });
+                conn.deliver(direction, &mut self.events);
+                self.memory = self.memory - before + conn.buffered();
+            } else if !self.evict_oldest(key) {
+                // Only the other direction of this connection is left holding bytes.
+                if let Some(conn) = self.connections.get_mut(key) {
+                    self.memory -= conn.buffered();
+                    conn.drain(&mut self.events);
+                }
+                return;
+            }
+        }
+    }
+
+    pub fn push_packet(&mut self, packet: &DissectedPacket, now: Duration) -> Result<Option<usize>, Error> {
+        let (src, dst) = match (packet.ipv4(), packet.ipv6()) {
+            (Some(ip), _) => (ip.src().map(IpAddr::V4), ip.dst().map(IpAddr::V4)),
+            (None, Some(ip)) => (ip.src().map(IpAddr::V6), ip.dst().map(IpAddr::V6)),
+            _ => (None, None),
+        };
+        let (layer, header) = match (packet.layer(Layer::TCP), packet.tcp()) {
+            (Some(l), Some(h)) if !l.is_truncated() => (l, h),
+            _ => return Ok(None),
+        };
+        match (src, dst) {
+            (Some(src), Some(dst)) => self.push(src, dst, header, layer.payload(), now),
+            _ => Ok(None),
+        }
+    }
+
+    pub fn push(
+        &mut self,
+        src: IpAddr,
+        dst: IpAddr,
+        header: &TcpHeader,
+        payload: &[u8],
+        now: Duration,
+    ) -> Result<Option<usize>, Error> {
+        let (sport, dport, seq, flags) = match (header.src_port(), header.dst_port(), header.seq(), header.flags()) {
+            (Some(s), Some(d), Some(q), Some(f)) => (s, d, q, f),
+            _ => return Err(Error::Reassembly(Layer::TCP, "incomplete header")),
+        };
+        let src = Endpoint { addr: src, port: sport };
+        let dst = Endpoint { addr: dst, port: dport };
+        let key = connection_key(src, dst);
+        self.expire(now);
+
+        if !self.connections.contains_key(&key) {
+            if !flags.syn() && payload.is_empty() {
+                return Ok(None);
+            }
+            if self.connections.len() >= self.config.max_pending {
+                self.evict_oldest(&key);
+            }
+        }
+
+        let conn = match self.connections.entry(key) {
+            Entry::Occupied(e) => e.into_mut(),
+            Entry::Vacant(e) => {
+                let (client, server) = if flags.syn() && flags.ack() { (dst, src) } else { (src, dst) };
+                let id = self.next_id;
+                self.next_id += 1;
+                self.events.push(StreamEvent::OPEN { id, client, server });
+                self.ids.insert(id, key);
+                self.timers.insert((now, id), key);
+                e.insert(TcpConnection {
+                    id,
+                    client,
+                    server,
+                    streams: Default::default(),
+                    last_seen: now,
+                })
+            },
+        };
+        let id = conn.id;
+        self.timers.remove(&(conn.last_seen, id));
+        self.timers.insert((now, id), key);
+        conn.last_seen = now;
+        if flags.rst() {
+            self.close(&key, CloseReason::RESET);
+            return Ok(Some(id));
+        }
+
+        let before = conn.buffered();
+        let direction = conn.direction(&src);
+        let half = &mut conn.streams[direction.index()];
+        let mut seq = seq;
+        if flags.syn() {
+            if half.base.is_none() {
+                half.isn = Some(seq);
+                half.base = Some(seq.wrapping_add(1));
+            }
+            seq = seq.wrapping_add(1);
+        }
+        if half.base.is_none() {
+            half.base = Some(seq);
+        }
+        let offset = half.offset(seq);
+        if !payload.is_empty() {
+            half.insert(offset, payload, self.config.overlap);
+        }
+        if flags.fin() && half.fin.is_none() {
+            half.fin = Some((offset + payload.len() as i64).max(0) as u64);
+        }
+        conn.deliver(direction, &mut self.events);
+        self.memory = self.memory - before + conn.buffered();
+        self.limit_memory(&key, direction);
+        if self.connections.get(&key).is_some_and(|c| c.streams.iter().all(|s| s.closed)) {
+            self.close(&key, CloseReason::FIN);
+        }
+        Ok(Some(id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::tcp::{TcpFlags, TcpHeaderParser};
+
+    const CLIENT: &str = "10.0.0.1";
+    const SERVER: &str = "10.0.0.2";
+
+    fn segment(from_client: bool, seq: u32, flags: u16) -> (IpAddr, IpAddr, TcpHeader) {
+        let (sport, dport): (u16, u16) = if from_client { (40000, 80) } else { (80, 40000) };
+        let mut bytes = vec![];
+        bytes.extend_from_slice(&sport.to_be_bytes());
+        bytes.extend_from_slice(&dport.to_be_bytes());
+        bytes.extend_from_slice(&seq.to_be_bytes());
+        bytes.extend_from_slice(&[0, 0, 0, 0, 0x50, flags as u8, 0xFF, 0xFF, 0, 0, 0, 0]);
+        let mut parser = TcpHeaderParser::new(false);
+        for b in bytes {
+            parser.parse(b).unwrap();
+        }
+        let (src, dst) = if from_client { (CLIENT, SERVER) } else { (SERVER, CLIENT) };
+        (src.parse().unwrap(), dst.parse().unwrap(), parser.get_header())
+    }
+
+    fn push(r: &mut TcpReassembler, from_client: bool, seq: u32, flags: u16, payload: &[u8]) {
+        let (src, dst, header) = segment(from_client, seq, flags);
+        r.push(src, dst, &header, payload, Duration::ZERO).unwrap();
+    }
+
+    fn stream(events: &[StreamEvent], direction: Direction) -> Vec<u8> {
+        events.iter().filter_map(|e| match e {
+            StreamEvent::DATA { direction: d, data, .. } if *d == direction => Some(data.clone()),
+            _ => None,
+        }).collect::<Vec<Vec<u8>>>().concat()
+    }
+
+    const SYN: u16 = TcpFlags::SYN;
+    const ACK: u16 = TcpFlags::ACK;
+    const FIN: u16 = TcpFlags::FIN | TcpFlags::ACK;
+
+    #[test]
+    fn test_handshake_data_and_close() {
+        let mut r = TcpReassembler::default();
+        push(&mut r, true, 1000, SYN, &[]);
+        push(&mut r, false, 5000, SYN | ACK, &[]);
+        push(&mut r, true, 1001, ACK, &[]);
+        push(&mut r, true, 1001, ACK, b"GET / HTTP/1.1\r\n");
+        push(&mut r, false, 5001, ACK, b"HTTP/1.1 200 OK\r\n");
+        let conn = r.connection(0).unwrap();
+        assert_eq!(conn.client().to_string(), "10.0.0.1:40000");
+        assert_eq!(conn.stream(Direction::CLIENT).isn(), Some(1000));
+        assert_eq!(conn.stream(Direction::SERVER).isn(), Some(5000));
+        assert_eq!(conn.stream(Direction::CLIENT).bytes(), 16);
+        push(&mut r, true, 1017, FIN, &[]);
+        push(&mut r, false, 5018, FIN, &[]);
+        assert_eq!(r.pending(), 0);
+
+        let events: Vec<StreamEvent> = r.events().collect();
+        assert!(matches!(events[0], StreamEvent::OPEN { id: 0, .. }));
+        assert_eq!(stream(&events, Direction::CLIENT), b"GET / HTTP/1.1\r\n");
+        assert_eq!(stream(&events, Direction::SERVER), b"HTTP/1.1 200 OK\r\n");
+        assert_eq!(events.last(), Some(&StreamEvent::CLOSE { id: 0, reason: CloseReason::FIN }));
+        assert_eq!(r.events().count(), 0);
+    }
+
+    #[test]
+    fn test_out_of_order_and_retransmission() {
+        let mut r = TcpReassembler::default();
+        push(&mut r, true, 100, SYN, &[]);
+        push(&mut r, true, 107, ACK, b"world");
+        push(&mut r, true, 101, ACK, b"hello ");
+        push(&mut r, true, 101, ACK, b"hello ");
+        push(&mut r, true, 110, ACK, b"ld!!");
+        push(&mut r, true, 120, ACK, b"xyz");
+        push(&mut r, true, 119, ACK, b"?ab");
+        let conn = r.connection(0).unwrap();
+        let half = conn.stream(Direction::CLIENT);
+        assert_eq!(half.retransmissions(), 1);
+        assert_eq!(half.out_of_order(), 3);
+        assert_eq!(half.overlaps(), 2);
+        assert_eq!(half.buffered(), 4);
+        push(&mut r, true, 113, ACK, b"######");
+        let events: Vec<StreamEvent> = r.events().collect();
+        assert_eq!(stream(&events, Direction::CLIENT), b"hello world!!#####?xyz");
+    }
+
+    #[test]
+    fn test_last_overlap_policy() {
+        let config = ReassemblyConfig { overlap: OverlapPolicy::LAST, ..Default::default() };
+        let mut r = TcpReassembler::new(config);
+        push(&mut r, true, 0, SYN, &[]);
+        push(&mut r, true, 5, ACK, b"aaaa");
+        push(&mut r, true, 3, ACK, b"bbbb");
+        push(&mut r, true, 1, ACK, b"cc");
+        let events: Vec<StreamEvent> = r.events().collect();
+        assert_eq!(stream(&events, Direction::CLIENT), b"ccbbbbaa");
+    }
+
+    #[test]
+    fn test_discard_overlap_policy() {
+        let config = ReassemblyConfig { overlap: OverlapPolicy::DISCARD, ..Default::default() };
+        let mut r = TcpReassembler::new(config);
+        push(&mut r, true, 0, SYN, &[]);
+        push(&mut r, true, 5, ACK, b"aaaa");
+        push(&mut r, true, 3, ACK, b"bbbb");
+        push(&mut r, true, 1, ACK, b"cccc");
+        assert_eq!(r.connection(0).unwrap().stream(Direction::CLIENT).overlaps(), 1);
+        let events: Vec<StreamEvent> = r.events().collect();
+        assert_eq!(stream(&events, Direction::CLIENT), b"ccccaaaa");
+    }
+
+    #[test]
+    fn test_global_memory_limit() {
+        let config = ReassemblyConfig { max_memory: 8, ..Default::default() };
+        let mut r = TcpReassembler::new(config);
+        push(&mut r, true, 0, SYN, &[]);
+        push(&mut r, true, 11, ACK, b"12345");
+        assert_eq!(r.memory(), 5);
+
+        let (_, dst, header) = segment(true, 0, SYN);
+        let src: IpAddr = "10.0.0.3".parse().unwrap();
+        r.push(src, dst, &header, &[], Duration::ZERO).unwrap();
+        let (_, _, header) = segment(true, 11, ACK);
+        r.push(src, dst, &header, b"abcde", Duration::ZERO).unwrap();
+        assert_eq!(r.memory(), 5);
+        assert_eq!(r.connection(0).unwrap().stream(Direction::CLIENT).buffered(), 5);
+        assert_eq!(r.connection(1).unwrap().stream(Direction::CLIENT).gaps(), 10);
+
+        r.flush();
+        assert_eq!(r.memory(), 0);
+        let events: Vec<StreamEvent> = r.events().collect();
+        assert_eq!(stream(&events, Direction::CLIENT), b"abcde12345");
+    }
+
+    #[test]
+    fn test_sequence_wraparound() {
+        let mut r = TcpReassembler::default();
+        push(&mut r, true, 0xFFFF_FFF9, SYN, &[]);
+        push(&mut r, true, 0xFFFF_FFFA, ACK, b"abcdef");
+        push(&mut r, true, 2, ACK, b"ijk");
+        push(&mut r, true, 0, ACK, b"ghij");
+        push(&mut r, true, 5, FIN, &[]);
+        let events: Vec<StreamEvent> = r.events().collect();
+        assert_eq!(stream(&events, Direction::CLIENT), b"abcdefghijk");
+        assert!(r.connection(0).unwrap().stream(Direction::CLIENT).is_closed());
+    }
+
+    #[test]
+    fn test_reset_midstream_and_gaps() {
+        let mut r = TcpReassembler::default();
+        push(&mut r, false, 9000, ACK, b"late");
+        push(&mut r, false, 9010, ACK, b"after gap");
+        push(&mut r, true, 1, ACK, &[]);
+        assert_eq!(r.connection(0).unwrap().client().port, 80);
+        r.flush();
+        let events: Vec<StreamEvent> = r.events().collect();
+        assert_eq!(stream(&events, Direction::CLIENT), b"lateafter gap");
+        assert!(events.contains(&StreamEvent::GAP { id: 0, direction: Direction::CLIENT, len: 6 }));
+        assert_eq!(events.last(), Some(&StreamEvent::CLOSE { id: 0, reason: CloseReason::END }));
+
+        push(&mut r, true, 0, SYN, &[]);
+        push(&mut r, true, 5, ACK, b"lost");
+        push(&mut r, false, 0, TcpFlags::RST, &[]);
+        let events: Vec<StreamEvent> = r.events().collect();
+        assert_eq!(events.last(), Some(&StreamEvent::CLOSE { id: 1, reason: CloseReason::RESET }));
+        assert_eq!(stream(&events, Direction::CLIENT), b"");
+    }
+
+    #[test]
+    fn test_limits_and_timeout() {
+        let config = ReassemblyConfig {
+            timeout: Duration::from_secs(10),
+            max_memory: 8,
+            max_pending: 1,
+            ..Default::default()
+        };
+        let mut r = TcpReassembler::new(config);
+        push(&mut r, true, 0, SYN, &[]);
+        push(&mut r, true, 11, ACK, b"12345");
+        push(&mut r, true, 21, ACK, b"67890");
+        let events: Vec<StreamEvent> = r.events().collect();
+        assert_eq!(events[1], StreamEvent::GAP { id: 0, direction: Direction::CLIENT, len: 10 });
+        assert_eq!(stream(&events, Direction::CLIENT), b"12345");
+        assert_eq!(r.connection(0).unwrap().stream(Direction::CLIENT).buffered(), 5);
+
+        let (src, dst, header) = segment(true, 0, SYN);
+        let src = match src { IpAddr::V4(_) => "10.0.0.3".parse().unwrap(), a => a };
+        r.push(src, dst, &header, &[], Duration::from_secs(1)).unwrap();
+        assert_eq!(r.pending(), 1);
+        assert!(r.events().any(|e| e == StreamEvent::CLOSE { id: 0, reason: CloseReason::EVICTED }));
+        assert_eq!(r.expire(Duration::from_secs(11)), vec![1]);
+        assert_eq!(r.pending(), 0);
+
+        assert_eq!(
+            r.push(src, dst, &TcpHeader::new(), &[], Duration::ZERO).err(),
+            Some(Error::Reassembly(Layer::TCP, "incomplete header"))
+        );
+    }
+}
@@ -0,0 +1,89 @@
+use crate::error::{Error, Layer};
+
+/// Layout of a kind-length-value option list, as used by IPv4, TCP and IPv6
+/// extension headers.
+pub(crate) struct OptionFormat {
+    pub layer: Layer,
+    /// Kinds that are a single byte without a length field.
+    pub single: &'static [u8],
+    /// Kind of the single-byte option that ends the list.
+    pub end: Option<u8>,
+    /// The length field counts the kind and length bytes themselves.
+    pub inclusive_len: bool,
+}
+
+impl OptionFormat {
+
+    /// Walks `bytes`, calling `option` with each kind, its value (`None` for single-byte
+    /// kinds) and its offset. `base` is the offset of `bytes[0]` in the frame, so errors
+    /// point at the option itself.
+    pub fn parse<T, F>(&self, bytes: &[u8], base: usize, mut option: F) -> Result<Vec<T>, Error>
+    where
+        F: FnMut(u8, Option<&[u8]>, usize) -> Result<T, Error>,
+    {
+        let mut options = vec![];
+        let mut i = 0;
+        while i < bytes.len() {
+            let kind = bytes[i];
+            let offset = base + i;
+            if self.single.contains(&kind) {
+                options.push(option(kind, None, offset)?);
+                if self.end == Some(kind) {
+                    break;
+                }
+                i += 1;
+                continue;
+            }
+            if i + 1 >= bytes.len() {
+                return Err(Error::Malformed(self.layer, offset, "option length missing"));
+            }
+            let len = bytes[i + 1] as usize + if self.inclusive_len { 0 } else { 2 };
+            if len < 2 || i + len > bytes.len() {
+                return Err(Error::Malformed(self.layer, offset, "option length out of bounds"));
+            }
+            options.push(option(kind, Some(&bytes[i + 2..i + len]), offset)?);
+            i += len;
+        }
+        Ok(options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INCLUSIVE: OptionFormat = OptionFormat {
+        layer: Layer::TCP,
+        single: &[0, 1],
+        end: Some(0),
+        inclusive_len: true,
+    };
+
+    const EXCLUSIVE: OptionFormat = OptionFormat {
+        layer: Layer::IPv6,
+        single: &[0],
+        end: None,
+        inclusive_len: false,
+    };
+
+    fn collect(format: &OptionFormat, bytes: &[u8]) -> Result<Vec<(u8, Vec<u8>, usize)>, Error> {
+        format.parse(bytes, 100, |kind, value, offset| {
+            Ok((kind, value.map(|v| v.to_vec()).unwrap_or_default(), offset))
+        })
+    }
+
+    #[test]
+    fn test_option_formats() {
+        assert_eq!(
+            collect(&INCLUSIVE, &[1, 2, 4, 5, 0xB4, 0, 9, 9]).unwrap(),
+            vec![(1, vec![], 100), (2, vec![5, 0xB4], 101), (0, vec![], 105)]
+        );
+        assert_eq!(
+            collect(&EXCLUSIVE, &[0, 1, 2, 0, 0, 5, 0]).unwrap(),
+            vec![(0, vec![], 100), (1, vec![0, 0], 101), (5, vec![], 105)]
+        );
+        assert_eq!(collect(&INCLUSIVE, &[1, 2]), Err(Error::Malformed(Layer::TCP, 101, "option length missing")));
+        assert_eq!(collect(&INCLUSIVE, &[2, 1]), Err(Error::Malformed(Layer::TCP, 100, "option length out of bounds")));
+        assert_eq!(collect(&EXCLUSIVE, &[5, 2, 0]), Err(Error::Malformed(Layer::IPv6, 100, "option length out of bounds")));
+    }
+}
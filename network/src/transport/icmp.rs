@@ -0,0 +1,516 @@
+use std::fmt::Display;
+use std::net::Ipv4Addr;
+
+use byte::{bytes_to_u16, ByteParser};
+
+use crate::error::{Error, Layer};
+use crate::ip::{IPv4Header, IPv4HeaderParser};
+use crate::transport::protocols::TransportProtocol;
+
+pub const ECHO_REPLY: u8 = 0;
+pub const DEST_UNREACHABLE: u8 = 3;
+pub const SOURCE_QUENCH: u8 = 4;
+pub const REDIRECT: u8 = 5;
+pub const ECHO_REQUEST: u8 = 8;
+pub const ROUTER_ADVERTISEMENT: u8 = 9;
+pub const ROUTER_SOLICITATION: u8 = 10;
+pub const TIME_EXCEEDED: u8 = 11;
+pub const PARAMETER_PROBLEM: u8 = 12;
+pub const TIMESTAMP: u8 = 13;
+pub const TIMESTAMP_REPLY: u8 = 14;
+pub const INFO_REQUEST: u8 = 15;
+pub const INFO_REPLY: u8 = 16;
+pub const MASK_REQUEST: u8 = 17;
+pub const MASK_REPLY: u8 = 18;
+
+pub fn type_name(icmp_type: u8) -> &'static str {
+    match icmp_type {
+        ECHO_REPLY => "Echo Reply",
+        DEST_UNREACHABLE => "Destination Unreachable",
+        SOURCE_QUENCH => "Source Quench",
+        REDIRECT => "Redirect",
+        ECHO_REQUEST => "Echo Request",
+        ROUTER_ADVERTISEMENT => "Router Advertisement",
+        ROUTER_SOLICITATION => "Router Solicitation",
+        TIME_EXCEEDED => "Time Exceeded",
+        PARAMETER_PROBLEM => "Parameter Problem",
+        TIMESTAMP => "Timestamp",
+        TIMESTAMP_REPLY => "Timestamp Reply",
+        INFO_REQUEST => "Information Request",
+        INFO_REPLY => "Information Reply",
+        MASK_REQUEST => "Address Mask Request",
+        MASK_REPLY => "Address Mask Reply",
+        _ => "Unknown",
+    }
+}
+
+pub fn code_name(icmp_type: u8, code: u8) -> Option<&'static str> {
+    let name = match (icmp_type, code) {
+        (DEST_UNREACHABLE, 0) => "Network Unreachable",
+        (DEST_UNREACHABLE, 1) => "Host Unreachable",
+        (DEST_UNREACHABLE, 2) => "Protocol Unreachable",
+        (DEST_UNREACHABLE, 3) => "Port Unreachable",
+        (DEST_UNREACHABLE, 4) => "Fragmentation Needed",
+        (DEST_UNREACHABLE, 5) => "Source Route Failed",
+        (DEST_UNREACHABLE, 6) => "Destination Network Unknown",
+        (DEST_UNREACHABLE, 7) => "Destination Host Unknown",
+        (DEST_UNREACHABLE, 8) => "Source Host Isolated",
+        (DEST_UNREACHABLE, 9) => "Network Administratively Prohibited",
+        (DEST_UNREACHABLE, 10) => "Host Administratively Prohibited",
+        (DEST_UNREACHABLE, 11) => "Network Unreachable for ToS",
+        (DEST_UNREACHABLE, 12) => "Host Unreachable for ToS",
+        (DEST_UNREACHABLE, 13) => "Communication Administratively Prohibited",
+        (DEST_UNREACHABLE, 14) => "Host Precedence Violation",
+        (DEST_UNREACHABLE, 15) => "Precedence Cutoff in Effect",
+        (REDIRECT, 0) => "Redirect for Network",
+        (REDIRECT, 1) => "Redirect for Host",
+        (REDIRECT, 2) => "Redirect for ToS and Network",
+        (REDIRECT, 3) => "Redirect for ToS and Host",
+        (TIME_EXCEEDED, 0) => "TTL Exceeded in Transit",
+        (TIME_EXCEEDED, 1) => "Fragment Reassembly Time Exceeded",
+        (PARAMETER_PROBLEM, 0) => "Pointer Indicates the Error",
+        (PARAMETER_PROBLEM, 1) => "Missing a Required Option",
+        (PARAMETER_PROBLEM, 2) => "Bad Length",
+        _ => return None,
+    };
+    Some(name)
+}
+
+/// RFC 1071 ones' complement sum; zero over a message with its checksum means valid.
+pub fn internet_checksum(data: &[u8]) -> u16 {
+    let mut sum: u32 = data.chunks(2)
+        .map(|c| bytes_to_u16(c[0], *c.get(1).unwrap_or(&0), false) as u32)
+        .sum();
+    while sum > 0xFFFF {
+        sum = (sum & 0xFFFF) + (sum >> 16);
+    }
+    !(sum as u16)
+}
+
+#[derive(Clone)]
+pub struct IcmpQuote {
+    header: IPv4Header,
+    transport: Vec<u8>,
+}
+
+impl IcmpQuote {
+
+    pub fn parse(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() < 20 {
+            return Err(Error::Truncated(Layer::ICMP, 8 + bytes.len()));
+        }
+        let mut parser = IPv4HeaderParser::new(false);
+        for b in bytes[..20].iter() {
+            parser.step(*b)?;
+        }
+        let hlen = parser.get_header().header_len().unwrap_or(20);
+        if hlen < 20 || hlen > bytes.len() {
+            return Err(Error::Malformed(Layer::ICMP, 8, "invalid quoted header length"));
+        }
+        for b in bytes[20..hlen].iter() {
+            parser.step(*b)?;
+        }
+        Ok(Self {
+            header: parser.get_header(),
+            transport: bytes[hlen..bytes.len().min(hlen + 8)].to_vec(),
+        })
+    }
+
+    pub fn header(&self) -> &IPv4Header {
+        &self.header
+    }
+
+    pub fn transport(&self) -> &[u8] {
+        &self.transport
+    }
+
+    fn has_ports(&self) -> bool {
+        self.transport.len() >= 4
+            && matches!(self.header.protocol(), Some(TransportProtocol::TCP) | Some(TransportProtocol::UDP))
+    }
+
+    pub fn src_port(&self) -> Option<u16> {
+        if !self.has_ports() {
+            return None;
+        }
+        Some(bytes_to_u16(self.transport[0], self.transport[1], false))
+    }
+
+    pub fn dst_port(&self) -> Option<u16> {
+        if !self.has_ports() {
+            return None;
+        }
+        Some(bytes_to_u16(self.transport[2], self.transport[3], false))
+    }
+}
+
+impl Display for IcmpQuote {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let addr = |a: Option<Ipv4Addr>| a.map_or("?".to_string(), |a| a.to_string());
+        let proto = self.header.protocol().map_or("?".to_string(), |p| p.to_str());
+        match (self.src_port(), self.dst_port()) {
+            (Some(s), Some(d)) => write!(
+                f,
+                "Quoted: {}:{} -> {}:{} ({})",
+                addr(self.header.src()), s, addr(self.header.dst()), d, proto
+            ),
+            _ => write!(f, "Quoted: {} -> {} ({})", addr(self.header.src()), addr(self.header.dst()), proto),
+        }
+    }
+}
+
+pub enum IcmpHeaderField {
+    TYPE(u8),
+    CODE(u8),
+    CHECK(u16),
+    REST(u32),
+    UNSET,
+}
+
+impl Display for IcmpHeaderField {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::TYPE(b) => write!(f, "Type: {} ({})", b, type_name(*b)),
+            Self::CODE(b) => write!(f, "Code: {}", b),
+            Self::CHECK(b) => write!(f, "Checksum: {:#06x}", b),
+            Self::REST(b) => write!(f, "Rest of Header: {:#010x}", b),
+            Self::UNSET => write!(f, "UNSET"),
+        }
+    }
+}
+
+impl Clone for IcmpHeaderField {
+    fn clone(&self) -> Self {
+        match self {
+            Self::TYPE(b) => Self::TYPE(*b),
+            Self::CODE(b) => Self::CODE(*b),
+            Self::CHECK(b) => Self::CHECK(*b),
+            Self::REST(b) => Self::REST(*b),
+            Self::UNSET => Self::UNSET,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct IcmpHeader {
+    icmp_type: IcmpHeaderField,
+    code: IcmpHeaderField,
+    check: IcmpHeaderField,
+    rest: IcmpHeaderField,
+    checksum_valid: Option<bool>,
+    quote: Option<IcmpQuote>,
+}
+
+impl Display for IcmpHeader {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.icmp_type)?;
+        match (self.icmp_type(), self.code()) {
+            (Some(t), Some(c)) => match code_name(t, c) {
+                Some(name) => write!(f, "\nCode: {} ({})", c, name)?,
+                None => write!(f, "\n{}", self.code)?,
+            },
+            _ => write!(f, "\n{}", self.code)?,
+        }
+        write!(f, "\n{}", self.check)?;
+        match self.checksum_valid {
+            Some(true) => write!(f, " [correct]")?,
+            Some(false) => write!(f, " [incorrect]")?,
+            None => (),
+        }
+        if let (Some(id), Some(seq)) = (self.identifier(), self.sequence()) {
+            write!(f, "\nIdentifier: {}\nSequence: {}", id, seq)?;
+        } else if let Some(gw) = self.gateway() {
+            write!(f, "\nGateway: {}", gw)?;
+        } else if let Some(mtu) = self.next_hop_mtu() {
+            write!(f, "\nNext-Hop MTU: {}", mtu)?;
+        } else if let Some(ptr) = self.pointer() {
+            write!(f, "\nPointer: {}", ptr)?;
+        }
+        if let Some(q) = &self.quote {
+            write!(f, "\n{}", q)?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for IcmpHeader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IcmpHeader {
+
+    pub fn new() -> Self {
+        Self {
+            icmp_type: IcmpHeaderField::UNSET,
+            code: IcmpHeaderField::UNSET,
+            check: IcmpHeaderField::UNSET,
+            rest: IcmpHeaderField::UNSET,
+            checksum_valid: None,
+            quote: None,
+        }
+    }
+
+    pub fn set_field(&mut self, field: IcmpHeaderField) {
+        match field {
+            IcmpHeaderField::TYPE(_) => self.icmp_type = field,
+            IcmpHeaderField::CODE(_) => self.code = field,
+            IcmpHeaderField::CHECK(_) => self.check = field,
+            IcmpHeaderField::REST(_) => self.rest = field,
+            IcmpHeaderField::UNSET => (),
+        }
+    }
+
+    pub fn set_quote(&mut self, quote: IcmpQuote) {
+        self.quote = Some(quote);
+    }
+
+    pub fn set_checksum_valid(&mut self, valid: bool) {
+        self.checksum_valid = Some(valid);
+    }
+
+    pub fn icmp_type(&self) -> Option<u8> {
+        match self.icmp_type {
+            IcmpHeaderField::TYPE(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn type_name(&self) -> Option<&'static str> {
+        self.icmp_type().map(type_name)
+    }
+
+    pub fn code(&self) -> Option<u8> {
+        match self.code {
+            IcmpHeaderField::CODE(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn code_name(&self) -> Option<&'static str> {
+        code_name(self.icmp_type()?, self.code()?)
+    }
+
+    pub fn checksum(&self) -> Option<u16> {
+        match self.check {
+            IcmpHeaderField::CHECK(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn checksum_valid(&self) -> Option<bool> {
+        self.checksum_valid
+    }
+
+    pub fn rest(&self) -> Option<u32> {
+        match self.rest {
+            IcmpHeaderField::REST(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn is_echo(&self) -> bool {
+        matches!(
+            self.icmp_type(),
+            Some(ECHO_REPLY | ECHO_REQUEST | TIMESTAMP | TIMESTAMP_REPLY | INFO_REQUEST | INFO_REPLY | MASK_REQUEST | MASK_REPLY)
+        )
+    }
+
+    pub fn is_error(&self) -> bool {
+        matches!(
+            self.icmp_type(),
+            Some(DEST_UNREACHABLE | SOURCE_QUENCH | REDIRECT | TIME_EXCEEDED | PARAMETER_PROBLEM)
+        )
+    }
+
+    pub fn identifier(&self) -> Option<u16> {
+        if !self.is_echo() {
+            return None;
+        }
+        self.rest().map(|r| (r >> 16) as u16)
+    }
+
+    pub fn sequence(&self) -> Option<u16> {
+        if !self.is_echo() {
+            return None;
+        }
+        self.rest().map(|r| r as u16)
+    }
+
+    pub fn gateway(&self) -> Option<Ipv4Addr> {
+        if self.icmp_type()? != REDIRECT {
+            return None;
+        }
+        self.rest().map(Ipv4Addr::from)
+    }
+
+    pub fn next_hop_mtu(&self) -> Option<u16> {
+        if self.icmp_type()? != DEST_UNREACHABLE || self.code()? != 4 {
+            return None;
+        }
+        self.rest().map(|r| r as u16)
+    }
+
+    pub fn pointer(&self) -> Option<u8> {
+        if self.icmp_type()? != PARAMETER_PROBLEM {
+            return None;
+        }
+        self.rest().map(|r| (r >> 24) as u8)
+    }
+
+    pub fn quote(&self) -> Option<&IcmpQuote> {
+        self.quote.as_ref()
+    }
+}
+
+pub struct IcmpHeaderParser {
+    parser: ByteParser,
+    curr_field: IcmpHeaderField,
+    header: IcmpHeader,
+}
+
+impl Default for IcmpHeaderParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IcmpHeaderParser {
+
+    pub fn new() -> Self {
+        Self {
+            parser: ByteParser::new(false),
+            curr_field: IcmpHeaderField::TYPE(0),
+            header: IcmpHeader::new(),
+        }
+    }
+
+    pub fn parse(&mut self, b: u8) -> Result<(), Error> {
+        self.step(b)?;
+        match self.curr_field {
+            IcmpHeaderField::TYPE(_) => self.icmp_type(),
+            IcmpHeaderField::CODE(_) => self.code(),
+            IcmpHeaderField::CHECK(_) => self.check(),
+            IcmpHeaderField::REST(_) => self.rest(),
+            IcmpHeaderField::UNSET => (),
+        }
+        Ok(())
+    }
+
+    pub fn get_header(&self) -> IcmpHeader {
+        self.header.clone()
+    }
+
+    fn step(&mut self, b: u8) -> Result<(), Error> {
+        match self.curr_field {
+            IcmpHeaderField::TYPE(_)|
+                IcmpHeaderField::CODE(_)
+                => {
+                    self.parser.set_word(b);
+                    Ok(())
+                },
+            IcmpHeaderField::CHECK(_)
+                => self.parser.set_d_byte(b).map_err(|e| Error::Byte(Layer::ICMP, e)),
+            IcmpHeaderField::REST(_)
+                => self.parser.set_q_byte(b).map_err(|e| Error::Byte(Layer::ICMP, e)),
+            IcmpHeaderField::UNSET => Err(Error::HeaderComplete(Layer::ICMP)),
+        }
+    }
+
+    fn icmp_type(&mut self) {
+        self.header.set_field(IcmpHeaderField::TYPE(self.parser.word()));
+        self.curr_field = IcmpHeaderField::CODE(0);
+    }
+
+    fn code(&mut self) {
+        self.header.set_field(IcmpHeaderField::CODE(self.parser.word()));
+        self.curr_field = IcmpHeaderField::CHECK(0);
+    }
+
+    fn check(&mut self) {
+        if self.parser.dword_done() {
+            self.header.set_field(
+                IcmpHeaderField::CHECK(self.parser.dword_as_u16())
+            );
+            self.parser.reset_dword();
+            self.curr_field = IcmpHeaderField::REST(0);
+        }
+    }
+
+    fn rest(&mut self) {
+        if self.parser.qword_done() {
+            self.header.set_field(
+                IcmpHeaderField::REST(self.parser.qword_as_u32())
+            );
+            self.parser.reset_qword();
+            self.curr_field = IcmpHeaderField::UNSET;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_icmp_header_parser() {
+        let mut parser = IcmpHeaderParser::new();
+        for b in [0x08, 0x00, 0xF7, 0xFF, 0x00, 0x01, 0x00, 0x02] {
+            parser.parse(b).unwrap();
+        }
+        assert_eq!(parser.parse(0), Err(Error::HeaderComplete(Layer::ICMP)));
+        assert_eq!(
+            parser.get_header().to_string(),
+            "Type: 8 (Echo Request)\nCode: 0\nChecksum: 0xf7ff\nIdentifier: 1\nSequence: 2".to_string()
+        );
+        let header = parser.get_header();
+        assert_eq!(header.identifier(), Some(1));
+        assert_eq!(header.sequence(), Some(2));
+        assert!(!header.is_error());
+        assert_eq!(header.gateway(), None);
+    }
+
+    #[test]
+    fn test_error_messages() {
+        let mut parser = IcmpHeaderParser::new();
+        for b in [0x05, 0x01, 0x00, 0x00, 0xC0, 0xA8, 0x00, 0xFE] {
+            parser.parse(b).unwrap();
+        }
+        let header = parser.get_header();
+        assert_eq!(header.type_name(), Some("Redirect"));
+        assert_eq!(header.code_name(), Some("Redirect for Host"));
+        assert_eq!(header.gateway(), Some(Ipv4Addr::new(192, 168, 0, 254)));
+        assert_eq!(header.identifier(), None);
+
+        let mut parser = IcmpHeaderParser::new();
+        for b in [0x03, 0x04, 0x00, 0x00, 0x00, 0x00, 0x05, 0xDC] {
+            parser.parse(b).unwrap();
+        }
+        assert_eq!(parser.get_header().next_hop_mtu(), Some(1500));
+        assert_eq!(code_name(3, 3), Some("Port Unreachable"));
+        assert_eq!(code_name(8, 1), None);
+        assert_eq!(type_name(200), "Unknown");
+    }
+
+    #[test]
+    fn test_quote_and_checksum() {
+        let quoted = [
+            0x45, 0x00, 0x00, 0x30, 0x00, 0x01, 0x00, 0x00,
+            0x01, 0x11, 0x00, 0x00, 0x0A, 0x00, 0x00, 0x01,
+            0x08, 0x08, 0x08, 0x08, 0x82, 0x9A, 0x82, 0x9B,
+            0x00, 0x1C, 0x00, 0x00, 0xFF, 0xFF,
+        ];
+        let quote = IcmpQuote::parse(&quoted).unwrap();
+        assert_eq!(quote.header().dst(), Some(Ipv4Addr::new(8, 8, 8, 8)));
+        assert_eq!(quote.transport().len(), 8);
+        assert_eq!(quote.src_port(), Some(33434));
+        assert_eq!(quote.dst_port(), Some(33435));
+        assert_eq!(quote.to_string(), "Quoted: 10.0.0.1:33434 -> 8.8.8.8:33435 (UDP)");
+        assert_eq!(IcmpQuote::parse(&quoted[..12]).err(), Some(Error::Truncated(Layer::ICMP, 20)));
+
+        assert_eq!(internet_checksum(&[0x08, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x02]), 0xF7FC);
+        assert_eq!(internet_checksum(&[0x08, 0x00, 0xF7, 0xFC, 0x00, 0x01, 0x00, 0x02]), 0);
+        assert_eq!(internet_checksum(&[0x01]), 0xFEFF);
+    }
+}
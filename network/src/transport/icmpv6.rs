@@ -0,0 +1,768 @@
+use std::fmt::Display;
+use std::net::Ipv6Addr;
+
+use byte::{bytes_to_u16, bytes_to_u32, ByteParser};
+
+use crate::error::{Error, Layer};
+use crate::ethernet_frame::MacAddress;
+use crate::transport::icmp::internet_checksum;
+
+pub const DEST_UNREACHABLE: u8 = 1;
+pub const PACKET_TOO_BIG: u8 = 2;
+pub const TIME_EXCEEDED: u8 = 3;
+pub const PARAMETER_PROBLEM: u8 = 4;
+pub const ECHO_REQUEST: u8 = 128;
+pub const ECHO_REPLY: u8 = 129;
+pub const MLD_QUERY: u8 = 130;
+pub const MLD_REPORT: u8 = 131;
+pub const MLD_DONE: u8 = 132;
+pub const ROUTER_SOLICITATION: u8 = 133;
+pub const ROUTER_ADVERTISEMENT: u8 = 134;
+pub const NEIGHBOR_SOLICITATION: u8 = 135;
+pub const NEIGHBOR_ADVERTISEMENT: u8 = 136;
+pub const REDIRECT: u8 = 137;
+pub const MLDV2_REPORT: u8 = 143;
+
+pub fn type_name(icmp_type: u8) -> &'static str {
+    match icmp_type {
+        DEST_UNREACHABLE => "Destination Unreachable",
+        PACKET_TOO_BIG => "Packet Too Big",
+        TIME_EXCEEDED => "Time Exceeded",
+        PARAMETER_PROBLEM => "Parameter Problem",
+        ECHO_REQUEST => "Echo Request",
+        ECHO_REPLY => "Echo Reply",
+        MLD_QUERY => "Multicast Listener Query",
+        MLD_REPORT => "Multicast Listener Report",
+        MLD_DONE => "Multicast Listener Done",
+        ROUTER_SOLICITATION => "Router Solicitation",
+        ROUTER_ADVERTISEMENT => "Router Advertisement",
+        NEIGHBOR_SOLICITATION => "Neighbor Solicitation",
+        NEIGHBOR_ADVERTISEMENT => "Neighbor Advertisement",
+        REDIRECT => "Redirect",
+        MLDV2_REPORT => "Version 2 Multicast Listener Report",
+        _ => "Unknown",
+    }
+}
+
+pub fn code_name(icmp_type: u8, code: u8) -> Option<&'static str> {
+    let name = match (icmp_type, code) {
+        (DEST_UNREACHABLE, 0) => "No Route to Destination",
+        (DEST_UNREACHABLE, 1) => "Communication Administratively Prohibited",
+        (DEST_UNREACHABLE, 2) => "Beyond Scope of Source Address",
+        (DEST_UNREACHABLE, 3) => "Address Unreachable",
+        (DEST_UNREACHABLE, 4) => "Port Unreachable",
+        (DEST_UNREACHABLE, 5) => "Source Address Failed Ingress/Egress Policy",
+        (DEST_UNREACHABLE, 6) => "Reject Route to Destination",
+        (TIME_EXCEEDED, 0) => "Hop Limit Exceeded in Transit",
+        (TIME_EXCEEDED, 1) => "Fragment Reassembly Time Exceeded",
+        (PARAMETER_PROBLEM, 0) => "Erroneous Header Field",
+        (PARAMETER_PROBLEM, 1) => "Unrecognized Next Header Type",
+        (PARAMETER_PROBLEM, 2) => "Unrecognized IPv6 Option",
+        _ => return None,
+    };
+    Some(name)
+}
+
+/// Checksum over the IPv6 pseudo-header and the message, zero when valid.
+pub fn icmpv6_checksum(src: Ipv6Addr, dst: Ipv6Addr, message: &[u8]) -> u16 {
+    let mut data = Vec::with_capacity(40 + message.len());
+    data.extend_from_slice(&src.octets());
+    data.extend_from_slice(&dst.octets());
+    data.extend_from_slice(&(message.len() as u32).to_be_bytes());
+    data.extend_from_slice(&[0, 0, 0, 58]);
+    data.extend_from_slice(message);
+    internet_checksum(&data)
+}
+
+fn ipv6_addr(b: &[u8]) -> Ipv6Addr {
+    let mut octets = [0u8;16];
+    octets.copy_from_slice(&b[..16]);
+    Ipv6Addr::from(octets)
+}
+
+fn u32_at(b: &[u8], i: usize) -> u32 {
+    bytes_to_u32(b[i], b[i + 1], b[i + 2], b[i + 3], false)
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum NdpOption {
+    SLLA(MacAddress),
+    TLLA(MacAddress),
+    PREFIX {
+        prefix_len: u8,
+        on_link: bool,
+        autonomous: bool,
+        valid_lifetime: u32,
+        preferred_lifetime: u32,
+        prefix: Ipv6Addr,
+    },
+    REDIRECTED(Vec<u8>),
+    MTU(u32),
+    RDNSS {
+        lifetime: u32,
+        servers: Vec<Ipv6Addr>,
+    },
+    UNKNOWN(u8, Vec<u8>),
+}
+
+impl NdpOption {
+
+    pub fn kind(&self) -> u8 {
+        match self {
+            Self::SLLA(_) => 1,
+            Self::TLLA(_) => 2,
+            Self::PREFIX { .. } => 3,
+            Self::REDIRECTED(_) => 4,
+            Self::MTU(_) => 5,
+            Self::RDNSS { .. } => 25,
+            Self::UNKNOWN(k, _) => *k,
+        }
+    }
+
+    pub fn parse_all(bytes: &[u8], base: usize) -> Result<Vec<Self>, Error> {
+        let mut options = vec![];
+        let mut i = 0;
+        while i < bytes.len() {
+            let offset = base + i;
+            if i + 2 > bytes.len() {
+                return Err(Error::Malformed(Layer::ICMPv6, offset, "option length missing"));
+            }
+            let len = bytes[i + 1] as usize * 8;
+            if len == 0 || i + len > bytes.len() {
+                return Err(Error::Malformed(Layer::ICMPv6, offset, "option length out of bounds"));
+            }
+            options.push(Self::parse(bytes[i], &bytes[i..i + len], offset)?);
+            i += len;
+        }
+        Ok(options)
+    }
+
+    fn parse(kind: u8, b: &[u8], offset: usize) -> Result<Self, Error> {
+        let option = match (kind, b.len()) {
+            (1, 8) => Self::SLLA(MacAddress::new([b[2], b[3], b[4], b[5], b[6], b[7]])),
+            (2, 8) => Self::TLLA(MacAddress::new([b[2], b[3], b[4], b[5], b[6], b[7]])),
+            (3, 32) => Self::PREFIX {
+                prefix_len: b[2],
+                on_link: b[3] & 0x80 != 0,
+                autonomous: b[3] & 0x40 != 0,
+                valid_lifetime: u32_at(b, 4),
+                preferred_lifetime: u32_at(b, 8),
+                prefix: ipv6_addr(&b[16..]),
+            },
+            (3, _) => return Err(Error::Malformed(Layer::ICMPv6, offset, "invalid prefix information length")),
+            (4, _) => Self::REDIRECTED(b[8.min(b.len())..].to_vec()),
+            (5, 8) => Self::MTU(u32_at(b, 4)),
+            (5, _) => return Err(Error::Malformed(Layer::ICMPv6, offset, "invalid MTU option length")),
+            (25, l) if l >= 8 => Self::RDNSS {
+                lifetime: u32_at(b, 4),
+                servers: b[8..].chunks_exact(16).map(ipv6_addr).collect(),
+            },
+            _ => Self::UNKNOWN(kind, b[2..].to_vec()),
+        };
+        Ok(option)
+    }
+}
+
+impl Display for NdpOption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::SLLA(m) => write!(f, "Source Link-Layer Address: {}", m),
+            Self::TLLA(m) => write!(f, "Target Link-Layer Address: {}", m),
+            Self::PREFIX { prefix_len, on_link, autonomous, valid_lifetime, preferred_lifetime, prefix } => write!(
+                f,
+                "Prefix: {}/{} (L={}, A={}, valid {}s, preferred {}s)",
+                prefix, prefix_len, *on_link as u8, *autonomous as u8, valid_lifetime, preferred_lifetime
+            ),
+            Self::REDIRECTED(d) => write!(f, "Redirected Header: {} bytes", d.len()),
+            Self::MTU(m) => write!(f, "MTU: {}", m),
+            Self::RDNSS { lifetime, servers } => {
+                let servers: Vec<String> = servers.iter().map(|s| s.to_string()).collect();
+                write!(f, "Recursive DNS Servers: {} (lifetime {}s)", servers.join(" "), lifetime)
+            },
+            Self::UNKNOWN(kind, value) => write!(f, "Unknown option type={} len={}", kind, value.len() + 2),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct MldRecord {
+    pub record_type: u8,
+    pub group: Ipv6Addr,
+    pub sources: Vec<Ipv6Addr>,
+    pub aux: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Icmpv6Message {
+    ECHO {
+        id: u16,
+        seq: u16,
+    },
+    ERROR {
+        param: u32,
+        invoking: Vec<u8>,
+    },
+    MLD {
+        max_delay: u16,
+        group: Ipv6Addr,
+    },
+    MLDV2QUERY {
+        max_response: u16,
+        group: Ipv6Addr,
+        suppress: bool,
+        qrv: u8,
+        qqic: u8,
+        sources: Vec<Ipv6Addr>,
+    },
+    MLDV2REPORT(Vec<MldRecord>),
+    RS(Vec<NdpOption>),
+    RA {
+        hop_limit: u8,
+        managed: bool,
+        other: bool,
+        lifetime: u16,
+        reachable: u32,
+        retrans: u32,
+        options: Vec<NdpOption>,
+    },
+    NS {
+        target: Ipv6Addr,
+        options: Vec<NdpOption>,
+    },
+    NA {
+        router: bool,
+        solicited: bool,
+        override_flag: bool,
+        target: Ipv6Addr,
+        options: Vec<NdpOption>,
+    },
+    REDIRECT {
+        target: Ipv6Addr,
+        destination: Ipv6Addr,
+        options: Vec<NdpOption>,
+    },
+    UNKNOWN(Vec<u8>),
+}
+
+impl Icmpv6Message {
+
+    /// `body` follows the 4 byte type/code/checksum header, `base` is the offset of
+    /// `body[0]` in the frame.
+    pub fn parse(icmp_type: u8, body: &[u8], base: usize) -> Result<Self, Error> {
+        let need = |n: usize| if body.len() < n {
+            Err(Error::Truncated(Layer::ICMPv6, base + body.len()))
+        } else {
+            Ok(())
+        };
+        let message = match icmp_type {
+            DEST_UNREACHABLE | PACKET_TOO_BIG | TIME_EXCEEDED | PARAMETER_PROBLEM => {
+                need(4)?;
+                Self::ERROR { param: u32_at(body, 0), invoking: body[4..].to_vec() }
+            },
+            ECHO_REQUEST | ECHO_REPLY => {
+                need(4)?;
+                Self::ECHO { id: bytes_to_u16(body[0], body[1], false), seq: bytes_to_u16(body[2], body[3], false) }
+            },
+            MLD_QUERY if body.len() >= 24 => {
+                let count = bytes_to_u16(body[22], body[23], false) as usize;
+                need(24 + count * 16)?;
+                Self::MLDV2QUERY {
+                    max_response: bytes_to_u16(body[0], body[1], false),
+                    group: ipv6_addr(&body[4..]),
+                    suppress: body[20] & 0x08 != 0,
+                    qrv: body[20] & 0x07,
+                    qqic: body[21],
+                    sources: body[24..24 + count * 16].chunks_exact(16).map(ipv6_addr).collect(),
+                }
+            },
+            MLD_QUERY | MLD_REPORT | MLD_DONE => {
+                need(20)?;
+                Self::MLD { max_delay: bytes_to_u16(body[0], body[1], false), group: ipv6_addr(&body[4..]) }
+            },
+            MLDV2_REPORT => {
+                need(4)?;
+                let count = bytes_to_u16(body[2], body[3], false) as usize;
+                let mut records = vec![];
+                let mut i = 4;
+                for _ in 0..count {
+                    need(i + 20)?;
+                    let aux_len = body[i + 1] as usize * 4;
+                    let sources = bytes_to_u16(body[i + 2], body[i + 3], false) as usize;
+                    let end = i + 20 + sources * 16 + aux_len;
+                    need(end)?;
+                    records.push(MldRecord {
+                        record_type: body[i],
+                        group: ipv6_addr(&body[i + 4..]),
+                        sources: body[i + 20..i + 20 + sources * 16].chunks_exact(16).map(ipv6_addr).collect(),
+                        aux: body[end - aux_len..end].to_vec(),
+                    });
+                    i = end;
+                }
+                Self::MLDV2REPORT(records)
+            },
+            ROUTER_SOLICITATION => {
+                need(4)?;
+                Self::RS(NdpOption::parse_all(&body[4..], base + 4)?)
+            },
+            ROUTER_ADVERTISEMENT => {
+                need(12)?;
+                Self::RA {
+                    hop_limit: body[0],
+                    managed: body[1] & 0x80 != 0,
+                    other: body[1] & 0x40 != 0,
+                    lifetime: bytes_to_u16(body[2], body[3], false),
+                    reachable: u32_at(body, 4),
+                    retrans: u32_at(body, 8),
+                    options: NdpOption::parse_all(&body[12..], base + 12)?,
+                }
+            },
+            NEIGHBOR_SOLICITATION => {
+                need(20)?;
+                Self::NS { target: ipv6_addr(&body[4..]), options: NdpOption::parse_all(&body[20..], base + 20)? }
+            },
+            NEIGHBOR_ADVERTISEMENT => {
+                need(20)?;
+                Self::NA {
+                    router: body[0] & 0x80 != 0,
+                    solicited: body[0] & 0x40 != 0,
+                    override_flag: body[0] & 0x20 != 0,
+                    target: ipv6_addr(&body[4..]),
+                    options: NdpOption::parse_all(&body[20..], base + 20)?,
+                }
+            },
+            REDIRECT => {
+                need(36)?;
+                Self::REDIRECT {
+                    target: ipv6_addr(&body[4..]),
+                    destination: ipv6_addr(&body[20..]),
+                    options: NdpOption::parse_all(&body[36..], base + 36)?,
+                }
+            },
+            _ => Self::UNKNOWN(body.to_vec()),
+        };
+        Ok(message)
+    }
+
+    pub fn options(&self) -> &[NdpOption] {
+        match self {
+            Self::RS(o) => o,
+            Self::RA { options, .. } => options,
+            Self::NS { options, .. } => options,
+            Self::NA { options, .. } => options,
+            Self::REDIRECT { options, .. } => options,
+            _ => &[],
+        }
+    }
+
+    /// Bytes that belong to the message header rather than its payload.
+    pub fn header_len(&self) -> Option<usize> {
+        match self {
+            Self::ECHO { .. } | Self::ERROR { .. } => Some(8),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Icmpv6Message {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ECHO { id, seq } => write!(f, "Identifier: {}\nSequence: {}", id, seq)?,
+            Self::ERROR { param, invoking } => write!(f, "Parameter: {}\nInvoking Packet: {} bytes", param, invoking.len())?,
+            Self::MLD { max_delay, group } => write!(f, "Maximum Response Delay: {}\nMulticast Address: {}", max_delay, group)?,
+            Self::MLDV2QUERY { max_response, group, qrv, sources, .. } => write!(
+                f,
+                "Maximum Response Code: {}\nMulticast Address: {}\nQRV: {}\nSources: {}",
+                max_response, group, qrv, sources.len()
+            )?,
+            Self::MLDV2REPORT(records) => {
+                write!(f, "Multicast Address Records: {}", records.len())?;
+                for r in records.iter() {
+                    write!(f, "\nRecord: type {} group {} ({} sources)", r.record_type, r.group, r.sources.len())?;
+                }
+            },
+            Self::RS(_) => (),
+            Self::RA { hop_limit, managed, other, lifetime, reachable, retrans, .. } => write!(
+                f,
+                "Cur Hop Limit: {}\nFlags: M={} O={}\nRouter Lifetime: {}\nReachable Time: {}\nRetrans Timer: {}",
+                hop_limit, *managed as u8, *other as u8, lifetime, reachable, retrans
+            )?,
+            Self::NS { target, .. } => write!(f, "Target: {}", target)?,
+            Self::NA { router, solicited, override_flag, target, .. } => write!(
+                f,
+                "Flags: R={} S={} O={}\nTarget: {}",
+                *router as u8, *solicited as u8, *override_flag as u8, target
+            )?,
+            Self::REDIRECT { target, destination, .. } => write!(f, "Target: {}\nDestination: {}", target, destination)?,
+            Self::UNKNOWN(b) => write!(f, "Data: {} bytes", b.len())?,
+        }
+        for (i, o) in self.options().iter().enumerate() {
+            if i == 0 && matches!(self, Self::RS(_)) {
+                write!(f, "{}", o)?;
+            } else {
+                write!(f, "\n{}", o)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+pub enum Icmpv6HeaderField {
+    TYPE(u8),
+    CODE(u8),
+    CHECK(u16),
+    UNSET,
+}
+
+impl Display for Icmpv6HeaderField {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::TYPE(b) => write!(f, "Type: {} ({})", b, type_name(*b)),
+            Self::CODE(b) => write!(f, "Code: {}", b),
+            Self::CHECK(b) => write!(f, "Checksum: {:#06x}", b),
+            Self::UNSET => write!(f, "UNSET"),
+        }
+    }
+}
+
+impl Clone for Icmpv6HeaderField {
+    fn clone(&self) -> Self {
+        match self {
+            Self::TYPE(b) => Self::TYPE(*b),
+            Self::CODE(b) => Self::CODE(*b),
+            Self::CHECK(b) => Self::CHECK(*b),
+            Self::UNSET => Self::UNSET,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Icmpv6Header {
+    icmp_type: Icmpv6HeaderField,
+    code: Icmpv6HeaderField,
+    check: Icmpv6HeaderField,
+    checksum_valid: Option<bool>,
+    message: Option<Icmpv6Message>,
+}
+
+impl Display for Icmpv6Header {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.icmp_type)?;
+        match self.code_name() {
+            Some(name) => write!(f, "\nCode: {} ({})", self.code().unwrap_or(0), name)?,
+            None => write!(f, "\n{}", self.code)?,
+        }
+        write!(f, "\n{}", self.check)?;
+        match self.checksum_valid {
+            Some(true) => write!(f, " [correct]")?,
+            Some(false) => write!(f, " [incorrect]")?,
+            None => (),
+        }
+        if let Some(m) = &self.message {
+            let body = m.to_string();
+            if !body.is_empty() {
+                write!(f, "\n{}", body)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Default for Icmpv6Header {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Icmpv6Header {
+
+    pub fn new() -> Self {
+        Self {
+            icmp_type: Icmpv6HeaderField::UNSET,
+            code: Icmpv6HeaderField::UNSET,
+            check: Icmpv6HeaderField::UNSET,
+            checksum_valid: None,
+            message: None,
+        }
+    }
+
+    pub fn set_field(&mut self, field: Icmpv6HeaderField) {
+        match field {
+            Icmpv6HeaderField::TYPE(_) => self.icmp_type = field,
+            Icmpv6HeaderField::CODE(_) => self.code = field,
+            Icmpv6HeaderField::CHECK(_) => self.check = field,
+            Icmpv6HeaderField::UNSET => (),
+        }
+    }
+
+    pub fn set_message(&mut self, message: Icmpv6Message) {
+        self.message = Some(message);
+    }
+
+    pub fn set_checksum_valid(&mut self, valid: bool) {
+        self.checksum_valid = Some(valid);
+    }
+
+    pub fn icmp_type(&self) -> Option<u8> {
+        match self.icmp_type {
+            Icmpv6HeaderField::TYPE(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn type_name(&self) -> Option<&'static str> {
+        self.icmp_type().map(type_name)
+    }
+
+    pub fn code(&self) -> Option<u8> {
+        match self.code {
+            Icmpv6HeaderField::CODE(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn code_name(&self) -> Option<&'static str> {
+        code_name(self.icmp_type()?, self.code()?)
+    }
+
+    pub fn checksum(&self) -> Option<u16> {
+        match self.check {
+            Icmpv6HeaderField::CHECK(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn checksum_valid(&self) -> Option<bool> {
+        self.checksum_valid
+    }
+
+    pub fn message(&self) -> Option<&Icmpv6Message> {
+        self.message.as_ref()
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.icmp_type().is_some_and(|t| t < 128)
+    }
+
+    pub fn is_ndp(&self) -> bool {
+        self.icmp_type().is_some_and(|t| (ROUTER_SOLICITATION..=REDIRECT).contains(&t))
+    }
+
+    pub fn is_mld(&self) -> bool {
+        matches!(self.icmp_type(), Some(MLD_QUERY | MLD_REPORT | MLD_DONE | MLDV2_REPORT))
+    }
+
+    pub fn link_layer_address(&self) -> Option<&MacAddress> {
+        self.message.as_ref()?.options().iter().find_map(|o| match o {
+            NdpOption::SLLA(m) | NdpOption::TLLA(m) => Some(m),
+            _ => None,
+        })
+    }
+}
+
+pub struct Icmpv6HeaderParser {
+    parser: ByteParser,
+    curr_field: Icmpv6HeaderField,
+    header: Icmpv6Header,
+}
+
+impl Default for Icmpv6HeaderParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Icmpv6HeaderParser {
+
+    pub fn new() -> Self {
+        Self {
+            parser: ByteParser::new(false),
+            curr_field: Icmpv6HeaderField::TYPE(0),
+            header: Icmpv6Header::new(),
+        }
+    }
+
+    pub fn parse(&mut self, b: u8) -> Result<(), Error> {
+        self.step(b)?;
+        match self.curr_field {
+            Icmpv6HeaderField::TYPE(_) => {
+                self.header.set_field(Icmpv6HeaderField::TYPE(self.parser.word()));
+                self.curr_field = Icmpv6HeaderField::CODE(0);
+            },
+            Icmpv6HeaderField::CODE(_) => {
+                self.header.set_field(Icmpv6HeaderField::CODE(self.parser.word()));
+                self.curr_field = Icmpv6HeaderField::CHECK(0);
+            },
+            Icmpv6HeaderField::CHECK(_) => {
+                if self.parser.dword_done() {
+                    self.header.set_field(Icmpv6HeaderField::CHECK(self.parser.dword_as_u16()));
+                    self.parser.reset_dword();
+                    self.curr_field = Icmpv6HeaderField::UNSET;
+                }
+            },
+            Icmpv6HeaderField::UNSET => (),
+        }
+        Ok(())
+    }
+
+    pub fn get_header(&self) -> Icmpv6Header {
+        self.header.clone()
+    }
+
+    fn step(&mut self, b: u8) -> Result<(), Error> {
+        match self.curr_field {
+            Icmpv6HeaderField::TYPE(_)|
+                Icmpv6HeaderField::CODE(_)
+                => {
+                    self.parser.set_word(b);
+                    Ok(())
+                },
+            Icmpv6HeaderField::CHECK(_)
+                => self.parser.set_d_byte(b).map_err(|e| Error::Byte(Layer::ICMPv6, e)),
+            Icmpv6HeaderField::UNSET => Err(Error::HeaderComplete(Layer::ICMPv6)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn addr(s: &str) -> Ipv6Addr {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_header_parser() {
+        let mut parser = Icmpv6HeaderParser::new();
+        for b in [0x01, 0x04, 0x12, 0x34] {
+            parser.parse(b).unwrap();
+        }
+        assert_eq!(parser.parse(0), Err(Error::HeaderComplete(Layer::ICMPv6)));
+        let header = parser.get_header();
+        assert_eq!(header.code_name(), Some("Port Unreachable"));
+        assert!(header.is_error());
+        assert_eq!(
+            header.to_string(),
+            "Type: 1 (Destination Unreachable)\nCode: 4 (Port Unreachable)\nChecksum: 0x1234"
+        );
+    }
+
+    #[test]
+    fn test_router_advertisement() {
+        let mut body = vec![64, 0xC0, 0x07, 0x08, 0, 0, 0, 0, 0, 0, 0, 0];
+        body.extend_from_slice(&[1, 1, 0x00, 0x11, 0x22, 0x33, 0x44, 0x55]);
+        body.extend_from_slice(&[5, 1, 0, 0, 0, 0, 0x05, 0xDC]);
+        body.extend_from_slice(&[3, 4, 64, 0xC0, 0, 0x27, 0x8D, 0x00, 0, 0x09, 0x3A, 0x80, 0, 0, 0, 0]);
+        body.extend_from_slice(&addr("2001:db8:1::").octets());
+        body.extend_from_slice(&[25, 3, 0, 0, 0, 0, 0x0E, 0x10]);
+        body.extend_from_slice(&addr("2001:db8::53").octets());
+        let message = Icmpv6Message::parse(ROUTER_ADVERTISEMENT, &body, 4).unwrap();
+        match &message {
+            Icmpv6Message::RA { hop_limit, managed, other, lifetime, options, .. } => {
+                assert_eq!((*hop_limit, *managed, *other, *lifetime), (64, true, true, 1800));
+                assert_eq!(options.len(), 4);
+                assert_eq!(options[0], NdpOption::SLLA(MacAddress::new([0x00, 0x11, 0x22, 0x33, 0x44, 0x55])));
+                assert_eq!(options[1], NdpOption::MTU(1500));
+                assert_eq!(options[2], NdpOption::PREFIX {
+                    prefix_len: 64,
+                    on_link: true,
+                    autonomous: true,
+                    valid_lifetime: 2592000,
+                    preferred_lifetime: 604800,
+                    prefix: addr("2001:db8:1::"),
+                });
+                assert_eq!(options[3], NdpOption::RDNSS { lifetime: 3600, servers: vec![addr("2001:db8::53")] });
+            },
+            m => panic!("unexpected {:?}", m),
+        }
+        assert!(message.to_string().contains("Prefix: 2001:db8:1::/64 (L=1, A=1, valid 2592000s, preferred 604800s)"));
+        assert_eq!(
+            Icmpv6Message::parse(ROUTER_ADVERTISEMENT, &body[..19], 4),
+            Err(Error::Malformed(Layer::ICMPv6, 16, "option length out of bounds"))
+        );
+    }
+
+    #[test]
+    fn test_neighbor_discovery() {
+        let mut body = vec![0x60, 0, 0, 0];
+        body.extend_from_slice(&addr("fe80::1").octets());
+        body.extend_from_slice(&[2, 1, 0xAA, 0xBB, 0xCC, 0xDD, 0xEE, 0xFF]);
+        let message = Icmpv6Message::parse(NEIGHBOR_ADVERTISEMENT, &body, 4).unwrap();
+        assert_eq!(message, Icmpv6Message::NA {
+            router: false,
+            solicited: true,
+            override_flag: true,
+            target: addr("fe80::1"),
+            options: vec![NdpOption::TLLA(MacAddress::new([0xAA, 0xBB, 0xCC, 0xDD, 0xEE, 0xFF]))],
+        });
+        let mut header = Icmpv6Header::new();
+        header.set_field(Icmpv6HeaderField::TYPE(NEIGHBOR_ADVERTISEMENT));
+        header.set_message(message);
+        assert!(header.is_ndp());
+        assert_eq!(header.link_layer_address().unwrap().octets(), [0xAA, 0xBB, 0xCC, 0xDD, 0xEE, 0xFF]);
+
+        body[0] = 0;
+        let message = Icmpv6Message::parse(NEIGHBOR_SOLICITATION, &body[..20], 4).unwrap();
+        assert_eq!(message, Icmpv6Message::NS { target: addr("fe80::1"), options: vec![] });
+        assert_eq!(
+            Icmpv6Message::parse(NEIGHBOR_SOLICITATION, &body[..10], 4),
+            Err(Error::Truncated(Layer::ICMPv6, 14))
+        );
+    }
+
+    #[test]
+    fn test_mld() {
+        let mut body = vec![0x03, 0xE8, 0, 0];
+        body.extend_from_slice(&addr("ff02::fb").octets());
+        assert_eq!(
+            Icmpv6Message::parse(MLD_REPORT, &body, 4).unwrap(),
+            Icmpv6Message::MLD { max_delay: 1000, group: addr("ff02::fb") }
+        );
+        body.extend_from_slice(&[0x0A, 125, 0, 1]);
+        body.extend_from_slice(&addr("2001:db8::1").octets());
+        assert_eq!(
+            Icmpv6Message::parse(MLD_QUERY, &body, 4).unwrap(),
+            Icmpv6Message::MLDV2QUERY {
+                max_response: 1000,
+                group: addr("ff02::fb"),
+                suppress: true,
+                qrv: 2,
+                qqic: 125,
+                sources: vec![addr("2001:db8::1")],
+            }
+        );
+
+        let mut body = vec![0, 0, 0, 2];
+        body.extend_from_slice(&[4, 0, 0, 0]);
+        body.extend_from_slice(&addr("ff02::fb").octets());
+        body.extend_from_slice(&[1, 1, 0, 1]);
+        body.extend_from_slice(&addr("ff05::2").octets());
+        body.extend_from_slice(&addr("2001:db8::9").octets());
+        body.extend_from_slice(&[0xDE, 0xAD, 0xBE, 0xEF]);
+        match Icmpv6Message::parse(MLDV2_REPORT, &body, 4).unwrap() {
+            Icmpv6Message::MLDV2REPORT(records) => {
+                assert_eq!(records.len(), 2);
+                assert_eq!(records[0].record_type, 4);
+                assert_eq!(records[1].group, addr("ff05::2"));
+                assert_eq!(records[1].sources, vec![addr("2001:db8::9")]);
+                assert_eq!(records[1].aux, vec![0xDE, 0xAD, 0xBE, 0xEF]);
+            },
+            m => panic!("unexpected {:?}", m),
+        }
+        assert!(Icmpv6Message::parse(MLDV2_REPORT, &body[..60], 4).is_err());
+    }
+
+    #[test]
+    fn test_checksum() {
+        let src = addr("fe80::1");
+        let dst = addr("ff02::1");
+        let mut message = vec![ECHO_REQUEST, 0, 0, 0, 0, 1, 0, 1];
+        let check = icmpv6_checksum(src, dst, &message);
+        message[2..4].copy_from_slice(&check.to_be_bytes());
+        assert_eq!(icmpv6_checksum(src, dst, &message), 0);
+        assert_eq!(
+            Icmpv6Message::parse(ECHO_REQUEST, &message[4..], 4).unwrap(),
+            Icmpv6Message::ECHO { id: 1, seq: 1 }
+        );
+    }
+}
@@ -1,3 +1,5 @@
 pub mod tcp;
 pub mod udp;
+pub mod icmp;
+pub mod icmpv6;
 pub mod protocols;
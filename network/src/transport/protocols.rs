@@ -1,5 +1,7 @@
 use std::fmt::Display;
 
+/// IP protocol numbers as assigned in the IANA Assigned Internet Protocol Numbers registry.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub enum TransportProtocol {
     IPv6HopByHop,
     ICMP,
@@ -9,10 +11,147 @@ pub enum TransportProtocol {
     ST,
     TCP,
     CBT,
-    UDP,
     EGP,
     IGP,
+    BBNRCCMON,
     NVP2,
+    PUP,
+    ARGUS,
+    EMCON,
+    XNET,
+    CHAOS,
+    UDP,
+    MUX,
+    DCNMEAS,
+    HMP,
+    PRM,
+    XNSIDP,
+    TRUNK1,
+    TRUNK2,
+    LEAF1,
+    LEAF2,
+    RDP,
+    IRTP,
+    ISOTP4,
+    NETBLT,
+    MFENSP,
+    MERITINP,
+    DCCP,
+    THREEPC,
+    IDPR,
+    XTP,
+    DDP,
+    IDPRCMTP,
+    TPPLUSPLUS,
+    IL,
+    IPv6,
+    SDRP,
+    IPv6Route,
+    IPv6Frag,
+    IDRP,
+    RSVP,
+    GRE,
+    DSR,
+    BNA,
+    ESP,
+    AH,
+    INLSP,
+    SWIPE,
+    NARP,
+    MINIPv4,
+    TLSP,
+    SKIP,
+    ICMPv6,
+    IPv6NoNxt,
+    IPv6Opts,
+    ANYHOST,
+    CFTP,
+    ANYLOCAL,
+    SATEXPAK,
+    KRYPTOLAN,
+    RVD,
+    IPPC,
+    ANYDFS,
+    SATMON,
+    VISA,
+    IPCV,
+    CPNX,
+    CPHB,
+    WSN,
+    PVP,
+    BRSATMON,
+    SUNND,
+    WBMON,
+    WBEXPAK,
+    ISOIP,
+    VMTP,
+    SECUREVMTP,
+    VINES,
+    TTP,
+    NSFNETIGP,
+    DGP,
+    TCF,
+    EIGRP,
+    OSPF,
+    SPRITERPC,
+    LARP,
+    MTP,
+    AX25,
+    IPIP,
+    MICP,
+    SCCSP,
+    ETHERIP,
+    ENCAP,
+    ANYENCRYPTION,
+    GMTP,
+    IFMP,
+    PNNI,
+    PIM,
+    ARIS,
+    SCPS,
+    QNX,
+    AN,
+    IPCOMP,
+    SNP,
+    COMPAQPEER,
+    IPXINIP,
+    VRRP,
+    PGM,
+    ANYZEROHOP,
+    L2TP,
+    DDX,
+    IATP,
+    STP,
+    SRP,
+    UTI,
+    SMP,
+    SM,
+    PTP,
+    ISIS,
+    FIRE,
+    CRTP,
+    CRUDP,
+    SSCOPMCE,
+    IPLT,
+    SPS,
+    PIPE,
+    SCTP,
+    FC,
+    RSVPE2EIGNORE,
+    MOBILITY,
+    UDPLITE,
+    MPLSINIP,
+    MANET,
+    HIP,
+    SHIM6,
+    WESP,
+    ROHC,
+    ETHERNET,
+    AGGFRAG,
+    NSH,
+    /// 253 and 254, reserved for experimentation and testing
+    EXPERIMENTAL(u8),
+    RESERVED,
     UNKNOWN(u8),
 }
 
@@ -30,54 +169,629 @@ impl TransportProtocol {
             7 => Self::CBT,
             8 => Self::EGP,
             9 => Self::IGP,
+            10 => Self::BBNRCCMON,
             11 => Self::NVP2,
+            12 => Self::PUP,
+            13 => Self::ARGUS,
+            14 => Self::EMCON,
+            15 => Self::XNET,
+            16 => Self::CHAOS,
             17 => Self::UDP,
+            18 => Self::MUX,
+            19 => Self::DCNMEAS,
+            20 => Self::HMP,
+            21 => Self::PRM,
+            22 => Self::XNSIDP,
+            23 => Self::TRUNK1,
+            24 => Self::TRUNK2,
+            25 => Self::LEAF1,
+            26 => Self::LEAF2,
+            27 => Self::RDP,
+            28 => Self::IRTP,
+            29 => Self::ISOTP4,
+            30 => Self::NETBLT,
+            31 => Self::MFENSP,
+            32 => Self::MERITINP,
+            33 => Self::DCCP,
+            34 => Self::THREEPC,
+            35 => Self::IDPR,
+            36 => Self::XTP,
+            37 => Self::DDP,
+            38 => Self::IDPRCMTP,
+            39 => Self::TPPLUSPLUS,
+            40 => Self::IL,
+            41 => Self::IPv6,
+            42 => Self::SDRP,
+            43 => Self::IPv6Route,
+            44 => Self::IPv6Frag,
+            45 => Self::IDRP,
+            46 => Self::RSVP,
+            47 => Self::GRE,
+            48 => Self::DSR,
+            49 => Self::BNA,
+            50 => Self::ESP,
+            51 => Self::AH,
+            52 => Self::INLSP,
+            53 => Self::SWIPE,
+            54 => Self::NARP,
+            55 => Self::MINIPv4,
+            56 => Self::TLSP,
+            57 => Self::SKIP,
+            58 => Self::ICMPv6,
+            59 => Self::IPv6NoNxt,
+            60 => Self::IPv6Opts,
+            61 => Self::ANYHOST,
+            62 => Self::CFTP,
+            63 => Self::ANYLOCAL,
+            64 => Self::SATEXPAK,
+            65 => Self::KRYPTOLAN,
+            66 => Self::RVD,
+            67 => Self::IPPC,
+            68 => Self::ANYDFS,
+            69 => Self::SATMON,
+            70 => Self::VISA,
+            71 => Self::IPCV,
+            72 => Self::CPNX,
+            73 => Self::CPHB,
+            74 => Self::WSN,
+            75 => Self::PVP,
+            76 => Self::BRSATMON,
+            77 => Self::SUNND,
+            78 => Self::WBMON,
+            79 => Self::WBEXPAK,
+            80 => Self::ISOIP,
+            81 => Self::VMTP,
+            82 => Self::SECUREVMTP,
+            83 => Self::VINES,
+            84 => Self::TTP,
+            85 => Self::NSFNETIGP,
+            86 => Self::DGP,
+            87 => Self::TCF,
+            88 => Self::EIGRP,
+            89 => Self::OSPF,
+            90 => Self::SPRITERPC,
+            91 => Self::LARP,
+            92 => Self::MTP,
+            93 => Self::AX25,
+            94 => Self::IPIP,
+            95 => Self::MICP,
+            96 => Self::SCCSP,
+            97 => Self::ETHERIP,
+            98 => Self::ENCAP,
+            99 => Self::ANYENCRYPTION,
+            100 => Self::GMTP,
+            101 => Self::IFMP,
+            102 => Self::PNNI,
+            103 => Self::PIM,
+            104 => Self::ARIS,
+            105 => Self::SCPS,
+            106 => Self::QNX,
+            107 => Self::AN,
+            108 => Self::IPCOMP,
+            109 => Self::SNP,
+            110 => Self::COMPAQPEER,
+            111 => Self::IPXINIP,
+            112 => Self::VRRP,
+            113 => Self::PGM,
+            114 => Self::ANYZEROHOP,
+            115 => Self::L2TP,
+            116 => Self::DDX,
+            117 => Self::IATP,
+            118 => Self::STP,
+            119 => Self::SRP,
+            120 => Self::UTI,
+            121 => Self::SMP,
+            122 => Self::SM,
+            123 => Self::PTP,
+            124 => Self::ISIS,
+            125 => Self::FIRE,
+            126 => Self::CRTP,
+            127 => Self::CRUDP,
+            128 => Self::SSCOPMCE,
+            129 => Self::IPLT,
+            130 => Self::SPS,
+            131 => Self::PIPE,
+            132 => Self::SCTP,
+            133 => Self::FC,
+            134 => Self::RSVPE2EIGNORE,
+            135 => Self::MOBILITY,
+            136 => Self::UDPLITE,
+            137 => Self::MPLSINIP,
+            138 => Self::MANET,
+            139 => Self::HIP,
+            140 => Self::SHIM6,
+            141 => Self::WESP,
+            142 => Self::ROHC,
+            143 => Self::ETHERNET,
+            144 => Self::AGGFRAG,
+            145 => Self::NSH,
+            253 | 254 => Self::EXPERIMENTAL(b),
+            255 => Self::RESERVED,
             _ => Self::UNKNOWN(b),
         }
     }
 
+    pub fn to_u8(&self) -> u8 {
+        match self {
+            Self::IPv6HopByHop => 0,
+            Self::ICMP => 1,
+            Self::IGMP => 2,
+            Self::GGP => 3,
+            Self::IPinIP => 4,
+            Self::ST => 5,
+            Self::TCP => 6,
+            Self::CBT => 7,
+            Self::EGP => 8,
+            Self::IGP => 9,
+            Self::BBNRCCMON => 10,
+            Self::NVP2 => 11,
+            Self::PUP => 12,
+            Self::ARGUS => 13,
+            Self::EMCON => 14,
+            Self::XNET => 15,
+            Self::CHAOS => 16,
+            Self::UDP => 17,
+            Self::MUX => 18,
+            Self::DCNMEAS => 19,
+            Self::HMP => 20,
+            Self::PRM => 21,
+            Self::XNSIDP => 22,
+            Self::TRUNK1 => 23,
+            Self::TRUNK2 => 24,
+            Self::LEAF1 => 25,
+            Self::LEAF2 => 26,
+            Self::RDP => 27,
+            Self::IRTP => 28,
+            Self::ISOTP4 => 29,
+            Self::NETBLT => 30,
+            Self::MFENSP => 31,
+            Self::MERITINP => 32,
+            Self::DCCP => 33,
+            Self::THREEPC => 34,
+            Self::IDPR => 35,
+            Self::XTP => 36,
+            Self::DDP => 37,
+            Self::IDPRCMTP => 38,
+            Self::TPPLUSPLUS => 39,
+            Self::IL => 40,
+            Self::IPv6 => 41,
+            Self::SDRP => 42,
+            Self::IPv6Route => 43,
+            Self::IPv6Frag => 44,
+            Self::IDRP => 45,
+            Self::RSVP => 46,
+            Self::GRE => 47,
+            Self::DSR => 48,
+            Self::BNA => 49,
+            Self::ESP => 50,
+            Self::AH => 51,
+            Self::INLSP => 52,
+            Self::SWIPE => 53,
+            Self::NARP => 54,
+            Self::MINIPv4 => 55,
+            Self::TLSP => 56,
+            Self::SKIP => 57,
+            Self::ICMPv6 => 58,
+            Self::IPv6NoNxt => 59,
+            Self::IPv6Opts => 60,
+            Self::ANYHOST => 61,
+            Self::CFTP => 62,
+            Self::ANYLOCAL => 63,
+            Self::SATEXPAK => 64,
+            Self::KRYPTOLAN => 65,
+            Self::RVD => 66,
+            Self::IPPC => 67,
+            Self::ANYDFS => 68,
+            Self::SATMON => 69,
+            Self::VISA => 70,
+            Self::IPCV => 71,
+            Self::CPNX => 72,
+            Self::CPHB => 73,
+            Self::WSN => 74,
+            Self::PVP => 75,
+            Self::BRSATMON => 76,
+            Self::SUNND => 77,
+            Self::WBMON => 78,
+            Self::WBEXPAK => 79,
+            Self::ISOIP => 80,
+            Self::VMTP => 81,
+            Self::SECUREVMTP => 82,
+            Self::VINES => 83,
+            Self::TTP => 84,
+            Self::NSFNETIGP => 85,
+            Self::DGP => 86,
+            Self::TCF => 87,
+            Self::EIGRP => 88,
+            Self::OSPF => 89,
+            Self::SPRITERPC => 90,
+            Self::LARP => 91,
+            Self::MTP => 92,
+            Self::AX25 => 93,
+            Self::IPIP => 94,
+            Self::MICP => 95,
+            Self::SCCSP => 96,
+            Self::ETHERIP => 97,
+            Self::ENCAP => 98,
+            Self::ANYENCRYPTION => 99,
+            Self::GMTP => 100,
+            Self::IFMP => 101,
+            Self::PNNI => 102,
+            Self::PIM => 103,
+            Self::ARIS => 104,
+            Self::SCPS => 105,
+            Self::QNX => 106,
+            Self::AN => 107,
+            Self::IPCOMP => 108,
+            Self::SNP => 109,
+            Self::COMPAQPEER => 110,
+            Self::IPXINIP => 111,
+            Self::VRRP => 112,
+            Self::PGM => 113,
+            Self::ANYZEROHOP => 114,
+            Self::L2TP => 115,
+            Self::DDX => 116,
+            Self::IATP => 117,
+            Self::STP => 118,
+            Self::SRP => 119,
+            Self::UTI => 120,
+            Self::SMP => 121,
+            Self::SM => 122,
+            Self::PTP => 123,
+            Self::ISIS => 124,
+            Self::FIRE => 125,
+            Self::CRTP => 126,
+            Self::CRUDP => 127,
+            Self::SSCOPMCE => 128,
+            Self::IPLT => 129,
+            Self::SPS => 130,
+            Self::PIPE => 131,
+            Self::SCTP => 132,
+            Self::FC => 133,
+            Self::RSVPE2EIGNORE => 134,
+            Self::MOBILITY => 135,
+            Self::UDPLITE => 136,
+            Self::MPLSINIP => 137,
+            Self::MANET => 138,
+            Self::HIP => 139,
+            Self::SHIM6 => 140,
+            Self::WESP => 141,
+            Self::ROHC => 142,
+            Self::ETHERNET => 143,
+            Self::AGGFRAG => 144,
+            Self::NSH => 145,
+            Self::EXPERIMENTAL(b) => *b,
+            Self::RESERVED => 255,
+            Self::UNKNOWN(b) => *b,
+        }
+    }
+
+    /// IANA keyword, `None` for numbers the registry leaves without one.
+    pub fn abbreviation(&self) -> Option<&'static str> {
+        let abbr = match self {
+            Self::IPv6HopByHop => "HOPOPT",
+            Self::ICMP => "ICMP",
+            Self::IGMP => "IGMP",
+            Self::GGP => "GGP",
+            Self::IPinIP => "IPv4",
+            Self::ST => "ST",
+            Self::TCP => "TCP",
+            Self::CBT => "CBT",
+            Self::EGP => "EGP",
+            Self::IGP => "IGP",
+            Self::BBNRCCMON => "BBN-RCC-MON",
+            Self::NVP2 => "NVP-II",
+            Self::PUP => "PUP",
+            Self::ARGUS => "ARGUS",
+            Self::EMCON => "EMCON",
+            Self::XNET => "XNET",
+            Self::CHAOS => "CHAOS",
+            Self::UDP => "UDP",
+            Self::MUX => "MUX",
+            Self::DCNMEAS => "DCN-MEAS",
+            Self::HMP => "HMP",
+            Self::PRM => "PRM",
+            Self::XNSIDP => "XNS-IDP",
+            Self::TRUNK1 => "TRUNK-1",
+            Self::TRUNK2 => "TRUNK-2",
+            Self::LEAF1 => "LEAF-1",
+            Self::LEAF2 => "LEAF-2",
+            Self::RDP => "RDP",
+            Self::IRTP => "IRTP",
+            Self::ISOTP4 => "ISO-TP4",
+            Self::NETBLT => "NETBLT",
+            Self::MFENSP => "MFE-NSP",
+            Self::MERITINP => "MERIT-INP",
+            Self::DCCP => "DCCP",
+            Self::THREEPC => "3PC",
+            Self::IDPR => "IDPR",
+            Self::XTP => "XTP",
+            Self::DDP => "DDP",
+            Self::IDPRCMTP => "IDPR-CMTP",
+            Self::TPPLUSPLUS => "TP++",
+            Self::IL => "IL",
+            Self::IPv6 => "IPv6",
+            Self::SDRP => "SDRP",
+            Self::IPv6Route => "IPv6-Route",
+            Self::IPv6Frag => "IPv6-Frag",
+            Self::IDRP => "IDRP",
+            Self::RSVP => "RSVP",
+            Self::GRE => "GRE",
+            Self::DSR => "DSR",
+            Self::BNA => "BNA",
+            Self::ESP => "ESP",
+            Self::AH => "AH",
+            Self::INLSP => "I-NLSP",
+            Self::SWIPE => "SWIPE",
+            Self::NARP => "NARP",
+            Self::MINIPv4 => "Min-IPv4",
+            Self::TLSP => "TLSP",
+            Self::SKIP => "SKIP",
+            Self::ICMPv6 => "IPv6-ICMP",
+            Self::IPv6NoNxt => "IPv6-NoNxt",
+            Self::IPv6Opts => "IPv6-Opts",
+            Self::CFTP => "CFTP",
+            Self::SATEXPAK => "SAT-EXPAK",
+            Self::KRYPTOLAN => "KRYPTOLAN",
+            Self::RVD => "RVD",
+            Self::IPPC => "IPPC",
+            Self::SATMON => "SAT-MON",
+            Self::VISA => "VISA",
+            Self::IPCV => "IPCV",
+            Self::CPNX => "CPNX",
+            Self::CPHB => "CPHB",
+            Self::WSN => "WSN",
+            Self::PVP => "PVP",
+            Self::BRSATMON => "BR-SAT-MON",
+            Self::SUNND => "SUN-ND",
+            Self::WBMON => "WB-MON",
+            Self::WBEXPAK => "WB-EXPAK",
+            Self::ISOIP => "ISO-IP",
+            Self::VMTP => "VMTP",
+            Self::SECUREVMTP => "SECURE-VMTP",
+            Self::VINES => "VINES",
+            Self::TTP => "TTP",
+            Self::NSFNETIGP => "NSFNET-IGP",
+            Self::DGP => "DGP",
+            Self::TCF => "TCF",
+            Self::EIGRP => "EIGRP",
+            Self::OSPF => "OSPFIGP",
+            Self::SPRITERPC => "Sprite-RPC",
+            Self::LARP => "LARP",
+            Self::MTP => "MTP",
+            Self::AX25 => "AX.25",
+            Self::IPIP => "IPIP",
+            Self::MICP => "MICP",
+            Self::SCCSP => "SCC-SP",
+            Self::ETHERIP => "ETHERIP",
+            Self::ENCAP => "ENCAP",
+            Self::GMTP => "GMTP",
+            Self::IFMP => "IFMP",
+            Self::PNNI => "PNNI",
+            Self::PIM => "PIM",
+            Self::ARIS => "ARIS",
+            Self::SCPS => "SCPS",
+            Self::QNX => "QNX",
+            Self::AN => "A/N",
+            Self::IPCOMP => "IPComp",
+            Self::SNP => "SNP",
+            Self::COMPAQPEER => "Compaq-Peer",
+            Self::IPXINIP => "IPX-in-IP",
+            Self::VRRP => "VRRP",
+            Self::PGM => "PGM",
+            Self::L2TP => "L2TP",
+            Self::DDX => "DDX",
+            Self::IATP => "IATP",
+            Self::STP => "STP",
+            Self::SRP => "SRP",
+            Self::UTI => "UTI",
+            Self::SMP => "SMP",
+            Self::SM => "SM",
+            Self::PTP => "PTP",
+            Self::ISIS => "ISIS over IPv4",
+            Self::FIRE => "FIRE",
+            Self::CRTP => "CRTP",
+            Self::CRUDP => "CRUDP",
+            Self::SSCOPMCE => "SSCOPMCE",
+            Self::IPLT => "IPLT",
+            Self::SPS => "SPS",
+            Self::PIPE => "PIPE",
+            Self::SCTP => "SCTP",
+            Self::FC => "FC",
+            Self::RSVPE2EIGNORE => "RSVP-E2E-IGNORE",
+            Self::MOBILITY => "Mobility Header",
+            Self::UDPLITE => "UDPLite",
+            Self::MPLSINIP => "MPLS-in-IP",
+            Self::MANET => "manet",
+            Self::HIP => "HIP",
+            Self::SHIM6 => "Shim6",
+            Self::WESP => "WESP",
+            Self::ROHC => "ROHC",
+            Self::ETHERNET => "Ethernet",
+            Self::AGGFRAG => "AGGFRAG",
+            Self::NSH => "NSH",
+            _ => return None,
+        };
+        Some(abbr)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::IPv6HopByHop => "IPv6 Hop-By-Hop Option",
+            Self::ICMP => "Internet Control Message",
+            Self::IGMP => "Internet Group Management Protocol",
+            Self::GGP => "Gateway-to-Gateway",
+            Self::IPinIP => "IP in IP (encapsulated)",
+            Self::ST => "Internet Stream Protocol",
+            Self::TCP => "Transmission Control",
+            Self::CBT => "Core Based Trees",
+            Self::EGP => "Exterior Gateway",
+            Self::IGP => "Interior Gateway",
+            Self::BBNRCCMON => "BBN RCC Monitoring",
+            Self::NVP2 => "Network Voice Protocol",
+            Self::PUP => "PUP",
+            Self::ARGUS => "ARGUS",
+            Self::EMCON => "EMCON",
+            Self::XNET => "Cross Net Debugger",
+            Self::CHAOS => "Chaos",
+            Self::UDP => "User Datagram",
+            Self::MUX => "Multiplexing",
+            Self::DCNMEAS => "DCN Measurement Subsystems",
+            Self::HMP => "Host Monitoring",
+            Self::PRM => "Packet Radio Measurement",
+            Self::XNSIDP => "XEROX NS IDP",
+            Self::TRUNK1 => "Trunk-1",
+            Self::TRUNK2 => "Trunk-2",
+            Self::LEAF1 => "Leaf-1",
+            Self::LEAF2 => "Leaf-2",
+            Self::RDP => "Reliable Data Protocol",
+            Self::IRTP => "Internet Reliable Transaction",
+            Self::ISOTP4 => "ISO Transport Protocol Class 4",
+            Self::NETBLT => "Bulk Data Transfer Protocol",
+            Self::MFENSP => "MFE Network Services Protocol",
+            Self::MERITINP => "MERIT Internodal Protocol",
+            Self::DCCP => "Datagram Congestion Control Protocol",
+            Self::THREEPC => "Third Party Connect Protocol",
+            Self::IDPR => "Inter-Domain Policy Routing Protocol",
+            Self::XTP => "XTP",
+            Self::DDP => "Datagram Delivery Protocol",
+            Self::IDPRCMTP => "IDPR Control Message Transport Protocol",
+            Self::TPPLUSPLUS => "TP++ Transport Protocol",
+            Self::IL => "IL Transport Protocol",
+            Self::IPv6 => "IPv6 Encapsulation",
+            Self::SDRP => "Source Demand Routing Protocol",
+            Self::IPv6Route => "Routing Header for IPv6",
+            Self::IPv6Frag => "Fragment Header for IPv6",
+            Self::IDRP => "Inter-Domain Routing Protocol",
+            Self::RSVP => "Reservation Protocol",
+            Self::GRE => "Generic Routing Encapsulation",
+            Self::DSR => "Dynamic Source Routing Protocol",
+            Self::BNA => "BNA",
+            Self::ESP => "Encapsulating Security Payload",
+            Self::AH => "Authentication Header",
+            Self::INLSP => "Integrated Net Layer Security TUBA",
+            Self::SWIPE => "IP with Encryption",
+            Self::NARP => "NBMA Address Resolution Protocol",
+            Self::MINIPv4 => "Minimal IPv4 Encapsulation",
+            Self::TLSP => "Transport Layer Security Protocol",
+            Self::SKIP => "SKIP",
+            Self::ICMPv6 => "ICMPv6",
+            Self::IPv6NoNxt => "No Next Header for IPv6",
+            Self::IPv6Opts => "Destination Options for IPv6",
+            Self::ANYHOST => "Any Host Internal Protocol",
+            Self::CFTP => "CFTP",
+            Self::ANYLOCAL => "Any Local Network",
+            Self::SATEXPAK => "SATNET and Backroom EXPAK",
+            Self::KRYPTOLAN => "Kryptolan",
+            Self::RVD => "MIT Remote Virtual Disk Protocol",
+            Self::IPPC => "Internet Pluribus Packet Core",
+            Self::ANYDFS => "Any Distributed File System",
+            Self::SATMON => "SATNET Monitoring",
+            Self::VISA => "VISA Protocol",
+            Self::IPCV => "Internet Packet Core Utility",
+            Self::CPNX => "Computer Protocol Network Executive",
+            Self::CPHB => "Computer Protocol Heart Beat",
+            Self::WSN => "Wang Span Network",
+            Self::PVP => "Packet Video Protocol",
+            Self::BRSATMON => "Backroom SATNET Monitoring",
+            Self::SUNND => "SUN ND Protocol-Temporary",
+            Self::WBMON => "WIDEBAND Monitoring",
+            Self::WBEXPAK => "WIDEBAND EXPAK",
+            Self::ISOIP => "ISO Internet Protocol",
+            Self::VMTP => "VMTP",
+            Self::SECUREVMTP => "SECURE-VMTP",
+            Self::VINES => "VINES",
+            Self::TTP => "TTP",
+            Self::NSFNETIGP => "NSFNET-IGP",
+            Self::DGP => "Dissimilar Gateway Protocol",
+            Self::TCF => "TCF",
+            Self::EIGRP => "EIGRP",
+            Self::OSPF => "Open Shortest Path First",
+            Self::SPRITERPC => "Sprite RPC Protocol",
+            Self::LARP => "Locus Address Resolution Protocol",
+            Self::MTP => "Multicast Transport Protocol",
+            Self::AX25 => "AX.25 Frames",
+            Self::IPIP => "IP-within-IP Encapsulation Protocol",
+            Self::MICP => "Mobile Internetworking Control Protocol",
+            Self::SCCSP => "Semaphore Communications Sec. Protocol",
+            Self::ETHERIP => "Ethernet-within-IP Encapsulation",
+            Self::ENCAP => "Encapsulation Header",
+            Self::ANYENCRYPTION => "Any Private Encryption Scheme",
+            Self::GMTP => "GMTP",
+            Self::IFMP => "Ipsilon Flow Management Protocol",
+            Self::PNNI => "PNNI over IP",
+            Self::PIM => "Protocol Independent Multicast",
+            Self::ARIS => "ARIS",
+            Self::SCPS => "SCPS",
+            Self::QNX => "QNX",
+            Self::AN => "Active Networks",
+            Self::IPCOMP => "IP Payload Compression Protocol",
+            Self::SNP => "Sitara Networks Protocol",
+            Self::COMPAQPEER => "Compaq Peer Protocol",
+            Self::IPXINIP => "IPX in IP",
+            Self::VRRP => "Virtual Router Redundancy Protocol",
+            Self::PGM => "PGM Reliable Transport Protocol",
+            Self::ANYZEROHOP => "Any 0-Hop Protocol",
+            Self::L2TP => "Layer Two Tunneling Protocol",
+            Self::DDX => "D-II Data Exchange",
+            Self::IATP => "Interactive Agent Transfer Protocol",
+            Self::STP => "Schedule Transfer Protocol",
+            Self::SRP => "SpectraLink Radio Protocol",
+            Self::UTI => "UTI",
+            Self::SMP => "Simple Message Protocol",
+            Self::SM => "Simple Multicast Protocol",
+            Self::PTP => "Performance Transparency Protocol",
+            Self::ISIS => "IS-IS over IPv4",
+            Self::FIRE => "FIRE",
+            Self::CRTP => "Combat Radio Transport Protocol",
+            Self::CRUDP => "Combat Radio User Datagram",
+            Self::SSCOPMCE => "SSCOPMCE",
+            Self::IPLT => "IPLT",
+            Self::SPS => "Secure Packet Shield",
+            Self::PIPE => "Private IP Encapsulation within IP",
+            Self::SCTP => "Stream Control Transmission Protocol",
+            Self::FC => "Fibre Channel",
+            Self::RSVPE2EIGNORE => "RSVP End-to-End Ignore",
+            Self::MOBILITY => "Mobility Header",
+            Self::UDPLITE => "Lightweight User Datagram",
+            Self::MPLSINIP => "MPLS in IP",
+            Self::MANET => "MANET Protocols",
+            Self::HIP => "Host Identity Protocol",
+            Self::SHIM6 => "Shim6 Protocol",
+            Self::WESP => "Wrapped Encapsulating Security Payload",
+            Self::ROHC => "Robust Header Compression",
+            Self::ETHERNET => "Ethernet",
+            Self::AGGFRAG => "AGGFRAG Encapsulation Payload for ESP",
+            Self::NSH => "Network Service Header",
+            Self::EXPERIMENTAL(_) => "Experimentation and Testing",
+            Self::RESERVED => "Reserved",
+            Self::UNKNOWN(_) => "Unassigned",
+        }
+    }
+
     pub fn to_str(&self) -> String {
         match self {
-            Self::IPv6HopByHop => "IPv6 Hop-By-Hop Option".to_string(),
-            Self::ICMP => "ICMP".to_string(),
-            Self::IGMP => "Internet Group Management Protocol".to_string(),
-            Self::GGP => "Gateway-to-Gateway".to_string(),
-            Self::IPinIP => "IP in IP (encapsulated)".to_string(),
-            Self::ST => "Internet Stream Protocol".to_string(),
-            Self::TCP => "TCP".to_string(),
-            Self::CBT => "Core Based Trees".to_string(),
-            Self::EGP => "Exterior Gateway".to_string(),
-            Self::IGP => "Interior Gateway".to_string(),
-            Self::NVP2 => "Network Voice Protocol".to_string(),
-            Self::UDP => "UDP".to_string(),
+            Self::ICMP | Self::TCP | Self::UDP => self.abbreviation().unwrap_or_default().to_string(),
             Self::UNKNOWN(b) => format!("Unknown {}", b),
+            _ => self.name().to_string(),
         }
     }
 }
 
 impl Display for TransportProtocol {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Protocol: {}", self)
+        write!(f, "Protocol: {}", self.to_str())
     }
 }
 
-impl Clone for TransportProtocol {
-    fn clone(&self) -> Self {
-        match self {
-            Self::IPv6HopByHop => Self::IPv6HopByHop,
-            Self::ICMP => Self::ICMP,
-            Self::IGMP => Self::IGMP,
-            Self::GGP => Self::GGP,
-            Self::IPinIP => Self::IPinIP,
-            Self::ST => Self::ST,
-            Self::TCP => Self::TCP,
-            Self::CBT => Self::CBT,
-            Self::EGP => Self::EGP,
-            Self::IGP => Self::IGP,
-            Self::NVP2 => Self::NVP2,
-            Self::UDP => Self::UDP,
-            Self::UNKNOWN(b) => Self::UNKNOWN(*b),
-        }
+impl From<u8> for TransportProtocol {
+    fn from(b: u8) -> Self {
+        Self::new(b)
+    }
+}
+
+impl From<TransportProtocol> for u8 {
+    fn from(p: TransportProtocol) -> Self {
+        p.to_u8()
     }
 }
 
@@ -100,27 +814,60 @@ mod tests {
         assert!(matches!(TransportProtocol::new(9), TransportProtocol::IGP));
         assert!(matches!(TransportProtocol::new(11), TransportProtocol::NVP2));
         assert!(matches!(TransportProtocol::new(17), TransportProtocol::UDP));
-        assert!(matches!(TransportProtocol::new(18), TransportProtocol::UNKNOWN(18)));
+        assert!(matches!(TransportProtocol::new(58), TransportProtocol::ICMPv6));
+        assert!(matches!(TransportProtocol::new(18), TransportProtocol::MUX));
+        assert!(matches!(TransportProtocol::new(47), TransportProtocol::GRE));
+        assert!(matches!(TransportProtocol::new(50), TransportProtocol::ESP));
+        assert!(matches!(TransportProtocol::new(89), TransportProtocol::OSPF));
+        assert!(matches!(TransportProtocol::new(132), TransportProtocol::SCTP));
+        assert!(matches!(TransportProtocol::new(145), TransportProtocol::NSH));
+        assert!(matches!(TransportProtocol::new(146), TransportProtocol::UNKNOWN(146)));
+        assert!(matches!(TransportProtocol::new(253), TransportProtocol::EXPERIMENTAL(253)));
+        assert!(matches!(TransportProtocol::new(255), TransportProtocol::RESERVED));
+
+        for b in 0..=255u8 {
+            assert_eq!(TransportProtocol::new(b).to_u8(), b);
+        }
         
     }
 
     #[test]
-    fn test_clone_transport_proto() {
-
-        assert!(matches!(TransportProtocol::IPv6HopByHop.clone(), TransportProtocol::IPv6HopByHop));
-        assert!(matches!(TransportProtocol::ICMP.clone(), TransportProtocol::ICMP));
-        assert!(matches!(TransportProtocol::IGMP.clone(), TransportProtocol::IGMP));
-        assert!(matches!(TransportProtocol::GGP.clone(), TransportProtocol::GGP));
-        assert!(matches!(TransportProtocol::IPinIP.clone(), TransportProtocol::IPinIP));
-        assert!(matches!(TransportProtocol::ST.clone(), TransportProtocol::ST));
-        assert!(matches!(TransportProtocol::TCP.clone(), TransportProtocol::TCP));
-        assert!(matches!(TransportProtocol::CBT.clone(), TransportProtocol::CBT));
-        assert!(matches!(TransportProtocol::EGP.clone(), TransportProtocol::EGP));
-        assert!(matches!(TransportProtocol::IGP.clone(), TransportProtocol::IGP));
-        assert!(matches!(TransportProtocol::NVP2.clone(), TransportProtocol::NVP2));
-        assert!(matches!(TransportProtocol::UDP.clone(), TransportProtocol::UDP));
-        assert!(matches!(TransportProtocol::UNKNOWN(18).clone(), TransportProtocol::UNKNOWN(18)));
-        
+    fn test_copy_hash_transport_proto() {
+
+        let proto = TransportProtocol::GRE;
+        let copy = proto;
+        assert_eq!(proto, copy);
+        assert_ne!(proto, TransportProtocol::ESP);
+
+        let mut counts = std::collections::HashMap::new();
+        for b in [6u8, 17, 6, 58, 6] {
+            *counts.entry(TransportProtocol::new(b)).or_insert(0) += 1;
+        }
+        assert_eq!(counts[&TransportProtocol::TCP], 3);
+        assert_eq!(counts[&TransportProtocol::ICMPv6], 1);
+        assert_eq!(u8::from(TransportProtocol::from(112)), 112);
+
+        let unique: std::collections::HashSet<_> = (0..=255u8).map(TransportProtocol::new).collect();
+        assert_eq!(unique.len(), 256);
+
+    }
+
+    #[test]
+    fn test_transport_proto_names() {
+
+        assert_eq!(TransportProtocol::ICMPv6.abbreviation(), Some("IPv6-ICMP"));
+        assert_eq!(TransportProtocol::OSPF.abbreviation(), Some("OSPFIGP"));
+        assert_eq!(TransportProtocol::THREEPC.abbreviation(), Some("3PC"));
+        assert_eq!(TransportProtocol::ANYHOST.abbreviation(), None);
+        assert_eq!(TransportProtocol::UNKNOWN(200).abbreviation(), None);
+        assert_eq!(TransportProtocol::ESP.name(), "Encapsulating Security Payload");
+        assert_eq!(TransportProtocol::VRRP.name(), "Virtual Router Redundancy Protocol");
+        assert_eq!(TransportProtocol::EXPERIMENTAL(254).name(), "Experimentation and Testing");
+        assert_eq!(TransportProtocol::UNKNOWN(200).name(), "Unassigned");
+        for b in 0..=145u8 {
+            assert_ne!(TransportProtocol::new(b).name(), "Unassigned");
+        }
+
     }
 
     #[test]
@@ -138,6 +885,8 @@ mod tests {
         assert_eq!(TransportProtocol::IGP.to_str(), "Interior Gateway".to_string());
         assert_eq!(TransportProtocol::NVP2.to_str(), "Network Voice Protocol".to_string());
         assert_eq!(TransportProtocol::UDP.to_str(), "UDP".to_string());
+        assert_eq!(TransportProtocol::ICMPv6.to_str(), "ICMPv6".to_string());
+        assert_eq!(TransportProtocol::SCTP.to_str(), "Stream Control Transmission Protocol".to_string());
         assert_eq!(TransportProtocol::UNKNOWN(18).to_str(), format!("Unknown {}", 18));
         
     }
@@ -1,6 +1,184 @@
 use std::fmt::Display;
 
-use byte::ByteParser;
+use byte::{bytes_to_u16, bytes_to_u32, ByteParser};
+
+use crate::error::{Error, Layer};
+use crate::tlv::OptionFormat;
+
+const OPTION_FORMAT: OptionFormat = OptionFormat {
+    layer: Layer::TCP,
+    single: &[0, 1],
+    end: Some(0),
+    inclusive_len: true,
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct TcpFlags(u16);
+
+impl TcpFlags {
+
+    pub const FIN: u16 = 0x001;
+    pub const SYN: u16 = 0x002;
+    pub const RST: u16 = 0x004;
+    pub const PSH: u16 = 0x008;
+    pub const ACK: u16 = 0x010;
+    pub const URG: u16 = 0x020;
+    pub const ECE: u16 = 0x040;
+    pub const CWR: u16 = 0x080;
+    pub const NS: u16 = 0x100;
+
+    const NAMES: [(u16, &'static str);9] = [
+        (Self::FIN, "FIN"),
+        (Self::SYN, "SYN"),
+        (Self::RST, "RST"),
+        (Self::PSH, "PSH"),
+        (Self::ACK, "ACK"),
+        (Self::URG, "URG"),
+        (Self::ECE, "ECE"),
+        (Self::CWR, "CWR"),
+        (Self::NS, "NS"),
+    ];
+
+    pub fn new(bits: u16) -> Self {
+        Self(bits & 0x1FF)
+    }
+
+    pub fn bits(&self) -> u16 {
+        self.0
+    }
+
+    pub fn contains(&self, flag: u16) -> bool {
+        self.0 & flag == flag
+    }
+
+    pub fn fin(&self) -> bool {
+        self.contains(Self::FIN)
+    }
+
+    pub fn syn(&self) -> bool {
+        self.contains(Self::SYN)
+    }
+
+    pub fn rst(&self) -> bool {
+        self.contains(Self::RST)
+    }
+
+    pub fn psh(&self) -> bool {
+        self.contains(Self::PSH)
+    }
+
+    pub fn ack(&self) -> bool {
+        self.contains(Self::ACK)
+    }
+
+    pub fn urg(&self) -> bool {
+        self.contains(Self::URG)
+    }
+
+    pub fn ece(&self) -> bool {
+        self.contains(Self::ECE)
+    }
+
+    pub fn cwr(&self) -> bool {
+        self.contains(Self::CWR)
+    }
+
+    pub fn ns(&self) -> bool {
+        self.contains(Self::NS)
+    }
+
+    pub fn summary(&self) -> String {
+        let names: Vec<&str> = Self::NAMES.iter()
+            .filter(|(flag, _)| self.contains(*flag))
+            .map(|(_, name)| *name)
+            .collect();
+        format!("[{}]", names.join(", "))
+    }
+}
+
+impl Display for TcpFlags {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:#05x} {}", self.0, self.summary())
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum TcpOption {
+    EOL,
+    NOP,
+    MSS(u16),
+    WS(u8),
+    SACKPERM,
+    SACK(Vec<(u32, u32)>),
+    TS(u32, u32),
+    TFO(Vec<u8>),
+    MPTCP(u8, Vec<u8>),
+    UNKNOWN(u8, Vec<u8>),
+}
+
+impl TcpOption {
+
+    pub fn kind(&self) -> u8 {
+        match self {
+            Self::EOL => 0,
+            Self::NOP => 1,
+            Self::MSS(_) => 2,
+            Self::WS(_) => 3,
+            Self::SACKPERM => 4,
+            Self::SACK(_) => 5,
+            Self::TS(_, _) => 8,
+            Self::MPTCP(_, _) => 30,
+            Self::TFO(_) => 34,
+            Self::UNKNOWN(k, _) => *k,
+        }
+    }
+
+    pub fn parse_all(bytes: &[u8], base: usize) -> Result<Vec<Self>, Error> {
+        OPTION_FORMAT.parse(bytes, base, |kind, value, offset| match (kind, value) {
+            (0, _) => Ok(Self::EOL),
+            (1, _) => Ok(Self::NOP),
+            (_, value) => Self::parse(kind, value.unwrap_or_default(), offset),
+        })
+    }
+
+    fn parse(kind: u8, value: &[u8], offset: usize) -> Result<Self, Error> {
+        let u32_at = |i: usize| bytes_to_u32(value[i], value[i + 1], value[i + 2], value[i + 3], false);
+        match (kind, value.len()) {
+            (2, 2) => Ok(Self::MSS(bytes_to_u16(value[0], value[1], false))),
+            (3, 1) => Ok(Self::WS(value[0])),
+            (4, 0) => Ok(Self::SACKPERM),
+            (5, n) if n % 8 == 0 => Ok(Self::SACK(
+                (0..n).step_by(8).map(|i| (u32_at(i), u32_at(i + 4))).collect()
+            )),
+            (8, 8) => Ok(Self::TS(u32_at(0), u32_at(4))),
+            (30, n) if n > 0 => Ok(Self::MPTCP(value[0] >> 4, value.to_vec())),
+            (34, _) => Ok(Self::TFO(value.to_vec())),
+            (2, _)|(3, _)|(4, _)|(5, _)|(8, _)|(30, _)
+                => Err(Error::Malformed(Layer::TCP, offset, "invalid option length")),
+            _ => Ok(Self::UNKNOWN(kind, value.to_vec())),
+        }
+    }
+}
+
+impl Display for TcpOption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::EOL => write!(f, "EOL"),
+            Self::NOP => write!(f, "NOP"),
+            Self::MSS(b) => write!(f, "MSS={}", b),
+            Self::WS(b) => write!(f, "WS={}", b),
+            Self::SACKPERM => write!(f, "SACK_PERM"),
+            Self::SACK(blocks) => {
+                let blocks: Vec<String> = blocks.iter().map(|(l, r)| format!("{}-{}", l, r)).collect();
+                write!(f, "SACK={}", blocks.join(","))
+            },
+            Self::TS(val, ecr) => write!(f, "TSval={} TSecr={}", val, ecr),
+            Self::TFO(cookie) => write!(f, "TFO cookie={}", cookie.iter().map(|b| format!("{:02x}", b)).collect::<String>()),
+            Self::MPTCP(subtype, _) => write!(f, "MPTCP subtype={}", subtype),
+            Self::UNKNOWN(kind, value) => write!(f, "Unknown kind={} len={}", kind, value.len() + 2),
+        }
+    }
+}
 
 pub enum TcpHeaderField {
     SRC(u16),
@@ -8,10 +186,11 @@ pub enum TcpHeaderField {
     SEQ(u32),
     ACK(u32),
     HLEN(u8),
-    FLAGS(u8),
+    FLAGS(TcpFlags),
     WSIZE(u16),
     CHECK(u16),
     UPOINT(u16),
+    OPTS(Vec<TcpOption>),
     UNSET,
 }
 
@@ -27,26 +206,29 @@ impl Clone for TcpHeaderField {
             Self::WSIZE(b) => Self::WSIZE(*b),
             Self::CHECK(b) => Self::CHECK(*b),
             Self::UPOINT(b) => Self::UPOINT(*b),
+            Self::OPTS(b) => Self::OPTS(b.clone()),
             Self::UNSET => Self::UNSET,
         }
     }
 }
 
-impl TcpHeaderField {
-
-    pub fn to_string(&self) -> String {
+impl Display for TcpHeaderField {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Self::SRC(b) => format!("{}", b),
-            Self::DST(b) => format!("{}", b),
-            Self::SEQ(b) => format!("{}", b),
-            Self::ACK(b) => format!("{}", b),
-            Self::HLEN(b) => format!("{}", b),
-            Self::FLAGS(b) => format!("{:#010b}", b),
-            Self::WSIZE(b) => format!("{}", b),
-            Self::CHECK(b) => format!("{}", b),
-            Self::UPOINT(b) => format!("{}", b),
-            Self::UNSET => "UNSET".to_string(),
-
+            Self::SRC(b) => write!(f, "{}", b),
+            Self::DST(b) => write!(f, "{}", b),
+            Self::SEQ(b) => write!(f, "{}", b),
+            Self::ACK(b) => write!(f, "{}", b),
+            Self::HLEN(b) => write!(f, "{}", b),
+            Self::FLAGS(b) => write!(f, "{}", b),
+            Self::WSIZE(b) => write!(f, "{}", b),
+            Self::CHECK(b) => write!(f, "{}", b),
+            Self::UPOINT(b) => write!(f, "{}", b),
+            Self::OPTS(b) => {
+                let opts: Vec<String> = b.iter().map(|o| o.to_string()).collect();
+                write!(f, "{}", opts.join(", "))
+            },
+            Self::UNSET => write!(f, "UNSET"),
         }
     }
 }
@@ -61,7 +243,8 @@ pub struct TcpHeader {
     flags: TcpHeaderField,
     wsize: TcpHeaderField,
     check: TcpHeaderField,
-    upoint: TcpHeaderField
+    upoint: TcpHeaderField,
+    options: TcpHeaderField,
 }
 
 impl Display for TcpHeader {
@@ -70,16 +253,26 @@ impl Display for TcpHeader {
         write!(
             f,
             "Src Port: {}\nDest Port: {}\nSeq: {}\nACK: {}\nLen: {}\nFlags: {}\nWSize: {}\nCheck: {}\nUpoint: {}\n",
-            self.src.to_string(),
-            self.dst.to_string(),
-            self.seq.to_string(),
-            self.ack.to_string(),
-            self.hlen.to_string(),
-            self.flags.to_string(),
-            self.wsize.to_string(),
-            self.check.to_string(),
-            self.upoint.to_string(),
-        )
+            self.src,
+            self.dst,
+            self.seq,
+            self.ack,
+            self.hlen,
+            self.flags,
+            self.wsize,
+            self.check,
+            self.upoint,
+        )?;
+        match self.options {
+            TcpHeaderField::OPTS(_) => writeln!(f, "Options: {}", self.options),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Default for TcpHeader {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
@@ -96,6 +289,7 @@ impl TcpHeader {
             wsize: TcpHeaderField::UNSET,
             check: TcpHeaderField::UNSET,
             upoint: TcpHeaderField::UNSET,
+            options: TcpHeaderField::UNSET,
         }
     }
 
@@ -110,15 +304,119 @@ impl TcpHeader {
             TcpHeaderField::WSIZE(_) => self.wsize = field,
             TcpHeaderField::CHECK(_) => self.check = field,
             TcpHeaderField::UPOINT(_) => self.upoint = field,
-            TcpHeaderField::UNSET => println!("Cant set unset field"),
+            TcpHeaderField::OPTS(_) => self.options = field,
+            TcpHeaderField::UNSET => (),
+        }
+    }
+
+    pub fn src_port(&self) -> Option<u16> {
+        match self.src {
+            TcpHeaderField::SRC(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn dst_port(&self) -> Option<u16> {
+        match self.dst {
+            TcpHeaderField::DST(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn seq(&self) -> Option<u32> {
+        match self.seq {
+            TcpHeaderField::SEQ(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn ack(&self) -> Option<u32> {
+        match self.ack {
+            TcpHeaderField::ACK(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn hlen(&self) -> Option<u8> {
+        match self.hlen {
+            TcpHeaderField::HLEN(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn header_len(&self) -> Option<usize> {
+        self.hlen().map(|b| b as usize * 4)
+    }
+
+    pub fn flags(&self) -> Option<TcpFlags> {
+        match self.flags {
+            TcpHeaderField::FLAGS(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn window(&self) -> Option<u16> {
+        match self.wsize {
+            TcpHeaderField::WSIZE(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn checksum(&self) -> Option<u16> {
+        match self.check {
+            TcpHeaderField::CHECK(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn urgent_ptr(&self) -> Option<u16> {
+        match self.upoint {
+            TcpHeaderField::UPOINT(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn options(&self) -> Option<&[TcpOption]> {
+        match &self.options {
+            TcpHeaderField::OPTS(b) => Some(b),
+            _ => None,
         }
     }
+
+    pub fn mss(&self) -> Option<u16> {
+        self.options()?.iter().find_map(|o| match o {
+            TcpOption::MSS(b) => Some(*b),
+            _ => None,
+        })
+    }
+
+    pub fn window_scale(&self) -> Option<u8> {
+        self.options()?.iter().find_map(|o| match o {
+            TcpOption::WS(b) => Some(*b),
+            _ => None,
+        })
+    }
+
+    pub fn sack_permitted(&self) -> bool {
+        self.options().is_some_and(|o| o.contains(&TcpOption::SACKPERM))
+    }
+
+    pub fn timestamps(&self) -> Option<(u32, u32)> {
+        self.options()?.iter().find_map(|o| match o {
+            TcpOption::TS(val, ecr) => Some((*val, *ecr)),
+            _ => None,
+        })
+    }
 }
 
 pub struct TcpHeaderParser {
     parser: ByteParser,
     curr_field: TcpHeaderField,
     header: TcpHeader,
+    ns: bool,
+    opts_len: usize,
+    opts: Vec<u8>,
+    offset: usize,
 }
 
 impl TcpHeaderParser {
@@ -127,34 +425,50 @@ impl TcpHeaderParser {
         Self { 
             parser: ByteParser::new(false),
             curr_field: TcpHeaderField::SRC(0),
-            header: TcpHeader::new()
+            header: TcpHeader::new(),
+            ns: false,
+            opts_len: 0,
+            opts: vec![],
+            offset: 0,
         }
     }
 
+    pub fn with_offset(mut self, offset: usize) -> Self {
+        self.offset = offset;
+        self
+    }
+
     pub fn get_header(&self) -> TcpHeader {
         self.header.clone()
     }
 
-    fn step(&mut self, b: u8) {
+    fn step(&mut self, b: u8) -> Result<(), Error> {
         match self.curr_field {
             TcpHeaderField::SRC(_)|
                 TcpHeaderField::DST(_)|
                 TcpHeaderField::WSIZE(_)|
                 TcpHeaderField::CHECK(_)|
                 TcpHeaderField::UPOINT(_) 
-                => self.parser.set_d_byte(b),
+                => self.parser.set_d_byte(b).map_err(|e| Error::Byte(Layer::TCP, e)),
             TcpHeaderField::SEQ(_)|
                 TcpHeaderField::ACK(_)
-                => self.parser.set_q_byte(b),
+                => self.parser.set_q_byte(b).map_err(|e| Error::Byte(Layer::TCP, e)),
             TcpHeaderField::HLEN(_)|
                 TcpHeaderField::FLAGS(_)
-                => self.parser.set_word(b),
-            TcpHeaderField::UNSET => println!("Cant set unset byte"),
+                => {
+                    self.parser.set_word(b);
+                    Ok(())
+                },
+            TcpHeaderField::OPTS(_) => {
+                self.opts.push(b);
+                Ok(())
+            },
+            TcpHeaderField::UNSET => Err(Error::HeaderComplete(Layer::TCP)),
         }
     }
 
-    pub fn parse(&mut self, b: u8) {
-        self.step(b);
+    pub fn parse(&mut self, b: u8) -> Result<(), Error> {
+        self.step(b)?;
         match self.curr_field {
             TcpHeaderField::SRC(_) => self.src(),
             TcpHeaderField::DST(_) => self.dst(),
@@ -165,8 +479,10 @@ impl TcpHeaderParser {
             TcpHeaderField::WSIZE(_) => self.wsize(),
             TcpHeaderField::CHECK(_) => self.check(),
             TcpHeaderField::UPOINT(_) => self.upoint(),
-            TcpHeaderField::UNSET => println!("Tcp Header full."),
+            TcpHeaderField::OPTS(_) => self.options()?,
+            TcpHeaderField::UNSET => (),
         }
+        Ok(())
     }
 
     fn src(&mut self) {
@@ -210,12 +526,16 @@ impl TcpHeaderParser {
     }
 
     fn hlen(&mut self) {
-        self.header.set_field(TcpHeaderField::HLEN(self.parser.word_l_nibble()));
-        self.curr_field = TcpHeaderField::FLAGS(0);
+        let hlen = self.parser.word_l_nibble();
+        self.header.set_field(TcpHeaderField::HLEN(hlen));
+        self.ns = self.parser.word() & 0x01 != 0;
+        self.opts_len = (hlen as usize * 4).saturating_sub(20);
+        self.curr_field = TcpHeaderField::FLAGS(TcpFlags::new(0));
     }
 
     fn flags(&mut self) {
-        self.header.set_field(TcpHeaderField::FLAGS(self.parser.word()));
+        let ns = if self.ns { TcpFlags::NS } else { 0 };
+        self.header.set_field(TcpHeaderField::FLAGS(TcpFlags::new(ns | self.parser.word() as u16)));
         self.curr_field = TcpHeaderField::WSIZE(0);
     }
 
@@ -245,7 +565,115 @@ impl TcpHeaderParser {
                 TcpHeaderField::UPOINT(self.parser.dword_as_u16())
             );
             self.parser.reset_dword();
+            self.curr_field = if self.opts_len > 0 {
+                TcpHeaderField::OPTS(vec![])
+            } else {
+                TcpHeaderField::UNSET
+            };
+        }
+    }
+
+    fn options(&mut self) -> Result<(), Error> {
+        if self.opts.len() == self.opts_len {
+            self.header.set_field(TcpHeaderField::OPTS(TcpOption::parse_all(&self.opts, self.offset + 20)?));
             self.curr_field = TcpHeaderField::UNSET;
         }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(bytes: &[u8]) -> Result<TcpHeader, Error> {
+        let mut parser = TcpHeaderParser::new(false);
+        for b in bytes {
+            parser.parse(*b)?;
+        }
+        Ok(parser.get_header())
+    }
+
+    #[test]
+    fn test_tcp_flags() {
+        let flags = TcpFlags::new(0x012);
+        assert!(flags.syn());
+        assert!(flags.ack());
+        assert!(!flags.fin());
+        assert!(!flags.ns());
+        assert_eq!(flags.summary(), "[SYN, ACK]".to_string());
+        assert_eq!(flags.to_string(), "0x012 [SYN, ACK]".to_string());
+        assert_eq!(TcpFlags::new(0x1FF).summary(), "[FIN, SYN, RST, PSH, ACK, URG, ECE, CWR, NS]".to_string());
+        assert_eq!(TcpFlags::new(0).summary(), "[]".to_string());
+        assert_eq!(TcpFlags::new(0xFFFF).bits(), 0x1FF);
+    }
+
+    #[test]
+    fn test_ns_flag_from_data_offset() {
+        let header = parse(&[
+            0x00, 0x50, 0xD4, 0x31, 0x00, 0x00, 0x00, 0x01,
+            0x00, 0x00, 0x00, 0x02, 0x51, 0x11, 0x01, 0x00,
+            0x00, 0x00, 0x00, 0x00,
+        ]).unwrap();
+        let flags = header.flags().unwrap();
+        assert!(flags.ns());
+        assert!(flags.fin());
+        assert!(flags.ack());
+        assert_eq!(flags.summary(), "[FIN, ACK, NS]".to_string());
+        assert_eq!(header.hlen(), Some(5));
+        assert_eq!(header.options(), None);
+    }
+
+    #[test]
+    fn test_tcp_options() {
+        let mut bytes = vec![
+            0xD4, 0x31, 0x00, 0x50, 0x2A, 0x4A, 0x8E, 0x01,
+            0x00, 0x00, 0x00, 0x00, 0xF0, 0x02, 0xFA, 0xF0,
+            0x00, 0x00, 0x00, 0x00,
+        ];
+        bytes.extend_from_slice(&[
+            0x02, 0x04, 0x05, 0xB4,
+            0x04, 0x02,
+            0x08, 0x0A, 0x00, 0xA7, 0xCE, 0x4F, 0x00, 0x00, 0x00, 0x00,
+            0x01,
+            0x03, 0x03, 0x07,
+            0x05, 0x0A, 0x00, 0x00, 0x00, 0x10, 0x00, 0x00, 0x00, 0x20,
+            0x22, 0x02,
+            0x1E, 0x04, 0x00, 0x81,
+            0xFD, 0x03, 0x01,
+            0x00,
+        ]);
+        assert_eq!(bytes.len(), 60);
+        let header = parse(&bytes).unwrap();
+        assert_eq!(header.options().unwrap(), &[
+            TcpOption::MSS(1460),
+            TcpOption::SACKPERM,
+            TcpOption::TS(0x00A7CE4F, 0),
+            TcpOption::NOP,
+            TcpOption::WS(7),
+            TcpOption::SACK(vec![(0x10, 0x20)]),
+            TcpOption::TFO(vec![]),
+            TcpOption::MPTCP(0, vec![0x00, 0x81]),
+            TcpOption::UNKNOWN(0xFD, vec![0x01]),
+            TcpOption::EOL,
+        ]);
+        assert_eq!(header.mss(), Some(1460));
+        assert_eq!(header.window_scale(), Some(7));
+        assert!(header.sack_permitted());
+        assert_eq!(header.timestamps(), Some((0x00A7CE4F, 0)));
+        assert!(header.to_string().contains("Options: MSS=1460, SACK_PERM, TSval=10997327 TSecr=0, NOP, WS=7"));
+        assert!(matches!(parse(&[0u8;61]), Err(Error::HeaderComplete(Layer::TCP))));
+    }
+
+    #[test]
+    fn test_malformed_options() {
+        let mut bytes = vec![0u8;20];
+        bytes[12] = 0x60;
+        bytes.extend_from_slice(&[0x02, 0x05, 0x05, 0xB4]);
+        assert_eq!(parse(&bytes).err(), Some(Error::Malformed(Layer::TCP, 20, "option length out of bounds")));
+        bytes[21] = 0x03;
+        assert_eq!(parse(&bytes).err(), Some(Error::Malformed(Layer::TCP, 20, "invalid option length")));
+        bytes[20..24].copy_from_slice(&[0x01, 0x01, 0x01, 0x08]);
+        assert_eq!(parse(&bytes).err(), Some(Error::Malformed(Layer::TCP, 23, "option length missing")));
     }
 }
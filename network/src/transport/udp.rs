@@ -2,6 +2,8 @@ use std::fmt::Display;
 
 use byte::ByteParser;
 
+use crate::error::{Error, Layer};
+
 pub enum UdpHeaderField {
     SRC(u16),
     DST(u16),
@@ -48,6 +50,12 @@ impl Display for UdpHeader {
     }
 }
 
+impl Default for UdpHeader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl UdpHeader {
 
     pub fn new() -> Self {
@@ -65,7 +73,35 @@ impl UdpHeader {
             UdpHeaderField::DST(_) => self.dst = field,
             UdpHeaderField::LEN(_) => self.len = field,
             UdpHeaderField::CHECK(_) => self.check = field,
-            UdpHeaderField::UNSET => print!("Cant set unset UDP Header Field"),
+            UdpHeaderField::UNSET => (),
+        }
+    }
+
+    pub fn src_port(&self) -> Option<u16> {
+        match self.src {
+            UdpHeaderField::SRC(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn dst_port(&self) -> Option<u16> {
+        match self.dst {
+            UdpHeaderField::DST(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn length(&self) -> Option<u16> {
+        match self.len {
+            UdpHeaderField::LEN(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn checksum(&self) -> Option<u16> {
+        match self.check {
+            UdpHeaderField::CHECK(b) => Some(b),
+            _ => None,
         }
     }
 }
@@ -86,29 +122,30 @@ impl UdpHeaderParser {
         }
     }
 
-    pub fn parse(&mut self, b: u8) {
-        self.step(b);
+    pub fn parse(&mut self, b: u8) -> Result<(), Error> {
+        self.step(b)?;
         match self.curr_field {
             UdpHeaderField::SRC(_) => self.src(),
             UdpHeaderField::DST(_) => self.dst(),
             UdpHeaderField::LEN(_) => self.len(),
             UdpHeaderField::CHECK(_) => self.check(),
-            UdpHeaderField::UNSET => println!("Cant parse byte for unset udp header field"),
+            UdpHeaderField::UNSET => (),
         }
+        Ok(())
     }
 
     pub fn get_header(&self) -> UdpHeader {
         self.header.clone()
     }
 
-    fn step(&mut self, b: u8) {
+    fn step(&mut self, b: u8) -> Result<(), Error> {
         match self.curr_field {
             UdpHeaderField::SRC(_)|
                 UdpHeaderField::DST(_)|
                 UdpHeaderField::LEN(_)|
                 UdpHeaderField::CHECK(_)
-                => self.parser.set_d_byte(b),
-            UdpHeaderField::UNSET => println!("Cant set byte to unset UDP Header field"),
+                => self.parser.set_d_byte(b).map_err(|e| Error::Byte(Layer::UDP, e)),
+            UdpHeaderField::UNSET => Err(Error::HeaderComplete(Layer::UDP)),
         }
     }
 
@@ -170,4 +207,17 @@ mod test {
         assert_eq!(UdpHeaderField::LEN(44).to_string(), "Length: 44".to_string());
         assert_eq!(UdpHeaderField::UNSET.to_string(), "UNSET".to_string());
     }
+
+    #[test]
+    fn test_udp_header_parser() {
+        let mut parser = UdpHeaderParser::new(false);
+        for b in [0x00, 0x35, 0xD4, 0x31, 0x00, 0x10, 0xAB, 0xCD] {
+            parser.parse(b).unwrap();
+        }
+        assert_eq!(parser.parse(0), Err(Error::HeaderComplete(Layer::UDP)));
+        assert_eq!(
+            parser.get_header().to_string(),
+            "Src Port: 53\nDest Port: 54321\nLength: 16\nChecksum: 0b1010101111001101".to_string()
+        );
+    }
 }
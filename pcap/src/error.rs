@@ -0,0 +1,68 @@
+use std::{fmt, io};
+
+use network::error::Layer;
+
+use crate::file::LinkType;
+
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    Network(network::error::Error),
+    UnexpectedEof,
+    InsufficientData(usize, usize),
+    OutOfBounds(usize),
+    InvalidMagic(u32),
+    InvalidByteOrder(u32),
+    MalformedBlock(u64, &'static str),
+    MalformedRecord(u64, &'static str),
+    OptionTooLong(u16, usize),
+    UnknownInterface(u32),
+    UnsupportedLinkType(LinkType),
+    MissingLayer(Layer),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "I/O error: {}", e),
+            Self::Network(e) => write!(f, "{}", e),
+            Self::UnexpectedEof => write!(f, "Unexpected end of capture data"),
+            Self::InsufficientData(needed, got) => write!(f, "Insufficient data: needed {} bytes, got {}", needed, got),
+            Self::OutOfBounds(n) => write!(f, "Index {} out of bounds", n),
+            Self::InvalidMagic(b) => write!(f, "Invalid magic number {:#010x}", b),
+            Self::InvalidByteOrder(b) => write!(f, "Invalid byte order magic {:#010x}", b),
+            Self::MalformedBlock(offset, reason) => write!(f, "Malformed block at offset {}: {}", offset, reason),
+            Self::MalformedRecord(offset, reason) => write!(f, "Malformed record at offset {}: {}", offset, reason),
+            Self::OptionTooLong(code, len) => write!(f, "Option {} value of {} bytes exceeds 65535", code, len),
+            Self::UnknownInterface(id) => write!(f, "Packet references unknown interface {}", id),
+            Self::UnsupportedLinkType(l) => write!(f, "Unsupported link type: {}", l.to_string()),
+            Self::MissingLayer(l) => write!(f, "Packet has no {} layer", l),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::Network(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<network::error::Error> for Error {
+    fn from(e: network::error::Error) -> Self {
+        Self::Network(e)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        if e.kind() == io::ErrorKind::UnexpectedEof {
+            Self::UnexpectedEof
+        } else {
+            Self::Io(e)
+        }
+    }
+}
@@ -1,11 +1,14 @@
 use core::fmt;
 use std::fmt::Display;
 
-use byte::{Byte, bytes_to_u32, bytes_to_u16};
+use crate::error::Error;
+use byte::{Byte, bytes_to_u32, bytes_to_u16, u16_to_bytes, u32_to_bytes};
 
 pub enum MagicNumber {
     PCAP,
     SWAPPED,
+    NSPCAP,
+    NSSWAPPED,
     PCAPNG,
     UNKNOWN,
 }
@@ -16,19 +19,37 @@ impl MagicNumber {
         match bytes {
             0xa1b2c3d4 => Self::PCAP,
             0xd4c3b2a1 => Self::SWAPPED,
+            0xa1b23c4d => Self::NSPCAP,
+            0x4d3cb2a1 => Self::NSSWAPPED,
             0x0a0d0d0a => Self::PCAPNG,
             _ => Self::UNKNOWN,
         }
     }
 
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        if bytes.len() < 4 {
+            return Self::UNKNOWN;
+        }
+        Self::from_row(bytes_to_u32(bytes[0], bytes[1], bytes[2], bytes[3], false))
+    }
+
     pub fn is_swapped(&self) -> bool {
         match self {
             Self::PCAP => false,
             Self::SWAPPED => true,
+            Self::NSPCAP => false,
+            Self::NSSWAPPED => true,
             Self::PCAPNG => false,
             Self::UNKNOWN => false,
         }
     }
+
+    pub fn ts_resolution(&self) -> TsResolution {
+        match self {
+            Self::NSPCAP | Self::NSSWAPPED => TsResolution::NANO,
+            _ => TsResolution::MICRO,
+        }
+    }
 }
 
 impl Display for MagicNumber {
@@ -36,13 +57,40 @@ impl Display for MagicNumber {
         let string = match self {
             Self::PCAP => "PCAP".to_string(),
             Self::SWAPPED => "PCAP (swapped)".to_string(),
-            Self::PCAPNG => "PCAPNG (not supported)".to_string(),
+            Self::NSPCAP => "PCAP (nanosecond)".to_string(),
+            Self::NSSWAPPED => "PCAP (nanosecond, swapped)".to_string(),
+            Self::PCAPNG => "PCAPNG".to_string(),
             Self::UNKNOWN => "Invalid Magic Number".to_string(),
         };
         write!(f, "{}", string)
     }
 }
 
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TsResolution {
+    MICRO,
+    NANO,
+}
+
+impl TsResolution {
+
+    pub fn units_per_sec(&self) -> u32 {
+        match self {
+            Self::MICRO => 1_000_000,
+            Self::NANO => 1_000_000_000,
+        }
+    }
+
+    pub fn to_str(&self) -> &str {
+        match self {
+            Self::MICRO => "us",
+            Self::NANO => "ns",
+        }
+    }
+}
+
+/// Link-layer header types from the tcpdump.org LINKTYPE_* registry.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum LinkType {
     NULL,
     ETHERNET,
@@ -50,7 +98,145 @@ pub enum LinkType {
     AX25,
     PRONET,
     CHAOS,
-    UNKNOWN,
+    IEEE8025,
+    ARCNETBSD,
+    SLIP,
+    PPP,
+    FDDI,
+    PPPHDLC,
+    PPPETHER,
+    ATMRFC1483,
+    RAW,
+    CHDLC,
+    IEEE80211,
+    FRELAY,
+    LOOP,
+    LINUXSLL,
+    LTALK,
+    PFLOG,
+    IEEE80211PRISM,
+    IPOVERFC,
+    SUNATM,
+    IEEE80211RADIOTAP,
+    ARCNETLINUX,
+    APPLEIPOVERIEEE1394,
+    MTP2WITHPHDR,
+    MTP2,
+    MTP3,
+    SCCP,
+    DOCSIS,
+    LINUXIRDA,
+    USER0,
+    USER1,
+    USER2,
+    USER3,
+    USER4,
+    USER5,
+    USER6,
+    USER7,
+    USER8,
+    USER9,
+    USER10,
+    USER11,
+    USER12,
+    USER13,
+    USER14,
+    USER15,
+    IEEE80211AVS,
+    BACNETMSTP,
+    PPPPPPD,
+    GPRSLLC,
+    GPFT,
+    GPFF,
+    LINUXLAPD,
+    MFR,
+    BLUETOOTHHCIH4,
+    USBLINUX,
+    PPI,
+    IEEE802154WITHFCS,
+    SITA,
+    ERF,
+    BLUETOOTHHCIH4WITHPHDR,
+    AX25KISS,
+    LAPD,
+    PPPWITHDIR,
+    CHDLCWITHDIR,
+    FRELAYWITHDIR,
+    LAPBWITHDIR,
+    IPMBLINUX,
+    FLEXRAY,
+    LIN,
+    IEEE802154NONASKPHY,
+    USBLINUXMMAPPED,
+    FC2,
+    FC2WITHFRAMEDELIMS,
+    IPNET,
+    CANSOCKETCAN,
+    IPV4,
+    IPV6,
+    IEEE802154NOFCS,
+    DBUS,
+    DVBCI,
+    MUX27010,
+    STANAG5066DPDU,
+    NFLOG,
+    NETANALYZER,
+    NETANALYZERTRANSPARENT,
+    IPOIB,
+    MPEG2TS,
+    NG40,
+    NFCLLCP,
+    INFINIBAND,
+    SCTP,
+    USBPCAP,
+    RTACSERIAL,
+    BLUETOOTHLELL,
+    NETLINK,
+    BLUETOOTHLINUXMONITOR,
+    BLUETOOTHBREDRBB,
+    BLUETOOTHLELLWITHPHDR,
+    PROFIBUSDL,
+    PKTAP,
+    EPON,
+    IPMIHPM2,
+    ZWAVER1R2,
+    ZWAVER3,
+    WATTSTOPPERDLM,
+    ISO14443,
+    RDS,
+    USBDARWIN,
+    SDLC,
+    LORATAP,
+    VSOCK,
+    NORDICBLE,
+    DOCSIS31XRA31,
+    ETHERNETMPACKET,
+    DISPLAYPORTAUX,
+    LINUXSLL2,
+    OPENVIZSLA,
+    EBHSCR,
+    VPPDISPATCH,
+    DSATAGBRCM,
+    DSATAGBRCMPREPEND,
+    IEEE802154TAP,
+    DSATAGDSA,
+    DSATAGEDSA,
+    ELEE,
+    ZWAVESERIAL,
+    USB20,
+    ATSCALP,
+    ETW,
+    ZBOSSNCP,
+    USB20LOWSPEED,
+    USB20FULLSPEED,
+    USB20HIGHSPEED,
+    AUERSWALDLOG,
+    ZWAVETAP,
+    SILABSDEBUGCHANNEL,
+    FIRAUCI,
+    MDB,
+    DECTNR,
+    UNKNOWN(u16),
 }
 
 impl LinkType {
@@ -63,7 +249,446 @@ impl LinkType {
             3 => LinkType::AX25,
             4 => LinkType::PRONET,
             5 => LinkType::CHAOS,
-            _ => LinkType::UNKNOWN,
+            6 => LinkType::IEEE8025,
+            7 => LinkType::ARCNETBSD,
+            8 => LinkType::SLIP,
+            9 => LinkType::PPP,
+            10 => LinkType::FDDI,
+            50 => LinkType::PPPHDLC,
+            51 => LinkType::PPPETHER,
+            100 => LinkType::ATMRFC1483,
+            101 => LinkType::RAW,
+            104 => LinkType::CHDLC,
+            105 => LinkType::IEEE80211,
+            107 => LinkType::FRELAY,
+            108 => LinkType::LOOP,
+            113 => LinkType::LINUXSLL,
+            114 => LinkType::LTALK,
+            117 => LinkType::PFLOG,
+            119 => LinkType::IEEE80211PRISM,
+            122 => LinkType::IPOVERFC,
+            123 => LinkType::SUNATM,
+            127 => LinkType::IEEE80211RADIOTAP,
+            129 => LinkType::ARCNETLINUX,
+            138 => LinkType::APPLEIPOVERIEEE1394,
+            139 => LinkType::MTP2WITHPHDR,
+            140 => LinkType::MTP2,
+            141 => LinkType::MTP3,
+            142 => LinkType::SCCP,
+            143 => LinkType::DOCSIS,
+            144 => LinkType::LINUXIRDA,
+            147 => LinkType::USER0,
+            148 => LinkType::USER1,
+            149 => LinkType::USER2,
+            150 => LinkType::USER3,
+            151 => LinkType::USER4,
+            152 => LinkType::USER5,
+            153 => LinkType::USER6,
+            154 => LinkType::USER7,
+            155 => LinkType::USER8,
+            156 => LinkType::USER9,
+            157 => LinkType::USER10,
+            158 => LinkType::USER11,
+            159 => LinkType::USER12,
+            160 => LinkType::USER13,
+            161 => LinkType::USER14,
+            162 => LinkType::USER15,
+            163 => LinkType::IEEE80211AVS,
+            165 => LinkType::BACNETMSTP,
+            166 => LinkType::PPPPPPD,
+            169 => LinkType::GPRSLLC,
+            170 => LinkType::GPFT,
+            171 => LinkType::GPFF,
+            177 => LinkType::LINUXLAPD,
+            182 => LinkType::MFR,
+            187 => LinkType::BLUETOOTHHCIH4,
+            189 => LinkType::USBLINUX,
+            192 => LinkType::PPI,
+            195 => LinkType::IEEE802154WITHFCS,
+            196 => LinkType::SITA,
+            197 => LinkType::ERF,
+            201 => LinkType::BLUETOOTHHCIH4WITHPHDR,
+            202 => LinkType::AX25KISS,
+            203 => LinkType::LAPD,
+            204 => LinkType::PPPWITHDIR,
+            205 => LinkType::CHDLCWITHDIR,
+            206 => LinkType::FRELAYWITHDIR,
+            207 => LinkType::LAPBWITHDIR,
+            209 => LinkType::IPMBLINUX,
+            210 => LinkType::FLEXRAY,
+            212 => LinkType::LIN,
+            215 => LinkType::IEEE802154NONASKPHY,
+            220 => LinkType::USBLINUXMMAPPED,
+            224 => LinkType::FC2,
+            225 => LinkType::FC2WITHFRAMEDELIMS,
+            226 => LinkType::IPNET,
+            227 => LinkType::CANSOCKETCAN,
+            228 => LinkType::IPV4,
+            229 => LinkType::IPV6,
+            230 => LinkType::IEEE802154NOFCS,
+            231 => LinkType::DBUS,
+            235 => LinkType::DVBCI,
+            236 => LinkType::MUX27010,
+            237 => LinkType::STANAG5066DPDU,
+            239 => LinkType::NFLOG,
+            240 => LinkType::NETANALYZER,
+            241 => LinkType::NETANALYZERTRANSPARENT,
+            242 => LinkType::IPOIB,
+            243 => LinkType::MPEG2TS,
+            244 => LinkType::NG40,
+            245 => LinkType::NFCLLCP,
+            247 => LinkType::INFINIBAND,
+            248 => LinkType::SCTP,
+            249 => LinkType::USBPCAP,
+            250 => LinkType::RTACSERIAL,
+            251 => LinkType::BLUETOOTHLELL,
+            253 => LinkType::NETLINK,
+            254 => LinkType::BLUETOOTHLINUXMONITOR,
+            255 => LinkType::BLUETOOTHBREDRBB,
+            256 => LinkType::BLUETOOTHLELLWITHPHDR,
+            257 => LinkType::PROFIBUSDL,
+            258 => LinkType::PKTAP,
+            259 => LinkType::EPON,
+            260 => LinkType::IPMIHPM2,
+            261 => LinkType::ZWAVER1R2,
+            262 => LinkType::ZWAVER3,
+            263 => LinkType::WATTSTOPPERDLM,
+            264 => LinkType::ISO14443,
+            265 => LinkType::RDS,
+            266 => LinkType::USBDARWIN,
+            268 => LinkType::SDLC,
+            270 => LinkType::LORATAP,
+            271 => LinkType::VSOCK,
+            272 => LinkType::NORDICBLE,
+            273 => LinkType::DOCSIS31XRA31,
+            274 => LinkType::ETHERNETMPACKET,
+            275 => LinkType::DISPLAYPORTAUX,
+            276 => LinkType::LINUXSLL2,
+            278 => LinkType::OPENVIZSLA,
+            279 => LinkType::EBHSCR,
+            280 => LinkType::VPPDISPATCH,
+            281 => LinkType::DSATAGBRCM,
+            282 => LinkType::DSATAGBRCMPREPEND,
+            283 => LinkType::IEEE802154TAP,
+            284 => LinkType::DSATAGDSA,
+            285 => LinkType::DSATAGEDSA,
+            286 => LinkType::ELEE,
+            287 => LinkType::ZWAVESERIAL,
+            288 => LinkType::USB20,
+            289 => LinkType::ATSCALP,
+            290 => LinkType::ETW,
+            292 => LinkType::ZBOSSNCP,
+            293 => LinkType::USB20LOWSPEED,
+            294 => LinkType::USB20FULLSPEED,
+            295 => LinkType::USB20HIGHSPEED,
+            296 => LinkType::AUERSWALDLOG,
+            297 => LinkType::ZWAVETAP,
+            298 => LinkType::SILABSDEBUGCHANNEL,
+            299 => LinkType::FIRAUCI,
+            300 => LinkType::MDB,
+            301 => LinkType::DECTNR,
+            b => LinkType::UNKNOWN(b),
+        }
+    }
+
+    pub fn value(&self) -> u16 {
+        match self {
+            LinkType::NULL => 0,
+            LinkType::ETHERNET => 1,
+            LinkType::EXPETHERNET => 2,
+            LinkType::AX25 => 3,
+            LinkType::PRONET => 4,
+            LinkType::CHAOS => 5,
+            LinkType::IEEE8025 => 6,
+            LinkType::ARCNETBSD => 7,
+            LinkType::SLIP => 8,
+            LinkType::PPP => 9,
+            LinkType::FDDI => 10,
+            LinkType::PPPHDLC => 50,
+            LinkType::PPPETHER => 51,
+            LinkType::ATMRFC1483 => 100,
+            LinkType::RAW => 101,
+            LinkType::CHDLC => 104,
+            LinkType::IEEE80211 => 105,
+            LinkType::FRELAY => 107,
+            LinkType::LOOP => 108,
+            LinkType::LINUXSLL => 113,
+            LinkType::LTALK => 114,
+            LinkType::PFLOG => 117,
+            LinkType::IEEE80211PRISM => 119,
+            LinkType::IPOVERFC => 122,
+            LinkType::SUNATM => 123,
+            LinkType::IEEE80211RADIOTAP => 127,
+            LinkType::ARCNETLINUX => 129,
+            LinkType::APPLEIPOVERIEEE1394 => 138,
+            LinkType::MTP2WITHPHDR => 139,
+            LinkType::MTP2 => 140,
+            LinkType::MTP3 => 141,
+            LinkType::SCCP => 142,
+            LinkType::DOCSIS => 143,
+            LinkType::LINUXIRDA => 144,
+            LinkType::USER0 => 147,
+            LinkType::USER1 => 148,
+            LinkType::USER2 => 149,
+            LinkType::USER3 => 150,
+            LinkType::USER4 => 151,
+            LinkType::USER5 => 152,
+            LinkType::USER6 => 153,
+            LinkType::USER7 => 154,
+            LinkType::USER8 => 155,
+            LinkType::USER9 => 156,
+            LinkType::USER10 => 157,
+            LinkType::USER11 => 158,
+            LinkType::USER12 => 159,
+            LinkType::USER13 => 160,
+            LinkType::USER14 => 161,
+            LinkType::USER15 => 162,
+            LinkType::IEEE80211AVS => 163,
+            LinkType::BACNETMSTP => 165,
+            LinkType::PPPPPPD => 166,
+            LinkType::GPRSLLC => 169,
+            LinkType::GPFT => 170,
+            LinkType::GPFF => 171,
+            LinkType::LINUXLAPD => 177,
+            LinkType::MFR => 182,
+            LinkType::BLUETOOTHHCIH4 => 187,
+            LinkType::USBLINUX => 189,
+            LinkType::PPI => 192,
+            LinkType::IEEE802154WITHFCS => 195,
+            LinkType::SITA => 196,
+            LinkType::ERF => 197,
+            LinkType::BLUETOOTHHCIH4WITHPHDR => 201,
+            LinkType::AX25KISS => 202,
+            LinkType::LAPD => 203,
+            LinkType::PPPWITHDIR => 204,
+            LinkType::CHDLCWITHDIR => 205,
+            LinkType::FRELAYWITHDIR => 206,
+            LinkType::LAPBWITHDIR => 207,
+            LinkType::IPMBLINUX => 209,
+            LinkType::FLEXRAY => 210,
+            LinkType::LIN => 212,
+            LinkType::IEEE802154NONASKPHY => 215,
+            LinkType::USBLINUXMMAPPED => 220,
+            LinkType::FC2 => 224,
+            LinkType::FC2WITHFRAMEDELIMS => 225,
+            LinkType::IPNET => 226,
+            LinkType::CANSOCKETCAN => 227,
+            LinkType::IPV4 => 228,
+            LinkType::IPV6 => 229,
+            LinkType::IEEE802154NOFCS => 230,
+            LinkType::DBUS => 231,
+            LinkType::DVBCI => 235,
+            LinkType::MUX27010 => 236,
+            LinkType::STANAG5066DPDU => 237,
+            LinkType::NFLOG => 239,
+            LinkType::NETANALYZER => 240,
+            LinkType::NETANALYZERTRANSPARENT => 241,
+            LinkType::IPOIB => 242,
+            LinkType::MPEG2TS => 243,
+            LinkType::NG40 => 244,
+            LinkType::NFCLLCP => 245,
+            LinkType::INFINIBAND => 247,
+            LinkType::SCTP => 248,
+            LinkType::USBPCAP => 249,
+            LinkType::RTACSERIAL => 250,
+            LinkType::BLUETOOTHLELL => 251,
+            LinkType::NETLINK => 253,
+            LinkType::BLUETOOTHLINUXMONITOR => 254,
+            LinkType::BLUETOOTHBREDRBB => 255,
+            LinkType::BLUETOOTHLELLWITHPHDR => 256,
+            LinkType::PROFIBUSDL => 257,
+            LinkType::PKTAP => 258,
+            LinkType::EPON => 259,
+            LinkType::IPMIHPM2 => 260,
+            LinkType::ZWAVER1R2 => 261,
+            LinkType::ZWAVER3 => 262,
+            LinkType::WATTSTOPPERDLM => 263,
+            LinkType::ISO14443 => 264,
+            LinkType::RDS => 265,
+            LinkType::USBDARWIN => 266,
+            LinkType::SDLC => 268,
+            LinkType::LORATAP => 270,
+            LinkType::VSOCK => 271,
+            LinkType::NORDICBLE => 272,
+            LinkType::DOCSIS31XRA31 => 273,
+            LinkType::ETHERNETMPACKET => 274,
+            LinkType::DISPLAYPORTAUX => 275,
+            LinkType::LINUXSLL2 => 276,
+            LinkType::OPENVIZSLA => 278,
+            LinkType::EBHSCR => 279,
+            LinkType::VPPDISPATCH => 280,
+            LinkType::DSATAGBRCM => 281,
+            LinkType::DSATAGBRCMPREPEND => 282,
+            LinkType::IEEE802154TAP => 283,
+            LinkType::DSATAGDSA => 284,
+            LinkType::DSATAGEDSA => 285,
+            LinkType::ELEE => 286,
+            LinkType::ZWAVESERIAL => 287,
+            LinkType::USB20 => 288,
+            LinkType::ATSCALP => 289,
+            LinkType::ETW => 290,
+            LinkType::ZBOSSNCP => 292,
+            LinkType::USB20LOWSPEED => 293,
+            LinkType::USB20FULLSPEED => 294,
+            LinkType::USB20HIGHSPEED => 295,
+            LinkType::AUERSWALDLOG => 296,
+            LinkType::ZWAVETAP => 297,
+            LinkType::SILABSDEBUGCHANNEL => 298,
+            LinkType::FIRAUCI => 299,
+            LinkType::MDB => 300,
+            LinkType::DECTNR => 301,
+            LinkType::UNKNOWN(b) => *b,
+        }
+    }
+
+    /// The LINKTYPE_* constant name.
+    pub fn name(&self) -> Option<&'static str> {
+        match self {
+            LinkType::NULL => Some("LINKTYPE_NULL"),
+            LinkType::ETHERNET => Some("LINKTYPE_ETHERNET"),
+            LinkType::EXPETHERNET => Some("LINKTYPE_EXP_ETHERNET"),
+            LinkType::AX25 => Some("LINKTYPE_AX25"),
+            LinkType::PRONET => Some("LINKTYPE_PRONET"),
+            LinkType::CHAOS => Some("LINKTYPE_CHAOS"),
+            LinkType::IEEE8025 => Some("LINKTYPE_IEEE802_5"),
+            LinkType::ARCNETBSD => Some("LINKTYPE_ARCNET_BSD"),
+            LinkType::SLIP => Some("LINKTYPE_SLIP"),
+            LinkType::PPP => Some("LINKTYPE_PPP"),
+            LinkType::FDDI => Some("LINKTYPE_FDDI"),
+            LinkType::PPPHDLC => Some("LINKTYPE_PPP_HDLC"),
+            LinkType::PPPETHER => Some("LINKTYPE_PPP_ETHER"),
+            LinkType::ATMRFC1483 => Some("LINKTYPE_ATM_RFC1483"),
+            LinkType::RAW => Some("LINKTYPE_RAW"),
+            LinkType::CHDLC => Some("LINKTYPE_C_HDLC"),
+            LinkType::IEEE80211 => Some("LINKTYPE_IEEE802_11"),
+            LinkType::FRELAY => Some("LINKTYPE_FRELAY"),
+            LinkType::LOOP => Some("LINKTYPE_LOOP"),
+            LinkType::LINUXSLL => Some("LINKTYPE_LINUX_SLL"),
+            LinkType::LTALK => Some("LINKTYPE_LTALK"),
+            LinkType::PFLOG => Some("LINKTYPE_PFLOG"),
+            LinkType::IEEE80211PRISM => Some("LINKTYPE_IEEE802_11_PRISM"),
+            LinkType::IPOVERFC => Some("LINKTYPE_IP_OVER_FC"),
+            LinkType::SUNATM => Some("LINKTYPE_SUNATM"),
+            LinkType::IEEE80211RADIOTAP => Some("LINKTYPE_IEEE802_11_RADIOTAP"),
+            LinkType::ARCNETLINUX => Some("LINKTYPE_ARCNET_LINUX"),
+            LinkType::APPLEIPOVERIEEE1394 => Some("LINKTYPE_APPLE_IP_OVER_IEEE1394"),
+            LinkType::MTP2WITHPHDR => Some("LINKTYPE_MTP2_WITH_PHDR"),
+            LinkType::MTP2 => Some("LINKTYPE_MTP2"),
+            LinkType::MTP3 => Some("LINKTYPE_MTP3"),
+            LinkType::SCCP => Some("LINKTYPE_SCCP"),
+            LinkType::DOCSIS => Some("LINKTYPE_DOCSIS"),
+            LinkType::LINUXIRDA => Some("LINKTYPE_LINUX_IRDA"),
+            LinkType::USER0 => Some("LINKTYPE_USER0"),
+            LinkType::USER1 => Some("LINKTYPE_USER1"),
+            LinkType::USER2 => Some("LINKTYPE_USER2"),
+            LinkType::USER3 => Some("LINKTYPE_USER3"),
+            LinkType::USER4 => Some("LINKTYPE_USER4"),
+            LinkType::USER5 => Some("LINKTYPE_USER5"),
+            LinkType::USER6 => Some("LINKTYPE_USER6"),
+            LinkType::USER7 => Some("LINKTYPE_USER7"),
+            LinkType::USER8 => Some("LINKTYPE_USER8"),
+            LinkType::USER9 => Some("LINKTYPE_USER9"),
+            LinkType::USER10 => Some("LINKTYPE_USER10"),
+            LinkType::USER11 => Some("LINKTYPE_USER11"),
+            LinkType::USER12 => Some("LINKTYPE_USER12"),
+            LinkType::USER13 => Some("LINKTYPE_USER13"),
+            LinkType::USER14 => Some("LINKTYPE_USER14"),
+            LinkType::USER15 => Some("LINKTYPE_USER15"),
+            LinkType::IEEE80211AVS => Some("LINKTYPE_IEEE802_11_AVS"),
+            LinkType::BACNETMSTP => Some("LINKTYPE_BACNET_MS_TP"),
+            LinkType::PPPPPPD => Some("LINKTYPE_PPP_PPPD"),
+            LinkType::GPRSLLC => Some("LINKTYPE_GPRS_LLC"),
+            LinkType::GPFT => Some("LINKTYPE_GPF_T"),
+            LinkType::GPFF => Some("LINKTYPE_GPF_F"),
+            LinkType::LINUXLAPD => Some("LINKTYPE_LINUX_LAPD"),
+            LinkType::MFR => Some("LINKTYPE_MFR"),
+            LinkType::BLUETOOTHHCIH4 => Some("LINKTYPE_BLUETOOTH_HCI_H4"),
+            LinkType::USBLINUX => Some("LINKTYPE_USB_LINUX"),
+            LinkType::PPI => Some("LINKTYPE_PPI"),
+            LinkType::IEEE802154WITHFCS => Some("LINKTYPE_IEEE802_15_4_WITHFCS"),
+            LinkType::SITA => Some("LINKTYPE_SITA"),
+            LinkType::ERF => Some("LINKTYPE_ERF"),
+            LinkType::BLUETOOTHHCIH4WITHPHDR => Some("LINKTYPE_BLUETOOTH_HCI_H4_WITH_PHDR"),
+            LinkType::AX25KISS => Some("LINKTYPE_AX25_KISS"),
+            LinkType::LAPD => Some("LINKTYPE_LAPD"),
+            LinkType::PPPWITHDIR => Some("LINKTYPE_PPP_WITH_DIR"),
+            LinkType::CHDLCWITHDIR => Some("LINKTYPE_C_HDLC_WITH_DIR"),
+            LinkType::FRELAYWITHDIR => Some("LINKTYPE_FRELAY_WITH_DIR"),
+            LinkType::LAPBWITHDIR => Some("LINKTYPE_LAPB_WITH_DIR"),
+            LinkType::IPMBLINUX => Some("LINKTYPE_IPMB_LINUX"),
+            LinkType::FLEXRAY => Some("LINKTYPE_FLEXRAY"),
+            LinkType::LIN => Some("LINKTYPE_LIN"),
+            LinkType::IEEE802154NONASKPHY => Some("LINKTYPE_IEEE802_15_4_NONASK_PHY"),
+            LinkType::USBLINUXMMAPPED => Some("LINKTYPE_USB_LINUX_MMAPPED"),
+            LinkType::FC2 => Some("LINKTYPE_FC_2"),
+            LinkType::FC2WITHFRAMEDELIMS => Some("LINKTYPE_FC_2_WITH_FRAME_DELIMS"),
+            LinkType::IPNET => Some("LINKTYPE_IPNET"),
+            LinkType::CANSOCKETCAN => Some("LINKTYPE_CAN_SOCKETCAN"),
+            LinkType::IPV4 => Some("LINKTYPE_IPV4"),
+            LinkType::IPV6 => Some("LINKTYPE_IPV6"),
+            LinkType::IEEE802154NOFCS => Some("LINKTYPE_IEEE802_15_4_NOFCS"),
+            LinkType::DBUS => Some("LINKTYPE_DBUS"),
+            LinkType::DVBCI => Some("LINKTYPE_DVB_CI"),
+            LinkType::MUX27010 => Some("LINKTYPE_MUX27010"),
+            LinkType::STANAG5066DPDU => Some("LINKTYPE_STANAG_5066_D_PDU"),
+            LinkType::NFLOG => Some("LINKTYPE_NFLOG"),
+            LinkType::NETANALYZER => Some("LINKTYPE_NETANALYZER"),
+            LinkType::NETANALYZERTRANSPARENT => Some("LINKTYPE_NETANALYZER_TRANSPARENT"),
+            LinkType::IPOIB => Some("LINKTYPE_IPOIB"),
+            LinkType::MPEG2TS => Some("LINKTYPE_MPEG_2_TS"),
+            LinkType::NG40 => Some("LINKTYPE_NG40"),
+            LinkType::NFCLLCP => Some("LINKTYPE_NFC_LLCP"),
+            LinkType::INFINIBAND => Some("LINKTYPE_INFINIBAND"),
+            LinkType::SCTP => Some("LINKTYPE_SCTP"),
+            LinkType::USBPCAP => Some("LINKTYPE_USBPCAP"),
+            LinkType::RTACSERIAL => Some("LINKTYPE_RTAC_SERIAL"),
+            LinkType::BLUETOOTHLELL => Some("LINKTYPE_BLUETOOTH_LE_LL"),
+            LinkType::NETLINK => Some("LINKTYPE_NETLINK"),
+            LinkType::BLUETOOTHLINUXMONITOR => Some("LINKTYPE_BLUETOOTH_LINUX_MONITOR"),
+            LinkType::BLUETOOTHBREDRBB => Some("LINKTYPE_BLUETOOTH_BREDR_BB"),
+            LinkType::BLUETOOTHLELLWITHPHDR => Some("LINKTYPE_BLUETOOTH_LE_LL_WITH_PHDR"),
+            LinkType::PROFIBUSDL => Some("LINKTYPE_PROFIBUS_DL"),
+            LinkType::PKTAP => Some("LINKTYPE_PKTAP"),
+            LinkType::EPON => Some("LINKTYPE_EPON"),
+            LinkType::IPMIHPM2 => Some("LINKTYPE_IPMI_HPM_2"),
+            LinkType::ZWAVER1R2 => Some("LINKTYPE_ZWAVE_R1_R2"),
+            LinkType::ZWAVER3 => Some("LINKTYPE_ZWAVE_R3"),
+            LinkType::WATTSTOPPERDLM => Some("LINKTYPE_WATTSTOPPER_DLM"),
+            LinkType::ISO14443 => Some("LINKTYPE_ISO_14443"),
+            LinkType::RDS => Some("LINKTYPE_RDS"),
+            LinkType::USBDARWIN => Some("LINKTYPE_USB_DARWIN"),
+            LinkType::SDLC => Some("LINKTYPE_SDLC"),
+            LinkType::LORATAP => Some("LINKTYPE_LORATAP"),
+            LinkType::VSOCK => Some("LINKTYPE_VSOCK"),
+            LinkType::NORDICBLE => Some("LINKTYPE_NORDIC_BLE"),
+            LinkType::DOCSIS31XRA31 => Some("LINKTYPE_DOCSIS31_XRA31"),
+            LinkType::ETHERNETMPACKET => Some("LINKTYPE_ETHERNET_MPACKET"),
+            LinkType::DISPLAYPORTAUX => Some("LINKTYPE_DISPLAYPORT_AUX"),
+            LinkType::LINUXSLL2 => Some("LINKTYPE_LINUX_SLL2"),
+            LinkType::OPENVIZSLA => Some("LINKTYPE_OPENVIZSLA"),
+            LinkType::EBHSCR => Some("LINKTYPE_EBHSCR"),
+            LinkType::VPPDISPATCH => Some("LINKTYPE_VPP_DISPATCH"),
+            LinkType::DSATAGBRCM => Some("LINKTYPE_DSA_TAG_BRCM"),
+            LinkType::DSATAGBRCMPREPEND => Some("LINKTYPE_DSA_TAG_BRCM_PREPEND"),
+            LinkType::IEEE802154TAP => Some("LINKTYPE_IEEE802_15_4_TAP"),
+            LinkType::DSATAGDSA => Some("LINKTYPE_DSA_TAG_DSA"),
+            LinkType::DSATAGEDSA => Some("LINKTYPE_DSA_TAG_EDSA"),
+            LinkType::ELEE => Some("LINKTYPE_ELEE"),
+            LinkType::ZWAVESERIAL => Some("LINKTYPE_Z_WAVE_SERIAL"),
+            LinkType::USB20 => Some("LINKTYPE_USB_2_0"),
+            LinkType::ATSCALP => Some("LINKTYPE_ATSC_ALP"),
+            LinkType::ETW => Some("LINKTYPE_ETW"),
+            LinkType::ZBOSSNCP => Some("LINKTYPE_ZBOSS_NCP"),
+            LinkType::USB20LOWSPEED => Some("LINKTYPE_USB_2_0_LOW_SPEED"),
+            LinkType::USB20FULLSPEED => Some("LINKTYPE_USB_2_0_FULL_SPEED"),
+            LinkType::USB20HIGHSPEED => Some("LINKTYPE_USB_2_0_HIGH_SPEED"),
+            LinkType::AUERSWALDLOG => Some("LINKTYPE_AUERSWALD_LOG"),
+            LinkType::ZWAVETAP => Some("LINKTYPE_ZWAVE_TAP"),
+            LinkType::SILABSDEBUGCHANNEL => Some("LINKTYPE_SILABS_DEBUG_CHANNEL"),
+            LinkType::FIRAUCI => Some("LINKTYPE_FIRA_UCI"),
+            LinkType::MDB => Some("LINKTYPE_MDB"),
+            LinkType::DECTNR => Some("LINKTYPE_DECT_NR"),
+            LinkType::UNKNOWN(_) => None,
         }
     }
 
@@ -75,21 +700,170 @@ impl LinkType {
             LinkType::AX25 => "AX 25",
             LinkType::PRONET => "ProNET TokenRing",
             LinkType::CHAOS => "Chaos",
-            LinkType::UNKNOWN => "Unknown",
+            LinkType::IEEE8025 => "IEEE 802.5 Token Ring",
+            LinkType::ARCNETBSD => "ARCNET",
+            LinkType::SLIP => "SLIP",
+            LinkType::PPP => "PPP",
+            LinkType::FDDI => "FDDI",
+            LinkType::PPPHDLC => "PPP in HDLC-like framing",
+            LinkType::PPPETHER => "PPPoE",
+            LinkType::ATMRFC1483 => "RFC 1483 LLC/SNAP-encapsulated ATM",
+            LinkType::RAW => "Raw IP",
+            LinkType::CHDLC => "Cisco PPP with HDLC framing",
+            LinkType::IEEE80211 => "IEEE 802.11 wireless LAN",
+            LinkType::FRELAY => "Frame Relay LAPF",
+            LinkType::LOOP => "OpenBSD loopback",
+            LinkType::LINUXSLL => "Linux cooked capture v1",
+            LinkType::LTALK => "Apple LocalTalk",
+            LinkType::PFLOG => "OpenBSD pflog",
+            LinkType::IEEE80211PRISM => "Prism monitor mode",
+            LinkType::IPOVERFC => "RFC 2625 IP-over-Fibre Channel",
+            LinkType::SUNATM => "ATM with SunATM header",
+            LinkType::IEEE80211RADIOTAP => "Radiotap with IEEE 802.11",
+            LinkType::ARCNETLINUX => "ARCNET with Linux header",
+            LinkType::APPLEIPOVERIEEE1394 => "Apple IP-over-IEEE 1394",
+            LinkType::MTP2WITHPHDR => "SS7 MTP2 with pseudo-header",
+            LinkType::MTP2 => "SS7 MTP2",
+            LinkType::MTP3 => "SS7 MTP3",
+            LinkType::SCCP => "SS7 SCCP",
+            LinkType::DOCSIS => "DOCSIS MAC frames",
+            LinkType::LINUXIRDA => "Linux-IrDA",
+            LinkType::USER0 => "User 0",
+            LinkType::USER1 => "User 1",
+            LinkType::USER2 => "User 2",
+            LinkType::USER3 => "User 3",
+            LinkType::USER4 => "User 4",
+            LinkType::USER5 => "User 5",
+            LinkType::USER6 => "User 6",
+            LinkType::USER7 => "User 7",
+            LinkType::USER8 => "User 8",
+            LinkType::USER9 => "User 9",
+            LinkType::USER10 => "User 10",
+            LinkType::USER11 => "User 11",
+            LinkType::USER12 => "User 12",
+            LinkType::USER13 => "User 13",
+            LinkType::USER14 => "User 14",
+            LinkType::USER15 => "User 15",
+            LinkType::IEEE80211AVS => "AVS monitor mode",
+            LinkType::BACNETMSTP => "BACnet MS/TP",
+            LinkType::PPPPPPD => "PPP in HDLC-like encapsulation with direction",
+            LinkType::GPRSLLC => "GPRS LLC",
+            LinkType::GPFT => "GPF-T (ITU-T G.7041/Y.1303)",
+            LinkType::GPFF => "GPF-F (ITU-T G.7041/Y.1303)",
+            LinkType::LINUXLAPD => "LAPD with Linux vISDN header",
+            LinkType::MFR => "FRF.16.1 Multi-Link Frame Relay",
+            LinkType::BLUETOOTHHCIH4 => "Bluetooth HCI UART H4",
+            LinkType::USBLINUX => "Linux USB",
+            LinkType::PPI => "Per-Packet Information",
+            LinkType::IEEE802154WITHFCS => "IEEE 802.15.4 with FCS",
+            LinkType::SITA => "SITA",
+            LinkType::ERF => "Endace ERF",
+            LinkType::BLUETOOTHHCIH4WITHPHDR => "Bluetooth HCI UART H4 with direction",
+            LinkType::AX25KISS => "AX.25 with KISS header",
+            LinkType::LAPD => "LAPD",
+            LinkType::PPPWITHDIR => "PPP with direction",
+            LinkType::CHDLCWITHDIR => "Cisco HDLC with direction",
+            LinkType::FRELAYWITHDIR => "Frame Relay with direction",
+            LinkType::LAPBWITHDIR => "LAPB with direction",
+            LinkType::IPMBLINUX => "IPMB with Linux header",
+            LinkType::FLEXRAY => "FlexRay",
+            LinkType::LIN => "LIN",
+            LinkType::IEEE802154NONASKPHY => "IEEE 802.15.4 with PHY header",
+            LinkType::USBLINUXMMAPPED => "Linux USB, memory-mapped",
+            LinkType::FC2 => "Fibre Channel FC-2",
+            LinkType::FC2WITHFRAMEDELIMS => "Fibre Channel FC-2 with frame delimiters",
+            LinkType::IPNET => "Solaris ipnet",
+            LinkType::CANSOCKETCAN => "SocketCAN",
+            LinkType::IPV4 => "Raw IPv4",
+            LinkType::IPV6 => "Raw IPv6",
+            LinkType::IEEE802154NOFCS => "IEEE 802.15.4 without FCS",
+            LinkType::DBUS => "D-Bus",
+            LinkType::DVBCI => "DVB-CI",
+            LinkType::MUX27010 => "3GPP TS 27.010 multiplexing",
+            LinkType::STANAG5066DPDU => "STANAG 5066 D_PDU",
+            LinkType::NFLOG => "Linux netlink NETLINK NFLOG",
+            LinkType::NETANALYZER => "Hilscher netANALYZER",
+            LinkType::NETANALYZERTRANSPARENT => "Hilscher netANALYZER transparent",
+            LinkType::IPOIB => "IP-over-InfiniBand",
+            LinkType::MPEG2TS => "MPEG-2 transport stream",
+            LinkType::NG40 => "ng4T ng40",
+            LinkType::NFCLLCP => "NFC LLCP",
+            LinkType::INFINIBAND => "InfiniBand",
+            LinkType::SCTP => "SCTP",
+            LinkType::USBPCAP => "USBPcap",
+            LinkType::RTACSERIAL => "SEL RTAC serial",
+            LinkType::BLUETOOTHLELL => "Bluetooth Low Energy link layer",
+            LinkType::NETLINK => "Linux netlink",
+            LinkType::BLUETOOTHLINUXMONITOR => "Bluetooth Linux monitor",
+            LinkType::BLUETOOTHBREDRBB => "Bluetooth BR/EDR baseband",
+            LinkType::BLUETOOTHLELLWITHPHDR => "Bluetooth Low Energy link layer with pseudo-header",
+            LinkType::PROFIBUSDL => "PROFIBUS data link",
+            LinkType::PKTAP => "Apple PKTAP",
+            LinkType::EPON => "Ethernet passive optical network",
+            LinkType::IPMIHPM2 => "IPMI HPM.2 trace",
+            LinkType::ZWAVER1R2 => "Z-Wave R1/R2",
+            LinkType::ZWAVER3 => "Z-Wave R3",
+            LinkType::WATTSTOPPERDLM => "WattStopper DLM",
+            LinkType::ISO14443 => "ISO 14443 contactless smartcard",
+            LinkType::RDS => "IEC 62106 Radio Data System",
+            LinkType::USBDARWIN => "Darwin USB",
+            LinkType::SDLC => "SDLC",
+            LinkType::LORATAP => "LoRaTap",
+            LinkType::VSOCK => "Linux vsock",
+            LinkType::NORDICBLE => "nRF Sniffer for Bluetooth LE",
+            LinkType::DOCSIS31XRA31 => "DOCSIS 3.1 XRA31",
+            LinkType::ETHERNETMPACKET => "IEEE 802.3br mPackets",
+            LinkType::DISPLAYPORTAUX => "DisplayPort AUX channel",
+            LinkType::LINUXSLL2 => "Linux cooked capture v2",
+            LinkType::OPENVIZSLA => "OpenVizsla USB",
+            LinkType::EBHSCR => "Elektrobit high speed capture and replay",
+            LinkType::VPPDISPATCH => "fd.io VPP graph dispatch trace",
+            LinkType::DSATAGBRCM => "Broadcom DSA tag",
+            LinkType::DSATAGBRCMPREPEND => "Broadcom DSA tag before Ethernet header",
+            LinkType::IEEE802154TAP => "IEEE 802.15.4 with TAP header",
+            LinkType::DSATAGDSA => "Marvell DSA tag",
+            LinkType::DSATAGEDSA => "Marvell EDSA tag",
+            LinkType::ELEE => "ELEE lawful intercept",
+            LinkType::ZWAVESERIAL => "Z-Wave serial API",
+            LinkType::USB20 => "USB 2.0/1.1/1.0",
+            LinkType::ATSCALP => "ATSC link-layer protocol",
+            LinkType::ETW => "Event Tracing for Windows",
+            LinkType::ZBOSSNCP => "ZBOSS NCP",
+            LinkType::USB20LOWSPEED => "Low-speed USB 2.0/1.1/1.0",
+            LinkType::USB20FULLSPEED => "Full-speed USB 2.0/1.1/1.0",
+            LinkType::USB20HIGHSPEED => "High-speed USB 2.0",
+            LinkType::AUERSWALDLOG => "Auerswald log",
+            LinkType::ZWAVETAP => "Z-Wave with TAP header",
+            LinkType::SILABSDEBUGCHANNEL => "Silicon Labs debug channel",
+            LinkType::FIRAUCI => "FiRa UWB controller interface",
+            LinkType::MDB => "MDB/ICP",
+            LinkType::DECTNR => "DECT-2020 New Radio",
+            LinkType::UNKNOWN(_) => "Unknown",
+        }
+    }
+
+    /// Largest capture length libpcap accepts for this link type.
+    pub fn max_snap_len(&self) -> u32 {
+        match self {
+            LinkType::DBUS => 128 * 1024 * 1024,
+            LinkType::EBHSCR => 8 * 1024 * 1024,
+            LinkType::USBPCAP => 1024 * 1024,
+            _ => 256 * 1024,
         }
     }
     
 }
 
+#[derive(Clone)]
 pub struct FileHeader {
     bytes: [u8;24],
 }
 
 impl FileHeader {
     
-    pub fn new(bytes: Vec<u8>) -> Result<Self, &'static str> {
+    pub fn new(bytes: Vec<u8>) -> Result<Self, Error> {
         if bytes.len() < 24 {
-            return Err("Insufficent data length to parse header.");
+            return Err(Error::InsufficientData(24, bytes.len()));
         }
         let mut data: [u8;24] = [0;24];
         let mut c: usize = 0;
@@ -102,19 +876,52 @@ impl FileHeader {
         Ok(Self { bytes: data })
     }
 
+    pub fn from_parts(
+        swapped: bool,
+        resolution: TsResolution,
+        snap_len: u32,
+        link_type: u16,
+        fcs: u8,
+    ) -> Self {
+        let magic: u32 = match resolution {
+            TsResolution::MICRO => 0xa1b2c3d4,
+            TsResolution::NANO => 0xa1b23c4d,
+        };
+        let link = ((fcs.r_nibble() as u32) << 28) | link_type as u32;
+        let mut bytes = [0u8;24];
+        bytes[0..4].copy_from_slice(&u32_to_bytes(magic, swapped));
+        bytes[4..6].copy_from_slice(&u16_to_bytes(2, swapped));
+        bytes[6..8].copy_from_slice(&u16_to_bytes(4, swapped));
+        bytes[16..20].copy_from_slice(&u32_to_bytes(snap_len, swapped));
+        bytes[20..24].copy_from_slice(&u32_to_bytes(link, swapped));
+        Self { bytes }
+    }
+
+    pub fn to_bytes(&self) -> [u8;24] {
+        self.bytes
+    }
+
     pub fn is_swapped(&self) -> bool {
-        self.magic_number() == 0xd4c3b2a1
+        MagicNumber::from_row(self.magic_number()).is_swapped()
+    }
+
+    pub fn ts_resolution(&self) -> TsResolution {
+        MagicNumber::from_row(self.magic_number()).ts_resolution()
     }
 
-    fn magic_number(&self) -> u32 {
+    pub fn magic(&self) -> MagicNumber {
+        MagicNumber::from_row(self.magic_number())
+    }
+
+    pub fn magic_number(&self) -> u32 {
         bytes_to_u32(self.bytes[0], self.bytes[1], self.bytes[2], self.bytes[3], false)
     }
 
-    fn major_version(&self) -> u16 {
+    pub fn major_version(&self) -> u16 {
         bytes_to_u16(self.bytes[4], self.bytes[5], self.is_swapped())
     }
 
-    fn minor_version(&self) -> u16 {
+    pub fn minor_version(&self) -> u16 {
         bytes_to_u16(self.bytes[6], self.bytes[7], self.is_swapped())
     }
 
@@ -122,11 +929,19 @@ impl FileHeader {
        format!("{}.{}", self.major_version(), self.minor_version())
     }
 
-    fn snap_len(&self) -> u32 {
+    pub fn this_zone(&self) -> i32 {
+        bytes_to_u32(self.bytes[8], self.bytes[9], self.bytes[10], self.bytes[11], self.is_swapped()) as i32
+    }
+
+    pub fn sig_figs(&self) -> u32 {
+        bytes_to_u32(self.bytes[12], self.bytes[13], self.bytes[14], self.bytes[15], self.is_swapped())
+    }
+
+    pub fn snap_len(&self) -> u32 {
         bytes_to_u32(self.bytes[16], self.bytes[17], self.bytes[18], self.bytes[19], self.is_swapped())
     }
 
-    fn link_type(&self) -> u16 {
+    pub fn link_type(&self) -> u16 {
         if self.is_swapped() {
             bytes_to_u16(self.bytes[20], self.bytes[21], self.is_swapped())
         } else {
@@ -134,7 +949,7 @@ impl FileHeader {
         }
     }
 
-    fn fcs(&self) -> u8 {
+    pub fn fcs(&self) -> u8 {
         if self.is_swapped() {
             self.bytes[23].l_nibble()
         } else {
@@ -172,8 +987,12 @@ mod tests {
 
         assert!(matches!(MagicNumber::from_row(0xa1b2c3d4), MagicNumber::PCAP));
         assert!(matches!(MagicNumber::from_row(0xd4c3b2a1), MagicNumber::SWAPPED));
+        assert!(matches!(MagicNumber::from_row(0xa1b23c4d), MagicNumber::NSPCAP));
+        assert!(matches!(MagicNumber::from_row(0x4d3cb2a1), MagicNumber::NSSWAPPED));
         assert!(matches!(MagicNumber::from_row(0x0a0d0d0a), MagicNumber::PCAPNG));
         assert!(matches!(MagicNumber::from_row(0x0AAAAAAA), MagicNumber::UNKNOWN));
+        assert!(matches!(MagicNumber::from_bytes(&[0x0A, 0x0D, 0x0D, 0x0A, 0x00]), MagicNumber::PCAPNG));
+        assert!(matches!(MagicNumber::from_bytes(&[0x0A, 0x0D]), MagicNumber::UNKNOWN));
 
     }
 
@@ -193,7 +1012,54 @@ mod tests {
         assert_eq!(fh.major_version(), 2);
         assert_eq!(fh.minor_version(), 4);
         assert_eq!(fh.snap_len(), 0xFFFFFFFF);
+        assert_eq!(fh.ts_resolution(), TsResolution::MICRO);
+        assert!(matches!(fh.magic(), MagicNumber::SWAPPED));
+        assert_eq!(fh.magic_number(), 0xD4C3B2A1);
+        assert_eq!(fh.this_zone(), 0);
+        assert_eq!(fh.sig_figs(), 0);
+
+    }
 
+    #[test]
+    fn test_file_header_from_parts() {
+        for swapped in [false, true] {
+            let fh = FileHeader::from_parts(swapped, TsResolution::NANO, 1500, 105, 0b1010);
+            assert_eq!(fh.is_swapped(), swapped);
+            assert_eq!(fh.ts_resolution(), TsResolution::NANO);
+            assert_eq!(fh.version(), "2.4".to_string());
+            assert_eq!(fh.snap_len(), 1500);
+            assert_eq!(fh.link_type(), 105);
+            assert_eq!(fh.fcs(), 0b1010);
+            assert_eq!(FileHeader::new(fh.to_bytes().to_vec()).unwrap().to_bytes(), fh.to_bytes());
+        }
+    }
+
+    #[test]
+    fn test_nanosecond_file_header() {
+        let mut b = vec![
+            0x4D, 0x3C, 0xB2, 0xA1,
+            0x02, 0x00, 0x04, 0x00,
+            0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00,
+            0xFF, 0xFF, 0x00, 0x00,
+            0x01, 0x00, 0x00, 0x00,
+        ];
+
+        let fh = FileHeader::new(b.clone()).unwrap();
+        assert!(fh.is_swapped());
+        assert_eq!(fh.ts_resolution(), TsResolution::NANO);
+        assert_eq!(fh.snap_len(), 0xFFFF);
+        assert_eq!(fh.link_type(), 1);
+
+        b[0..4].copy_from_slice(&[0xA1, 0xB2, 0x3C, 0x4D]);
+        let fh = FileHeader::new(b).unwrap();
+        assert!(!fh.is_swapped());
+        assert_eq!(fh.ts_resolution(), TsResolution::NANO);
+    }
+
+    #[test]
+    fn test_short_file_header() {
+        assert!(matches!(FileHeader::new(vec![0xD4, 0xC3]), Err(Error::InsufficientData(24, 2))));
     }
 
     #[test]
@@ -205,10 +1071,24 @@ mod tests {
         assert!(matches!(LinkType::new(3), LinkType::AX25));
         assert!(matches!(LinkType::new(4), LinkType::PRONET));
         assert!(matches!(LinkType::new(5), LinkType::CHAOS));
-        assert!(matches!(LinkType::new(6), LinkType::UNKNOWN));
+        assert!(matches!(LinkType::new(6), LinkType::IEEE8025));
+        assert!(matches!(LinkType::new(11), LinkType::UNKNOWN(11)));
+        assert!(matches!(LinkType::new(127), LinkType::IEEE80211RADIOTAP));
+        assert!(matches!(LinkType::new(276), LinkType::LINUXSLL2));
 
     }
 
+    #[test]
+    fn test_link_type_registry() {
+        for value in 0..=u16::MAX {
+            let link = LinkType::new(value);
+            assert_eq!(link.value(), value);
+            assert_eq!(link.name().is_some(), !matches!(link, LinkType::UNKNOWN(_)));
+        }
+        assert_eq!(LinkType::USER15.value(), 162);
+        assert_eq!(LinkType::CANSOCKETCAN.name(), Some("LINKTYPE_CAN_SOCKETCAN"));
+    }
+
     #[test]
     fn test_link_type_to_str() {
 
@@ -218,7 +1098,8 @@ mod tests {
             assert_eq!(LinkType::AX25.to_string(), "AX 25".to_string());
             assert_eq!(LinkType::PRONET.to_string(), "ProNET TokenRing".to_string());
             assert_eq!(LinkType::CHAOS.to_string(), "Chaos".to_string());
-            assert_eq!(LinkType::UNKNOWN.to_string(), "Unknown".to_string());
+            assert_eq!(LinkType::UNKNOWN(11).to_string(), "Unknown".to_string());
+            assert_eq!(LinkType::LINUXSLL.to_string(), "Linux cooked capture v1".to_string());
 
     }
 
@@ -1,5 +1,7 @@
 use byte::Byte;
 
+use crate::error::Error;
+
 pub trait Row {
     fn as_u32(&self) -> u32;
     fn l_half_u16(&self) -> u16;
@@ -37,25 +39,21 @@ impl RowParser {
         Self{stream, loaded_row: [0;4]}
     }
 
-    pub fn range_to_bytestream(&self, start: usize, end: usize) -> Result<Vec<u8>, &'static str> {
-        if start > end || end > self.len_rows() {
-            return Err("Invalid range.")
+    pub fn range_to_bytestream(&self, start: usize, end: usize) -> Result<Vec<u8>, Error> {
+        if start > end {
+            return Err(Error::OutOfBounds(start))
         }
         let mut result: Vec<u8> = vec![];
-        let mut tmp_row: [u8;4];
         for i in start..end + 1 {
-            tmp_row = self.get_nth_row(i).unwrap();
-            for j in 0..4 {
-                result.push(tmp_row[j]);
-            }
+            result.extend_from_slice(&self.get_nth_row(i)?);
         }
         Ok(result)
     }
 
     #[allow(clippy::explicit_counter_loop)]
-    pub fn get_nth_row(&self, n: usize) -> Result<[u8;4], &'static str> {
-        if n > self.len_rows() {
-            Err("Row out of bound.")
+    pub fn get_nth_row(&self, n: usize) -> Result<[u8;4], Error> {
+        if n >= self.len_rows() {
+            Err(Error::OutOfBounds(n))
         } else {
             let start = n * 4;
             let end = start + 4;
@@ -70,11 +68,12 @@ impl RowParser {
         }
     }
 
-    pub fn load_row(&mut self, n: usize, swapped: bool) { 
-        self.loaded_row = self.get_nth_row(n).unwrap();
+    pub fn load_row(&mut self, n: usize, swapped: bool) -> Result<(), Error> {
+        self.loaded_row = self.get_nth_row(n)?;
         if swapped {
             self.loaded_row = self.loaded_row.swapped_copy()
         }
+        Ok(())
     }
 
     pub fn len_rows(&self) -> usize {
@@ -146,19 +145,23 @@ mod tests {
             0xD1, 0xD1, 0xD1, 0xD1,
         ];
         let mut parser = RowParser::new(stream);
-        parser.load_row(0, false);
+        parser.load_row(0, false).unwrap();
         assert_eq!(parser.len_rows(), 4);
         assert_eq!(
             parser.loaded_as_u32(), 0xA1A1A1A1
         );
-        parser.load_row(0, true);
+        parser.load_row(0, true).unwrap();
         assert_eq!(parser.loaded_as_u32(), 0xA1A1A1A1);
         assert_eq!(parser.r_nib_loaded_nth(0), 1);
         assert_eq!(parser.l_nib_loaded_nth(0), 10);
 
-        parser.load_row(1, false);
+        parser.load_row(1, false).unwrap();
         assert_eq!(parser.loaded_l_half(), 0xB1B1);
         assert_eq!(parser.loaded_r_half(), 0xB1B1);
+
+        assert!(matches!(parser.load_row(4, false), Err(Error::OutOfBounds(4))));
+        assert_eq!(parser.range_to_bytestream(2, 3).unwrap().len(), 8);
+        assert!(parser.range_to_bytestream(3, 4).is_err());
     }
 
     #[test]
@@ -171,7 +174,7 @@ mod tests {
             0xD1, 0xD1, 0xD1, 0xD1,
         ];
         let mut parser = RowParser::new(stream);
-        parser.load_row(0, false);
+        parser.load_row(0, false).unwrap();
         parser.get_nth_loaded_byte(5);
     }
 }
@@ -1,4 +1,8 @@
+pub mod error;
 pub mod formatter;
 pub mod parser;
 pub mod file;
+pub mod pcapng;
+pub mod reader;
+pub mod writer;
 pub mod record;
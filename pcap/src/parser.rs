@@ -1,3 +1,4 @@
+use crate::file::TsResolution;
 use crate::record::{Record, RecordHeader};
 
 enum ParserState {
@@ -14,12 +15,13 @@ pub struct RecordParser {
     curr_body_len: usize,
     packets: Vec<Record>,
     is_swapped: bool,
+    resolution: TsResolution,
 }
 
 
 impl RecordParser {
 
-    pub fn new(swapped: bool) -> Self {
+    pub fn new(swapped: bool, resolution: TsResolution) -> Self {
         RecordParser{
             state: ParserState::Header,
             active_header: [0;16],
@@ -29,6 +31,7 @@ impl RecordParser {
             curr_body_len: 0,
             packets: vec![],
             is_swapped: swapped,
+            resolution,
         }
     }
 
@@ -36,7 +39,7 @@ impl RecordParser {
         if self.active_header.len() == 16 && self.curr_pos == 16 {
             self.state = ParserState::Body;
             self.curr_pos = 0;
-            let tmph = RecordHeader::new(self.active_header, self.is_swapped);
+            let tmph = RecordHeader::new(self.active_header, self.is_swapped, self.resolution);
             self.curr_body_len = tmph.cap_len() as usize;
         }
     }
@@ -45,7 +48,7 @@ impl RecordParser {
         if  self.curr_pos == self.curr_body_len {
             self.packets.push(
                 Record::new(RecordHeader::new(
-                        self.active_header, self.is_swapped),
+                        self.active_header, self.is_swapped, self.resolution),
                         self.active_data.to_vec()
                 )
             );
@@ -0,0 +1,583 @@
+use std::fmt;
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use byte::{bytes_to_u16, bytes_to_u32, qwords_to_u64};
+
+use crate::error::Error;
+use crate::file::LinkType;
+
+pub const SECTION_HEADER: u32 = 0x0A0D0D0A;
+pub const INTERFACE_DESCRIPTION: u32 = 0x00000001;
+pub const SIMPLE_PACKET: u32 = 0x00000003;
+pub const NAME_RESOLUTION: u32 = 0x00000004;
+pub const INTERFACE_STATISTICS: u32 = 0x00000005;
+pub const ENHANCED_PACKET: u32 = 0x00000006;
+
+pub const BYTE_ORDER_MAGIC: u32 = 0x1A2B3C4D;
+
+pub const OPT_ENDOFOPT: u16 = 0;
+pub const OPT_COMMENT: u16 = 1;
+
+pub const IF_NAME: u16 = 2;
+pub const IF_DESCRIPTION: u16 = 3;
+pub const IF_TSRESOL: u16 = 9;
+pub const IF_FCSLEN: u16 = 13;
+pub const IF_TSOFFSET: u16 = 14;
+
+pub const ISB_IFRECV: u16 = 4;
+pub const ISB_IFDROP: u16 = 5;
+
+const NRB_RECORD_END: u16 = 0;
+const NRB_RECORD_IPV4: u16 = 1;
+const NRB_RECORD_IPV6: u16 = 2;
+
+struct BodyReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+    swapped: bool,
+    offset: u64,
+}
+
+impl<'a> BodyReader<'a> {
+
+    fn new(data: &'a [u8], swapped: bool, offset: u64) -> Self {
+        Self { data, pos: 0, swapped, offset }
+    }
+
+    fn remaining(&self) -> usize {
+        self.data.len() - self.pos
+    }
+
+    fn bytes(&mut self, n: usize) -> Result<&'a [u8], Error> {
+        if n > self.remaining() {
+            return Err(Error::MalformedBlock(self.offset, "Block body too short."));
+        }
+        let b = &self.data[self.pos..self.pos + n];
+        self.pos += n;
+        Ok(b)
+    }
+
+    fn padded_bytes(&mut self, n: usize) -> Result<&'a [u8], Error> {
+        let b = self.bytes(n)?;
+        let pad = (4 - n % 4) % 4;
+        self.pos += pad.min(self.remaining());
+        Ok(b)
+    }
+
+    fn u16(&mut self) -> Result<u16, Error> {
+        let b = self.bytes(2)?;
+        Ok(bytes_to_u16(b[0], b[1], self.swapped))
+    }
+
+    fn u32(&mut self) -> Result<u32, Error> {
+        let b = self.bytes(4)?;
+        Ok(bytes_to_u32(b[0], b[1], b[2], b[3], self.swapped))
+    }
+
+    fn u64(&mut self) -> Result<u64, Error> {
+        let first = self.u32()?;
+        let second = self.u32()?;
+        if self.swapped {
+            Ok(qwords_to_u64(second, first))
+        } else {
+            Ok(qwords_to_u64(first, second))
+        }
+    }
+
+    fn options(&mut self) -> Result<Vec<BlockOption>, Error> {
+        let mut options = vec![];
+        while self.remaining() >= 4 {
+            let code = self.u16()?;
+            let len = self.u16()? as usize;
+            if code == OPT_ENDOFOPT {
+                break;
+            }
+            let value = self.padded_bytes(len)?;
+            options.push(BlockOption::new(code, value.to_vec(), self.swapped));
+        }
+        Ok(options)
+    }
+}
+
+#[derive(Clone)]
+pub struct BlockOption {
+    code: u16,
+    value: Vec<u8>,
+    is_swapped: bool,
+}
+
+impl BlockOption {
+
+    pub fn new(code: u16, value: Vec<u8>, is_swapped: bool) -> Self {
+        Self { code, value, is_swapped }
+    }
+
+    pub fn code(&self) -> u16 {
+        self.code
+    }
+
+    pub fn value(&self) -> &[u8] {
+        &self.value
+    }
+
+    pub fn as_string(&self) -> String {
+        String::from_utf8_lossy(&self.value).trim_end_matches('\0').to_string()
+    }
+
+    pub fn as_u64(&self) -> Option<u64> {
+        if self.value.len() != 8 {
+            return None;
+        }
+        let mut r = BodyReader::new(&self.value, self.is_swapped, 0);
+        r.u64().ok()
+    }
+}
+
+fn find_option(options: &[BlockOption], code: u16) -> Option<&BlockOption> {
+    options.iter().find(|o| o.code() == code)
+}
+
+fn comments(options: &[BlockOption]) -> Vec<String> {
+    options.iter()
+        .filter(|o| o.code() == OPT_COMMENT)
+        .map(|o| o.as_string())
+        .collect()
+}
+
+#[derive(Clone)]
+pub struct SectionHeader {
+    major_version: u16,
+    minor_version: u16,
+    section_length: i64,
+    is_swapped: bool,
+    options: Vec<BlockOption>,
+}
+
+impl SectionHeader {
+
+    fn parse(r: &mut BodyReader) -> Result<Self, Error> {
+        let _bom = r.u32()?;
+        let major_version = r.u16()?;
+        let minor_version = r.u16()?;
+        let section_length = r.u64()? as i64;
+        let options = r.options()?;
+        Ok(Self { major_version, minor_version, section_length, is_swapped: r.swapped, options })
+    }
+
+    pub fn version(&self) -> String {
+        format!("{}.{}", self.major_version, self.minor_version)
+    }
+
+    pub fn section_length(&self) -> i64 {
+        self.section_length
+    }
+
+    pub fn is_swapped(&self) -> bool {
+        self.is_swapped
+    }
+
+    pub fn options(&self) -> &[BlockOption] {
+        &self.options
+    }
+
+    pub fn comments(&self) -> Vec<String> {
+        comments(&self.options)
+    }
+}
+
+impl fmt::Display for SectionHeader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Magic: PCAPNG{}\nVersion: {}\n",
+            if self.is_swapped { " (swapped)" } else { "" },
+            self.version(),
+        )?;
+        for c in self.comments() {
+            writeln!(f, "Comment: {}", c)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone)]
+pub struct InterfaceDescription {
+    link_type: u16,
+    snap_len: u32,
+    options: Vec<BlockOption>,
+}
+
+impl InterfaceDescription {
+
+    fn parse(r: &mut BodyReader) -> Result<Self, Error> {
+        let link_type = r.u16()?;
+        let _reserved = r.u16()?;
+        let snap_len = r.u32()?;
+        let options = r.options()?;
+        Ok(Self { link_type, snap_len, options })
+    }
+
+    pub fn link_type(&self) -> u16 {
+        self.link_type
+    }
+
+    pub fn snap_len(&self) -> u32 {
+        self.snap_len
+    }
+
+    pub fn name(&self) -> Option<String> {
+        find_option(&self.options, IF_NAME).map(|o| o.as_string())
+    }
+
+    pub fn description(&self) -> Option<String> {
+        find_option(&self.options, IF_DESCRIPTION).map(|o| o.as_string())
+    }
+
+    pub fn ts_resolution(&self) -> u8 {
+        match find_option(&self.options, IF_TSRESOL) {
+            Some(o) if !o.value().is_empty() => o.value()[0],
+            _ => 6,
+        }
+    }
+
+    pub fn ts_units_per_sec(&self) -> u64 {
+        let resol = self.ts_resolution();
+        let exp = (resol & 0x7F) as u32;
+        if resol & 0x80 == 0 {
+            10u64.checked_pow(exp).unwrap_or(u64::MAX)
+        } else {
+            2u64.checked_pow(exp).unwrap_or(u64::MAX)
+        }
+    }
+
+    /// Length of the FCS trailing each frame, in bits.
+    pub fn fcs_len(&self) -> Option<u8> {
+        find_option(&self.options, IF_FCSLEN).and_then(|o| o.value().first().copied())
+    }
+
+    pub fn ts_offset(&self) -> i64 {
+        find_option(&self.options, IF_TSOFFSET)
+            .and_then(|o| o.as_u64())
+            .map(|v| v as i64)
+            .unwrap_or(0)
+    }
+
+    pub fn options(&self) -> &[BlockOption] {
+        &self.options
+    }
+}
+
+impl fmt::Display for InterfaceDescription {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Interface: {}\nSnap Length: {}\nLink: {}\n",
+            self.name().unwrap_or_else(|| "unnamed".to_string()),
+            self.snap_len,
+            LinkType::new(self.link_type).to_string(),
+        )
+    }
+}
+
+#[derive(Clone)]
+pub struct EnhancedPacket {
+    interface_id: u32,
+    timestamp: u64,
+    cap_len: u32,
+    orig_len: u32,
+    data: Vec<u8>,
+    options: Vec<BlockOption>,
+}
+
+impl EnhancedPacket {
+
+    fn parse(r: &mut BodyReader) -> Result<Self, Error> {
+        let interface_id = r.u32()?;
+        let ts_high = r.u32()?;
+        let ts_low = r.u32()?;
+        let cap_len = r.u32()?;
+        let orig_len = r.u32()?;
+        let data = r.padded_bytes(cap_len as usize)?.to_vec();
+        let options = r.options()?;
+        Ok(Self {
+            interface_id,
+            timestamp: qwords_to_u64(ts_high, ts_low),
+            cap_len,
+            orig_len,
+            data,
+            options,
+        })
+    }
+
+    pub fn interface_id(&self) -> u32 {
+        self.interface_id
+    }
+
+    pub fn timestamp(&self) -> u64 {
+        self.timestamp
+    }
+
+    pub fn cap_len(&self) -> u32 {
+        self.cap_len
+    }
+
+    pub fn orig_len(&self) -> u32 {
+        self.orig_len
+    }
+
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    pub fn comments(&self) -> Vec<String> {
+        comments(&self.options)
+    }
+
+    pub fn options(&self) -> &[BlockOption] {
+        &self.options
+    }
+}
+
+#[derive(Clone)]
+pub struct SimplePacket {
+    orig_len: u32,
+    data: Vec<u8>,
+}
+
+impl SimplePacket {
+
+    fn parse(r: &mut BodyReader) -> Result<Self, Error> {
+        let orig_len = r.u32()?;
+        let available = r.remaining();
+        let data = r.bytes(available.min(orig_len as usize))?.to_vec();
+        Ok(Self { orig_len, data })
+    }
+
+    pub fn orig_len(&self) -> u32 {
+        self.orig_len
+    }
+
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+}
+
+#[derive(Clone)]
+pub enum NameRecord {
+    IPv4(Ipv4Addr, Vec<String>),
+    IPv6(Ipv6Addr, Vec<String>),
+    UNKNOWN(u16, Vec<u8>),
+}
+
+impl NameRecord {
+
+    fn names(b: &[u8]) -> Vec<String> {
+        b.split(|c| *c == 0)
+            .filter(|n| !n.is_empty())
+            .map(|n| String::from_utf8_lossy(n).to_string())
+            .collect()
+    }
+
+    fn new(record_type: u16, value: &[u8]) -> Self {
+        match record_type {
+            NRB_RECORD_IPV4 if value.len() >= 4 => Self::IPv4(
+                Ipv4Addr::new(value[0], value[1], value[2], value[3]),
+                Self::names(&value[4..]),
+            ),
+            NRB_RECORD_IPV6 if value.len() >= 16 => {
+                let mut addr = [0u8;16];
+                addr.copy_from_slice(&value[..16]);
+                Self::IPv6(Ipv6Addr::from(addr), Self::names(&value[16..]))
+            },
+            _ => Self::UNKNOWN(record_type, value.to_vec()),
+        }
+    }
+}
+
+impl fmt::Display for NameRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::IPv4(a, n) => write!(f, "{} {}", a, n.join(" ")),
+            Self::IPv6(a, n) => write!(f, "{} {}", a, n.join(" ")),
+            Self::UNKNOWN(t, v) => write!(f, "Unknown record type {} ({} bytes)", t, v.len()),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct NameResolution {
+    records: Vec<NameRecord>,
+    options: Vec<BlockOption>,
+}
+
+impl NameResolution {
+
+    fn parse(r: &mut BodyReader) -> Result<Self, Error> {
+        let mut records = vec![];
+        while r.remaining() >= 4 {
+            let record_type = r.u16()?;
+            let len = r.u16()? as usize;
+            if record_type == NRB_RECORD_END {
+                break;
+            }
+            let value = r.padded_bytes(len)?;
+            records.push(NameRecord::new(record_type, value));
+        }
+        let options = r.options()?;
+        Ok(Self { records, options })
+    }
+
+    pub fn records(&self) -> &[NameRecord] {
+        &self.records
+    }
+
+    pub fn options(&self) -> &[BlockOption] {
+        &self.options
+    }
+}
+
+#[derive(Clone)]
+pub struct InterfaceStatistics {
+    interface_id: u32,
+    timestamp: u64,
+    options: Vec<BlockOption>,
+}
+
+impl InterfaceStatistics {
+
+    fn parse(r: &mut BodyReader) -> Result<Self, Error> {
+        let interface_id = r.u32()?;
+        let ts_high = r.u32()?;
+        let ts_low = r.u32()?;
+        let options = r.options()?;
+        Ok(Self { interface_id, timestamp: qwords_to_u64(ts_high, ts_low), options })
+    }
+
+    pub fn interface_id(&self) -> u32 {
+        self.interface_id
+    }
+
+    pub fn timestamp(&self) -> u64 {
+        self.timestamp
+    }
+
+    pub fn received(&self) -> Option<u64> {
+        find_option(&self.options, ISB_IFRECV).and_then(|o| o.as_u64())
+    }
+
+    pub fn dropped(&self) -> Option<u64> {
+        find_option(&self.options, ISB_IFDROP).and_then(|o| o.as_u64())
+    }
+
+    pub fn options(&self) -> &[BlockOption] {
+        &self.options
+    }
+}
+
+#[derive(Clone)]
+pub enum Block {
+    SectionHeader(SectionHeader),
+    InterfaceDescription(InterfaceDescription),
+    EnhancedPacket(EnhancedPacket),
+    SimplePacket(SimplePacket),
+    NameResolution(NameResolution),
+    InterfaceStatistics(InterfaceStatistics),
+    UNKNOWN(u32, Vec<u8>),
+}
+
+impl Block {
+
+    pub fn parse(block_type: u32, body: &[u8], swapped: bool, offset: u64) -> Result<Self, Error> {
+        let mut r = BodyReader::new(body, swapped, offset);
+        let block = match block_type {
+            SECTION_HEADER => Self::SectionHeader(SectionHeader::parse(&mut r)?),
+            INTERFACE_DESCRIPTION => Self::InterfaceDescription(InterfaceDescription::parse(&mut r)?),
+            ENHANCED_PACKET => Self::EnhancedPacket(EnhancedPacket::parse(&mut r)?),
+            SIMPLE_PACKET => Self::SimplePacket(SimplePacket::parse(&mut r)?),
+            NAME_RESOLUTION => Self::NameResolution(NameResolution::parse(&mut r)?),
+            INTERFACE_STATISTICS => Self::InterfaceStatistics(InterfaceStatistics::parse(&mut r)?),
+            _ => Self::UNKNOWN(block_type, body.to_vec()),
+        };
+        Ok(block)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_options() {
+        let body: Vec<u8> = vec![
+            0x00, 0x01, 0x00, 0x05,
+            b'h', b'e', b'l', b'l',
+            b'o', 0x00, 0x00, 0x00,
+            0x00, 0x09, 0x00, 0x01,
+            0x09, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00,
+        ];
+        let mut r = BodyReader::new(&body, false, 0);
+        let options = r.options().unwrap();
+        assert_eq!(options.len(), 2);
+        assert_eq!(options[0].code(), OPT_COMMENT);
+        assert_eq!(options[0].as_string(), "hello".to_string());
+        assert_eq!(options[1].value(), &[0x09]);
+    }
+
+    #[test]
+    fn test_interface_ts_resolution() {
+        let body: Vec<u8> = vec![
+            0x01, 0x00, 0x00, 0x00,
+            0xFF, 0xFF, 0x00, 0x00,
+            0x09, 0x00, 0x01, 0x00,
+            0x09, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00,
+        ];
+        let block = Block::parse(INTERFACE_DESCRIPTION, &body, true, 0).unwrap();
+        match block {
+            Block::InterfaceDescription(idb) => {
+                assert_eq!(idb.link_type(), 1);
+                assert_eq!(idb.snap_len(), 0xFFFF);
+                assert_eq!(idb.ts_resolution(), 9);
+                assert_eq!(idb.ts_units_per_sec(), 1_000_000_000);
+            },
+            _ => panic!("Expected interface description block"),
+        }
+    }
+
+    #[test]
+    fn test_truncated_enhanced_packet() {
+        let body: Vec<u8> = vec![
+            0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x10,
+            0x00, 0x00, 0x00, 0x10,
+            0xAA, 0xBB,
+        ];
+        assert!(matches!(
+            Block::parse(ENHANCED_PACKET, &body, false, 64),
+            Err(Error::MalformedBlock(64, _))
+        ));
+    }
+
+    #[test]
+    fn test_name_resolution_records() {
+        let body: Vec<u8> = vec![
+            0x00, 0x01, 0x00, 0x0E,
+            0x7F, 0x00, 0x00, 0x01,
+            b'l', b'o', b'c', b'a',
+            b'l', b'h', b'o', b's',
+            b't', 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00,
+        ];
+        let block = Block::parse(NAME_RESOLUTION, &body, false, 0).unwrap();
+        match block {
+            Block::NameResolution(nrb) => {
+                assert_eq!(nrb.records().len(), 1);
+                assert_eq!(nrb.records()[0].to_string(), "127.0.0.1 localhost".to_string());
+            },
+            _ => panic!("Expected name resolution block"),
+        }
+    }
+}
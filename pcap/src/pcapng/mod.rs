@@ -0,0 +1,3 @@
+pub mod block;
+pub mod reader;
+pub mod writer;
@@ -0,0 +1,328 @@
+use std::io::Read;
+
+use byte::bytes_to_u32;
+
+use crate::error::Error;
+use crate::file::{LinkType, TsResolution};
+use crate::reader::read_full;
+use crate::record::{Record, RecordHeader};
+
+use super::block::{
+    Block,
+    BYTE_ORDER_MAGIC,
+    EnhancedPacket,
+    InterfaceDescription,
+    InterfaceStatistics,
+    NameRecord,
+    SECTION_HEADER,
+    SectionHeader,
+    SimplePacket,
+};
+
+/// Upper bound on a single block, so a corrupt length cannot trigger a huge allocation.
+const MAX_BLOCK_LEN: usize = 16 * 1024 * 1024;
+
+pub struct PcapNgReader<R: Read> {
+    reader: R,
+    is_swapped: bool,
+    offset: u64,
+    section: Option<SectionHeader>,
+    interfaces: Vec<InterfaceDescription>,
+    names: Vec<NameRecord>,
+    statistics: Vec<InterfaceStatistics>,
+    pending: Option<Block>,
+    done: bool,
+}
+
+impl<R: Read> PcapNgReader<R> {
+
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            is_swapped: false,
+            offset: 0,
+            section: None,
+            interfaces: vec![],
+            names: vec![],
+            statistics: vec![],
+            pending: None,
+            done: false,
+        }
+    }
+
+    pub fn read_header(&mut self) -> Result<(), Error> {
+        while self.pending.is_none() {
+            match self.read_block()? {
+                None => break,
+                Some(Block::SectionHeader(_)) |
+                    Some(Block::InterfaceDescription(_)) |
+                    Some(Block::NameResolution(_)) => (),
+                Some(block) => self.pending = Some(block),
+            }
+        }
+        Ok(())
+    }
+
+    pub fn section(&self) -> Option<&SectionHeader> {
+        self.section.as_ref()
+    }
+
+    pub fn interfaces(&self) -> &[InterfaceDescription] {
+        &self.interfaces
+    }
+
+    pub fn names(&self) -> &[NameRecord] {
+        &self.names
+    }
+
+    pub fn statistics(&self) -> &[InterfaceStatistics] {
+        &self.statistics
+    }
+
+    fn u32(&self, b: &[u8]) -> u32 {
+        bytes_to_u32(b[0], b[1], b[2], b[3], self.is_swapped)
+    }
+
+    pub fn next_block(&mut self) -> Result<Option<Block>, Error> {
+        match self.pending.take() {
+            Some(block) => Ok(Some(block)),
+            None => self.read_block(),
+        }
+    }
+
+    fn read_block(&mut self) -> Result<Option<Block>, Error> {
+        let block_offset = self.offset;
+        let mut head = [0u8;8];
+        if !read_full(&mut self.reader, &mut head)? {
+            return Ok(None);
+        }
+        let block_type = self.u32(&head[0..4]);
+        let mut body_prefix: Vec<u8> = vec![];
+        if block_type == SECTION_HEADER {
+            let mut bom = [0u8;4];
+            if !read_full(&mut self.reader, &mut bom)? {
+                return Err(Error::UnexpectedEof);
+            }
+            self.is_swapped = match bytes_to_u32(bom[0], bom[1], bom[2], bom[3], false) {
+                BYTE_ORDER_MAGIC => false,
+                b if b == BYTE_ORDER_MAGIC.swap_bytes() => true,
+                b => return Err(Error::InvalidByteOrder(b)),
+            };
+            body_prefix.extend_from_slice(&bom);
+        } else if self.section.is_none() {
+            return Err(Error::MalformedBlock(block_offset, "First block is not a section header."));
+        }
+
+        let total_len = self.u32(&head[4..8]) as usize;
+        if total_len < 12 + body_prefix.len() || !total_len.is_multiple_of(4) {
+            return Err(Error::MalformedBlock(block_offset, "Invalid block total length."));
+        }
+        if total_len > MAX_BLOCK_LEN {
+            return Err(Error::MalformedBlock(block_offset, "Block exceeds maximum length."));
+        }
+        let mut body = vec![0u8; total_len - 8 - body_prefix.len()];
+        if !read_full(&mut self.reader, &mut body)? {
+            return Err(Error::UnexpectedEof);
+        }
+        let trailer = self.u32(&body[body.len() - 4..]) as usize;
+        if trailer != total_len {
+            return Err(Error::MalformedBlock(block_offset, "Trailing block length mismatch."));
+        }
+        body.truncate(body.len() - 4);
+        body_prefix.extend_from_slice(&body);
+        self.offset += total_len as u64;
+
+        let block = Block::parse(block_type, &body_prefix, self.is_swapped, block_offset)?;
+        match &block {
+            Block::SectionHeader(shb) => {
+                self.section = Some(shb.clone());
+                self.interfaces.clear();
+            },
+            Block::InterfaceDescription(idb) => self.interfaces.push(idb.clone()),
+            Block::NameResolution(nrb) => self.names.extend_from_slice(nrb.records()),
+            Block::InterfaceStatistics(isb) => self.statistics.push(isb.clone()),
+            _ => (),
+        }
+        Ok(Some(block))
+    }
+
+    fn interface(&self, id: u32) -> Result<&InterfaceDescription, Error> {
+        self.interfaces.get(id as usize).ok_or(Error::UnknownInterface(id))
+    }
+
+    fn enhanced_to_record(&self, epb: EnhancedPacket) -> Result<Record, Error> {
+        let idb = self.interface(epb.interface_id())?;
+        let units = idb.ts_units_per_sec() as u128;
+        let ts = epb.timestamp() as u128;
+        // Records hold 32-bit seconds, clamp instead of wrapping around.
+        let secs = ((ts / units) as i128 + idb.ts_offset() as i128).clamp(0, u32::MAX as i128);
+        let resolution = if units > 1_000_000 { TsResolution::NANO } else { TsResolution::MICRO };
+        let frac = (ts % units) * resolution.units_per_sec() as u128 / units;
+        let header = RecordHeader::from_parts(
+            secs as u32,
+            frac as u32,
+            epb.cap_len(),
+            epb.orig_len(),
+            resolution,
+        );
+        Ok(Record::with_link_type(header, epb.data().to_vec(), LinkType::new(idb.link_type())))
+    }
+
+    fn simple_to_record(&self, spb: SimplePacket) -> Result<Record, Error> {
+        let idb = self.interface(0)?;
+        let mut data = spb.data().to_vec();
+        if idb.snap_len() != 0 {
+            data.truncate(idb.snap_len() as usize);
+        }
+        let header = RecordHeader::from_parts(0, 0, data.len() as u32, spb.orig_len(), TsResolution::MICRO);
+        Ok(Record::with_link_type(header, data, LinkType::new(idb.link_type())))
+    }
+
+    pub fn next_record(&mut self) -> Result<Option<Record>, Error> {
+        loop {
+            match self.next_block()? {
+                None => return Ok(None),
+                Some(Block::EnhancedPacket(epb)) => return self.enhanced_to_record(epb).map(Some),
+                Some(Block::SimplePacket(spb)) => return self.simple_to_record(spb).map(Some),
+                Some(_) => (),
+            }
+        }
+    }
+}
+
+impl<R: Read> Iterator for PcapNgReader<R> {
+    type Item = Result<Record, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let next = self.next_record().transpose();
+        if !matches!(next, Some(Ok(_))) {
+            self.done = true;
+        }
+        next
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block(block_type: u32, body: &[u8], swapped: bool) -> Vec<u8> {
+        let conv = |v: u32| if swapped { v.to_le_bytes() } else { v.to_be_bytes() };
+        let total = (12 + body.len()) as u32;
+        let mut b = vec![];
+        b.extend_from_slice(&conv(block_type));
+        b.extend_from_slice(&conv(total));
+        b.extend_from_slice(body);
+        b.extend_from_slice(&conv(total));
+        b
+    }
+
+    fn capture(swapped: bool) -> Vec<u8> {
+        let u16b = |v: u16| if swapped { v.to_le_bytes() } else { v.to_be_bytes() };
+        let u32b = |v: u32| if swapped { v.to_le_bytes() } else { v.to_be_bytes() };
+
+        let mut shb = vec![];
+        shb.extend_from_slice(&u32b(BYTE_ORDER_MAGIC));
+        shb.extend_from_slice(&u16b(1));
+        shb.extend_from_slice(&u16b(0));
+        shb.extend_from_slice(&[0xFF;8]);
+
+        let mut idb = vec![];
+        idb.extend_from_slice(&u16b(1));
+        idb.extend_from_slice(&u16b(0));
+        idb.extend_from_slice(&u32b(0));
+        idb.extend_from_slice(&u16b(9));
+        idb.extend_from_slice(&u16b(1));
+        idb.extend_from_slice(&[3, 0, 0, 0]);
+        idb.extend_from_slice(&[0;4]);
+
+        let mut epb = vec![];
+        epb.extend_from_slice(&u32b(0));
+        epb.extend_from_slice(&u32b(0));
+        epb.extend_from_slice(&u32b(5_250));
+        epb.extend_from_slice(&u32b(3));
+        epb.extend_from_slice(&u32b(60));
+        epb.extend_from_slice(&[0xAA, 0xBB, 0xCC, 0x00]);
+
+        let mut spb = vec![];
+        spb.extend_from_slice(&u32b(2));
+        spb.extend_from_slice(&[0xDE, 0xAD, 0x00, 0x00]);
+
+        let mut data = vec![];
+        data.extend(block(SECTION_HEADER, &shb, swapped));
+        data.extend(block(super::super::block::INTERFACE_DESCRIPTION, &idb, swapped));
+        data.extend(block(0xBAD, &[0;4], swapped));
+        data.extend(block(super::super::block::ENHANCED_PACKET, &epb, swapped));
+        data.extend(block(super::super::block::SIMPLE_PACKET, &spb, swapped));
+        data
+    }
+
+    #[test]
+    fn test_read_records() {
+        for swapped in [false, true] {
+            let data = capture(swapped);
+            let mut reader = PcapNgReader::new(&data[..]);
+            let records: Vec<Record> = reader.by_ref().map(|r| r.unwrap()).collect();
+            assert_eq!(records.len(), 2);
+            assert_eq!(records[0].header().cap_len(), 3);
+            assert_eq!(records[0].header().packet_len(), 60);
+            assert_eq!(records[0].header().ts_sec(), 5);
+            assert_eq!(records[0].header().ts_ms_or_ns(), 250_000);
+            assert!(matches!(records[0].link_type(), LinkType::ETHERNET));
+            assert_eq!(records[1].header().cap_len(), 2);
+            assert_eq!(reader.section().unwrap().is_swapped(), swapped);
+            assert_eq!(reader.interfaces().len(), 1);
+        }
+    }
+
+    #[test]
+    fn test_truncated_capture() {
+        let data = capture(false);
+        let mut reader = PcapNgReader::new(&data[..data.len() - 6]);
+        assert!(reader.next().unwrap().is_ok());
+        assert!(matches!(reader.next(), Some(Err(Error::UnexpectedEof))));
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn test_read_header() {
+        let data = capture(true);
+        let mut reader = PcapNgReader::new(&data[..]);
+        reader.read_header().unwrap();
+        assert!(reader.section().is_some());
+        assert_eq!(reader.interfaces().len(), 1);
+        assert_eq!(reader.count(), 2);
+    }
+
+    #[test]
+    fn test_invalid_byte_order() {
+        let data = block(SECTION_HEADER, &[0xAA;16], false);
+        let mut reader = PcapNgReader::new(&data[..]);
+        assert!(matches!(reader.next(), Some(Err(Error::InvalidByteOrder(0xAAAAAAAA)))));
+    }
+
+    #[test]
+    fn test_oversized_block() {
+        let mut data = capture(false);
+        data.truncate(28);
+        data.extend_from_slice(&super::super::block::INTERFACE_DESCRIPTION.to_be_bytes());
+        data.extend_from_slice(&(MAX_BLOCK_LEN as u32 + 4).to_be_bytes());
+        let mut reader = PcapNgReader::new(&data[..]);
+        assert!(matches!(
+            reader.next(),
+            Some(Err(Error::MalformedBlock(28, "Block exceeds maximum length.")))
+        ));
+    }
+
+    #[test]
+    fn test_timestamp_overflow() {
+        let mut data = capture(false);
+        // Timestamp high and low words of the enhanced packet block.
+        data[88..96].copy_from_slice(&[0xFF;8]);
+        let record = PcapNgReader::new(&data[..]).next().unwrap().unwrap();
+        assert_eq!(record.header().ts_sec(), u32::MAX);
+    }
+}
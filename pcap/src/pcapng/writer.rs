@@ -0,0 +1,251 @@
+use std::io::Write;
+
+use byte::{u16_to_bytes, u32_to_bytes};
+
+use crate::error::Error;
+use crate::file::{FileHeader, TsResolution};
+use crate::record::Record;
+
+use super::block::{
+    BYTE_ORDER_MAGIC,
+    ENHANCED_PACKET,
+    IF_FCSLEN,
+    IF_NAME,
+    IF_TSRESOL,
+    INTERFACE_DESCRIPTION,
+    OPT_COMMENT,
+    OPT_ENDOFOPT,
+    SECTION_HEADER,
+};
+
+struct Interface {
+    units_per_sec: u64,
+}
+
+pub struct PcapNgWriter<W: Write> {
+    writer: W,
+    is_swapped: bool,
+    interfaces: Vec<Interface>,
+}
+
+impl<W: Write> PcapNgWriter<W> {
+
+    pub fn new(writer: W, swapped: bool, comments: &[&str]) -> Result<Self, Error> {
+        let mut w = Self { writer, is_swapped: swapped, interfaces: vec![] };
+        let mut body = vec![];
+        body.extend_from_slice(&w.u32(BYTE_ORDER_MAGIC));
+        body.extend_from_slice(&w.u16(1));
+        body.extend_from_slice(&w.u16(0));
+        body.extend_from_slice(&[0xFF;8]);
+        let options: Vec<(u16, &[u8])> = comments.iter().map(|c| (OPT_COMMENT, c.as_bytes())).collect();
+        w.put_options(&mut body, &options)?;
+        w.write_block(SECTION_HEADER, &body)?;
+        Ok(w)
+    }
+
+    pub fn from_file_header(writer: W, header: &FileHeader, comments: &[&str]) -> Result<Self, Error> {
+        let mut w = Self::new(writer, header.is_swapped(), comments)?;
+        // The F bit flags a valid FCS length, which is given in 16-bit words.
+        let fcs = header.fcs();
+        let fcs_len = (fcs & 1 != 0).then_some((fcs >> 1) * 16);
+        w.add_interface(header.link_type(), header.snap_len(), header.ts_resolution(), None, fcs_len)?;
+        Ok(w)
+    }
+
+    fn u16(&self, v: u16) -> [u8;2] {
+        u16_to_bytes(v, self.is_swapped)
+    }
+
+    fn u32(&self, v: u32) -> [u8;4] {
+        u32_to_bytes(v, self.is_swapped)
+    }
+
+    fn put_options(&self, body: &mut Vec<u8>, options: &[(u16, &[u8])]) -> Result<(), Error> {
+        if options.is_empty() {
+            return Ok(());
+        }
+        for (code, value) in options {
+            let len = u16::try_from(value.len()).map_err(|_| Error::OptionTooLong(*code, value.len()))?;
+            body.extend_from_slice(&self.u16(*code));
+            body.extend_from_slice(&self.u16(len));
+            body.extend_from_slice(value);
+            body.resize(body.len() + (4 - value.len() % 4) % 4, 0);
+        }
+        body.extend_from_slice(&self.u16(OPT_ENDOFOPT));
+        body.extend_from_slice(&self.u16(0));
+        Ok(())
+    }
+
+    fn write_block(&mut self, block_type: u32, body: &[u8]) -> Result<(), Error> {
+        let pad = (4 - body.len() % 4) % 4;
+        let total = self.u32((12 + body.len() + pad) as u32);
+        self.writer.write_all(&self.u32(block_type))?;
+        self.writer.write_all(&total)?;
+        self.writer.write_all(body)?;
+        self.writer.write_all(&[0u8;3][..pad])?;
+        self.writer.write_all(&total)?;
+        Ok(())
+    }
+
+    pub fn add_interface(
+        &mut self,
+        link_type: u16,
+        snap_len: u32,
+        resolution: TsResolution,
+        name: Option<&str>,
+        fcs_len: Option<u8>,
+    ) -> Result<u32, Error> {
+        let tsresol: u8 = match resolution {
+            TsResolution::MICRO => 6,
+            TsResolution::NANO => 9,
+        };
+        let mut body = vec![];
+        body.extend_from_slice(&self.u16(link_type));
+        body.extend_from_slice(&self.u16(0));
+        body.extend_from_slice(&self.u32(snap_len));
+        let mut options: Vec<(u16, &[u8])> = vec![];
+        if let Some(n) = name {
+            options.push((IF_NAME, n.as_bytes()));
+        }
+        let tsresol_value = [tsresol];
+        if resolution != TsResolution::MICRO {
+            options.push((IF_TSRESOL, &tsresol_value));
+        }
+        let fcs_value = [fcs_len.unwrap_or(0)];
+        if fcs_len.is_some() {
+            options.push((IF_FCSLEN, &fcs_value));
+        }
+        self.put_options(&mut body, &options)?;
+        self.write_block(INTERFACE_DESCRIPTION, &body)?;
+        self.interfaces.push(Interface { units_per_sec: resolution.units_per_sec() as u64 });
+        Ok(self.interfaces.len() as u32 - 1)
+    }
+
+    pub fn write_record(&mut self, interface_id: u32, record: &Record, comments: &[&str]) -> Result<(), Error> {
+        let units = match self.interfaces.get(interface_id as usize) {
+            Some(i) => i.units_per_sec,
+            None => return Err(Error::UnknownInterface(interface_id)),
+        };
+        let header = record.header();
+        let ts = header.ts_sec() as u64 * units + header.ts_nanos() as u64 * units / 1_000_000_000;
+        let mut body = vec![];
+        body.extend_from_slice(&self.u32(interface_id));
+        body.extend_from_slice(&self.u32((ts >> 32) as u32));
+        body.extend_from_slice(&self.u32(ts as u32));
+        body.extend_from_slice(&self.u32(record.data().len() as u32));
+        body.extend_from_slice(&self.u32(header.packet_len()));
+        body.extend_from_slice(record.data());
+        body.resize(body.len() + (4 - body.len() % 4) % 4, 0);
+        let options: Vec<(u16, &[u8])> = comments.iter().map(|c| (OPT_COMMENT, c.as_bytes())).collect();
+        self.put_options(&mut body, &options)?;
+        self.write_block(ENHANCED_PACKET, &body)
+    }
+
+    pub fn flush(&mut self) -> Result<(), Error> {
+        self.writer.flush()?;
+        Ok(())
+    }
+
+    pub fn into_inner(mut self) -> Result<W, Error> {
+        self.flush()?;
+        Ok(self.writer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pcapng::block::Block;
+    use crate::pcapng::reader::PcapNgReader;
+    use crate::reader::PcapReader;
+    use crate::record::RecordHeader;
+    use crate::writer::PcapWriter;
+
+    #[test]
+    fn test_write_comments() {
+        for swapped in [false, true] {
+            let mut writer = PcapNgWriter::new(vec![], swapped, &["analyst notes"]).unwrap();
+            let id = writer.add_interface(1, 0xFFFF, TsResolution::NANO, Some("eth0"), None).unwrap();
+            let rh = RecordHeader::from_parts(10, 5, 3, 64, TsResolution::MICRO);
+            writer.write_record(id, &Record::new(rh, vec![1, 2, 3]), &["suspicious", "second"]).unwrap();
+            assert!(matches!(writer.write_record(1, &Record::new(
+                RecordHeader::from_parts(0, 0, 0, 0, TsResolution::MICRO), vec![]), &[]),
+                Err(Error::UnknownInterface(1))
+            ));
+            let output = writer.into_inner().unwrap();
+            assert_eq!(output.len() % 4, 0);
+
+            let mut reader = PcapNgReader::new(&output[..]);
+            let mut packets = 0;
+            while let Some(block) = reader.next_block().unwrap() {
+                match block {
+                    Block::SectionHeader(shb) => {
+                        assert_eq!(shb.is_swapped(), swapped);
+                        assert_eq!(shb.comments(), vec!["analyst notes".to_string()]);
+                    },
+                    Block::InterfaceDescription(idb) => {
+                        assert_eq!(idb.name(), Some("eth0".to_string()));
+                        assert_eq!(idb.ts_resolution(), 9);
+                        assert_eq!(idb.fcs_len(), None);
+                    },
+                    Block::EnhancedPacket(epb) => {
+                        packets += 1;
+                        assert_eq!(epb.timestamp(), 10_000_005_000);
+                        assert_eq!(epb.data(), &[1, 2, 3]);
+                        assert_eq!(epb.orig_len(), 64);
+                        assert_eq!(epb.comments(), vec!["suspicious".to_string(), "second".to_string()]);
+                    },
+                    _ => panic!("Unexpected block"),
+                }
+            }
+            assert_eq!(packets, 1);
+        }
+    }
+
+    #[test]
+    fn test_convert_pcap() {
+        let header = FileHeader::from_parts(true, TsResolution::MICRO, 0xFFFF, 1, 0b0101);
+        let mut pcap = PcapWriter::new(vec![], header).unwrap();
+        for i in 0..3u32 {
+            let rh = RecordHeader::from_parts(100 + i, 250 + i, 2, 2, TsResolution::MICRO);
+            pcap.write_record(&Record::new(rh, vec![0xAA, i as u8])).unwrap();
+        }
+        let input = pcap.into_inner().unwrap();
+
+        let reader = PcapReader::new(&input[..]).unwrap();
+        let mut writer = PcapNgWriter::from_file_header(vec![], reader.header(), &[]).unwrap();
+        let expected: Vec<Record> = PcapReader::new(&input[..]).unwrap().map(|r| r.unwrap()).collect();
+        for record in reader {
+            writer.write_record(0, &record.unwrap(), &[]).unwrap();
+        }
+        let output = writer.into_inner().unwrap();
+
+        let mut ng = PcapNgReader::new(&output[..]);
+        let records: Vec<Record> = ng.by_ref().map(|r| r.unwrap()).collect();
+        assert_eq!(records.len(), expected.len());
+        for (a, b) in records.iter().zip(expected.iter()) {
+            assert_eq!(a.header().timestamp(), b.header().timestamp());
+            assert_eq!(a.header().cap_len(), b.header().cap_len());
+            assert_eq!(a.data(), b.data());
+        }
+        assert!(ng.section().unwrap().is_swapped());
+        assert_eq!(ng.interfaces()[0].snap_len(), 0xFFFF);
+        assert_eq!(ng.interfaces()[0].fcs_len(), Some(32));
+    }
+
+    #[test]
+    fn test_option_too_long() {
+        let comment = "x".repeat(u16::MAX as usize + 1);
+        assert!(matches!(
+            PcapNgWriter::new(vec![], false, &[&comment]),
+            Err(Error::OptionTooLong(OPT_COMMENT, 65536))
+        ));
+        let mut writer = PcapNgWriter::new(vec![], false, &[]).unwrap();
+        let id = writer.add_interface(1, 0xFFFF, TsResolution::MICRO, None, None).unwrap();
+        let rh = RecordHeader::from_parts(0, 0, 1, 1, TsResolution::MICRO);
+        assert!(matches!(
+            writer.write_record(id, &Record::new(rh, vec![0]), &[&comment]),
+            Err(Error::OptionTooLong(OPT_COMMENT, 65536))
+        ));
+    }
+}
@@ -0,0 +1,182 @@
+use std::io::Read;
+
+use crate::error::Error;
+use crate::file::{FileHeader, LinkType, MagicNumber};
+use crate::record::{Record, RecordHeader};
+
+pub(crate) fn read_full<R: Read>(reader: &mut R, buf: &mut [u8]) -> Result<bool, Error> {
+    let mut read = 0;
+    while read < buf.len() {
+        match reader.read(&mut buf[read..]) {
+            Ok(0) if read == 0 => return Ok(false),
+            Ok(0) => return Err(Error::UnexpectedEof),
+            Ok(n) => read += n,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => (),
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok(true)
+}
+
+pub struct PcapReader<R: Read> {
+    reader: R,
+    header: FileHeader,
+    offset: u64,
+    done: bool,
+}
+
+impl<R: Read> PcapReader<R> {
+
+    pub fn new(mut reader: R) -> Result<Self, Error> {
+        let mut head = [0u8;24];
+        reader.read_exact(&mut head)?;
+        if matches!(MagicNumber::from_bytes(&head), MagicNumber::UNKNOWN | MagicNumber::PCAPNG) {
+            return Err(Error::InvalidMagic(u32::from_be_bytes([head[0], head[1], head[2], head[3]])));
+        }
+        let header = FileHeader::new(head.to_vec())?;
+        Ok(Self { reader, header, offset: 24, done: false })
+    }
+
+    pub fn header(&self) -> &FileHeader {
+        &self.header
+    }
+
+    pub fn next_record(&mut self) -> Result<Option<Record>, Error> {
+        let mut head = [0u8;16];
+        if !read_full(&mut self.reader, &mut head)? {
+            return Ok(None);
+        }
+        let header = RecordHeader::new(head, self.header.is_swapped(), self.header.ts_resolution());
+        let link_type = LinkType::new(self.header.link_type());
+        // A corrupt header could declare a snaplen of up to 4 GiB, so the link type bounds it.
+        let max_len = match self.header.snap_len() {
+            0 => link_type.max_snap_len(),
+            s => s.min(link_type.max_snap_len()),
+        };
+        if header.cap_len() > max_len {
+            return Err(Error::MalformedRecord(self.offset, "Capture length exceeds maximum record size."));
+        }
+        let mut data = vec![0u8; header.cap_len() as usize];
+        if !read_full(&mut self.reader, &mut data)? {
+            return Err(Error::UnexpectedEof);
+        }
+        self.offset += 16 + data.len() as u64;
+        Ok(Some(Record::with_link_type(header, data, link_type)))
+    }
+}
+
+impl<R: Read> Iterator for PcapReader<R> {
+    type Item = Result<Record, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let next = self.next_record().transpose();
+        if !matches!(next, Some(Ok(_))) {
+            self.done = true;
+        }
+        next
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Classic capture with a -60 minute zone, FCS bits in the link type and `records`
+    /// records, the i-th one carrying the bytes 0..=i.
+    pub(crate) fn capture(swapped: bool, magic: u32, records: u32) -> Vec<u8> {
+        let u16b = |v: u16| if swapped { v.to_le_bytes() } else { v.to_be_bytes() };
+        let u32b = |v: u32| if swapped { v.to_le_bytes() } else { v.to_be_bytes() };
+        let mut b = vec![];
+        b.extend_from_slice(&u32b(magic));
+        b.extend_from_slice(&u16b(2));
+        b.extend_from_slice(&u16b(4));
+        b.extend_from_slice(&u32b(0xFFFFFFC4));
+        b.extend_from_slice(&u32b(0));
+        b.extend_from_slice(&u32b(0xFFFF));
+        b.extend_from_slice(&u32b(0x1000_0001));
+        for i in 0..records {
+            b.extend_from_slice(&u32b(1_700_000_000 + i));
+            b.extend_from_slice(&u32b(999_999 - i));
+            b.extend_from_slice(&u32b(i + 1));
+            b.extend_from_slice(&u32b(1514));
+            b.extend((0..=i).map(|x| x as u8));
+        }
+        b
+    }
+
+    #[test]
+    fn test_read_records() {
+        for swapped in [false, true] {
+            let data = capture(swapped, 0xa1b2c3d4, 3);
+            let reader = PcapReader::new(&data[..]).unwrap();
+            assert_eq!(reader.header().is_swapped(), swapped);
+            let records: Vec<Record> = reader.map(|r| r.unwrap()).collect();
+            assert_eq!(records.len(), 3);
+            assert_eq!(records[2].header().ts_sec(), 1_700_000_002);
+            assert_eq!(records[2].header().cap_len(), 3);
+            assert_eq!(records[2].header().packet_len(), 1514);
+            assert_eq!(records[2].data(), &[0, 1, 2]);
+        }
+    }
+
+    #[test]
+    fn test_truncated_record() {
+        let data = capture(false, 0xa1b2c3d4, 2);
+        let mut reader = PcapReader::new(&data[..data.len() - 1]).unwrap();
+        assert!(reader.next().unwrap().is_ok());
+        assert!(matches!(reader.next(), Some(Err(Error::UnexpectedEof))));
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn test_invalid_magic() {
+        let mut data = capture(false, 0xa1b2c3d4, 0);
+        data[0] = 0;
+        assert!(matches!(PcapReader::new(&data[..]), Err(Error::InvalidMagic(0x00b2c3d4))));
+        assert!(matches!(PcapReader::new(&data[..10]), Err(Error::UnexpectedEof)));
+    }
+
+    #[test]
+    fn test_oversized_record() {
+        let mut data = capture(false, 0xa1b2c3d4, 1);
+        data[32..36].copy_from_slice(&0x7FFFFFFFu32.to_be_bytes());
+        let mut reader = PcapReader::new(&data[..]).unwrap();
+        assert!(matches!(reader.next(), Some(Err(Error::MalformedRecord(24, _)))));
+    }
+
+    #[test]
+    fn test_oversized_snap_len() {
+        let mut data = capture(false, 0xa1b2c3d4, 1);
+        data[16..20].copy_from_slice(&0xFFFFFFFFu32.to_be_bytes());
+        data[32..36].copy_from_slice(&(LinkType::ETHERNET.max_snap_len() + 1).to_be_bytes());
+        let mut reader = PcapReader::new(&data[..]).unwrap();
+        assert_eq!(reader.header().snap_len(), 0xFFFFFFFF);
+        assert!(matches!(reader.next(), Some(Err(Error::MalformedRecord(24, _)))));
+    }
+
+    #[test]
+    fn test_large_record() {
+        let len: u32 = 300 * 1024;
+        let mut data = capture(false, 0xa1b2c3d4, 0);
+        data[16..20].copy_from_slice(&0x0800_0000u32.to_be_bytes());
+        data[20..24].copy_from_slice(&231u32.to_be_bytes());
+        data.extend_from_slice(&[0;8]);
+        data.extend_from_slice(&len.to_be_bytes());
+        data.extend_from_slice(&len.to_be_bytes());
+        data.resize(data.len() + len as usize, 0xAB);
+        let record = PcapReader::new(&data[..]).unwrap().next().unwrap().unwrap();
+        assert_eq!(record.data().len(), len as usize);
+
+        data[20..24].copy_from_slice(&1u32.to_be_bytes());
+        let mut reader = PcapReader::new(&data[..]).unwrap();
+        assert!(matches!(reader.next(), Some(Err(Error::MalformedRecord(24, _)))));
+
+        data[16..20].copy_from_slice(&0xFFFFu32.to_be_bytes());
+        data[20..24].copy_from_slice(&231u32.to_be_bytes());
+        let mut reader = PcapReader::new(&data[..]).unwrap();
+        assert!(matches!(reader.next(), Some(Err(Error::MalformedRecord(24, _)))));
+    }
+}
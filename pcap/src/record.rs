@@ -1,27 +1,34 @@
 use std::fmt;
-use byte::bytes_to_u32;
+use std::time::Duration;
+use byte::{bytes_to_u32, u32_to_bytes};
+use crate::error::Error;
+use crate::file::{LinkType, TsResolution};
 use network::{
-    ethernet_frame::{EthernetFrame, EthernetFrameParser, PacketType},
-    ip::{IPv4Header, IPv4HeaderParser},
-    transport::udp::{UdpHeader, UdpHeaderParser},
-    transport::tcp::{TcpHeader, TcpHeaderParser},
-    netw::ipv6::{IPv6Header, IPv6HeaderParser}, link::arp::{ARPHeader, ARPHeaderParser}
+    dissect::{DissectedPacket, LinkLayer},
+    error::Layer,
+    ethernet_frame::EthernetFrame,
+    ip::IPv4Header,
+    link::arp::ARPHeader,
+    netw::ipv6::IPv6Header,
+    transport::{tcp::TcpHeader, udp::UdpHeader},
 };
 
 
 #[derive(Clone)]
 pub struct RecordHeader {
     data: [u8;16],
-    is_swapped: bool
+    is_swapped: bool,
+    resolution: TsResolution,
 }
 
 impl fmt::Display for RecordHeader {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "TS upper: {}\nTS lower: {}\nCapture Len: {}\nOriginal Len: {}",
+            "TS upper: {}\nTS lower: {} {}\nCapture Len: {}\nOriginal Len: {}",
             self.ts_sec(),
             self.ts_ms_or_ns(),
+            self.resolution.to_str(),
             self.cap_len(),
             self.packet_len(),
         )
@@ -30,15 +37,59 @@ impl fmt::Display for RecordHeader {
 
 impl RecordHeader {
     
-    pub fn new(data: [u8;16], is_swapped: bool) -> Self {
-        Self{data, is_swapped}
+    pub fn new(data: [u8;16], is_swapped: bool, resolution: TsResolution) -> Self {
+        Self{data, is_swapped, resolution}
     }
 
-    fn ts_sec(&self) -> u32 {
+    pub fn from_parts(
+        ts_sec: u32,
+        ts_ms_or_ns: u32,
+        cap_len: u32,
+        packet_len: u32,
+        resolution: TsResolution,
+    ) -> Self {
+        let mut data = [0u8;16];
+        data[0..4].copy_from_slice(&ts_sec.to_be_bytes());
+        data[4..8].copy_from_slice(&ts_ms_or_ns.to_be_bytes());
+        data[8..12].copy_from_slice(&cap_len.to_be_bytes());
+        data[12..16].copy_from_slice(&packet_len.to_be_bytes());
+        Self{data, is_swapped: false, resolution}
+    }
+
+    pub fn ts_resolution(&self) -> TsResolution {
+        self.resolution
+    }
+
+    pub fn to_bytes(&self, swapped: bool, resolution: TsResolution) -> [u8;16] {
+        let frac = match (self.resolution, resolution) {
+            (TsResolution::MICRO, TsResolution::NANO) => self.ts_nanos(),
+            (TsResolution::NANO, TsResolution::MICRO) => self.ts_ms_or_ns() / 1_000,
+            _ => self.ts_ms_or_ns(),
+        };
+        let mut data = [0u8;16];
+        data[0..4].copy_from_slice(&u32_to_bytes(self.ts_sec(), swapped));
+        data[4..8].copy_from_slice(&u32_to_bytes(frac, swapped));
+        data[8..12].copy_from_slice(&u32_to_bytes(self.cap_len(), swapped));
+        data[12..16].copy_from_slice(&u32_to_bytes(self.packet_len(), swapped));
+        data
+    }
+
+    pub fn ts_nanos(&self) -> u32 {
+        match self.resolution {
+            TsResolution::MICRO => self.ts_ms_or_ns().saturating_mul(1_000),
+            TsResolution::NANO => self.ts_ms_or_ns(),
+        }
+    }
+
+    pub fn timestamp(&self) -> Duration {
+        Duration::new(self.ts_sec() as u64, 0) + Duration::from_nanos(self.ts_nanos() as u64)
+    }
+
+    pub fn ts_sec(&self) -> u32 {
         bytes_to_u32(self.data[0], self.data[1], self.data[2], self.data[3], self.is_swapped)
     }
 
-    fn ts_ms_or_ns(&self) -> u32 {
+    pub fn ts_ms_or_ns(&self) -> u32 {
         bytes_to_u32(self.data[4], self.data[5], self.data[6], self.data[7], self.is_swapped)
     }
 
@@ -46,105 +97,158 @@ impl RecordHeader {
         bytes_to_u32(self.data[8], self.data[9], self.data[10], self.data[11], self.is_swapped)
     }
 
-    fn packet_len(&self) -> u32 {
+    pub fn packet_len(&self) -> u32 {
         bytes_to_u32(self.data[12], self.data[13], self.data[14], self.data[15], self.is_swapped)
     }
 }
 
+/// A single header taken out of a dissected record, see the `Record::parse_*` accessors.
+#[derive(Clone)]
+pub struct Parsed<T> {
+    header: T,
+    truncated: bool,
+}
+
+impl<T: fmt::Display> fmt::Display for Parsed<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.truncated {
+            write!(f, "{}\n[Truncated]", self.header)
+        } else {
+            write!(f, "{}", self.header)
+        }
+    }
+}
+
+impl<T> Parsed<T> {
+
+    pub fn new(header: T, truncated: bool) -> Self {
+        Self{header, truncated}
+    }
+
+    pub fn header(&self) -> &T {
+        &self.header
+    }
+
+    pub fn into_header(self) -> T {
+        self.header
+    }
+
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+}
+
 #[derive(Clone)]
 pub struct Record {
     header: RecordHeader,
-    data: Vec<u8>
+    data: Vec<u8>,
+    link_type: LinkType,
 }
 
 impl fmt::Display for Record {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let frame = self.parse_ethernet_frame();
-        if frame.is_arp() {
-            return write!(f, "{}\n{}\n{}", self.header, frame, self.parse_arp())
-        }
-        let ip = self.ip_header_to_string(frame.packet_type());
         let mut bytes: String = "".to_string();
-        for i in 0..self.data.len() {
-            bytes = format!("{} {:02X?}", bytes,  self.data[i])
+        for b in self.data.iter() {
+            bytes = format!("{} {:02X?}", bytes, b)
+        }
+        match self.dissect() {
+            Ok(packet) => write!(f, "{}\n{}{}", self.header, packet, bytes),
+            Err(e) => write!(f, "{}\n{}\n{}", self.header, e, bytes),
         }
-        write!(
-            f,
-            "{}\n{}\n{}\n{}\n{}",
-            self.header,
-            frame,
-            ip,
-            self.parse_tcp_header(),
-            bytes,
-        )
     }
 }
 
 impl Record {
 
     pub fn new(header: RecordHeader, data: Vec<u8>) -> Self {
-        Self{header, data} 
+        Self{header, data, link_type: LinkType::ETHERNET}
     }
 
-    pub fn parse_ethernet_frame(&self) -> EthernetFrame {
-        let mut parser = EthernetFrameParser::new();
-        for i in 0..14 {
-            parser.put_byte(self.data[i])
-        }
-        parser.parse()
+    pub fn with_link_type(header: RecordHeader, data: Vec<u8>, link_type: LinkType) -> Self {
+        Self{header, data, link_type}
     }
 
-    pub fn parse_arp(&self) -> ARPHeader {
-        let mut parser = ARPHeaderParser::new(false);
-        for i in 14..42 {
-            parser.parse(self.data[i])
-        }
-        parser.get_header()
+    pub fn header(&self) -> &RecordHeader {
+        &self.header
     }
 
-    pub fn parse_ipv4_header(&self) -> IPv4Header {
-        let mut parser = IPv4HeaderParser::new(true);
-        parser.step(self.data[14]);
-        for i in 15..34 {
-            parser.step(self.data[i])
-        }
-        parser.get_header()
+    pub fn data(&self) -> &[u8] {
+        &self.data
     }
 
-    pub fn parse_ipv6_header(&self) -> IPv6Header {
-        let mut parser = IPv6HeaderParser::new(true);
-        for i in 14..54 {
-            parser.parse(self.data[i])
-        }
-        parser.get_header()
+    pub fn link_type(&self) -> LinkType {
+        self.link_type
     }
 
-    pub fn ip_header_to_string(&self, t: PacketType) -> String {
-        match t {
-            PacketType::IPv4 => format!("{}", self.parse_ipv4_header()),
-            PacketType::IPv6 => format!("{}", self.parse_ipv6_header()),
-            PacketType::ARP => "ARP Header parsing not implemented".to_string(),
-            PacketType::IPX => "IPX Header parsing not implemented".to_string(),
-            PacketType::LENGTH(b) => format!("IEEE 802.3 Header parsing not implemented (length: {})", b),
-            PacketType::UNKNWON => "UNKNWON ip header type encountered".to_string(),
+    pub fn is_truncated(&self) -> bool {
+        (self.data.len() as u32) < self.header.packet_len()
+    }
 
-        }
+    pub fn dissect(&self) -> Result<DissectedPacket<'_>, Error> {
+        let link = match self.link_type {
+            LinkType::ETHERNET => LinkLayer::ETHERNET,
+            LinkType::NULL => LinkLayer::NULL,
+            LinkType::LOOP => LinkLayer::LOOP,
+            LinkType::RAW => LinkLayer::RAW,
+            LinkType::IPV4 => LinkLayer::IPV4,
+            LinkType::IPV6 => LinkLayer::IPV6,
+            LinkType::LINUXSLL => LinkLayer::SLL,
+            LinkType::LINUXSLL2 => LinkLayer::SLL2,
+            LinkType::PPP | LinkType::PPPHDLC => LinkLayer::PPP,
+            LinkType::PPPWITHDIR => LinkLayer::PPPWITHDIR,
+            LinkType::IEEE80211 => LinkLayer::IEEE80211,
+            LinkType::IEEE80211RADIOTAP => LinkLayer::RADIOTAP,
+            LinkType::CANSOCKETCAN => LinkLayer::CAN,
+            LinkType::USBLINUX => LinkLayer::USB,
+            LinkType::USBLINUXMMAPPED => LinkLayer::USBMMAPPED,
+            _ => return Err(Error::UnsupportedLinkType(self.link_type)),
+        };
+        Ok(DissectedPacket::from_link(link, &self.data))
     }
 
-    pub fn parse_udp_header(&self) -> UdpHeader {
-        let mut parser = UdpHeaderParser::new(false);
-        for i in 34..43 {
-            parser.parse(self.data[i])
+    fn parsed<'a, T, F>(&'a self, layer: Layer, header: F) -> Result<Parsed<T>, Error>
+    where
+        T: Clone,
+        F: for<'p> Fn(&'p DissectedPacket<'a>) -> Option<&'p T>,
+    {
+        let packet = self.dissect()?;
+        match (header(&packet), packet.layer(layer)) {
+            (Some(h), Some(l)) => Ok(Parsed::new(h.clone(), l.is_truncated())),
+            _ => match packet.error() {
+                Some(e) => Err(e.clone().into()),
+                None => Err(Error::MissingLayer(layer)),
+            },
         }
-        parser.get_header()
     }
 
-    pub fn parse_tcp_header(&self) -> TcpHeader {
-        let mut parser = TcpHeaderParser::new(false);
-        for i in  34..54 {
-            parser.parse(self.data[i])
-        }
-        parser.get_header()
+    #[deprecated(note = "use Record::dissect and DissectedPacket::ethernet_frame")]
+    pub fn parse_ethernet_frame(&self) -> Result<Parsed<EthernetFrame>, Error> {
+        self.parsed(Layer::Ethernet, DissectedPacket::ethernet_frame)
+    }
+
+    #[deprecated(note = "use Record::dissect and DissectedPacket::arp")]
+    pub fn parse_arp(&self) -> Result<Parsed<ARPHeader>, Error> {
+        self.parsed(Layer::ARP, DissectedPacket::arp)
+    }
+
+    #[deprecated(note = "use Record::dissect and DissectedPacket::ipv4")]
+    pub fn parse_ipv4_header(&self) -> Result<Parsed<IPv4Header>, Error> {
+        self.parsed(Layer::IPv4, DissectedPacket::ipv4)
+    }
+
+    #[deprecated(note = "use Record::dissect and DissectedPacket::ipv6")]
+    pub fn parse_ipv6_header(&self) -> Result<Parsed<IPv6Header>, Error> {
+        self.parsed(Layer::IPv6, DissectedPacket::ipv6)
+    }
+
+    #[deprecated(note = "use Record::dissect and DissectedPacket::udp")]
+    pub fn parse_udp_header(&self) -> Result<Parsed<UdpHeader>, Error> {
+        self.parsed(Layer::UDP, DissectedPacket::udp)
+    }
+
+    #[deprecated(note = "use Record::dissect and DissectedPacket::tcp")]
+    pub fn parse_tcp_header(&self) -> Result<Parsed<TcpHeader>, Error> {
+        self.parsed(Layer::TCP, DissectedPacket::tcp)
     }
 }
 
@@ -160,11 +264,147 @@ mod tests {
             0x00, 0x00, 0x00, 0xAA,
             0x00, 0x00, 0x00, 0xAA,
         ];
-        let unswapped_rh = RecordHeader::new(bytes, false);
+        let unswapped_rh = RecordHeader::new(bytes, false, TsResolution::MICRO);
         assert_eq!(unswapped_rh.ts_sec(), 0x00000001);
         assert_eq!(unswapped_rh.ts_ms_or_ns(), 0x00000002);
         assert_eq!(unswapped_rh.cap_len(), 0x000000AA);
         assert_eq!(unswapped_rh.packet_len(), 0x000000AA);
     }
-}
 
+    #[test]
+    fn test_record_header_from_parts() {
+        let rh = RecordHeader::from_parts(1, 2, 0xAA, 0xBB, TsResolution::MICRO);
+        assert_eq!(rh.ts_sec(), 1);
+        assert_eq!(rh.ts_ms_or_ns(), 2);
+        assert_eq!(rh.cap_len(), 0xAA);
+        assert_eq!(rh.packet_len(), 0xBB);
+        assert_eq!(rh.timestamp(), Duration::new(1, 2_000));
+    }
+
+    #[test]
+    fn test_record_header_nanosecond_timestamp() {
+        let bytes: [u8;16] = [
+            0x01, 0x00, 0x00, 0x00,
+            0x15, 0xCD, 0x5B, 0x07,
+            0xAA, 0x00, 0x00, 0x00,
+            0xAA, 0x00, 0x00, 0x00,
+        ];
+        let rh = RecordHeader::new(bytes, true, TsResolution::NANO);
+        assert_eq!(rh.ts_ms_or_ns(), 123_456_789);
+        assert_eq!(rh.ts_nanos(), 123_456_789);
+        assert_eq!(rh.timestamp(), Duration::new(1, 123_456_789));
+        assert!(rh.to_string().contains("TS lower: 123456789 ns"));
+        assert_eq!(rh.to_bytes(true, TsResolution::NANO), bytes);
+        let micro = RecordHeader::new(rh.to_bytes(false, TsResolution::MICRO), false, TsResolution::MICRO);
+        assert_eq!(micro.ts_ms_or_ns(), 123_456);
+        assert_eq!(micro.cap_len(), 0xAA);
+    }
+
+    fn frame(len: usize) -> Vec<u8> {
+        let mut data = vec![
+            0xA1, 0xA1, 0xA1, 0xA1, 0xA1, 0xA1,
+            0xB1, 0xB1, 0xB1, 0xB1, 0xB1, 0xB1,
+            0x08, 0x00,
+            0x45, 0x00, 0x00, 0x28, 0x00, 0x01, 0x40, 0x00,
+            0x40, 0x06, 0x00, 0x00, 0xC0, 0xA8, 0x00, 0x01,
+            0xC0, 0xA8, 0x00, 0x02,
+            0x30, 0x39, 0x00, 0x50, 0x00, 0x00, 0x00, 0x01,
+            0x00, 0x00, 0x00, 0x00, 0x50, 0x02, 0x20, 0x00,
+            0x00, 0x00, 0x00, 0x00,
+        ];
+        data.truncate(len);
+        data
+    }
+
+    fn record(data: Vec<u8>) -> Record {
+        let rh = RecordHeader::from_parts(0, 0, data.len() as u32, 54, TsResolution::MICRO);
+        Record::new(rh, data)
+    }
+
+    #[test]
+    fn test_truncated_record() {
+        for len in 0..=54 {
+            let r = record(frame(len));
+            let packet = r.dissect().unwrap();
+            assert_eq!(r.is_truncated(), len < 54);
+            assert_eq!(packet.is_truncated(), len < 54);
+            assert_eq!(packet.layers()[0].is_truncated(), len < 14);
+            assert_eq!(packet.ipv4().is_some(), len >= 14);
+            assert_eq!(packet.tcp().is_some(), len >= 34);
+            assert_eq!(r.to_string().contains("[Truncated"), len < 54);
+        }
+    }
+
+    #[test]
+    fn test_dissect_record() {
+        let r = record(frame(10));
+        let packet = r.dissect().unwrap();
+        assert!(packet.ethernet_frame().unwrap().to_string().starts_with("Destination: A1:A1:A1:A1:A1:A1"));
+        assert!(r.to_string().ends_with(" A1 A1 A1 A1 A1 A1 B1 B1 B1 B1"));
+
+        let r = record(frame(54));
+        let packet = r.dissect().unwrap();
+        assert!(packet.ipv4().unwrap().to_string().contains("Source: 192.168.0.1\nDestination: 192.168.0.2"));
+        assert!(packet.tcp().unwrap().to_string().starts_with("Src Port: 12345\nDest Port: 80\nSeq: 1"));
+
+        let rh = RecordHeader::from_parts(0, 0, 54, 54, TsResolution::MICRO);
+        let r = Record::with_link_type(rh, frame(54), LinkType::FDDI);
+        assert!(matches!(r.dissect(), Err(Error::UnsupportedLinkType(LinkType::FDDI))));
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_parsed_wrappers() {
+        for len in 0..=54 {
+            let r = record(frame(len));
+            assert_eq!(r.parse_ethernet_frame().unwrap().is_truncated(), len < 14);
+            assert_eq!(r.parse_ipv4_header().map(|p| p.is_truncated()).ok(), (len >= 14).then_some(len < 34));
+            assert_eq!(r.parse_tcp_header().map(|p| p.is_truncated()).ok(), (len >= 34).then_some(len < 54));
+            assert!(matches!(r.parse_udp_header(), Err(Error::MissingLayer(Layer::UDP))));
+            assert!(matches!(r.parse_ipv6_header(), Err(Error::MissingLayer(Layer::IPv6))));
+            assert!(matches!(r.parse_arp(), Err(Error::MissingLayer(Layer::ARP))));
+        }
+        let tcp = record(frame(40)).parse_tcp_header().unwrap();
+        assert!(tcp.to_string().starts_with("Src Port: 12345\nDest Port: 80\nSeq: UNSET"));
+        assert!(tcp.to_string().ends_with("\n[Truncated]"));
+        assert_eq!(tcp.into_header().src_port(), Some(12345));
+    }
+
+    #[test]
+    fn test_dissect_link_types() {
+        let ip = frame(54)[14..].to_vec();
+        let rh = RecordHeader::from_parts(0, 0, 40, 40, TsResolution::MICRO);
+        for link in [LinkType::RAW, LinkType::IPV4] {
+            let r = Record::with_link_type(rh.clone(), ip.clone(), link);
+            let packet = r.dissect().unwrap();
+            assert_eq!(packet.layers()[0].layer(), network::error::Layer::IPv4);
+            assert!(packet.tcp().is_some());
+        }
+
+        let mut null = vec![0x02, 0x00, 0x00, 0x00];
+        null.extend_from_slice(&ip);
+        let r = Record::with_link_type(rh.clone(), null, LinkType::NULL);
+        let packet = r.dissect().unwrap();
+        assert!(packet.loopback().unwrap().is_ipv4());
+        assert_eq!(packet.tcp().unwrap().src_port(), Some(12345));
+
+        let mut sll = vec![0x00, 0x00, 0x00, 0x01, 0x00, 0x06, 0xB1, 0xB1, 0xB1, 0xB1, 0xB1, 0xB1, 0x00, 0x00, 0x08, 0x00];
+        sll.extend_from_slice(&ip);
+        let r = Record::with_link_type(rh.clone(), sll, LinkType::LINUXSLL);
+        let packet = r.dissect().unwrap();
+        assert_eq!(packet.sll().unwrap().protocol(), 0x0800);
+        assert!(packet.tcp().is_some());
+        assert!(r.to_string().contains("Packet Type: Unicast to us (0)"));
+
+        let mut ppp = vec![0xFF, 0x03, 0x00, 0x21];
+        ppp.extend_from_slice(&ip);
+        let r = Record::with_link_type(rh.clone(), ppp, LinkType::PPP);
+        assert!(r.dissect().unwrap().ipv4().is_some());
+
+        let can = vec![0x00, 0x00, 0x01, 0x23, 0x02, 0x00, 0x00, 0x00, 0xAB, 0xCD];
+        let r = Record::with_link_type(rh, can, LinkType::CANSOCKETCAN);
+        let packet = r.dissect().unwrap();
+        assert_eq!(packet.can().unwrap().id(), 0x123);
+        assert_eq!(packet.payload(), &[0xAB, 0xCD]);
+    }
+}
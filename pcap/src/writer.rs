@@ -0,0 +1,92 @@
+use std::io::Write;
+
+use crate::error::Error;
+use crate::file::FileHeader;
+use crate::record::Record;
+
+pub struct PcapWriter<W: Write> {
+    writer: W,
+    header: FileHeader,
+}
+
+impl<W: Write> PcapWriter<W> {
+
+    pub fn new(mut writer: W, header: FileHeader) -> Result<Self, Error> {
+        writer.write_all(&header.to_bytes())?;
+        Ok(Self { writer, header })
+    }
+
+    pub fn header(&self) -> &FileHeader {
+        &self.header
+    }
+
+    pub fn write_record(&mut self, record: &Record) -> Result<(), Error> {
+        let head = record.header().to_bytes(self.header.is_swapped(), self.header.ts_resolution());
+        self.writer.write_all(&head)?;
+        self.writer.write_all(record.data())?;
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> Result<(), Error> {
+        self.writer.flush()?;
+        Ok(())
+    }
+
+    pub fn into_inner(mut self) -> Result<W, Error> {
+        self.flush()?;
+        Ok(self.writer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file::TsResolution;
+    use crate::reader::PcapReader;
+    use crate::reader::tests::capture;
+    use crate::record::RecordHeader;
+
+    #[test]
+    fn test_round_trip() {
+        for swapped in [false, true] {
+            for magic in [0xa1b2c3d4, 0xa1b23c4d] {
+                let input = capture(swapped, magic, 4);
+                let reader = PcapReader::new(&input[..]).unwrap();
+                let mut writer = PcapWriter::new(vec![], reader.header().clone()).unwrap();
+                for record in reader {
+                    writer.write_record(&record.unwrap()).unwrap();
+                }
+                assert_eq!(writer.into_inner().unwrap(), input);
+            }
+        }
+    }
+
+    #[test]
+    fn test_write_converts_byte_order_and_resolution() {
+        let input = capture(true, 0xa1b2c3d4, 4);
+        let reader = PcapReader::new(&input[..]).unwrap();
+        let header = FileHeader::from_parts(false, TsResolution::NANO, 0xFFFF, 1, 1);
+        let mut writer = PcapWriter::new(vec![], header).unwrap();
+        for record in reader {
+            writer.write_record(&record.unwrap()).unwrap();
+        }
+        let output = writer.into_inner().unwrap();
+        let records: Vec<Record> = PcapReader::new(&output[..]).unwrap().map(|r| r.unwrap()).collect();
+        assert_eq!(records.len(), 4);
+        assert_eq!(records[0].header().ts_sec(), 1_700_000_000);
+        assert_eq!(records[0].header().ts_ms_or_ns(), 999_999_000);
+        assert_eq!(records[3].data(), &[0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn test_write_built_record() {
+        let header = FileHeader::from_parts(true, TsResolution::MICRO, 64, 1, 0);
+        let mut writer = PcapWriter::new(vec![], header).unwrap();
+        let rh = RecordHeader::from_parts(1, 2, 2, 60, TsResolution::MICRO);
+        writer.write_record(&Record::new(rh, vec![0xAB, 0xCD])).unwrap();
+        let output = writer.into_inner().unwrap();
+        assert_eq!(output.len(), 24 + 16 + 2);
+        assert_eq!(&output[24..28], &[1, 0, 0, 0]);
+        assert_eq!(&output[40..], &[0xAB, 0xCD]);
+    }
+}